@@ -5,20 +5,46 @@
 use rustc_hir::def_id::LocalDefId;
 use rustc_index::vec::IndexVec;
 use rustc_infer::infer::{DefiningAnchor, TyCtxtInferExt};
-use rustc_middle::mir::Body;
+use rustc_middle::mir::{Body, Location};
 use rustc_middle::ty::{self, TyCtxt};
+use rustc_mir_dataflow::{Analysis, ResultsCursor};
 
 pub use super::{
+    borrow_set::{BorrowData, BorrowSet, TwoPhaseActivation},
+    dataflow::{BorrowIndex, Borrows},
     facts::{AllFacts as PoloniusInput, RustcFacts},
     location::{LocationTable, RichLocation},
     nll::PoloniusOutput,
+    region_infer::RegionInferenceContext,
     BodyWithBorrowckFacts,
 };
 
-/// This function computes Polonius facts for the given body. It makes a copy of
-/// the body because it needs to regenerate the region identifiers. This function
-/// should never be invoked during a typical compilation session due to performance
-/// issues with Polonius.
+/// Which results of the borrow checker [`get_body_with_borrowck_facts`] returns, in addition to
+/// the body, its borrow set and the results of region inference.
+#[derive(Debug, Copy, Clone)]
+pub enum ConsumerOptions {
+    /// Nothing else.
+    RegionInferenceContext,
+    /// The Polonius input facts.
+    PoloniusInputFacts,
+    /// The Polonius input facts and the Polonius output facts. Computing the latter is expensive.
+    PoloniusOutputFacts,
+}
+
+impl ConsumerOptions {
+    pub(crate) fn polonius_input(self) -> bool {
+        matches!(self, Self::PoloniusInputFacts | Self::PoloniusOutputFacts)
+    }
+
+    pub(crate) fn polonius_output(self) -> bool {
+        matches!(self, Self::PoloniusOutputFacts)
+    }
+}
+
+/// This function borrow checks the given body again, and returns it with the results of the
+/// borrow checker that `options` asks for. It makes a copy of the body because it needs to
+/// regenerate the region identifiers. This function should never be invoked during a typical
+/// compilation session due to performance issues, especially with Polonius.
 ///
 /// Note:
 /// *   This function will panic if the required body was already stolen. This
@@ -31,10 +57,38 @@
 pub fn get_body_with_borrowck_facts(
     tcx: TyCtxt<'_>,
     def: ty::WithOptConstParam<LocalDefId>,
+    options: ConsumerOptions,
 ) -> BodyWithBorrowckFacts<'_> {
     let (input_body, promoted) = tcx.mir_promoted(def);
     let infcx = tcx.infer_ctxt().with_opaque_type_inference(DefiningAnchor::Bind(def.did)).build();
     let input_body: &Body<'_> = &input_body.borrow();
     let promoted: &IndexVec<_, _> = &promoted.borrow();
-    *super::do_mir_borrowck(&infcx, input_body, promoted, true).1.unwrap()
+    *super::do_mir_borrowck(&infcx, input_body, promoted, Some(options)).1.unwrap()
+}
+
+/// The loans that are in scope at each location of a body, according to NLL: the loans whose
+/// region contains the location, and whose borrowed place hasn't been overwritten since they were
+/// created.
+pub struct LoansInScope<'a, 'tcx> {
+    cursor: ResultsCursor<'a, 'tcx, Borrows<'a, 'tcx>>,
+}
+
+impl<'a, 'tcx> LoansInScope<'a, 'tcx> {
+    pub fn new(tcx: TyCtxt<'tcx>, body_with_facts: &'a BodyWithBorrowckFacts<'tcx>) -> Self {
+        let BodyWithBorrowckFacts { body, borrow_set, region_inference_context, .. } =
+            body_with_facts;
+        let cursor = Borrows::new(tcx, body, region_inference_context, borrow_set)
+            .into_engine(tcx, body)
+            .pass_name("borrowck_consumers")
+            .iterate_to_fixpoint()
+            .into_results_cursor(body);
+        LoansInScope { cursor }
+    }
+
+    /// Returns the loans that are in scope when the statement or terminator at `location` is
+    /// executed. Loans activated or created by it aren't included.
+    pub fn at(&mut self, location: Location) -> Vec<BorrowIndex> {
+        self.cursor.seek_before_primary_effect(location);
+        self.cursor.get().iter().collect()
+    }
 }
@@ -35,6 +35,7 @@
 use rustc_middle::mir::{InlineAsmOperand, Terminator, TerminatorKind};
 use rustc_middle::ty::query::Providers;
 use rustc_middle::ty::{self, CapturedPlace, ParamEnv, RegionVid, TyCtxt};
+use rustc_session::config::Polonius;
 use rustc_session::lint::builtin::UNUSED_MUT;
 use rustc_span::{Span, Symbol};
 
@@ -77,6 +78,7 @@
 mod path_utils;
 mod place_ext;
 mod places_conflict;
+mod polonius;
 mod prefixes;
 mod region_infer;
 mod renumber;
@@ -89,10 +91,12 @@
 pub mod consumers;
 
 use borrow_set::{BorrowData, BorrowSet};
+use consumers::ConsumerOptions;
 use dataflow::{BorrowIndex, BorrowckFlowState as Flows, BorrowckResults, Borrows};
 use nll::{PoloniusOutput, ToRegionVid};
 use place_ext::PlaceExt;
 use places_conflict::{places_conflict, PlaceConflictBias};
+use polonius::HybridPolonius;
 use region_infer::RegionInferenceContext;
 
 // FIXME(eddyb) perhaps move this somewhere more centrally.
@@ -148,7 +152,7 @@ fn mir_borrowck(tcx: TyCtxt<'_>, def: ty::WithOptConstParam<LocalDefId>) -> &Bor
         tcx.infer_ctxt().with_opaque_type_inference(DefiningAnchor::Bind(hir_owner.def_id)).build();
     let input_body: &Body<'_> = &input_body.borrow();
     let promoted: &IndexVec<_, _> = &promoted.borrow();
-    let opt_closure_req = do_mir_borrowck(&infcx, input_body, promoted, false).0;
+    let opt_closure_req = do_mir_borrowck(&infcx, input_body, promoted, None).0;
     debug!("mir_borrowck done");
 
     tcx.arena.alloc(opt_closure_req)
@@ -156,15 +160,15 @@ fn mir_borrowck(tcx: TyCtxt<'_>, def: ty::WithOptConstParam<LocalDefId>) -> &Bor
 
 /// Perform the actual borrow checking.
 ///
-/// If `return_body_with_facts` is true, then return the body with non-erased
-/// region ids on which the borrow checking was performed together with Polonius
-/// facts.
+/// If `consumer_options` is `Some`, then return the body with non-erased region ids on which the
+/// borrow checking was performed, together with the results of the borrow checker that the
+/// options ask for.
 #[instrument(skip(infcx, input_body, input_promoted), fields(id=?input_body.source.with_opt_param().as_local().unwrap()), level = "debug")]
 fn do_mir_borrowck<'tcx>(
     infcx: &InferCtxt<'tcx>,
     input_body: &Body<'tcx>,
     input_promoted: &IndexVec<Promoted, Body<'tcx>>,
-    return_body_with_facts: bool,
+    consumer_options: Option<ConsumerOptions>,
 ) -> (BorrowCheckResult<'tcx>, Option<Box<BodyWithBorrowckFacts<'tcx>>>) {
     let def = input_body.source.with_opt_param().as_local().unwrap();
 
@@ -245,13 +249,16 @@ fn do_mir_borrowck<'tcx>(
     let borrow_set =
         Rc::new(BorrowSet::build(tcx, body, locals_are_invalidated_at_exit, &mdpe.move_data));
 
-    let use_polonius = return_body_with_facts || infcx.tcx.sess.opts.unstable_opts.polonius;
+    let polonius = infcx.tcx.sess.opts.unstable_opts.polonius;
+    let generate_polonius_facts = consumer_options.map_or(false, |o| o.polonius_input());
+    let use_polonius =
+        consumer_options.map_or(false, |o| o.polonius_output()) || polonius == Polonius::Full;
 
     // Compute non-lexical lifetimes.
     let nll::NllOutput {
         regioncx,
         opaque_type_values,
-        polonius_input,
+        mut polonius_input,
         polonius_output,
         opt_closure_req,
         nll_errors,
@@ -266,6 +273,7 @@ fn do_mir_borrowck<'tcx>(
         &mdpe.move_data,
         &borrow_set,
         &upvars,
+        generate_polonius_facts,
         use_polonius,
     );
 
@@ -273,6 +281,14 @@ fn do_mir_borrowck<'tcx>(
     // write unit-tests, as well as helping with debugging.
     nll::dump_mir_results(infcx, &body, &regioncx, &opt_closure_req);
 
+    // In the hybrid mode, the borrow conflicts found by NLL are checked with Polonius, from the
+    // facts that have been generated along with the region constraints.
+    let hybrid_polonius = if polonius == Polonius::Hybrid && consumer_options.is_none() {
+        polonius_input.take().map(HybridPolonius::new)
+    } else {
+        None
+    };
+
     // We also have a `#[rustc_regions]` annotation that causes us to dump
     // information.
     nll::dump_annotation(
@@ -341,6 +357,7 @@ fn do_mir_borrowck<'tcx>(
                 region_names: RefCell::default(),
                 next_region_name: RefCell::new(1),
                 polonius_output: None,
+                hybrid_polonius: None,
                 errors,
             };
             promoted_mbcx.report_move_errors(move_errors);
@@ -370,6 +387,7 @@ fn do_mir_borrowck<'tcx>(
         region_names: RefCell::default(),
         next_region_name: RefCell::new(1),
         polonius_output,
+        hybrid_polonius,
         errors,
     };
 
@@ -448,13 +466,14 @@ fn do_mir_borrowck<'tcx>(
         tainted_by_errors,
     };
 
-    let body_with_facts = if return_body_with_facts {
-        let output_facts = mbcx.polonius_output.expect("Polonius output was not computed");
+    let body_with_facts = if consumer_options.is_some() {
         Some(Box::new(BodyWithBorrowckFacts {
             body: body_owned,
-            input_facts: *polonius_input.expect("Polonius input facts were not generated"),
-            output_facts,
+            borrow_set,
+            region_inference_context: regioncx,
             location_table: location_table_owned,
+            input_facts: polonius_input,
+            output_facts: mbcx.polonius_output,
         }))
     } else {
         None
@@ -469,16 +488,21 @@ fn do_mir_borrowck<'tcx>(
 /// intended to be consumed by compiler consumers.
 ///
 /// We need to include the MIR body here because the region identifiers must
-/// match the ones in the Polonius facts.
+/// match the ones in the region inference results and the Polonius facts.
 pub struct BodyWithBorrowckFacts<'tcx> {
     /// A mir body that contains region identifiers.
     pub body: Body<'tcx>,
-    /// Polonius input facts.
-    pub input_facts: AllFacts,
-    /// Polonius output facts.
-    pub output_facts: Rc<self::nll::PoloniusOutput>,
+    /// The borrows of the body, with the activations of two-phase borrows.
+    pub borrow_set: Rc<BorrowSet<'tcx>>,
+    /// The results of region inference: the value of every region of the body.
+    pub region_inference_context: Rc<RegionInferenceContext<'tcx>>,
     /// The table that maps Polonius points to locations in the table.
     pub location_table: LocationTable,
+    /// Polonius input facts, if requested with [`ConsumerOptions::PoloniusInputFacts`] or
+    /// [`ConsumerOptions::PoloniusOutputFacts`].
+    pub input_facts: Option<Box<AllFacts>>,
+    /// Polonius output facts, if requested with [`ConsumerOptions::PoloniusOutputFacts`].
+    pub output_facts: Option<Rc<self::nll::PoloniusOutput>>,
 }
 
 struct MirBorrowckCtxt<'cx, 'tcx> {
@@ -552,6 +576,10 @@ struct MirBorrowckCtxt<'cx, 'tcx> {
     /// Results of Polonius analysis.
     polonius_output: Option<Rc<PoloniusOutput>>,
 
+    /// The Polonius analyses that check the borrow conflicts found by NLL, with
+    /// `-Z polonius=hybrid`.
+    hybrid_polonius: Option<HybridPolonius>,
+
     errors: error::BorrowckErrors<'tcx>,
 }
 
@@ -982,6 +1010,14 @@ fn access_place(
         }
     }
 
+    /// Returns whether the conflict with `borrow` that NLL found at `location` is an error. With
+    /// `-Z polonius=hybrid`, it is checked with Polonius, otherwise NLL is trusted.
+    fn polonius_confirms_conflict(&self, location: Location, borrow: BorrowIndex) -> bool {
+        self.hybrid_polonius.as_ref().map_or(true, |polonius| {
+            polonius.confirms_conflict(self.infcx.tcx, self.location_table, location, borrow)
+        })
+    }
+
     #[instrument(level = "debug", skip(self, flow_state))]
     fn check_access_for_conflict(
         &mut self,
@@ -1056,6 +1092,10 @@ fn check_access_for_conflict(
                         return Control::Continue;
                     }
 
+                    if !this.polonius_confirms_conflict(location, borrow_index) {
+                        return Control::Continue;
+                    }
+
                     error_reported = true;
                     match kind {
                         ReadKind::Copy => {
@@ -1073,6 +1113,10 @@ fn check_access_for_conflict(
                 }
 
                 (Reservation(kind) | Activation(kind, _) | Write(kind), _) => {
+                    if !this.polonius_confirms_conflict(location, borrow_index) {
+                        return Control::Continue;
+                    }
+
                     match rw {
                         Reservation(..) => {
                             debug!(
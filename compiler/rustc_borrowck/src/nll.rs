@@ -155,6 +155,9 @@ fn populate_polonius_move_facts(
 
 /// Computes the (non-lexical) regions from the input MIR.
 ///
+/// The Polonius facts are generated if `polonius_input` is true, or if they are needed anyway, and
+/// the Polonius analysis is computed if `polonius_output` is true.
+///
 /// This may result in errors being reported.
 pub(crate) fn compute_regions<'cx, 'tcx>(
     infcx: &InferCtxt<'tcx>,
@@ -167,10 +170,11 @@ pub(crate) fn compute_regions<'cx, 'tcx>(
     move_data: &MoveData<'tcx>,
     borrow_set: &BorrowSet<'tcx>,
     upvars: &[Upvar<'tcx>],
-    use_polonius: bool,
+    polonius_input: bool,
+    polonius_output: bool,
 ) -> NllOutput<'tcx> {
-    let mut all_facts =
-        (use_polonius || AllFacts::enabled(infcx.tcx)).then_some(AllFacts::default());
+    let polonius_input = polonius_input || polonius_output || AllFacts::enabled(infcx.tcx);
+    let mut all_facts = polonius_input.then_some(AllFacts::default());
 
     let universal_regions = Rc::new(universal_regions);
 
@@ -191,7 +195,7 @@ pub(crate) fn compute_regions<'cx, 'tcx>(
             move_data,
             elements,
             upvars,
-            use_polonius,
+            polonius_input,
         );
 
     if let Some(all_facts) = &mut all_facts {
@@ -285,7 +289,7 @@ pub(crate) fn compute_regions<'cx, 'tcx>(
             all_facts.write_to_dir(dir_path, location_table).unwrap();
         }
 
-        if use_polonius {
+        if polonius_output {
             let algorithm =
                 env::var("POLONIUS_ALGORITHM").unwrap_or_else(|_| String::from("Hybrid"));
             let algorithm = Algorithm::from_str(&algorithm).unwrap();
@@ -0,0 +1,59 @@
+#![deny(rustc::untranslatable_diagnostic)]
+#![deny(rustc::diagnostic_outside_of_impl)]
+//! Borrow checking in the `-Z polonius=hybrid` mode.
+//!
+//! NLL is precise enough for almost all programs, and much cheaper than Polonius. So in this mode,
+//! bodies are borrow checked with NLL as usual, and only the borrow conflicts that NLL reports are
+//! checked with Polonius, to accept programs like the conditional return of a borrow (NLL problem
+//! case #3). The location-insensitive analysis of Polonius is cheap and rules out most conflicts.
+//! The full analysis is only computed for a body if some conflict remains after that.
+
+use polonius_engine::{Algorithm, Output};
+use rustc_middle::mir::Location;
+use rustc_middle::ty::TyCtxt;
+use std::cell::OnceCell;
+
+use crate::dataflow::BorrowIndex;
+use crate::facts::AllFacts;
+use crate::location::LocationTable;
+use crate::nll::PoloniusOutput;
+
+pub(crate) struct HybridPolonius {
+    facts: Box<AllFacts>,
+    location_insensitive: OnceCell<PoloniusOutput>,
+    full: OnceCell<PoloniusOutput>,
+}
+
+impl HybridPolonius {
+    pub(crate) fn new(facts: Box<AllFacts>) -> Self {
+        HybridPolonius { facts, location_insensitive: OnceCell::new(), full: OnceCell::new() }
+    }
+
+    /// Returns whether `borrow` is still live at `location` according to Polonius, where NLL
+    /// reports that an access conflicts with it.
+    pub(crate) fn confirms_conflict(
+        &self,
+        tcx: TyCtxt<'_>,
+        location_table: &LocationTable,
+        location: Location,
+        borrow: BorrowIndex,
+    ) -> bool {
+        let point = location_table.start_index(location);
+
+        // The errors of the location-insensitive analysis are a superset of the errors of the full
+        // analysis.
+        let location_insensitive = self.location_insensitive.get_or_init(|| {
+            let _prof_timer = tcx.prof.generic_activity("polonius_location_insensitive_analysis");
+            Output::compute(&self.facts, Algorithm::LocationInsensitive, false)
+        });
+        if !location_insensitive.errors_at(point).contains(&borrow) {
+            return false;
+        }
+
+        let full = self.full.get_or_init(|| {
+            let _prof_timer = tcx.prof.generic_activity("polonius_analysis");
+            Output::compute(&self.facts, Algorithm::Opt, false)
+        });
+        full.errors_at(point).contains(&borrow)
+    }
+}
@@ -19,6 +19,7 @@
 use rustc_middle::traits::ObligationCause;
 use rustc_middle::traits::ObligationCauseCode;
 use rustc_middle::ty::{self, RegionVid, Ty, TyCtxt, TypeFoldable, TypeVisitable};
+use rustc_session::config::Polonius;
 use rustc_span::Span;
 
 use crate::{
@@ -521,8 +522,14 @@ pub(crate) fn region_contains(&self, r: impl ToRegionVid, p: impl ToElementIndex
         self.scc_values.contains(scc, p)
     }
 
+    /// Returns whether the value of the region `r` contains `location`, for the consumers of the
+    /// borrow checker.
+    pub fn region_contains_location(&self, r: RegionVid, location: Location) -> bool {
+        self.region_contains(r, location)
+    }
+
     /// Returns access to the value of `r` for debugging purposes.
-    pub(crate) fn region_value_str(&self, r: RegionVid) -> String {
+    pub fn region_value_str(&self, r: RegionVid) -> String {
         let scc = self.constraint_sccs.scc(r.to_region_vid());
         self.scc_values.region_value_str(scc)
     }
@@ -590,7 +597,7 @@ pub(super) fn solve(
         // In Polonius mode, the errors about missing universal region relations are in the output
         // and need to be emitted or propagated. Otherwise, we need to check whether the
         // constraints were too strong, and if so, emit or propagate those errors.
-        if infcx.tcx.sess.opts.unstable_opts.polonius {
+        if infcx.tcx.sess.opts.unstable_opts.polonius == Polonius::Full {
             self.check_polonius_subset_errors(
                 outlives_requirements.as_mut(),
                 &mut errors_buffer,
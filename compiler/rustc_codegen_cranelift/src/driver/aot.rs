@@ -277,6 +277,7 @@ fn module_codegen(
             match mono_item {
                 MonoItem::Fn(inst) => {
                     tcx.sess.time("codegen fn", || {
+                        let self_time = tcx.prof.self_time();
                         let codegened_function = crate::base::codegen_fn(
                             tcx,
                             &mut cx,
@@ -284,6 +285,7 @@ fn module_codegen(
                             &mut module,
                             inst,
                         );
+                        tcx.record_codegen_self_time(inst.def_id(), self_time);
                         codegened_functions.push(codegened_function);
                     });
                 }
@@ -43,10 +43,6 @@ pub fn crates_export_threshold(crate_types: &[CrateType]) -> SymbolExportLevel {
 fn reachable_non_generics_provider(tcx: TyCtxt<'_>, cnum: CrateNum) -> DefIdMap<SymbolExportInfo> {
     assert_eq!(cnum, LOCAL_CRATE);
 
-    if !tcx.sess.opts.output_types.should_codegen() {
-        return Default::default();
-    }
-
     // Check to see if this crate is a "special runtime crate". These
     // crates, implementation details of the standard library, typically
     // have a bunch of `pub extern` and `#[no_mangle]` functions as the
@@ -169,10 +165,6 @@ fn exported_symbols_provider_local(
 ) -> &[(ExportedSymbol<'_>, SymbolExportInfo)] {
     assert_eq!(cnum, LOCAL_CRATE);
 
-    if !tcx.sess.opts.output_types.should_codegen() {
-        return &[];
-    }
-
     // FIXME: Sorting this is unnecessary since we are sorting later anyway.
     //        Can we skip the later sorting?
     let mut symbols: Vec<_> = tcx.with_stable_hashing_context(|hcx| {
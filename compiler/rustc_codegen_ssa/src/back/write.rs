@@ -487,6 +487,7 @@ pub fn start_async_codegen<B: ExtraBackendMethods>(
             phantom: PhantomData,
         },
         output_filenames: tcx.output_filenames(()).clone(),
+        retained_work_products: Default::default(),
     }
 }
 
@@ -1871,6 +1872,9 @@ pub struct OngoingCodegen<B: ExtraBackendMethods> {
     pub shared_emitter_main: SharedEmitterMain,
     pub output_filenames: Arc<OutputFilenames>,
     pub coordinator: Coordinator<B>,
+    /// The work products of the previous session that are kept without being codegened
+    /// again, by a session that doesn't codegen at all.
+    pub retained_work_products: FxHashMap<WorkProductId, WorkProduct>,
 }
 
 impl<B: ExtraBackendMethods> OngoingCodegen<B> {
@@ -1893,8 +1897,9 @@ pub fn join(self, sess: &Session) -> (CodegenResults, FxHashMap<WorkProductId, W
 
         sess.abort_if_errors();
 
-        let work_products =
+        let mut work_products =
             copy_all_cgu_workproducts_to_incr_comp_cache_dir(sess, &compiled_modules);
+        work_products.extend(self.retained_work_products);
         produce_final_output_artifacts(sess, &compiled_modules, &self.output_filenames);
 
         // FIXME: time_llvm_passes support - does this use a global context or
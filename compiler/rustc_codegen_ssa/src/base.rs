@@ -25,6 +25,7 @@
 use rustc_hir::lang_items::LangItem;
 use rustc_index::vec::Idx;
 use rustc_metadata::EncodedMetadata;
+use rustc_middle::dep_graph::{make_compile_codegen_unit, WorkProduct, WorkProductId};
 use rustc_middle::middle::codegen_fn_attrs::CodegenFnAttrs;
 use rustc_middle::middle::exported_symbols;
 use rustc_middle::middle::exported_symbols::SymbolExportKind;
@@ -553,7 +554,8 @@ pub fn codegen_crate<B: ExtraBackendMethods>(
 ) -> OngoingCodegen<B> {
     // Skip crate items and just output metadata in -Z no-codegen mode.
     if tcx.sess.opts.unstable_opts.no_codegen || !tcx.sess.opts.output_types.should_codegen() {
-        let ongoing_codegen = start_async_codegen(backend, tcx, target_cpu, metadata, None, 1);
+        let mut ongoing_codegen = start_async_codegen(backend, tcx, target_cpu, metadata, None, 1);
+        ongoing_codegen.retained_work_products = retained_work_products(tcx);
 
         ongoing_codegen.codegen_finished(tcx);
 
@@ -672,9 +674,10 @@ pub fn codegen_crate<B: ExtraBackendMethods>(
     };
 
     // Calculate the CGU reuse
-    let cgu_reuse = tcx.sess.time("find_cgu_reuse", || {
-        codegen_units.iter().map(|cgu| determine_cgu_reuse(tcx, &cgu)).collect::<Vec<_>>()
-    });
+    let (cgu_reuse, mut shared_work_products): (Vec<_>, Vec<_>) =
+        tcx.sess.time("find_cgu_reuse", || {
+            codegen_units.iter().map(|cgu| determine_cgu_reuse(tcx, &cgu)).unzip()
+        });
 
     let mut total_codegen_time = Duration::new(0, 0);
     let start_rss = tcx.sess.opts.unstable_opts.time_passes.then(|| get_resident_set_size());
@@ -754,7 +757,9 @@ pub fn codegen_crate<B: ExtraBackendMethods>(
                     &ongoing_codegen.coordinator.sender,
                     CachedModuleCodegen {
                         name: cgu.name().to_string(),
-                        source: cgu.previous_work_product(tcx),
+                        source: shared_work_products[i]
+                            .take()
+                            .unwrap_or_else(|| cgu.previous_work_product(tcx)),
                     },
                 );
                 true
@@ -765,7 +770,9 @@ pub fn codegen_crate<B: ExtraBackendMethods>(
                     &ongoing_codegen.coordinator.sender,
                     CachedModuleCodegen {
                         name: cgu.name().to_string(),
-                        source: cgu.previous_work_product(tcx),
+                        source: shared_work_products[i]
+                            .take()
+                            .unwrap_or_else(|| cgu.previous_work_product(tcx)),
                     },
                 );
                 true
@@ -792,19 +799,59 @@ pub fn codegen_crate<B: ExtraBackendMethods>(
     ongoing_codegen
 }
 
+/// Returns the work products of the previous session that are still up to date, so that a
+/// session without codegen keeps them for a later session that codegens again.
+///
+/// They are only kept if nothing that the analysis looked at changed. Otherwise, marking their
+/// codegen units green could force the monomorphization collector, which a session without
+/// codegen should not run.
+fn retained_work_products(tcx: TyCtxt<'_>) -> FxHashMap<WorkProductId, WorkProduct> {
+    if !tcx.dep_graph.is_fully_enabled() || !tcx.dep_graph.red_nodes().is_empty() {
+        return FxHashMap::default();
+    }
+
+    tcx.dep_graph
+        .previous_work_products()
+        .iter()
+        .filter(|(_, work_product)| {
+            let cgu_name = Symbol::intern(&work_product.cgu_name);
+            tcx.try_mark_green(&make_compile_codegen_unit(tcx, cgu_name))
+        })
+        .map(|(&id, work_product)| (id, work_product.clone()))
+        .collect()
+}
+
 impl CrateInfo {
     pub fn new(tcx: TyCtxt<'_>, target_cpu: String) -> CrateInfo {
+        // A build without codegen doesn't link, so the symbols and dependency formats are left
+        // out. The queries computing them don't check for codegen themselves, so that builds
+        // with and without codegen can share their incremental state.
+        let should_codegen = tcx.sess.opts.output_types.should_codegen();
         let exported_symbols = tcx
             .sess
             .crate_types()
             .iter()
-            .map(|&c| (c, crate::back::linker::exported_symbols(tcx, c)))
+            .map(|&c| {
+                let symbols = if should_codegen {
+                    crate::back::linker::exported_symbols(tcx, c)
+                } else {
+                    Vec::new()
+                };
+                (c, symbols)
+            })
             .collect();
         let linked_symbols = tcx
             .sess
             .crate_types()
             .iter()
-            .map(|&c| (c, crate::back::linker::linked_symbols(tcx, c)))
+            .map(|&c| {
+                let symbols = if should_codegen {
+                    crate::back::linker::linked_symbols(tcx, c)
+                } else {
+                    Vec::new()
+                };
+                (c, symbols)
+            })
             .collect();
         let local_crate_name = tcx.crate_name(LOCAL_CRATE);
         let crate_attrs = tcx.hir().attrs(rustc_hir::CRATE_HIR_ID);
@@ -855,7 +902,11 @@ pub fn new(tcx: TyCtxt<'_>, target_cpu: String) -> CrateInfo {
             crate_name: Default::default(),
             used_crates,
             used_crate_source: Default::default(),
-            dependency_formats: tcx.dependency_formats(()).clone(),
+            dependency_formats: if should_codegen {
+                tcx.dependency_formats(()).clone()
+            } else {
+                Default::default()
+            },
             windows_subsystem,
             natvis_debugger_visualizers: Default::default(),
         };
@@ -981,24 +1032,29 @@ pub fn provide(providers: &mut Providers) {
     };
 }
 
-fn determine_cgu_reuse<'tcx>(tcx: TyCtxt<'tcx>, cgu: &CodegenUnit<'tcx>) -> CguReuse {
+/// Determines whether the work product of `cgu` can be reused, either the one of the previous
+/// session or one imported from the shared incremental cache, which is returned along with it.
+fn determine_cgu_reuse<'tcx>(
+    tcx: TyCtxt<'tcx>,
+    cgu: &CodegenUnit<'tcx>,
+) -> (CguReuse, Option<WorkProduct>) {
     if !tcx.dep_graph.is_fully_enabled() {
-        return CguReuse::No;
+        return (CguReuse::No, None);
     }
 
     let work_product_id = &cgu.work_product_id();
     if tcx.dep_graph.previous_work_product(work_product_id).is_none() {
         // We don't have anything cached for this CGU. This can happen
         // if the CGU did not exist in the previous session.
-        return CguReuse::No;
+        if let Some(work_product) = rustc_incremental::import_work_product(tcx, cgu) {
+            return (reused_cgu_kind(tcx), Some(work_product));
+        }
+        rustc_incremental::explain_cgu_recompilation(tcx, cgu.name(), None);
+        return (CguReuse::No, None);
     }
 
     // Try to mark the CGU as green. If it we can do so, it means that nothing
     // affecting the LLVM module has changed and we can re-use a cached version.
-    // If we compile with any kind of LTO, this means we can re-use the bitcode
-    // of the Pre-LTO stage (possibly also the Post-LTO version but we'll only
-    // know that later). If we are not doing LTO, there is only one optimized
-    // version of each module, so we re-use that.
     let dep_node = cgu.codegen_dep_node(tcx);
     assert!(
         !tcx.dep_graph.dep_node_exists(&dep_node),
@@ -1007,19 +1063,29 @@ fn determine_cgu_reuse<'tcx>(tcx: TyCtxt<'tcx>, cgu: &CodegenUnit<'tcx>) -> CguR
     );
 
     if tcx.try_mark_green(&dep_node) {
-        // We can re-use either the pre- or the post-thinlto state. If no LTO is
-        // being performed then we can use post-LTO artifacts, otherwise we must
-        // reuse pre-LTO artifacts
-        match compute_per_cgu_lto_type(
-            &tcx.sess.lto(),
-            &tcx.sess.opts,
-            &tcx.sess.crate_types(),
-            ModuleKind::Regular,
-        ) {
-            ComputedLtoType::No => CguReuse::PostLto,
-            _ => CguReuse::PreLto,
-        }
+        (reused_cgu_kind(tcx), None)
+    } else if let Some(work_product) = rustc_incremental::import_work_product(tcx, cgu) {
+        (reused_cgu_kind(tcx), Some(work_product))
     } else {
-        CguReuse::No
+        rustc_incremental::explain_cgu_recompilation(tcx, cgu.name(), Some(&dep_node));
+        (CguReuse::No, None)
+    }
+}
+
+/// Returns how the cached version of an up-to-date CGU is reused.
+///
+/// If we compile with any kind of LTO, we can re-use the bitcode of the
+/// Pre-LTO stage (possibly also the Post-LTO version but we'll only know
+/// that later). If we are not doing LTO, there is only one optimized version
+/// of each module, so we re-use that.
+fn reused_cgu_kind(tcx: TyCtxt<'_>) -> CguReuse {
+    match compute_per_cgu_lto_type(
+        &tcx.sess.lto(),
+        &tcx.sess.opts,
+        &tcx.sess.crate_types(),
+        ModuleKind::Regular,
+    ) {
+        ComputedLtoType::No => CguReuse::PostLto,
+        _ => CguReuse::PreLto,
     }
 }
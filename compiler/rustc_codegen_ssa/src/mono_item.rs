@@ -88,7 +88,9 @@ fn define<Bx: BuilderMethods<'a, 'tcx>>(&self, cx: &'a Bx::CodegenCx) {
                 }
             }
             MonoItem::Fn(instance) => {
+                let self_time = cx.tcx().prof.self_time();
                 base::codegen_instance::<Bx>(&cx, instance);
+                cx.tcx().record_codegen_self_time(instance.def_id(), self_time);
             }
         }
 
@@ -1,5 +1,6 @@
 use std::error::Error;
 use std::fmt;
+use std::iter;
 
 use rustc_errors::Diagnostic;
 use rustc_middle::mir::AssertKind;
@@ -59,6 +60,8 @@ pub(super) struct ConstEvalErr<'tcx> {
     pub span: Span,
     pub error: InterpError<'tcx>,
     pub stacktrace: Vec<FrameInfo<'tcx>>,
+    /// The rendered locals of each frame of `stacktrace`, if `-Zdump-const-eval-locals` is set.
+    pub frame_locals: Vec<Vec<String>>,
 }
 
 impl<'tcx> ConstEvalErr<'tcx> {
@@ -74,12 +77,33 @@ pub fn new<'mir, M: Machine<'mir, 'tcx>>(
         'tcx: 'mir,
     {
         error.print_backtrace();
-        let mut stacktrace = ecx.generate_stacktrace();
-        // Filter out `requires_caller_location` frames.
-        stacktrace.retain(|frame| !frame.instance.def.requires_caller_location(*ecx.tcx));
+        let dump_locals = ecx.tcx.sess.opts.unstable_opts.dump_const_eval_locals;
+        let mut stacktrace = Vec::new();
+        let mut frame_locals = Vec::new();
+        for (frame_info, frame) in iter::zip(ecx.generate_stacktrace(), ecx.stack().iter().rev()) {
+            // Filter out `requires_caller_location` frames.
+            if frame_info.instance.def.requires_caller_location(*ecx.tcx) {
+                continue;
+            }
+            if dump_locals {
+                frame_locals.push(ecx.dump_frame_locals(frame));
+            }
+            stacktrace.push(frame_info);
+        }
         // If `span` is missing, use topmost remaining frame, or else the "root" span from `ecx.tcx`.
         let span = span.or_else(|| stacktrace.first().map(|f| f.span)).unwrap_or(ecx.tcx.span);
-        ConstEvalErr { error: error.into_kind(), stacktrace, span }
+        ConstEvalErr { error: error.into_kind(), stacktrace, frame_locals, span }
+    }
+
+    /// Appends the locals of the frame at `index` of the stacktrace to `line`, if
+    /// `-Zdump-const-eval-locals` is set and the frame has any.
+    fn with_locals(&self, line: &str, index: usize) -> String {
+        match self.frame_locals.get(index) {
+            Some(locals) if !locals.is_empty() => {
+                format!("{line}, with locals:\n{}", locals.join("\n"))
+            }
+            _ => line.to_string(),
+        }
     }
 
     pub(super) fn report(&self, tcx: TyCtxtAt<'tcx>, message: &str) -> ErrorHandled {
@@ -103,14 +127,15 @@ pub(super) fn decorate(&self, err: &mut Diagnostic, decorate: impl FnOnce(&mut D
         }
         // Add spans for the stacktrace. Don't print a single-line backtrace though.
         if self.stacktrace.len() > 1 {
-            // Helper closure to print duplicated lines.
+            // Helper closure to print duplicated lines. `last_frame` also has the index of the
+            // first of the duplicated frames, so that their locals can be shown.
             let mut flush_last_line = |last_frame, times| {
-                if let Some((line, span)) = last_frame {
-                    err.span_note(span, &line);
+                if let Some(((line, span), index)) = last_frame {
+                    err.span_note(span, self.with_locals(&line, index));
                     // Don't print [... additional calls ...] if the number of lines is small
                     if times < 3 {
-                        for _ in 0..times {
-                            err.span_note(span, &line);
+                        for i in 1..=times {
+                            err.span_note(span, self.with_locals(&line, index + i));
                         }
                     } else {
                         err.span_note(
@@ -123,17 +148,22 @@ pub(super) fn decorate(&self, err: &mut Diagnostic, decorate: impl FnOnce(&mut D
 
             let mut last_frame = None;
             let mut times = 0;
-            for frame_info in &self.stacktrace {
+            for (index, frame_info) in self.stacktrace.iter().enumerate() {
                 let frame = (frame_info.to_string(), frame_info.span);
-                if last_frame.as_ref() == Some(&frame) {
+                if last_frame.as_ref().map(|(last, _)| last) == Some(&frame) {
                     times += 1;
                 } else {
                     flush_last_line(last_frame, times);
-                    last_frame = Some(frame);
+                    last_frame = Some((frame, index));
                     times = 0;
                 }
             }
             flush_last_line(last_frame, times);
+        } else if let Some(frame_info) = self.stacktrace.first()
+            && self.frame_locals.first().is_some_and(|locals| !locals.is_empty())
+        {
+            // There is no note for the only frame to show its locals with.
+            err.note(self.with_locals(&format!("locals {frame_info}"), 0));
         }
         // Let the caller attach any additional information it wants.
         decorate(err);
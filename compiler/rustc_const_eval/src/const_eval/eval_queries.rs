@@ -253,7 +253,12 @@ pub fn eval_to_const_value_raw_provider<'tcx>(
         };
         return eval_nullary_intrinsic(tcx, key.param_env, def_id, substs).map_err(|error| {
             let span = tcx.def_span(def_id);
-            let error = ConstEvalErr { error: error.into_kind(), stacktrace: vec![], span };
+            let error = ConstEvalErr {
+                error: error.into_kind(),
+                stacktrace: vec![],
+                frame_locals: vec![],
+                span,
+            };
             error.report(tcx.at(span), "could not evaluate nullary intrinsic")
         });
     }
@@ -316,8 +321,16 @@ pub fn eval_to_allocation_raw_provider<'tcx>(
         ),
     );
 
+    if tcx.sess.opts.unstable_opts.const_eval_profile {
+        ecx.machine.profiler = Some(Default::default());
+    }
+
     let res = ecx.load_mir(cid.instance.def, cid.promoted);
-    match res.and_then(|body| eval_body_using_ecx(&mut ecx, cid, &body)) {
+    let res = res.and_then(|body| eval_body_using_ecx(&mut ecx, cid, &body));
+    if let Some(profiler) = ecx.machine.profiler.take() {
+        profiler.finish(tcx, def.did);
+    }
+    match res {
         Err(error) => {
             let err = ConstEvalErr::new(&ecx, error, None);
             let msg = if is_static {
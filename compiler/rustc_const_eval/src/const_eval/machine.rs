@@ -4,7 +4,7 @@
 use rustc_middle::mir::interpret::PointerArithmetic;
 use rustc_middle::ty::layout::FnAbiOf;
 use rustc_middle::ty::{self, Ty, TyCtxt};
-use rustc_session::lint::builtin::INVALID_ALIGNMENT;
+use rustc_session::lint::builtin::{INVALID_ALIGNMENT, LONG_RUNNING_CONST_EVAL};
 use std::borrow::Borrow;
 use std::hash::Hash;
 use std::ops::ControlFlow;
@@ -21,12 +21,21 @@
 use rustc_target::abi::{Align, Size};
 use rustc_target::spec::abi::Abi as CallAbi;
 
+use crate::errors::LongRunningEval;
 use crate::interpret::{
     self, compile_time_machine, AllocId, ConstAllocation, FnVal, Frame, ImmTy, InterpCx,
-    InterpResult, OpTy, PlaceTy, Pointer, Scalar, StackPopUnwind,
+    InterpResult, OpTy, PlaceTy, Pointer, Scalar, StackPopJump, StackPopUnwind,
 };
 
 use super::error::*;
+use super::profile::EvalProfiler;
+
+/// The number of steps after which an evaluation is first reported by the
+/// `long_running_const_eval` lint if `#![const_eval_limit]` is disabled. Otherwise it is first
+/// reported after a quarter of the limit, but not before this many steps either, so that crates
+/// that lower the limit to fail early aren't warned about short evaluations. It is reported
+/// again each time the number of steps doubles.
+const MIN_LONG_RUNNING_EVAL_STEPS: usize = 500_000;
 
 /// Extra machine state for CTFE, and the Machine instance
 pub struct CompileTimeInterpreter<'mir, 'tcx> {
@@ -36,6 +45,15 @@ pub struct CompileTimeInterpreter<'mir, 'tcx> {
     /// Setting this to `0` disables the limit and allows the interpreter to run forever.
     pub(super) steps_remaining: usize,
 
+    /// The number of terminators evaluated so far.
+    steps_taken: usize,
+
+    /// The number of steps at which the evaluation is next reported as long-running.
+    next_long_running_report: usize,
+
+    /// Gathers the cost of the evaluation with `-Z const-eval-profile`.
+    pub(super) profiler: Option<Box<EvalProfiler>>,
+
     /// The virtual call stack.
     pub(super) stack: Vec<Frame<'mir, 'tcx, AllocId, ()>>,
 
@@ -79,6 +97,9 @@ pub(crate) fn new(
     ) -> Self {
         CompileTimeInterpreter {
             steps_remaining: const_eval_limit.0,
+            steps_taken: 0,
+            next_long_running_report: (const_eval_limit.0 / 4).max(MIN_LONG_RUNNING_EVAL_STEPS),
+            profiler: None,
             stack: Vec::new(),
             can_access_statics,
             check_alignment,
@@ -562,14 +583,29 @@ fn binary_ptr_op(
     }
 
     fn before_terminator(ecx: &mut InterpCx<'mir, 'tcx, Self>) -> InterpResult<'tcx> {
-        // The step limit has already been hit in a previous call to `before_terminator`.
-        if ecx.machine.steps_remaining == 0 {
-            return Ok(());
+        ecx.machine.steps_taken += 1;
+        if let Some(profiler) = &mut ecx.machine.profiler {
+            profiler.step();
+        }
+        // A limit of `0` disables it, and it can't be reached again once it has been.
+        if ecx.machine.steps_remaining != 0 {
+            ecx.machine.steps_remaining -= 1;
+            if ecx.machine.steps_remaining == 0 {
+                throw_exhaust!(StepLimitReached)
+            }
         }
 
-        ecx.machine.steps_remaining -= 1;
-        if ecx.machine.steps_remaining == 0 {
-            throw_exhaust!(StepLimitReached)
+        // An evaluation that has just reached the limit is reported by the error instead.
+        if ecx.machine.steps_taken == ecx.machine.next_long_running_report {
+            ecx.machine.next_long_running_report = ecx.machine.steps_taken.saturating_mul(2);
+            let lint_root =
+                ecx.stack().iter().find_map(|frame| frame.lint_root()).unwrap_or(CRATE_HIR_ID);
+            ecx.tcx.emit_spanned_lint(
+                LONG_RUNNING_CONST_EVAL,
+                lint_root,
+                ecx.tcx.span,
+                LongRunningEval { steps: ecx.machine.steps_taken },
+            );
         }
 
         Ok(())
@@ -597,6 +633,38 @@ fn init_frame_extra(
         }
     }
 
+    fn after_stack_push(ecx: &mut InterpCx<'mir, 'tcx, Self>) -> InterpResult<'tcx> {
+        if ecx.machine.profiler.is_some() {
+            // The first frame is the body of the item being evaluated.
+            let function = (ecx.stack().len() > 1).then(|| ecx.frame().instance.def_id());
+            ecx.machine.profiler.as_mut().unwrap().push_frame(function);
+        }
+        Ok(())
+    }
+
+    fn after_stack_pop(
+        ecx: &mut InterpCx<'mir, 'tcx, Self>,
+        _frame: Frame<'mir, 'tcx>,
+        unwinding: bool,
+    ) -> InterpResult<'tcx, StackPopJump> {
+        // CTFE does not unwind, panics are reported as errors.
+        assert!(!unwinding);
+        if let Some(profiler) = &mut ecx.machine.profiler {
+            profiler.pop_frame();
+        }
+        Ok(StackPopJump::Normal)
+    }
+
+    fn after_memory_allocation(
+        ecx: &mut InterpCx<'mir, 'tcx, Self>,
+        size: Size,
+        _kind: interpret::MemoryKind<MemoryKind>,
+    ) {
+        if let Some(profiler) = &mut ecx.machine.profiler {
+            profiler.allocation(size);
+        }
+    }
+
     #[inline(always)]
     fn stack<'a>(
         ecx: &'a InterpCx<'mir, 'tcx, Self>,
@@ -0,0 +1,94 @@
+//! Gathers the cost of a single const evaluation for `-Z const-eval-profile`.
+
+use rustc_data_structures::fx::FxHashMap;
+use rustc_hir::def_id::DefId;
+use rustc_middle::ty::print::with_no_trimmed_paths;
+use rustc_middle::ty::TyCtxt;
+use rustc_session::{ConstCallProfile, ConstEvalCost, ConstFnProfile};
+use rustc_target::abi::Size;
+
+#[derive(Default)]
+pub(crate) struct EvalProfiler {
+    /// The cost of the whole evaluation.
+    total: ConstEvalCost,
+    fns: FxHashMap<DefId, ConstFnProfile>,
+    /// Keyed by the caller, or `None` for the body of the item, and the callee.
+    calls: FxHashMap<(Option<DefId>, DefId), ConstCallProfile>,
+    /// Mirrors the stack of the interpreter: the function of each frame, or `None` for the
+    /// body of the item being evaluated, and the steps of the evaluation when it was pushed.
+    stack: Vec<(Option<DefId>, u64)>,
+}
+
+impl EvalProfiler {
+    pub(crate) fn push_frame(&mut self, function: Option<DefId>) {
+        if let Some(function) = function {
+            self.fns.entry(function).or_default().calls += 1;
+            let caller = self.caller();
+            self.calls.entry((caller, function)).or_default().calls += 1;
+        }
+        self.stack.push((function, self.total.steps));
+    }
+
+    pub(crate) fn pop_frame(&mut self) {
+        let (function, steps_at_push) = self.stack.pop().unwrap();
+        let Some(function) = function else { return };
+        let steps = self.total.steps - steps_at_push;
+        // Recursive calls are already included in the outermost call.
+        if !self.stack.iter().any(|&(caller, _)| caller == Some(function)) {
+            self.fns.get_mut(&function).unwrap().total_steps += steps;
+        }
+        let caller = self.caller();
+        if !self
+            .stack
+            .windows(2)
+            .any(|frames| frames[0].0 == caller && frames[1].0 == Some(function))
+        {
+            self.calls.get_mut(&(caller, function)).unwrap().total_steps += steps;
+        }
+    }
+
+    /// The function of the frame on top of the stack, or `None` for the body of the item.
+    fn caller(&self) -> Option<DefId> {
+        self.stack.last().and_then(|&(function, _)| function)
+    }
+
+    fn current_cost(&mut self) -> Option<&mut ConstEvalCost> {
+        let function = self.stack.last()?.0?;
+        Some(&mut self.fns.get_mut(&function).unwrap().self_cost)
+    }
+
+    pub(crate) fn step(&mut self) {
+        self.total.steps += 1;
+        if let Some(cost) = self.current_cost() {
+            cost.steps += 1;
+        }
+    }
+
+    pub(crate) fn allocation(&mut self, size: Size) {
+        let cost = ConstEvalCost { steps: 0, allocations: 1, allocated_bytes: size.bytes() };
+        self.total += cost;
+        if let Some(cost_of_fn) = self.current_cost() {
+            *cost_of_fn += cost;
+        }
+    }
+
+    /// Adds the cost of the evaluation of `item` to the profile of the session. The evaluation
+    /// may have been stopped by an error, in which case frames are left on the stack.
+    pub(crate) fn finish(mut self, tcx: TyCtxt<'_>, item: DefId) {
+        while !self.stack.is_empty() {
+            self.pop_frame();
+        }
+        let profile = &tcx.sess.const_eval_profile;
+        with_no_trimmed_paths!({
+            let item = tcx.def_path_str(item);
+            for ((caller, callee), call_profile) in self.calls {
+                let caller = caller.map_or_else(|| item.clone(), |caller| tcx.def_path_str(caller));
+                profile.record_call(caller, tcx.def_path_str(callee), call_profile);
+            }
+            profile.record_item(item, self.total);
+            for (function, fn_profile) in self.fns {
+                profile.record_fn(tcx.def_path_str(function), fn_profile);
+            }
+        });
+    }
+}
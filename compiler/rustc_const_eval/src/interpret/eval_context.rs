@@ -955,6 +955,33 @@ pub fn dump_place(&self, place: Place<M::Provenance>) -> PlacePrinter<'_, 'mir,
         PlacePrinter { ecx: self, place }
     }
 
+    /// Renders the user variables of `frame` as `name = value` lines, using `render_operand`.
+    /// Variables whose storage is not live at the current point of execution are rendered as
+    /// `<dead>`.
+    #[must_use]
+    pub fn dump_frame_locals(
+        &self,
+        frame: &Frame<'mir, 'tcx, M::Provenance, M::FrameExtra>,
+    ) -> Vec<String> {
+        frame
+            .body
+            .var_debug_info
+            .iter()
+            .filter_map(|var| {
+                let mir::VarDebugInfoContents::Place(place) = var.value else { return None };
+                let local = place.as_local()?;
+                let value = match frame.locals[local].value {
+                    LocalValue::Dead => "<dead>".to_string(),
+                    LocalValue::Live(_) => match self.local_to_op(frame, local, None) {
+                        Ok(op) => self.render_operand(&op),
+                        Err(_) => "<uninit>".to_string(),
+                    },
+                };
+                Some(format!("{} = {}", var.name, value))
+            })
+            .collect()
+    }
+
     #[must_use]
     pub fn generate_stacktrace_from_stack(
         stack: &[Frame<'mir, 'tcx, M::Provenance, M::FrameExtra>],
@@ -20,6 +20,7 @@
 use rustc_hir as hir;
 use rustc_middle::mir::interpret::InterpResult;
 use rustc_middle::ty::{self, layout::TyAndLayout, Ty};
+use rustc_span::sym;
 
 use rustc_ast::Mutability;
 
@@ -28,6 +29,7 @@
     ValueVisitor,
 };
 use crate::const_eval;
+use crate::errors::OwnsHeapErr;
 
 pub trait CompileTimeMachine<'mir, 'tcx, T> = Machine<
     'mir,
@@ -55,6 +57,11 @@ struct InternVisitor<'rt, 'mir, 'tcx, M: CompileTimeMachine<'mir, 'tcx, const_ev
     /// This field stores whether we are *currently* inside an `UnsafeCell`. This can affect
     /// the intern mode of references we encounter.
     inside_unsafe_cell: bool,
+    /// Whether we are looking at the final value itself, or at memory that it owns through a
+    /// `Box`, rather than at memory behind a reference.
+    by_value: bool,
+    /// Set when we find a `Box` whose heap allocation could be freed at runtime.
+    owns_heap: &'rt mut bool,
 }
 
 #[derive(Copy, Clone, Debug, PartialEq, Hash, Eq)]
@@ -151,6 +158,49 @@ fn intern_shallow(
     ) -> Option<IsStaticOrFn> {
         intern_shallow(self.ecx, self.leftover_allocations, alloc_id, mode, ty)
     }
+
+    /// Returns the mode to intern the memory that a `Box` or a `Unique` in the current place
+    /// owns with, and notes if that is heap memory that could be freed.
+    fn owned_memory_mode(&mut self, alloc_id: AllocId) -> InternMode {
+        let is_heap = matches!(
+            self.ecx.memory.alloc_map.get(alloc_id),
+            Some((MemoryKind::Machine(const_eval::MemoryKind::Heap), _))
+        );
+        // The allocation is freed when its owner is dropped, which happens at every use of a
+        // constant, or when the owner can be replaced through a `static mut` or an `UnsafeCell`.
+        // Only an owner behind a shared reference is never dropped.
+        if is_heap
+            && (self.by_value
+                || self.inside_unsafe_cell
+                || self.mode == InternMode::Static(Mutability::Mut))
+        {
+            *self.owns_heap = true;
+        }
+        match self.mode {
+            InternMode::Static(_) if self.inside_unsafe_cell => InternMode::Static(Mutability::Mut),
+            mode => mode,
+        }
+    }
+
+    /// Interns the memory that a `Unique<T>` points to, like the buffer of a `Vec` or `String`,
+    /// according to `T`. Unlike behind a `Box`, the number of initialized elements isn't known
+    /// here, so the memory isn't walked, and the pointers in it are left to the untyped interning
+    /// of the leftover allocations.
+    fn visit_unique(&mut self, unique: &MPlaceTy<'tcx>, elem_ty: Ty<'tcx>) -> InterpResult<'tcx> {
+        let ptr = self.ecx.read_pointer(&(*unique).into())?;
+        let Some(alloc_id) = ptr.provenance else {
+            // Dangling, e.g. the buffer of an empty `Vec`.
+            return Ok(());
+        };
+        let mode = self.owned_memory_mode(alloc_id);
+        // Validation doesn't look at the elements either, so elements with interior mutability
+        // are left to the leftover allocations too, which rejects them in constants.
+        if mode == InternMode::Const && !elem_ty.is_freeze(*self.ecx.tcx, self.ecx.param_env) {
+            return Ok(());
+        }
+        self.intern_shallow(alloc_id, mode, Some(elem_ty));
+        Ok(())
+    }
 }
 
 impl<'rt, 'mir, 'tcx: 'mir, M: CompileTimeMachine<'mir, 'tcx, const_eval::MemoryKind>>
@@ -232,9 +282,25 @@ fn visit_aggregate(
         self.walk_aggregate(mplace, fields)
     }
 
+    fn visit_box(&mut self, ptr: &MPlaceTy<'tcx>) -> InterpResult<'tcx> {
+        // Heap memory that a `Box` points to is part of the value of the `Box`, so we intern it
+        // with the mode of the place the `Box` is in, and then walk it like any other field.
+        let value = self.ecx.read_immediate(&(*ptr).into())?;
+        let mplace = self.ecx.ref_to_mplace(&value)?;
+        let Some(alloc_id) = mplace.ptr.provenance else {
+            // Dangling boxes, e.g. of zero-sized types, are left to validation.
+            return Ok(());
+        };
+        let mode = self.owned_memory_mode(alloc_id);
+        match self.intern_shallow(alloc_id, mode, Some(mplace.layout.ty)) {
+            Some(IsStaticOrFn) => Ok(()),
+            None => self.visit_value(&mplace),
+        }
+    }
+
     fn visit_value(&mut self, mplace: &MPlaceTy<'tcx>) -> InterpResult<'tcx> {
         // Handle Reference types, as these are the only types with provenance supported by const eval.
-        // Raw pointers (and boxes) are handled by the `leftover_allocations` logic.
+        // Raw pointers are handled by the `leftover_allocations` logic, boxes by `visit_box`.
         let tcx = self.ecx.tcx;
         let ty = mplace.layout.ty;
         if let ty::Ref(_, referenced_ty, ref_mutability) = *ty.kind() {
@@ -313,6 +379,11 @@ fn visit_value(&mut self, mplace: &MPlaceTy<'tcx>) -> InterpResult<'tcx> {
                 }
             }
             Ok(())
+        } else if let ty::Adt(def, substs) = *ty.kind()
+            && tcx.is_diagnostic_item(sym::ptr_unique, def.did())
+            && substs.type_at(0).is_sized(*tcx, self.ecx.param_env)
+        {
+            self.visit_unique(mplace, substs.type_at(0))
         } else {
             // Not a reference -- proceed recursively.
             self.walk_value(mplace)
@@ -370,6 +441,7 @@ pub fn intern_const_alloc_recursive<
 
     ref_tracking.track((*ret, base_intern_mode), || ());
 
+    let mut owns_heap = false;
     while let Some(((mplace, mode), _)) = ref_tracking.todo.pop() {
         let res = InternVisitor {
             ref_tracking: &mut ref_tracking,
@@ -377,6 +449,9 @@ pub fn intern_const_alloc_recursive<
             mode,
             leftover_allocations,
             inside_unsafe_cell: false,
+            // Everything else is behind a reference.
+            by_value: mplace == *ret,
+            owns_heap: &mut owns_heap,
         }
         .visit_value(&mplace);
         // We deliberately *ignore* interpreter errors here. When there is a problem, the remaining
@@ -396,6 +471,14 @@ pub fn intern_const_alloc_recursive<
         }
     }
 
+    if owns_heap {
+        let what = match intern_kind {
+            InternKind::Static(_) => "static",
+            InternKind::Constant | InternKind::Promoted => "constant",
+        };
+        return Err(ecx.tcx.sess.emit_err(OwnsHeapErr { span: ecx.tcx.span, kind: what }));
+    }
+
     // Intern the rest of the allocations as mutable. These might be inside unions, padding, raw
     // pointers, ... So we can't intern them according to their type rules
 
@@ -427,7 +510,10 @@ pub fn intern_const_alloc_recursive<
                 }
                 InternKind::Constant => {
                     // If it's a constant, we should not have any "leftovers" as everything
-                    // is tracked by const-checking.
+                    // is tracked by const-checking. This includes heap memory that is only
+                    // reachable through a raw pointer other than a `Unique`, or whose elements
+                    // have interior mutability: without a type, validation can't check that it
+                    // contains no `UnsafeCell` that interning would make immutable.
                     // FIXME: downgrade this to a warning? It rejects some legitimate consts,
                     // such as `const CONST_RAW: *const Vec<i32> = &Vec::new() as *const _;`.
                     ecx.tcx
@@ -370,6 +370,16 @@ fn before_memory_write(
         Ok(())
     }
 
+    /// Called immediately after a new allocation of `size` bytes got created, either by the
+    /// program or by the interpreter itself, e.g. for a local.
+    #[inline(always)]
+    fn after_memory_allocation(
+        _ecx: &mut InterpCx<'mir, 'tcx, Self>,
+        _size: Size,
+        _kind: MemoryKind<Self::MemoryKind>,
+    ) {
+    }
+
     /// Hook for performing extra operations on a memory deallocation.
     #[inline(always)]
     fn before_memory_deallocation(
@@ -1,9 +1,11 @@
 //! Functions concerning immediate values and operands, and reading from operands.
 //! All high-level functions to read from memory work on operands as sources.
 
+use std::iter;
+
 use either::{Either, Left, Right};
 
-use rustc_hir::def::Namespace;
+use rustc_hir::def::{CtorKind, Namespace};
 use rustc_middle::ty::layout::{LayoutOf, PrimitiveExt, TyAndLayout};
 use rustc_middle::ty::print::{FmtPrinter, PrettyPrinter};
 use rustc_middle::ty::{ConstInt, Ty, ValTree};
@@ -431,6 +433,85 @@ pub fn read_str(&self, mplace: &MPlaceTy<'tcx, M::Provenance>) -> InterpResult<'
         Ok(str)
     }
 
+    /// Renders a value for diagnostics. Immediates use the `ImmTy` pretty printer, and
+    /// structs, enums, tuples and arrays are rendered field by field, up to a small depth.
+    /// Parts of the value that cannot be read are rendered as `<uninit>`.
+    pub fn render_operand(&self, op: &OpTy<'tcx, M::Provenance>) -> String {
+        self.render_operand_at_depth(op, 0)
+    }
+
+    fn render_operand_at_depth(&self, op: &OpTy<'tcx, M::Provenance>, depth: usize) -> String {
+        self.try_render_operand(op, depth).unwrap_or_else(|_| "<uninit>".to_string())
+    }
+
+    fn try_render_operand(
+        &self,
+        op: &OpTy<'tcx, M::Provenance>,
+        depth: usize,
+    ) -> InterpResult<'tcx, String> {
+        const MAX_DEPTH: usize = 4;
+        const MAX_ELEMENTS: u64 = 8;
+
+        if depth >= MAX_DEPTH {
+            return Ok("..".to_string());
+        }
+        let render_fields = |op: &OpTy<'tcx, M::Provenance>, count: usize| {
+            (0..count)
+                .map(|i| Ok(self.render_operand_at_depth(&self.operand_field(op, i)?, depth + 1)))
+                .collect::<InterpResult<'tcx, Vec<_>>>()
+        };
+        match *op.layout.ty.kind() {
+            ty::Tuple(tys) => {
+                let fields = render_fields(op, tys.len())?;
+                if fields.len() == 1 {
+                    return Ok(format!("({},)", fields[0]));
+                }
+                return Ok(format!("({})", fields.join(", ")));
+            }
+            ty::Array(..) if !op.layout.is_unsized() => {
+                let len = op.len(self)?;
+                let mut elems = (0..len.min(MAX_ELEMENTS))
+                    .map(|i| {
+                        let elem = self.operand_index(op, i)?;
+                        Ok(self.render_operand_at_depth(&elem, depth + 1))
+                    })
+                    .collect::<InterpResult<'tcx, Vec<_>>>()?;
+                if len > MAX_ELEMENTS {
+                    elems.push("..".to_string());
+                }
+                return Ok(format!("[{}]", elems.join(", ")));
+            }
+            ty::Adt(adt, _) if adt.is_struct() || adt.is_enum() => {
+                let (op, variant) = if adt.is_enum() {
+                    let (_, variant) = self.read_discriminant(op)?;
+                    (self.operand_downcast(op, variant)?, adt.variant(variant))
+                } else {
+                    (op.clone(), adt.non_enum_variant())
+                };
+                let name = self.tcx.def_path_str(variant.def_id);
+                let fields = render_fields(&op, variant.fields.len())?;
+                return Ok(match variant.ctor_kind() {
+                    Some(CtorKind::Const) => name,
+                    Some(CtorKind::Fn) => format!("{}({})", name, fields.join(", ")),
+                    None => {
+                        let fields = iter::zip(&variant.fields, fields)
+                            .map(|(field, value)| format!("{}: {}", field.name, value))
+                            .collect::<Vec<_>>();
+                        format!("{} {{ {} }}", name, fields.join(", "))
+                    }
+                });
+            }
+            _ => {}
+        }
+        match op.layout.abi {
+            Abi::Scalar(abi::Scalar::Initialized { .. })
+            | Abi::ScalarPair(abi::Scalar::Initialized { .. }, abi::Scalar::Initialized { .. }) => {
+                Ok(self.read_immediate(op)?.to_string())
+            }
+            _ => Ok(format!("<{}>", op.layout.ty)),
+        }
+    }
+
     /// Converts a repr(simd) operand into an operand where `place_index` accesses the SIMD elements.
     /// Also returns the number of elements.
     ///
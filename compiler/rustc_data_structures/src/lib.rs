@@ -58,6 +58,8 @@ pub fn cold_path<F: FnOnce() -> R, R>(f: F) -> R {
 pub mod map_in_place;
 pub mod obligation_forest;
 pub mod owning_ref;
+#[cfg(parallel_compiler)]
+pub mod sequential_order;
 pub mod sip128;
 pub mod small_c_str;
 pub mod small_str;
@@ -83,8 +83,10 @@
 
 use crate::cold_path;
 use crate::fx::FxHashMap;
+use crate::sync::Lock;
 
 use std::borrow::Borrow;
+use std::cell::RefCell;
 use std::collections::hash_map::Entry;
 use std::error::Error;
 use std::fs;
@@ -159,19 +161,30 @@ pub struct SelfProfilerRef {
 
     // Print verbose generic activities to stderr?
     print_verbose_generic_activities: bool,
+
+    // The self time of each query provider invocation, if recorded for
+    // `-Z self-profile-summary`.
+    query_self_times: Option<Arc<Lock<FxHashMap<u32, Duration>>>>,
 }
 
 impl SelfProfilerRef {
     pub fn new(
         profiler: Option<Arc<SelfProfiler>>,
         print_verbose_generic_activities: bool,
+        record_query_self_times: bool,
     ) -> SelfProfilerRef {
         // If there is no SelfProfiler then the filter mask is set to NONE,
         // ensuring that nothing ever tries to actually access it.
         let event_filter_mask =
             profiler.as_ref().map_or(EventFilter::empty(), |p| p.event_filter_mask);
+        let query_self_times = record_query_self_times.then(Default::default);
 
-        SelfProfilerRef { profiler, event_filter_mask, print_verbose_generic_activities }
+        SelfProfilerRef {
+            profiler,
+            event_filter_mask,
+            print_verbose_generic_activities,
+            query_self_times,
+        }
     }
 
     /// This shim makes sure that calls only get executed if the filter mask
@@ -487,6 +500,85 @@ pub fn llvm_recording_enabled(&self) -> bool {
     pub fn get_self_profiler(&self) -> Option<Arc<SelfProfiler>> {
         self.profiler.clone()
     }
+
+    /// Whether self times are measured, see `SelfTimeGuard`.
+    #[inline]
+    pub fn self_times_enabled(&self) -> bool {
+        self.query_self_times.is_some()
+    }
+
+    /// Starts measuring the self time of a query provider or of another
+    /// per-item activity, if self times are measured.
+    #[inline]
+    pub fn self_time(&self) -> SelfTimeGuard {
+        if self.query_self_times.is_some() { SelfTimeGuard::start() } else { SelfTimeGuard(None) }
+    }
+
+    /// Finishes measuring the self time of a query provider started with
+    /// `SelfProfilerRef::self_time`.
+    #[inline]
+    pub fn finish_query_self_time(
+        &self,
+        guard: SelfTimeGuard,
+        query_invocation_id: QueryInvocationId,
+    ) {
+        if let Some(query_self_times) = &self.query_self_times {
+            if let Some(self_time) = guard.finish() {
+                *query_self_times.lock().entry(query_invocation_id.0).or_default() += self_time;
+            }
+        }
+    }
+
+    /// Returns the self time recorded for a query invocation, if any.
+    pub fn query_self_time(&self, query_invocation_id: QueryInvocationId) -> Option<Duration> {
+        self.query_self_times.as_ref()?.lock().get(&query_invocation_id.0).copied()
+    }
+}
+
+thread_local! {
+    // The time spent in nested self time measurements for each measurement
+    // that is currently running on this thread, innermost last.
+    static NESTED_SELF_TIMES: RefCell<Vec<Duration>> = RefCell::new(Vec::new());
+}
+
+/// Measures the time spent in an activity, minus the time spent in the
+/// activities nested in it which are measured the same way.
+///
+/// A guard that is dropped without being finished, because the activity
+/// unwound, still counts as nested in the enclosing activity.
+#[must_use]
+pub struct SelfTimeGuard(Option<Instant>);
+
+impl SelfTimeGuard {
+    fn start() -> SelfTimeGuard {
+        NESTED_SELF_TIMES.with(|nested| nested.borrow_mut().push(Duration::ZERO));
+        SelfTimeGuard(Some(Instant::now()))
+    }
+
+    /// Returns the self time of the activity, if it was measured.
+    pub fn finish(mut self) -> Option<Duration> {
+        self.stop()
+    }
+
+    fn stop(&mut self) -> Option<Duration> {
+        let total = self.0.take()?.elapsed();
+        let nested = NESTED_SELF_TIMES.with(|nested| {
+            let mut nested = nested.borrow_mut();
+            let time = nested.pop().unwrap();
+            if let Some(parent) = nested.last_mut() {
+                *parent += total;
+            }
+            time
+        });
+        Some(total.saturating_sub(nested))
+    }
+}
+
+impl Drop for SelfTimeGuard {
+    fn drop(&mut self) {
+        // Keeps `NESTED_SELF_TIMES` balanced if the activity unwinds.
+        self.stop();
+    }
 }
 
 /// A helper for recording costly arguments to self-profiling events. Used with
@@ -0,0 +1,317 @@
+//! Positions in the order in which a single-threaded compiler runs its work.
+//!
+//! The parallel helpers in [`sync`](crate::sync) split work into *sections*, each of which
+//! consists of a sequence of *work items*: the blocks of a `parallel!` invocation or the
+//! elements passed to `par_for_each_in`. A single-threaded compiler runs the items of a
+//! section one after another, in their order in the section, while a parallel compiler runs
+//! them in any order.
+//!
+//! Code that produces output which has to be the same no matter how many threads are used,
+//! like diagnostics, can record the [`SequentialPosition`] at which the output was produced,
+//! and later put everything produced in a section into the order of these positions.
+//!
+//! Positions are tracked per thread, so they stay accurate when a thread that waits for the
+//! items of a nested section picks up unrelated items in the meantime.
+//!
+//! A query that is requested by several work items is only run by one of them: whichever
+//! gets to it first. A single-threaded compiler runs it for the first of them in sequential
+//! order, so the position of everything a query produces is relative to the earliest
+//! position it was requested from, and that position is only known once all work items ran.
+//! To keep track of it, every request of a query is a step of the requesting work item, see
+//! [`request_query`], and the positions of the steps that requested a query are recorded
+//! once the query has produced any output. Those queries are tracked per outermost section,
+//! and forgotten once it ends, as everything that comes after the section also comes after
+//! them. The query system only records requests when diagnostics are emitted in sequential
+//! order, which is the only output that needs them.
+//!
+//! This is only compiled with `parallel_compiler`, a single-threaded compiler always runs its
+//! work in sequential order.
+
+use crate::fingerprint::Fingerprint;
+use crate::fx::FxHashMap;
+use crate::sync::Lock;
+
+use std::cell::RefCell;
+use std::mem;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::Arc;
+
+#[cfg(test)]
+mod tests;
+
+/// The position of a piece of work in the order in which a single-threaded compiler would
+/// run it. Comparing the [resolved](Self::resolve) positions of two pieces of work tells which
+/// of the two would run first.
+#[derive(Clone, Debug)]
+pub struct SequentialPosition {
+    /// The query that this position is relative to, if any.
+    query: Option<Arc<QueryPosition>>,
+    path: Vec<u32>,
+}
+
+impl SequentialPosition {
+    /// Returns the position as a sequence that can be compared with those of other positions.
+    ///
+    /// Positions inside of queries are only final once all work items that might request the
+    /// query have run.
+    pub fn resolve(&self) -> Vec<u32> {
+        let mut resolved = match &self.query {
+            Some(query) => query.resolve(),
+            None => Vec::new(),
+        };
+        resolved.extend_from_slice(&self.path);
+        resolved
+    }
+}
+
+impl PartialEq for SequentialPosition {
+    fn eq(&self, other: &Self) -> bool {
+        self.resolve() == other.resolve()
+    }
+}
+
+/// Identifies a query invocation by the address of its cache and the fingerprint of its dep
+/// node. The caches outlive the sections, so identities don't change while a section runs.
+pub type QueryIdentity = (usize, Fingerprint);
+
+/// The positions a query was requested from.
+#[derive(Debug)]
+struct QueryPosition {
+    identity: QueryIdentity,
+    /// Whether this query is in [`SectionQueries`], which it is once it produced output.
+    registered: AtomicBool,
+    /// The positions of the requests, only keeping the earliest one for each query (or work
+    /// item outside of queries) that requested it, as those can be compared with each other.
+    requests: Lock<Vec<SequentialPosition>>,
+}
+
+impl QueryPosition {
+    fn resolve(&self) -> Vec<u32> {
+        let requests = self.requests.lock().clone();
+        requests.iter().map(|request| request.resolve()).min().unwrap_or_default()
+    }
+
+    fn add_request(&self, position: SequentialPosition) {
+        let mut requests = self.requests.lock();
+        let same_query = |request: &SequentialPosition| match (&request.query, &position.query) {
+            (Some(a), Some(b)) => Arc::ptr_eq(a, b),
+            (None, None) => true,
+            _ => false,
+        };
+        match requests.iter_mut().find(|request| same_query(request)) {
+            Some(request) => {
+                if position.path < request.path {
+                    *request = position;
+                }
+            }
+            None => requests.push(position),
+        }
+    }
+}
+
+/// The queries that have produced output from the work items of an outermost section, to
+/// record later requests of them. They are shared by everything the section runs, and dropped
+/// with it.
+#[derive(Debug, Default)]
+struct SectionQueries {
+    queries: Lock<FxHashMap<QueryIdentity, Arc<QueryPosition>>>,
+    /// The number of entries in `queries`, to skip looking up requests while there are none.
+    count: AtomicUsize,
+}
+
+impl SectionQueries {
+    fn register(&self, query: &Arc<QueryPosition>) {
+        if !query.registered.swap(true, Ordering::Relaxed) {
+            self.queries.lock().insert(query.identity, query.clone());
+            self.count.fetch_add(1, Ordering::Relaxed);
+        }
+    }
+
+    fn is_empty(&self) -> bool {
+        self.count.load(Ordering::Relaxed) == 0
+    }
+
+    fn add_request(&self, identity: impl FnOnce() -> QueryIdentity, position: SequentialPosition) {
+        if self.is_empty() {
+            return;
+        }
+        let query = self.queries.lock().get(&identity()).cloned();
+        if let Some(query) = query {
+            query.add_request(position);
+        }
+    }
+}
+
+/// The position of the work item that the current thread runs.
+#[derive(Default)]
+struct Cursor {
+    /// The queries of the outermost section the current work item belongs to, `None` outside
+    /// of sections.
+    section_queries: Option<Arc<SectionQueries>>,
+    /// The query the current thread runs, if it was requested from a work item.
+    query: Option<Arc<QueryPosition>>,
+    /// The position of the current work item, relative to `query`.
+    item: Vec<u32>,
+    /// The number of sections the current work item has started and queries it has requested
+    /// so far.
+    steps: u32,
+}
+
+impl Cursor {
+    fn position(&self) -> SequentialPosition {
+        let mut path = self.item.clone();
+        path.push(self.steps);
+        SequentialPosition { query: self.query.clone(), path }
+    }
+
+    /// Returns the position of a new section or request, which comes after everything the
+    /// current work item has run so far and before everything it runs later.
+    fn step(&mut self) -> SequentialPosition {
+        let position = self.position();
+        self.steps += 1;
+        position
+    }
+}
+
+thread_local! {
+    static CURSOR: RefCell<Cursor> = RefCell::new(Cursor::default());
+}
+
+/// Returns the position of the code that is currently running, or `None` if it is not part
+/// of a parallel section and therefore runs in sequential order anyway.
+pub fn current_position() -> Option<SequentialPosition> {
+    CURSOR.with(|cursor| {
+        let cursor = cursor.borrow();
+        let section_queries = cursor.section_queries.as_ref()?;
+        // Later requests of the query need to be recorded now that it produced output.
+        if let Some(query) = &cursor.query {
+            section_queries.register(query);
+        }
+        // Anything the item runs before its next step comes before the things run by that
+        // step, and anything it runs after that step comes after them.
+        Some(cursor.position())
+    })
+}
+
+/// Records a request of a query from the running work item, if any. This must be called
+/// exactly once for every request of a query, no matter if the query has run already.
+///
+/// `identity` is only called when some query has produced output from a work item.
+#[inline]
+pub fn request_query(identity: impl FnOnce() -> QueryIdentity) {
+    CURSOR.with(|cursor| {
+        let mut cursor = cursor.borrow_mut();
+        let Some(section_queries) = &cursor.section_queries else { return };
+        if section_queries.is_empty() {
+            // No query needs the position yet, so don't build it.
+            cursor.steps += 1;
+            return;
+        }
+        let section_queries = section_queries.clone();
+        let position = cursor.step();
+        section_queries.add_request(identity, position);
+    })
+}
+
+/// Records the last request of the running work item again, after waiting for the query to be
+/// run by another thread. The query may have produced output since it was requested.
+pub fn request_query_again(identity: impl FnOnce() -> QueryIdentity) {
+    CURSOR.with(|cursor| {
+        let cursor = cursor.borrow();
+        if let Some(section_queries) = &cursor.section_queries {
+            section_queries.add_request(identity, last_request(&cursor));
+        }
+    })
+}
+
+/// The position of the last step of the cursor, which is the request of the query that is
+/// about to run. Queries that are forced without a request get the position of the last step.
+fn last_request(cursor: &Cursor) -> SequentialPosition {
+    let mut position = cursor.position();
+    let last = position.path.last_mut().unwrap();
+    *last = last.saturating_sub(1);
+    position
+}
+
+/// Runs the query with the given `identity`, which has just been requested by
+/// [`request_query`] from the running work item, if any. Everything the query produces is
+/// positioned relative to the earliest request of the query.
+pub fn run_query<R>(identity: impl FnOnce() -> QueryIdentity, f: impl FnOnce() -> R) -> R {
+    let cursor = CURSOR.with(|cursor| {
+        let cursor = cursor.borrow();
+        let section_queries = cursor.section_queries.clone()?;
+        let query = Arc::new(QueryPosition {
+            identity: identity(),
+            registered: AtomicBool::new(false),
+            requests: Lock::new(vec![last_request(&cursor)]),
+        });
+        Some(Cursor {
+            section_queries: Some(section_queries),
+            query: Some(query),
+            item: Vec::new(),
+            steps: 0,
+        })
+    });
+    match cursor {
+        Some(cursor) => {
+            let _guard = ItemGuard::enter(cursor);
+            f()
+        }
+        None => f(),
+    }
+}
+
+/// A parallel section, whose items are positioned after everything the current work item
+/// has run so far, and before everything it runs after the section.
+pub struct Section {
+    section_queries: Arc<SectionQueries>,
+    position: SequentialPosition,
+}
+
+impl Section {
+    pub fn enter() -> Section {
+        CURSOR.with(|cursor| {
+            let mut cursor = cursor.borrow_mut();
+            // An outermost section starts tracking queries anew.
+            let section_queries = cursor.section_queries.clone().unwrap_or_default();
+            Section { section_queries, position: cursor.step() }
+        })
+    }
+
+    /// Marks the current thread as running the item with the given index in this section,
+    /// until the returned guard is dropped.
+    pub fn enter_item(&self, index: usize) -> ItemGuard {
+        let mut item = self.position.path.clone();
+        item.push(index as u32);
+        ItemGuard::enter(Cursor {
+            section_queries: Some(self.section_queries.clone()),
+            query: self.position.query.clone(),
+            item,
+            steps: 0,
+        })
+    }
+
+    /// Runs `f` as the item with the given index in this section.
+    pub fn run_item<R>(&self, index: usize, f: impl FnOnce() -> R) -> R {
+        let _item = self.enter_item(index);
+        f()
+    }
+}
+
+/// Restores the position of the current thread when dropped, see [`Section::enter_item`].
+pub struct ItemGuard {
+    outer: Cursor,
+}
+
+impl ItemGuard {
+    fn enter(cursor: Cursor) -> ItemGuard {
+        let outer = CURSOR.with(|current| mem::replace(&mut *current.borrow_mut(), cursor));
+        ItemGuard { outer }
+    }
+}
+
+impl Drop for ItemGuard {
+    fn drop(&mut self) {
+        CURSOR.with(|cursor| *cursor.borrow_mut() = mem::take(&mut self.outer));
+    }
+}
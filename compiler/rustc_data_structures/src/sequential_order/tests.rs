@@ -0,0 +1,73 @@
+use super::*;
+
+#[test]
+fn outside_of_sections() {
+    assert_eq!(current_position(), None);
+    let section = Section::enter();
+    section.run_item(0, || assert!(current_position().is_some()));
+    assert_eq!(current_position(), None);
+}
+
+fn sorted_labels(log: Vec<(SequentialPosition, &'static str)>) -> Vec<&'static str> {
+    let mut log: Vec<_> =
+        log.into_iter().map(|(position, label)| (position.resolve(), label)).collect();
+    log.sort_by(|a, b| a.0.cmp(&b.0));
+    log.into_iter().map(|(_, label)| label).collect()
+}
+
+#[test]
+fn out_of_order_items() {
+    let mut log = Vec::new();
+    let mut record = |label: &'static str| log.push((current_position().unwrap(), label));
+
+    // Run the items of a section and of a section nested in one of its items out of order,
+    // like a parallel compiler might.
+    let outer = Section::enter();
+    outer.run_item(0, || {
+        record("a: before");
+        let nested = Section::enter();
+        nested.run_item(1, || record("a1"));
+        nested.run_item(0, || {
+            record("a0: before");
+            // The thread picks up another item of the outer section while it waits.
+            outer.run_item(1, || record("b"));
+            record("a0: after");
+        });
+        record("a: after");
+    });
+    drop(outer);
+    Section::enter().run_item(0, || record("c"));
+
+    assert_eq!(
+        sorted_labels(log),
+        ["a: before", "a0: before", "a0: after", "a1", "a: after", "b", "c"]
+    );
+}
+
+#[test]
+fn queries_run_by_later_items() {
+    let mut log = Vec::new();
+    let mut record = |label: &'static str| log.push((current_position().unwrap(), label));
+    let query = (0, Fingerprint::new(0, 42));
+
+    // The second item runs a query that the first item requests later on, like a parallel
+    // compiler might. A single-threaded compiler would run it for the first item.
+    let section = Section::enter();
+    section.run_item(1, || {
+        record("b: before");
+        request_query(|| query);
+        run_query(|| query, || record("query"));
+        record("b: after");
+    });
+    section.run_item(0, || {
+        record("a: before");
+        request_query(|| query);
+        record("a: after");
+    });
+    let section_queries = Arc::downgrade(&section.section_queries);
+    drop(section);
+
+    assert_eq!(sorted_labels(log), ["a: before", "query", "a: after", "b: before", "b: after"]);
+    // The queries are forgotten once the outermost section ends.
+    assert!(section_queries.upgrade().is_none());
+}
@@ -305,39 +305,54 @@ pub fn lock_mut(&self) -> LockGuard<'_, T> {
         /// the current thread. Use that for the longest running block.
         #[macro_export]
         macro_rules! parallel {
-            (impl $fblock:tt [$($c:tt,)*] [$block:tt $(, $rest:tt)*]) => {
-                parallel!(impl $fblock [$block, $($c,)*] [$($rest),*])
+            (impl $section:ident $fblock:tt ($i:expr) [$($c:tt,)*] [$block:tt $(, $rest:tt)*]) => {
+                parallel!(impl $section $fblock ($i + 1) [(($i) $block), $($c,)*] [$($rest),*])
             };
-            (impl $fblock:tt [$($blocks:tt,)*] []) => {
+            (impl $section:ident $fblock:tt ($i:expr) [$((($ci:expr) $blocks:tt),)*] []) => {
                 ::rustc_data_structures::sync::scope(|s| {
                     $(
-                        s.spawn(|_| $blocks);
+                        s.spawn(|_| {
+                            let _item = $section.enter_item($ci);
+                            $blocks
+                        });
                     )*
+                    let _item = $section.enter_item(0);
                     $fblock;
                 })
             };
-            ($fblock:tt, $($blocks:tt),*) => {
+            ($fblock:tt, $($blocks:tt),*) => {{
                 // Reverse the order of the later blocks since Rayon executes them in reverse order
                 // when using a single thread. This ensures the execution order matches that
-                // of a single threaded rustc
-                parallel!(impl $fblock [] [$($blocks),*]);
-            };
+                // of a single threaded rustc. Each block is also run as an item of a section
+                // in the order of a single threaded rustc, see `sequential_order`.
+                let section = ::rustc_data_structures::sequential_order::Section::enter();
+                parallel!(impl section $fblock (1) [] [$($blocks),*]);
+            }};
         }
 
         pub use rayon_core::WorkerLocal;
 
         pub use rayon::iter::ParallelIterator;
-        use rayon::iter::IntoParallelIterator;
+        use rayon::iter::{IndexedParallelIterator, IntoParallelIterator};
 
         pub fn par_iter<T: IntoParallelIterator>(t: T) -> T::Iter {
             t.into_par_iter()
         }
 
-        pub fn par_for_each_in<T: IntoParallelIterator>(
+        /// Runs `for_each` on every element of `t` in parallel. Each element is run as an item
+        /// of a section, in the order of `t`, see `sequential_order`.
+        pub fn par_for_each_in<T: IntoParallelIterator<Iter: IndexedParallelIterator>>(
             t: T,
             for_each: impl Fn(T::Item) + Sync + Send,
         ) {
-            let ps: Vec<_> = t.into_par_iter().map(|i| catch_unwind(AssertUnwindSafe(|| for_each(i)))).collect();
+            let section = crate::sequential_order::Section::enter();
+            let ps: Vec<_> = t
+                .into_par_iter()
+                .enumerate()
+                .map(|(index, i)| {
+                    catch_unwind(AssertUnwindSafe(|| section.run_item(index, || for_each(i))))
+                })
+                .collect();
             ps.into_iter().for_each(|p| if let Err(panic) = p {
                 resume_unwind(panic)
             });
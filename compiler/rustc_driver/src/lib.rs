@@ -23,6 +23,7 @@
 use rustc_data_structures::profiling::{get_resident_set_size, print_time_passes_entry};
 use rustc_data_structures::sync::SeqCst;
 use rustc_errors::registry::{InvalidErrorCode, Registry};
+use rustc_errors::snapshot::SnapshotPhase;
 use rustc_errors::{ErrorGuaranteed, PResult};
 use rustc_feature::find_gated_cfg;
 use rustc_hir::def_id::LOCAL_CRATE;
@@ -227,6 +228,7 @@ fn run_compiler(
         register_lints: None,
         override_queries: None,
         make_codegen_backend,
+        diagnostic_snapshot: None,
         registry: diagnostics_registry(),
     };
 
@@ -345,6 +347,7 @@ fn run_compiler(
 
             queries.global_ctxt()?.enter(|tcx| {
                 let result = tcx.analysis(());
+                sess.snapshot_diagnostics(SnapshotPhase::Analysis);
                 if sess.opts.unstable_opts.save_analysis {
                     let crate_name = tcx.crate_name(LOCAL_CRATE);
                     sess.time("save_analysis", || {
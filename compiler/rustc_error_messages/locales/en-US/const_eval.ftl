@@ -81,3 +81,11 @@ const_eval_interior_mutable_data_refer =
 
 const_eval_interior_mutability_borrow =
     cannot borrow here, since the borrowed element may contain interior mutability
+
+const_eval_owns_heap =
+    the final value of a {$kind} must not own heap memory that could be freed
+
+const_eval_long_running =
+    constant evaluation is taking a long time
+    .note = this evaluation has run for {$steps} steps so far
+    .help = compile with `-Z const-eval-profile` to see which `const fn`s take the longest
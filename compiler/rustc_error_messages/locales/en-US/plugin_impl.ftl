@@ -2,3 +2,19 @@ plugin_impl_load_plugin_error = {$msg}
 
 plugin_impl_malformed_plugin_attribute = malformed `plugin` attribute
     .label = malformed attribute
+
+plugin_impl_load_lint_plugin_error = couldn't load lint plugin `{$path}`: {$msg}
+
+plugin_impl_lint_plugin_api_mismatch =
+    lint plugin `{$path}` was built against version {$found} of the lint plugin interface, but this compiler implements version {$expected}
+    .help = rebuild the plugin against this compiler
+
+plugin_impl_lint_plugin_rustc_mismatch =
+    lint plugin `{$path}` was built with rustc {$found}, but this is rustc {$expected}
+    .help = rebuild the plugin with this compiler
+
+plugin_impl_lint_plugin_tool_mismatch =
+    lint plugin `{$path}` declares the tool `{$declared}`, but was loaded as `{$requested}`
+
+plugin_impl_lint_plugin_lint_not_named_after_tool =
+    lint plugin `{$path}` registered the lint `{$lint}`, which isn't named after its tool `{$tool}`
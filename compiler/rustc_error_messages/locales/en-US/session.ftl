@@ -23,6 +23,13 @@ session_linker_plugin_lto_windows_not_supported = linker plugin based LTO is not
 
 session_profile_sample_use_file_does_not_exist = file `{$path}` passed to `-C profile-sample-use` does not exist.
 
+session_couldnt_read_call_graph_profile =
+    failed to read call-graph profile `{$path}`: {$error}
+
+session_malformed_call_graph_profile =
+    malformed line {$line} in call-graph profile `{$path}`
+    .note = every line has to be of the form `caller callee count`
+
 session_target_requires_unwind_tables = target requires unwind tables, they cannot be disabled with `-C force-unwind-tables=no`
 
 session_sanitizer_not_supported = {$us} sanitizer is not supported for this target
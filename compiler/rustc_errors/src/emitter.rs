@@ -9,9 +9,11 @@
 
 use Destination::*;
 
+use rustc_span::edition::Edition;
 use rustc_span::source_map::SourceMap;
 use rustc_span::{FileLines, SourceFile, Span};
 
+use crate::highlight::{highlight_line, SyntaxClass};
 use crate::snippet::{Annotation, AnnotationType, Line, MultilineAnnotation, Style, StyledString};
 use crate::styled_buffer::StyledBuffer;
 use crate::translation::{to_fluent_args, Translate};
@@ -648,6 +650,7 @@ pub struct EmitterWriter {
     short_message: bool,
     teach: bool,
     ui_testing: bool,
+    syntax_highlighting: Option<Edition>,
     diagnostic_width: Option<usize>,
 
     macro_backtrace: bool,
@@ -682,6 +685,7 @@ pub fn stderr(
             short_message,
             teach,
             ui_testing: false,
+            syntax_highlighting: None,
             diagnostic_width,
             macro_backtrace,
             track_diagnostics,
@@ -708,6 +712,7 @@ pub fn new(
             short_message,
             teach,
             ui_testing: false,
+            syntax_highlighting: None,
             diagnostic_width,
             macro_backtrace,
             track_diagnostics,
@@ -719,6 +724,13 @@ pub fn ui_testing(mut self, ui_testing: bool) -> Self {
         self
     }
 
+    /// Highlight the quoted source lines using the token kinds of `rustc_lexer` and the keywords
+    /// of the given edition. This has no effect when the destination does not support colors.
+    pub fn syntax_highlighting(mut self, syntax_highlighting: Option<Edition>) -> Self {
+        self.syntax_highlighting = syntax_highlighting;
+        self
+    }
+
     fn maybe_anonymized(&self, line_num: usize) -> Cow<'static, str> {
         if self.ui_testing {
             Cow::Borrowed(ANONYMIZED_LINE_NUM)
@@ -761,6 +773,23 @@ fn draw_line(
             })
             .collect();
         buffer.puts(line_offset, code_offset, &code, Style::Quotation);
+        if let Some(edition) = self.syntax_highlighting {
+            // Only the styles of the characters change, so the underlines stay aligned.
+            let shown = left..left + code.chars().count();
+            for (range, class) in highlight_line(source_string, edition) {
+                let start = range.start.max(shown.start);
+                let end = range.end.min(shown.end);
+                if start < end {
+                    buffer.set_style_range(
+                        line_offset,
+                        code_offset + start - left,
+                        code_offset + end - left,
+                        Style::Syntax(class),
+                        true,
+                    );
+                }
+            }
+        }
         if margin.was_cut_left() {
             // We have stripped some code/whitespace from the beginning, make it clear.
             buffer.puts(line_offset, code_offset, "...", Style::LineNumber);
@@ -2648,6 +2677,19 @@ fn apply_style(&mut self, lvl: Level, style: Style) -> io::Result<()> {
             Style::Highlight => {
                 spec.set_bold(true);
             }
+            Style::Syntax(class) => {
+                let color = match class {
+                    SyntaxClass::Keyword => Color::Magenta,
+                    SyntaxClass::Macro | SyntaxClass::Lifetime => Color::Cyan,
+                    SyntaxClass::String => Color::Green,
+                    SyntaxClass::Number => Color::Yellow,
+                    SyntaxClass::Comment => Color::White,
+                };
+                spec.set_fg(Some(color));
+                if class == SyntaxClass::Comment {
+                    spec.set_dimmed(true);
+                }
+            }
         }
         self.set_color(&spec)
     }
@@ -0,0 +1,62 @@
+//! Lexer-driven syntax highlighting of the source lines quoted in diagnostics.
+
+use rustc_lexer::{LiteralKind, TokenKind};
+use rustc_span::edition::Edition;
+use rustc_span::Symbol;
+use std::ops::Range;
+
+/// The class of a token, which determines the color it is highlighted with.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash, Encodable, Decodable)]
+pub enum SyntaxClass {
+    Keyword,
+    Macro,
+    Lifetime,
+    String,
+    Number,
+    Comment,
+}
+
+/// Classifies the tokens of a single source line, returning the ranges of `char`s that should
+/// be highlighted. Keywords are those of the given `edition`.
+///
+/// Each line is lexed on its own, so a line in the middle of a multi-line comment or string
+/// literal can be classified incorrectly. This only ever affects the colors of the line.
+pub fn highlight_line(line: &str, edition: Edition) -> Vec<(Range<usize>, SyntaxClass)> {
+    let tokens: Vec<_> = rustc_lexer::tokenize(line).collect();
+    let mut highlights = Vec::new();
+    let mut byte_pos = 0;
+    let mut char_pos = 0;
+    for (i, token) in tokens.iter().enumerate() {
+        let text = &line[byte_pos..byte_pos + token.len as usize];
+        let len = text.chars().count();
+        let next_kind = |n: usize| tokens.get(i + n).map(|token| token.kind);
+        let class = match token.kind {
+            TokenKind::LineComment { .. } | TokenKind::BlockComment { .. } => {
+                Some(SyntaxClass::Comment)
+            }
+            TokenKind::Lifetime { .. } => Some(SyntaxClass::Lifetime),
+            TokenKind::Literal {
+                kind: LiteralKind::Int { .. } | LiteralKind::Float { .. }, ..
+            } => Some(SyntaxClass::Number),
+            TokenKind::Literal { .. } => Some(SyntaxClass::String),
+            // `name!`, but not `name != other`.
+            TokenKind::Ident
+                if next_kind(1) == Some(TokenKind::Bang) && next_kind(2) != Some(TokenKind::Eq) =>
+            {
+                Some(SyntaxClass::Macro)
+            }
+            TokenKind::Ident if Symbol::intern(text).is_reserved(|| edition) => {
+                Some(SyntaxClass::Keyword)
+            }
+            _ => None,
+        };
+        if let Some(class) = class {
+            // Highlight the `!` of a macro invocation along with its name.
+            let end = if class == SyntaxClass::Macro { char_pos + len + 1 } else { char_pos + len };
+            highlights.push((char_pos..end, class));
+        }
+        byte_pos += token.len as usize;
+        char_pos += len;
+    }
+    highlights
+}
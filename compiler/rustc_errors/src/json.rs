@@ -9,6 +9,7 @@
 
 // FIXME: spec the JSON output properly.
 
+use rustc_span::edition::Edition;
 use rustc_span::source_map::{FilePathMapping, SourceMap};
 
 use crate::emitter::{Emitter, HumanReadableErrorType};
@@ -43,6 +44,7 @@ pub struct JsonEmitter {
     fallback_bundle: LazyFallbackBundle,
     pretty: bool,
     ui_testing: bool,
+    syntax_highlighting: Option<Edition>,
     json_rendered: HumanReadableErrorType,
     diagnostic_width: Option<usize>,
     macro_backtrace: bool,
@@ -69,6 +71,7 @@ pub fn stderr(
             fallback_bundle,
             pretty,
             ui_testing: false,
+            syntax_highlighting: None,
             json_rendered,
             diagnostic_width,
             macro_backtrace,
@@ -119,6 +122,7 @@ pub fn new(
             fallback_bundle,
             pretty,
             ui_testing: false,
+            syntax_highlighting: None,
             json_rendered,
             diagnostic_width,
             macro_backtrace,
@@ -129,6 +133,10 @@ pub fn new(
     pub fn ui_testing(self, ui_testing: bool) -> Self {
         Self { ui_testing, ..self }
     }
+
+    pub fn syntax_highlighting(self, syntax_highlighting: Option<Edition>) -> Self {
+        Self { syntax_highlighting, ..self }
+    }
 }
 
 impl Translate for JsonEmitter {
@@ -362,6 +370,7 @@ fn flush(&mut self) -> io::Result<()> {
                 je.track_diagnostics,
             )
             .ui_testing(je.ui_testing)
+            .syntax_highlighting(je.syntax_highlighting)
             .emit_diagnostic(diag);
         let output = Arc::try_unwrap(output.0).unwrap().into_inner().unwrap();
         let output = String::from_utf8(output).unwrap();
@@ -31,6 +31,8 @@
 use emitter::{is_case_difference, Emitter, EmitterWriter};
 use registry::Registry;
 use rustc_data_structures::fx::{FxHashMap, FxHashSet, FxIndexMap, FxIndexSet};
+#[cfg(parallel_compiler)]
+use rustc_data_structures::sequential_order::{self, SequentialPosition};
 use rustc_data_structures::stable_hasher::StableHasher;
 use rustc_data_structures::sync::{self, Lock, Lrc};
 use rustc_data_structures::AtomicRef;
@@ -60,9 +62,11 @@
 mod diagnostic_impls;
 pub mod emitter;
 pub mod error;
+mod highlight;
 pub mod json;
 mod lock;
 pub mod registry;
+pub mod snapshot;
 mod snippet;
 mod styled_buffer;
 #[cfg(test)]
@@ -70,6 +74,7 @@
 pub mod translation;
 
 pub use diagnostic_builder::IntoDiagnostic;
+pub use highlight::SyntaxClass;
 pub use snippet::Style;
 
 pub type PErr<'a> = DiagnosticBuilder<'a, ErrorGuaranteed>;
@@ -458,6 +463,42 @@ struct HandlerInner {
     ///
     /// [RFC-2383]: https://rust-lang.github.io/rfcs/2383-lint-reasons.html
     fulfilled_expectations: FxHashSet<LintExpectationId>,
+
+    /// The diagnostics held back by [`HandlerFlags::diagnostic_grouping`] since the end of
+    /// the last pass, in the order in which they were emitted. They already count towards the
+    /// total error and warning counts, and are emitted by `.emit_diagnostic_groups()`, which
+    /// is called at the end of every pass and by `.abort_if_errors()` and
+    /// `.print_error_count()`.
+    diagnostic_groups: Vec<DiagnosticGroup>,
+
+    /// The index in `diagnostic_groups` of the group of each group key, i.e. of the hash of
+    /// everything but the spans of the errors in it.
+    diagnostic_group_indices: FxHashMap<u128, usize>,
+
+    /// The number of warnings emitted by each lint, including the ones that were not shown
+    /// because of [`HandlerFlags::warnings_per_lint_cap`].
+    lint_warning_counts: FxIndexMap<String, usize>,
+
+    /// If `Some`, every diagnostic that is emitted is also recorded here, until it is taken
+    /// by [`Handler::take_diagnostic_snapshot`].
+    recorded_diagnostics: Option<Vec<Diagnostic>>,
+
+    /// Diagnostics emitted from parallel sections, buffered by
+    /// [`HandlerFlags::sequential_diagnostic_order`] together with the position of the work
+    /// item that emitted them. They already count towards the total error and warning counts.
+    #[cfg(parallel_compiler)]
+    parallel_diagnostics: Vec<(SequentialPosition, Diagnostic)>,
+}
+
+/// Errors with the same code and the same primary cause, see
+/// [`HandlerFlags::diagnostic_grouping`]. Diagnostics that can't be grouped are a group of
+/// their own.
+struct DiagnosticGroup {
+    /// The first error of the group, which is the only one that is emitted. It also carries
+    /// the suggestions of the other errors.
+    representative: Diagnostic,
+    /// The primary spans of the other errors of the group.
+    similar: Vec<Span>,
 }
 
 /// A key denoting where from a diagnostic was stashed.
@@ -504,11 +545,32 @@ pub struct HandlerFlags {
     pub deduplicate_diagnostics: bool,
     /// Track where errors are created. Enabled with `-Ztrack-diagnostics`.
     pub track_diagnostics: bool,
+    /// If true, errors with the same code and the same primary cause that are emitted by the
+    /// same pass are reported as a single error, in the place of the first of them and followed
+    /// by a list of the locations of the others. Diagnostics are held back until the end of the
+    /// pass for that.
+    /// (rustc: see `-Z diagnostic-grouping`)
+    pub diagnostic_grouping: bool,
+    /// If `Some`, each lint emits at most this many warnings, and the number of remaining
+    /// warnings is reported in a summary note once compilation ends.
+    ///
+    /// The lint level machinery only decides whether a lint is a warning, and marks the
+    /// warnings of lints requested with `--force-warn`, which are never capped. The warnings are
+    /// counted here, after deduplication and in sequential order, so that the same warnings are
+    /// shown and the counts are exact no matter how many threads the compiler uses.
+    /// (rustc: see `--cap-warnings-per-lint`)
+    pub warnings_per_lint_cap: Option<usize>,
+    /// If true, diagnostics emitted from parallel sections are buffered, and emitted in the
+    /// order in which a single-threaded compiler would have emitted them once a diagnostic is
+    /// emitted from outside of parallel sections or the error count is checked.
+    /// (rustc: enabled with `-Z threads` greater than 1)
+    pub sequential_diagnostic_order: bool,
 }
 
 impl Drop for HandlerInner {
     fn drop(&mut self) {
         self.emit_stashed_diagnostics();
+        self.emit_diagnostic_groups();
 
         if !self.has_errors() {
             let bugs = std::mem::replace(&mut self.delayed_span_bugs, Vec::new());
@@ -612,6 +674,12 @@ pub fn with_emitter_and_flags(
                 check_unstable_expect_diagnostics: false,
                 unstable_expect_diagnostics: Vec::new(),
                 fulfilled_expectations: Default::default(),
+                diagnostic_groups: Vec::new(),
+                diagnostic_group_indices: Default::default(),
+                lint_warning_counts: Default::default(),
+                recorded_diagnostics: None,
+                #[cfg(parallel_compiler)]
+                parallel_diagnostics: Vec::new(),
             }),
         }
     }
@@ -659,6 +727,27 @@ pub fn reset_err_count(&self) {
         inner.emitted_diagnostic_codes = Default::default();
         inner.emitted_diagnostics = Default::default();
         inner.stashed_diagnostics = Default::default();
+        inner.diagnostic_groups = Default::default();
+        inner.diagnostic_group_indices = Default::default();
+        inner.lint_warning_counts = Default::default();
+    }
+
+    /// Starts recording every emitted diagnostic, for [`Handler::take_diagnostic_snapshot`].
+    pub fn record_diagnostics(&self) {
+        self.inner.borrow_mut().recorded_diagnostics.get_or_insert_with(Vec::new);
+    }
+
+    /// Returns the diagnostics that were emitted since recording was started or the previous
+    /// snapshot was taken, with their messages translated and their spans resolved.
+    pub fn take_diagnostic_snapshot(&self) -> Vec<snapshot::ResolvedDiagnostic> {
+        let mut inner = self.inner.borrow_mut();
+        inner.emit_parallel_diagnostics();
+        let Some(recorded) = &mut inner.recorded_diagnostics else { return Vec::new() };
+        let recorded = std::mem::take(recorded);
+        recorded
+            .iter()
+            .map(|diag| snapshot::ResolvedDiagnostic::from_diagnostic(diag, &*inner.emitter))
+            .collect()
     }
 
     /// Stash a given diagnostic with the given `Span` and [`StashKey`] as the key.
@@ -685,6 +774,19 @@ pub fn emit_stashed_diagnostics(&self) -> Option<ErrorGuaranteed> {
         self.inner.borrow_mut().emit_stashed_diagnostics()
     }
 
+    /// Emit all diagnostics buffered by [`HandlerFlags::sequential_diagnostic_order`].
+    pub fn emit_parallel_diagnostics(&self) {
+        self.inner.borrow_mut().emit_parallel_diagnostics()
+    }
+
+    /// Emit all diagnostics held back by [`HandlerFlags::diagnostic_grouping`]. This is called
+    /// at the end of every pass.
+    pub fn emit_diagnostic_groups(&self) {
+        let mut inner = self.inner.borrow_mut();
+        inner.emit_parallel_diagnostics();
+        inner.emit_diagnostic_groups();
+    }
+
     /// Construct a builder with the `msg` at the level appropriate for the specific `EmissionGuarantee`.
     #[rustc_lint_diagnostics]
     #[track_caller]
@@ -1247,6 +1349,7 @@ fn force_print_diagnostic(&mut self, db: Diagnostic) {
 
     /// Emit all stashed diagnostics.
     fn emit_stashed_diagnostics(&mut self) -> Option<ErrorGuaranteed> {
+        self.emit_parallel_diagnostics();
         let has_errors = self.has_errors();
         let diags = self.stashed_diagnostics.drain(..).map(|x| x.1).collect::<Vec<_>>();
         let mut reported = None;
@@ -1331,42 +1434,9 @@ fn emit_diagnostic(&mut self, diagnostic: &mut Diagnostic) -> Option<ErrorGuaran
 
         let mut guaranteed = None;
         (*TRACK_DIAGNOSTICS)(diagnostic, &mut |diagnostic| {
-            if let Some(ref code) = diagnostic.code {
-                self.emitted_diagnostic_codes.insert(code.clone());
-            }
-
-            let already_emitted = |this: &mut Self| {
-                let mut hasher = StableHasher::new();
-                diagnostic.hash(&mut hasher);
-                let diagnostic_hash = hasher.finish();
-                !this.emitted_diagnostics.insert(diagnostic_hash)
-            };
-
-            // Only emit the diagnostic if we've been asked to deduplicate or
-            // haven't already emitted an equivalent diagnostic.
-            if !(self.flags.deduplicate_diagnostics && already_emitted(self)) {
-                debug!(?diagnostic);
-                debug!(?self.emitted_diagnostics);
-                let already_emitted_sub = |sub: &mut SubDiagnostic| {
-                    debug!(?sub);
-                    if sub.level != Level::OnceNote {
-                        return false;
-                    }
-                    let mut hasher = StableHasher::new();
-                    sub.hash(&mut hasher);
-                    let diagnostic_hash = hasher.finish();
-                    debug!(?diagnostic_hash);
-                    !self.emitted_diagnostics.insert(diagnostic_hash)
-                };
-
-                diagnostic.children.drain_filter(already_emitted_sub).for_each(|_| {});
-
-                self.emitter.emit_diagnostic(diagnostic);
-                if diagnostic.is_error() {
-                    self.deduplicated_err_count += 1;
-                } else if let Warning(_) = diagnostic.level {
-                    self.deduplicated_warn_count += 1;
-                }
+            if !self.buffer_parallel_diagnostic(diagnostic) {
+                self.emit_parallel_diagnostics();
+                self.emit_deduplicated_diagnostic(diagnostic);
             }
             if diagnostic.is_error() {
                 if matches!(diagnostic.level, Level::Error { lint: true }) {
@@ -1384,6 +1454,223 @@ fn emit_diagnostic(&mut self, diagnostic: &mut Diagnostic) -> Option<ErrorGuaran
         guaranteed
     }
 
+    fn emit_deduplicated_diagnostic(&mut self, diagnostic: &mut Diagnostic) {
+        if let Some(ref code) = diagnostic.code {
+            self.emitted_diagnostic_codes.insert(code.clone());
+        }
+
+        let already_emitted = |this: &mut Self| {
+            let mut hasher = StableHasher::new();
+            diagnostic.hash(&mut hasher);
+            let diagnostic_hash = hasher.finish();
+            !this.emitted_diagnostics.insert(diagnostic_hash)
+        };
+
+        // Only emit the diagnostic if we've been asked to deduplicate or
+        // haven't already emitted an equivalent diagnostic.
+        if !(self.flags.deduplicate_diagnostics && already_emitted(self)) {
+            debug!(?diagnostic);
+            debug!(?self.emitted_diagnostics);
+            let already_emitted_sub = |sub: &mut SubDiagnostic| {
+                debug!(?sub);
+                if sub.level != Level::OnceNote {
+                    return false;
+                }
+                let mut hasher = StableHasher::new();
+                sub.hash(&mut hasher);
+                let diagnostic_hash = hasher.finish();
+                debug!(?diagnostic_hash);
+                !self.emitted_diagnostics.insert(diagnostic_hash)
+            };
+
+            diagnostic.children.drain_filter(already_emitted_sub).for_each(|_| {});
+
+            // Lint warnings over the cap are only counted, see
+            // `emit_lint_warning_summaries`.
+            if !self.exceeds_warnings_per_lint_cap(diagnostic) {
+                if let Some(recorded) = &mut self.recorded_diagnostics {
+                    recorded.push(diagnostic.clone());
+                }
+                self.emit_or_group_diagnostic(diagnostic);
+            }
+            if diagnostic.is_error() {
+                self.deduplicated_err_count += 1;
+            } else if let Warning(_) = diagnostic.level {
+                self.deduplicated_warn_count += 1;
+            }
+        }
+    }
+
+    /// Buffers `diagnostic` together with the position of the running work item if it is
+    /// emitted from a parallel section and has to be emitted in sequential order, see
+    /// [`HandlerFlags::sequential_diagnostic_order`]. Returns whether it was buffered.
+    #[cfg(parallel_compiler)]
+    fn buffer_parallel_diagnostic(&mut self, diagnostic: &Diagnostic) -> bool {
+        // Bugs and fatal errors are emitted right away, as compilation stops after them.
+        if !self.flags.sequential_diagnostic_order
+            || self.flags.dont_buffer_diagnostics
+            || self.flags.treat_err_as_bug.is_some()
+            || matches!(diagnostic.level, Level::Bug | Level::Fatal)
+        {
+            return false;
+        }
+        let Some(position) = sequential_order::current_position() else { return false };
+        self.parallel_diagnostics.push((position, diagnostic.clone()));
+        true
+    }
+
+    #[cfg(not(parallel_compiler))]
+    fn buffer_parallel_diagnostic(&mut self, _diagnostic: &Diagnostic) -> bool {
+        false
+    }
+
+    /// Emit all diagnostics buffered by [`HandlerFlags::sequential_diagnostic_order`], in the
+    /// order in which a single-threaded compiler would have emitted them.
+    #[cfg(parallel_compiler)]
+    fn emit_parallel_diagnostics(&mut self) {
+        let mut diagnostics: Vec<_> = std::mem::take(&mut self.parallel_diagnostics)
+            .into_iter()
+            .map(|(position, diagnostic)| (position.resolve(), diagnostic))
+            .collect();
+        // The sort is stable, so diagnostics of the same work item keep their order.
+        diagnostics.sort_by(|(a, _), (b, _)| a.cmp(b));
+        for (_, mut diagnostic) in diagnostics {
+            self.emit_deduplicated_diagnostic(&mut diagnostic);
+        }
+    }
+
+    #[cfg(not(parallel_compiler))]
+    fn emit_parallel_diagnostics(&mut self) {}
+
+    /// Emits `diagnostic`, or holds it back until the end of the pass if
+    /// [`HandlerFlags::diagnostic_grouping`] is enabled. If an error of the same group was
+    /// already held back, `diagnostic` is folded into it.
+    fn emit_or_group_diagnostic(&mut self, diagnostic: &Diagnostic) {
+        if !self.flags.diagnostic_grouping
+            || self.flags.dont_buffer_diagnostics
+            || self.flags.treat_err_as_bug.is_some()
+            || matches!(diagnostic.level, Level::Bug | Level::Fatal)
+        {
+            // Compilation stops after bugs and fatal errors, so whatever was held back has to
+            // come first.
+            self.emit_diagnostic_groups();
+            self.emitter.emit_diagnostic(diagnostic);
+            return;
+        }
+
+        let key = self.diagnostic_group_key(diagnostic);
+        if let Some(&idx) = key.and_then(|key| self.diagnostic_group_indices.get(&key)) {
+            let group = &mut self.diagnostic_groups[idx];
+            group.similar.extend(diagnostic.span.primary_span());
+            // Suggestions are specific to each error, so keep all of them.
+            if let (Ok(kept), Ok(suggestions)) =
+                (&mut group.representative.suggestions, &diagnostic.suggestions)
+            {
+                kept.extend(suggestions.iter().cloned());
+            }
+            return;
+        }
+        if let Some(key) = key {
+            self.diagnostic_group_indices.insert(key, self.diagnostic_groups.len());
+        }
+        self.diagnostic_groups
+            .push(DiagnosticGroup { representative: diagnostic.clone(), similar: Vec::new() });
+    }
+
+    /// Returns the key under which `diagnostic` is grouped with similar errors, or `None` if
+    /// it is never grouped.
+    fn diagnostic_group_key(&self, diagnostic: &Diagnostic) -> Option<u128> {
+        if diagnostic.level != (Level::Error { lint: false }) {
+            return None;
+        }
+        let Some(DiagnosticId::Error(code)) = &diagnostic.code else { return None };
+        diagnostic.span.primary_span()?;
+
+        // Two errors have the same primary cause if everything but their spans is the same.
+        // The translated messages are compared, rather than the messages and the arguments
+        // they are built from, because unrelated arguments may be set on the diagnostic.
+        let args = translation::to_fluent_args(diagnostic.args());
+        let mut hasher = StableHasher::new();
+        code.hash(&mut hasher);
+        self.emitter.translate_messages(&diagnostic.message, &args).hash(&mut hasher);
+        for label in diagnostic.span.span_labels() {
+            label.is_primary.hash(&mut hasher);
+            if let Some(label) = &label.label {
+                let label = self.emitter.translate_message(label, &args);
+                label.map_err(Report::new).unwrap().hash(&mut hasher);
+            }
+        }
+        for child in &diagnostic.children {
+            child.level.hash(&mut hasher);
+            self.emitter.translate_messages(&child.message, &args).hash(&mut hasher);
+        }
+        Some(hasher.finish())
+    }
+
+    /// Counts `diagnostic` if it is a lint warning, and returns whether that lint has already
+    /// emitted as many warnings as `--cap-warnings-per-lint` allows.
+    fn exceeds_warnings_per_lint_cap(&mut self, diagnostic: &Diagnostic) -> bool {
+        let Some(cap) = self.flags.warnings_per_lint_cap else { return false };
+        if !matches!(diagnostic.level, Warning(_)) {
+            return false;
+        }
+        // Lints explicitly requested with `--force-warn` are never capped.
+        let Some(DiagnosticId::Lint { name, is_force_warn: false, .. }) = &diagnostic.code else {
+            return false;
+        };
+        let count = self.lint_warning_counts.entry(name.clone()).or_insert(0);
+        *count += 1;
+        *count > cap
+    }
+
+    /// Emit a note for every lint that emitted more warnings than `--cap-warnings-per-lint`
+    /// allows, with the total number of warnings it emitted.
+    fn emit_lint_warning_summaries(&mut self) {
+        let Some(cap) = self.flags.warnings_per_lint_cap else { return };
+        let counts = std::mem::take(&mut self.lint_warning_counts);
+        for (name, count) in counts {
+            if count <= cap {
+                continue;
+            }
+            let hidden = count - cap;
+            let msg = format!(
+                "lint `{name}` emitted {count} warnings, {hidden} of which {} not shown because \
+                 of `--cap-warnings-per-lint={cap}`",
+                if hidden == 1 { "was" } else { "were" }
+            );
+            // The note carries the lint name as its code, so that tools reading the JSON output
+            // can tell which lint it summarizes.
+            let code =
+                DiagnosticId::Lint { name, has_future_breakage: false, is_force_warn: false };
+            self.emitter.emit_diagnostic(&Diagnostic::new_with_code(Level::Note, Some(code), msg));
+        }
+    }
+
+    /// Emit the diagnostics held back by `-Z diagnostic-grouping`, with one representative
+    /// error for every group.
+    fn emit_diagnostic_groups(&mut self) {
+        self.diagnostic_group_indices.clear();
+        for DiagnosticGroup { mut representative, similar } in
+            std::mem::take(&mut self.diagnostic_groups)
+        {
+            if !similar.is_empty() {
+                let mut msg = format!(
+                    "and {} similar error{} at:",
+                    similar.len(),
+                    pluralize!(similar.len())
+                );
+                if let Some(source_map) = self.emitter.source_map() {
+                    for span in similar {
+                        msg.push('\n');
+                        msg.push_str(&source_map.span_to_diagnostic_string(span));
+                    }
+                }
+                representative.note(msg);
+            }
+            self.emitter.emit_diagnostic(&representative);
+        }
+    }
+
     fn emit_artifact_notification(&mut self, path: &Path, artifact_type: &str) {
         self.emitter.emit_artifact_notification(path, artifact_type);
     }
@@ -1404,6 +1691,8 @@ fn delayed_bug_count(&self) -> usize {
 
     fn print_error_count(&mut self, registry: &Registry) {
         self.emit_stashed_diagnostics();
+        self.emit_diagnostic_groups();
+        self.emit_lint_warning_summaries();
 
         let warnings = match self.deduplicated_warn_count {
             0 => String::new(),
@@ -1535,6 +1824,7 @@ fn is_compilation_going_to_fail(&self) -> bool {
 
     fn abort_if_errors(&mut self) {
         self.emit_stashed_diagnostics();
+        self.emit_diagnostic_groups();
 
         if self.has_errors() {
             FatalError.raise();
@@ -0,0 +1,167 @@
+//! Snapshots of the emitted diagnostics, for tools that embed the compiler.
+//!
+//! The diagnostics in a snapshot have their messages translated and their spans resolved to
+//! file, line and column ranges, so tools can present them without installing their own
+//! [`Emitter`] or parsing the JSON output.
+
+use crate::emitter::Emitter;
+use crate::translation::to_fluent_args;
+use crate::{Applicability, Diagnostic, DiagnosticId, DiagnosticMessage, Level, MultiSpan};
+use rustc_span::source_map::SourceMap;
+use rustc_span::Span;
+use std::error::Report;
+
+/// The phase of compilation after which a snapshot was taken.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum SnapshotPhase {
+    Parse,
+    Expansion,
+    Analysis,
+    /// Compilation ended without aborting, successfully or not. The snapshot has the
+    /// diagnostics that were not passed in a previous snapshot, e.g. those of codegen, or of
+    /// the phases whose snapshots were not taken.
+    Finish,
+    /// Compilation was aborted by a fatal error or a panic. The snapshot has the diagnostics
+    /// that were not passed in a previous snapshot.
+    Abort,
+}
+
+/// A diagnostic as it was emitted, with its messages translated and its spans resolved.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ResolvedDiagnostic {
+    pub level: Level,
+    pub message: String,
+    /// Always `None` for children.
+    pub code: Option<DiagnosticId>,
+    pub spans: Vec<ResolvedSpan>,
+    /// Always empty for children.
+    pub children: Vec<ResolvedDiagnostic>,
+    /// Always empty for children.
+    pub suggestions: Vec<ResolvedSuggestion>,
+}
+
+/// A span resolved to a range of lines and columns in a file.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ResolvedSpan {
+    /// The name of the file, as it is printed in diagnostics.
+    pub file_name: String,
+    /// The byte offsets of the span in the file, end exclusive.
+    pub byte_start: u32,
+    pub byte_end: u32,
+    /// 1-based, inclusive.
+    pub line_start: usize,
+    pub line_end: usize,
+    /// 1-based, counted in `char`s, end exclusive.
+    pub column_start: usize,
+    pub column_end: usize,
+    pub is_primary: bool,
+    pub label: Option<String>,
+}
+
+/// A suggestion, with the replacements of each of its alternatives resolved.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ResolvedSuggestion {
+    pub message: String,
+    pub applicability: Applicability,
+    /// The alternatives of the suggestion. The replacements of an alternative must all be
+    /// applied together.
+    pub substitutions: Vec<Vec<(ResolvedSpan, String)>>,
+}
+
+impl ResolvedDiagnostic {
+    pub(crate) fn from_diagnostic(diag: &Diagnostic, emitter: &dyn Emitter) -> Self {
+        let args = to_fluent_args(diag.args());
+        let source_map = emitter.source_map().map(|sm| &**sm);
+        let translate = |message: &DiagnosticMessage| {
+            emitter.translate_message(message, &args).map_err(Report::new).unwrap().into_owned()
+        };
+        let resolve_spans = |span: &MultiSpan| -> Vec<ResolvedSpan> {
+            let Some(source_map) = source_map else { return Vec::new() };
+            span.span_labels()
+                .into_iter()
+                .filter_map(|label| {
+                    let text = label.label.as_ref().map(translate);
+                    ResolvedSpan::resolve(source_map, label.span, label.is_primary, text)
+                })
+                .collect()
+        };
+
+        let children = diag
+            .children
+            .iter()
+            .map(|child| ResolvedDiagnostic {
+                level: child.level,
+                message: emitter.translate_messages(&child.message, &args).into_owned(),
+                code: None,
+                spans: resolve_spans(&child.span),
+                children: Vec::new(),
+                suggestions: Vec::new(),
+            })
+            .collect();
+
+        let suggestions = diag
+            .suggestions
+            .as_deref()
+            .unwrap_or(&[])
+            .iter()
+            .map(|suggestion| ResolvedSuggestion {
+                message: translate(&suggestion.msg),
+                applicability: suggestion.applicability,
+                substitutions: suggestion
+                    .substitutions
+                    .iter()
+                    .map(|substitution| {
+                        substitution
+                            .parts
+                            .iter()
+                            .filter_map(|part| {
+                                let span = ResolvedSpan::resolve(
+                                    source_map?,
+                                    part.span,
+                                    true,
+                                    None,
+                                )?;
+                                Some((span, part.snippet.clone()))
+                            })
+                            .collect()
+                    })
+                    .collect(),
+            })
+            .collect();
+
+        ResolvedDiagnostic {
+            level: diag.level,
+            message: emitter.translate_messages(&diag.message, &args).into_owned(),
+            code: diag.code.clone(),
+            spans: resolve_spans(&diag.span),
+            children,
+            suggestions,
+        }
+    }
+}
+
+impl ResolvedSpan {
+    fn resolve(
+        source_map: &SourceMap,
+        span: Span,
+        is_primary: bool,
+        label: Option<String>,
+    ) -> Option<Self> {
+        if span.is_dummy() {
+            return None;
+        }
+        let start = source_map.lookup_char_pos(span.lo());
+        let end = source_map.lookup_char_pos(span.hi());
+        Some(ResolvedSpan {
+            file_name: source_map.filename_for_diagnostics(&start.file.name).to_string(),
+            byte_start: start.file.original_relative_byte_pos(span.lo()).0,
+            byte_end: start.file.original_relative_byte_pos(span.hi()).0,
+            line_start: start.line,
+            line_end: end.line,
+            column_start: start.col.0 + 1,
+            column_end: end.col.0 + 1,
+            is_primary,
+            label,
+        })
+    }
+}
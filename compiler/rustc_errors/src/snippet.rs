@@ -1,5 +1,6 @@
 // Code for annotating snippets.
 
+use crate::highlight::SyntaxClass;
 use crate::Level;
 
 #[derive(Clone, Debug, PartialOrd, Ord, PartialEq, Eq)]
@@ -176,4 +177,5 @@ pub enum Style {
     Highlight,
     Addition,
     Removal,
+    Syntax(SyntaxClass),
 }
@@ -122,7 +122,8 @@ pub fn num_lines(&self) -> usize {
 
     /// Set `style` for `line`, `col_start..col_end` range if:
     /// 1. That line and column range exist in `StyledBuffer`
-    /// 2. `overwrite` is `true` or existing style is `Style::NoStyle` or `Style::Quotation`
+    /// 2. `overwrite` is `true` or existing style is `Style::NoStyle`, `Style::Quotation` or
+    ///    `Style::Syntax`
     pub fn set_style_range(
         &mut self,
         line: usize,
@@ -142,7 +143,9 @@ pub fn set_style_range(
     pub fn set_style(&mut self, line: usize, col: usize, style: Style, overwrite: bool) {
         if let Some(ref mut line) = self.lines.get_mut(line) {
             if let Some(StyledChar { style: s, .. }) = line.get_mut(col) {
-                if overwrite || *s == Style::NoStyle || *s == Style::Quotation {
+                if overwrite
+                    || matches!(*s, Style::NoStyle | Style::Quotation | Style::Syntax(_))
+                {
                     *s = style;
                 }
             }
@@ -1,11 +1,21 @@
+use crate::emitter::EmitterWriter;
 use crate::error::{TranslateError, TranslateErrorKind};
 use crate::fluent_bundle::*;
+use crate::highlight::{highlight_line, SyntaxClass};
 use crate::translation::Translate;
-use crate::FluentBundle;
+use crate::{FluentBundle, Handler, HandlerFlags};
+#[cfg(parallel_compiler)]
+use rustc_data_structures::sequential_order::Section;
 use rustc_data_structures::sync::Lrc;
 use rustc_error_messages::fluent_bundle::resolver::errors::{ReferenceKind, ResolverError};
 use rustc_error_messages::langid;
 use rustc_error_messages::DiagnosticMessage;
+use rustc_span::edition::Edition;
+use rustc_span::source_map::{FilePathMapping, SourceMap};
+use rustc_span::{BytePos, Span};
+use std::io::{self, Write};
+use std::path::Path;
+use std::sync::{Arc, Mutex};
 
 struct Dummy {
     bundle: FluentBundle,
@@ -186,3 +196,162 @@ fn misformed_fluent() {
         );
     }
 }
+
+struct Shared(Arc<Mutex<Vec<u8>>>);
+
+impl Write for Shared {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.0.lock().unwrap().write(buf)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.0.lock().unwrap().flush()
+    }
+}
+
+/// Renders an error spanning `span` in `code`, with colors and syntax highlighting if `colored`.
+fn render_error(code: &str, span: (u32, u32), colored: bool) -> String {
+    rustc_span::create_default_session_globals_then(|| {
+        let sm = Lrc::new(SourceMap::new(FilePathMapping::empty()));
+        sm.new_source_file(Path::new("test.rs").to_owned().into(), code.to_owned());
+        let fallback_bundle =
+            crate::fallback_fluent_bundle(rustc_error_messages::DEFAULT_LOCALE_RESOURCES, false);
+
+        let output = Arc::new(Mutex::new(Vec::new()));
+        let emitter = EmitterWriter::new(
+            Box::new(Shared(output.clone())),
+            Some(sm),
+            None,
+            fallback_bundle,
+            false,
+            false,
+            colored,
+            None,
+            false,
+            false,
+        )
+        .ui_testing(true)
+        .syntax_highlighting(colored.then_some(Edition::Edition2021));
+
+        let span = Span::with_root_ctxt(BytePos(span.0), BytePos(span.1));
+        let handler = Handler::with_emitter(true, None, Box::new(emitter));
+        handler.span_err(span, "foo");
+
+        let bytes = output.lock().unwrap();
+        String::from_utf8(bytes.clone()).unwrap()
+    })
+}
+
+fn strip_ansi_escapes(s: &str) -> String {
+    let mut stripped = String::new();
+    let mut chars = s.chars();
+    while let Some(c) = chars.next() {
+        if c == '\u{1b}' {
+            chars.by_ref().find(|&c| c == 'm');
+        } else {
+            stripped.push(c);
+        }
+    }
+    stripped
+}
+
+#[test]
+fn syntax_highlighting_keeps_alignment() {
+    let code = "fn main() {\n    let s = \"héllo\"; /* é */ foo!(s, 'a', 42);\n}\n";
+    let start = code.find("42").unwrap() as u32;
+    let plain = render_error(code, (start, start + 2), false);
+    let highlighted = render_error(code, (start, start + 2), true);
+
+    assert_eq!(strip_ansi_escapes(&highlighted), plain);
+    assert!(highlighted.contains("\u{1b}[35mlet"), "{highlighted:?}");
+    assert!(highlighted.contains("\u{1b}[36mfoo!"), "{highlighted:?}");
+    assert!(highlighted.contains("\u{1b}[32m\"héllo\""), "{highlighted:?}");
+}
+
+#[cfg(parallel_compiler)]
+#[test]
+fn parallel_diagnostics_in_sequential_order() {
+    rustc_span::create_default_session_globals_then(|| {
+        let fallback_bundle =
+            crate::fallback_fluent_bundle(rustc_error_messages::DEFAULT_LOCALE_RESOURCES, false);
+        let output = Arc::new(Mutex::new(Vec::new()));
+        let emitter = EmitterWriter::new(
+            Box::new(Shared(output.clone())),
+            None,
+            None,
+            fallback_bundle,
+            false,
+            false,
+            false,
+            None,
+            false,
+            false,
+        );
+        let flags = HandlerFlags {
+            can_emit_warnings: true,
+            sequential_diagnostic_order: true,
+            ..Default::default()
+        };
+        let handler = Handler::with_emitter_and_flags(Box::new(emitter), flags);
+
+        // Emit from the items of a section in reverse order, like a parallel compiler might.
+        let section = Section::enter();
+        section.run_item(1, || handler.warn("second"));
+        section.run_item(0, || {
+            handler.err("first");
+        });
+        // Diagnostics are counted right away, but only emitted later.
+        assert_eq!(handler.err_count(), 1);
+        assert!(output.lock().unwrap().is_empty());
+
+        handler.warn("third");
+        let output = String::from_utf8(output.lock().unwrap().clone()).unwrap();
+        let positions =
+            ["error: first", "warning: second", "warning: third"].map(|msg| output.find(msg));
+        assert!(positions.iter().all(Option::is_some), "{output:?}");
+        assert!(positions.windows(2).all(|w| w[0] < w[1]), "{output:?}");
+    })
+}
+
+/// Returns the highlighted parts of `line` along with their classes.
+fn highlighted(line: &str, edition: Edition) -> Vec<(String, SyntaxClass)> {
+    let chars: Vec<char> = line.chars().collect();
+    rustc_span::create_default_session_globals_then(|| {
+        highlight_line(line, edition)
+            .into_iter()
+            .map(|(range, class)| (chars[range].iter().collect(), class))
+            .collect()
+    })
+}
+
+#[test]
+fn highlight_line_classes() {
+    let line = "let é = 'a'; 'l: loop { println!(\"{}\", 1.5 != x); } // é";
+    assert_eq!(
+        highlighted(line, Edition::Edition2021),
+        [
+            ("let".to_string(), SyntaxClass::Keyword),
+            ("'a'".to_string(), SyntaxClass::String),
+            ("'l".to_string(), SyntaxClass::Lifetime),
+            ("loop".to_string(), SyntaxClass::Keyword),
+            ("println!".to_string(), SyntaxClass::Macro),
+            ("\"{}\"".to_string(), SyntaxClass::String),
+            ("1.5".to_string(), SyntaxClass::Number),
+            ("// é".to_string(), SyntaxClass::Comment),
+        ]
+    );
+}
+
+#[test]
+fn highlight_line_edition_keywords() {
+    let line = "async fn f() -> Box<dyn T> { try {} }";
+    let keywords = |edition| -> Vec<String> {
+        highlighted(line, edition)
+            .into_iter()
+            .filter(|(_, class)| *class == SyntaxClass::Keyword)
+            .map(|(text, _)| text)
+            .collect()
+    };
+    assert_eq!(keywords(Edition::Edition2015), ["fn"]);
+    assert_eq!(keywords(Edition::Edition2018), ["async", "fn", "dyn", "try"]);
+}
@@ -17,8 +17,10 @@
 use assert_dep_graph::assert_dep_graph;
 pub use persist::copy_cgu_workproduct_to_incr_comp_cache_dir;
 pub use persist::delete_workproduct_files;
+pub use persist::explain_cgu_recompilation;
 pub use persist::finalize_session_directory;
 pub use persist::garbage_collect_session_directories;
+pub use persist::import_work_product;
 pub use persist::in_incr_comp_dir;
 pub use persist::in_incr_comp_dir_sess;
 pub use persist::load_query_result_cache;
@@ -0,0 +1,46 @@
+//! The hash of the tracked command-line options that the dependency graph is saved with, and
+//! that it has to match to be loaded.
+//!
+//! The output types are only hashed as far as they need codegen, and so are the crate hash
+//! and `output_filenames`, so that builds that only emit metadata and builds that also link
+//! share their incremental state both ways: a build that links reuses the analysis of a
+//! build that only emitted metadata, and a build that only emits metadata keeps the work
+//! products of a build that linked, see `retained_work_products` in `rustc_codegen_ssa`.
+//! Queries that are only needed for linking, like `exported_symbols`, aren't run at all
+//! without codegen, instead of returning something different. As the crate hash is the same,
+//! the builds of dependent crates share their state too. The metadata of a build that doesn't
+//! codegen is still identified by a different hash when crates are loaded, see
+//! `CrateRoot::hash` in `rustc_metadata`, so that it is never loaded in place of the metadata
+//! of one that does.
+//!
+//! This needs builds to pass the same options apart from `--emit`, `--out-dir` and
+//! `-C extra-filename`, and to use the same incremental directory. That's how `cargo check`
+//! followed by `cargo build` invokes rustc, as long as it passes the same `-C metadata` to
+//! both, which picks the `StableCrateId` of the crate and with it the session directory
+//! within the incremental directory; see the `check-then-build` run-make test.
+
+use rustc_session::config::Options;
+use rustc_session::Session;
+use std::collections::BTreeMap;
+
+use super::shared_cache;
+
+/// Returns the hash of the tracked command-line options that the dependency
+/// graph is saved with, and that it has to match to be loaded.
+pub fn commandline_args_hash(sess: &Session) -> u64 {
+    hashed_options(sess).dep_tracking_hash(false)
+}
+
+/// Returns the hashes of the individual options that make up
+/// [`commandline_args_hash`], to tell which options changed when it does.
+pub fn commandline_args_hashes(sess: &Session) -> BTreeMap<String, u64> {
+    hashed_options(sess).dep_tracking_hashes(false)
+}
+
+/// Returns the options as far as they are hashed into [`commandline_args_hash`].
+fn hashed_options(sess: &Session) -> Options {
+    let mut opts = sess.opts.clone();
+    opts.output_types = opts.output_types.for_incremental_state();
+    shared_cache::make_path_independent(sess, &mut opts);
+    opts
+}
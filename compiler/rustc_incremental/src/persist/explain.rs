@@ -0,0 +1,156 @@
+//! Explanations of why an incremental session redoes work, for `-Z incremental-explain`.
+//!
+//! Each explanation walks the previous dep-graph from the redone codegen unit or query to
+//! the dependency that changed, see `DepGraph::red_dependency_chain`, and is printed to
+//! stderr, either as text or as one JSON object per line.
+
+use rustc_middle::dep_graph::{DepContext, DepKind, DepNode, DepNodeExt, RedDependencyCause};
+use rustc_middle::ty::print::with_no_trimmed_paths;
+use rustc_middle::ty::TyCtxt;
+use rustc_session::config::IncrementalExplainFormat;
+use rustc_span::Symbol;
+
+/// The chain of dependencies from a redone dep-node to the dependency that changed.
+struct Explanation {
+    /// What happened to the last dependency of the chain, `None` if the redone dep-node did
+    /// not exist in the previous session.
+    cause: Option<RedDependencyCause>,
+    /// The dependency to blame, usually the last one of the chain.
+    culprit: Option<String>,
+    /// The dependencies of the redone dep-node, starting at the one that changed.
+    chain: Vec<String>,
+}
+
+impl Explanation {
+    fn new(tcx: TyCtxt<'_>, dep_node: Option<&DepNode>) -> Explanation {
+        let chain =
+            dep_node.and_then(|dep_node| tcx.dep_graph.red_dependency_chain(tcx, dep_node));
+        let (cause, mut nodes) = match chain {
+            // The chain starts with the redone dep-node itself, leave it out.
+            Some(chain) => (Some(chain.cause), chain.nodes[1..].to_vec()),
+            None => (None, Vec::new()),
+        };
+        // Start the chain at the dependency that changed.
+        nodes.reverse();
+
+        // Eval-always nodes like `hir_crate` change whenever anything at all changes, so
+        // blame the first node depending on them instead.
+        let culprit = match cause {
+            Some(RedDependencyCause::Changed) => {
+                nodes.iter().find(|node| !tcx.is_eval_always(node.kind)).or(nodes.first())
+            }
+            _ => nodes.first(),
+        };
+
+        Explanation {
+            cause,
+            culprit: culprit.map(|node| describe(tcx, node)),
+            chain: nodes.iter().map(|node| describe(tcx, node)).collect(),
+        }
+    }
+
+    /// Prints the explanation as text, as the reason why `what` is `redone`.
+    fn print_text(&self, what: &str, redone: &str) {
+        let because = match (self.cause, &self.culprit) {
+            (Some(RedDependencyCause::Changed), Some(culprit)) => format!("`{culprit}` changed"),
+            (Some(RedDependencyCause::Removed), Some(culprit)) => {
+                format!("`{culprit}` no longer exists")
+            }
+            (Some(RedDependencyCause::Unknown), Some(culprit)) => {
+                format!("`{culprit}` was not recomputed and may have changed")
+            }
+            _ => "it was not compiled in the previous session".to_string(),
+        };
+        let mut message = format!("[incremental] {what} is {redone} because {because}");
+        for (i, node) in self.chain.iter().enumerate() {
+            message.push_str(if i == 0 { ":\n    " } else { "\n    -> " });
+            message.push_str(node);
+        }
+        eprintln!("{message}");
+    }
+
+    /// Prints the explanation as a JSON object, with `key` set to `value`.
+    fn print_json(&self, key: &str, value: &str) {
+        let reason = match self.cause {
+            Some(RedDependencyCause::Changed) => "changed",
+            Some(RedDependencyCause::Removed) => "removed",
+            Some(RedDependencyCause::Unknown) => "unknown",
+            None => "new",
+        };
+        let explanation = serde_json::json!({
+            key: value,
+            "reason": reason,
+            "culprit": self.culprit,
+            "chain": self.chain,
+        });
+        eprintln!("{explanation}");
+    }
+}
+
+fn describe(tcx: TyCtxt<'_>, node: &DepNode) -> String {
+    match node.extract_def_id(tcx) {
+        Some(def_id) => {
+            with_no_trimmed_paths!(format!("{:?}({})", node.kind, tcx.def_path_str(def_id)))
+        }
+        None => format!("{:?}", node),
+    }
+}
+
+/// Explains why the codegen unit `cgu_name` is recompiled. `dep_node` is its
+/// `CompileCodegenUnit` dep-node that could not be marked green, if anything was cached for
+/// the codegen unit at all.
+pub fn explain_cgu_recompilation(tcx: TyCtxt<'_>, cgu_name: Symbol, dep_node: Option<&DepNode>) {
+    let Some(format) = tcx.sess.opts.unstable_opts.incremental_explain else { return };
+
+    let explanation = Explanation::new(tcx, dep_node);
+    match format {
+        IncrementalExplainFormat::Text => {
+            explanation.print_text(&format!("codegen unit `{cgu_name}`"), "recompiled")
+        }
+        IncrementalExplainFormat::Json => explanation.print_json("cgu", cgu_name.as_str()),
+    }
+}
+
+/// Explains why the queries whose results changed since the previous session are
+/// re-executed. Queries that were re-executed to the same result are left out, as they
+/// don't cause any further work.
+pub(crate) fn explain_reexecuted_queries(tcx: TyCtxt<'_>, format: IncrementalExplainFormat) {
+    for dep_node in tcx.dep_graph.red_nodes() {
+        // Eval-always queries are re-executed in every session and are blamed by the
+        // queries depending on them, codegen units are explained when they are recompiled.
+        if tcx.is_eval_always(dep_node.kind) || dep_node.kind == DepKind::CompileCodegenUnit {
+            continue;
+        }
+
+        let explanation = Explanation::new(tcx, Some(&dep_node));
+        let query = describe(tcx, &dep_node);
+        match format {
+            IncrementalExplainFormat::Text => {
+                explanation.print_text(&format!("query `{query}`"), "re-executed")
+            }
+            IncrementalExplainFormat::Json => explanation.print_json("query", &query),
+        }
+    }
+}
+
+/// Explains that nothing of the previous session is reused because the tracked
+/// command-line options in `changed` changed.
+pub(crate) fn explain_changed_options(format: IncrementalExplainFormat, changed: &[String]) {
+    match format {
+        IncrementalExplainFormat::Text => {
+            let options =
+                changed.iter().map(|option| format!("`{option}`")).collect::<Vec<_>>().join(", ");
+            eprintln!(
+                "[incremental] all codegen units are recompiled because the tracked \
+                 command-line options changed: {options}"
+            );
+        }
+        IncrementalExplainFormat::Json => {
+            let explanation = serde_json::json!({
+                "reason": "command-line",
+                "options": changed,
+            });
+            eprintln!("{explanation}");
+        }
+    }
+}
@@ -118,6 +118,8 @@
 
 use rand::{thread_rng, RngCore};
 
+use super::shared_cache;
+
 #[cfg(test)]
 mod tests;
 
@@ -126,6 +128,7 @@
 const STAGING_DEP_GRAPH_FILENAME: &str = "dep-graph.part.bin";
 const WORK_PRODUCTS_FILENAME: &str = "work-products.bin";
 const QUERY_CACHE_FILENAME: &str = "query-cache.bin";
+const CODEGEN_UNIT_DEPS_FILENAME: &str = "codegen-unit-deps.bin";
 
 // We encode integers using the following base, so they are shorter than decimal
 // or hexadecimal numbers (we want short file and directory names). Since these
@@ -151,6 +154,11 @@ pub fn work_products_path(sess: &Session) -> PathBuf {
 pub fn query_cache_path(sess: &Session) -> PathBuf {
     in_incr_comp_dir_sess(sess, QUERY_CACHE_FILENAME)
 }
+/// Returns the path to the dependencies of a session's codegen units that are
+/// shared along with its work products, see `shared_cache`.
+pub fn codegen_unit_deps_path(sess: &Session) -> PathBuf {
+    in_incr_comp_dir_sess(sess, CODEGEN_UNIT_DEPS_FILENAME)
+}
 
 /// Locks a given session directory.
 pub fn lock_file_path(session_dir: &Path) -> PathBuf {
@@ -347,6 +355,10 @@ pub fn finalize_session_directory(sess: &Session, svh: Svh) {
 
     debug!("finalize_session_directory() - session directory: {}", incr_comp_session_dir.display());
 
+    if sess.has_errors_or_delayed_span_bugs().is_none() {
+        shared_cache::export_work_products(sess, &incr_comp_session_dir);
+    }
+
     let old_sub_dir_name = incr_comp_session_dir.file_name().unwrap().to_string_lossy();
     assert_no_characters_lost(&old_sub_dir_name);
 
@@ -8,9 +8,12 @@
 use rustc_serialize::Decodable;
 use rustc_session::config::IncrementalStateAssertion;
 use rustc_session::Session;
+use std::collections::BTreeMap;
 use std::path::Path;
 
+use super::commandline_args;
 use super::data::*;
+use super::explain;
 use super::file_format;
 use super::fs::*;
 use super::work_product;
@@ -142,7 +145,13 @@ pub fn load_dep_graph(sess: &Session) -> DepGraphFuture {
     // Fortunately, we just checked that this isn't the case.
     let path = dep_graph_path(&sess);
     let report_incremental_info = sess.opts.unstable_opts.incremental_info;
-    let expected_hash = sess.opts.dep_tracking_hash(false);
+    let explain = sess.opts.unstable_opts.incremental_explain;
+    let expected_hash = commandline_args::commandline_args_hash(sess);
+    let expected_hashes = if explain.is_some() {
+        commandline_args::commandline_args_hashes(sess)
+    } else {
+        BTreeMap::new()
+    };
 
     let mut prev_work_products = FxHashMap::default();
     let nightly_build = sess.is_nightly_build();
@@ -189,6 +198,7 @@ pub fn load_dep_graph(sess: &Session) -> DepGraphFuture {
             LoadResult::Ok { data: (bytes, start_pos) } => {
                 let mut decoder = MemDecoder::new(&bytes, start_pos);
                 let prev_commandline_args_hash = u64::decode(&mut decoder);
+                let prev_commandline_args_hashes = BTreeMap::<String, u64>::decode(&mut decoder);
 
                 if prev_commandline_args_hash != expected_hash {
                     if report_incremental_info {
@@ -197,6 +207,21 @@ pub fn load_dep_graph(sess: &Session) -> DepGraphFuture {
                                     differing commandline arguments"
                         );
                     }
+                    if let Some(format) = explain {
+                        let mut changed: Vec<String> = expected_hashes
+                            .iter()
+                            .filter(|&(option, hash)| {
+                                prev_commandline_args_hashes.get(option) != Some(hash)
+                            })
+                            .map(|(option, _)| option.clone())
+                            .collect();
+                        changed.extend(
+                            prev_commandline_args_hashes
+                                .into_keys()
+                                .filter(|option| !expected_hashes.contains_key(option)),
+                        );
+                        explain::explain_changed_options(format, &changed);
+                    }
                     // We can't reuse the cache, purge it.
                     debug!("load_dep_graph_new: differing commandline arg hashes");
 
@@ -2,14 +2,18 @@
 //! into the given directory. At the same time, it also hashes the
 //! various HIR nodes.
 
+mod commandline_args;
 mod data;
 mod dirty_clean;
+mod explain;
 mod file_format;
 mod fs;
 mod load;
 mod save;
+mod shared_cache;
 mod work_product;
 
+pub use explain::explain_cgu_recompilation;
 pub use fs::finalize_session_directory;
 pub use fs::garbage_collect_session_directories;
 pub use fs::in_incr_comp_dir;
@@ -21,5 +25,6 @@
 pub use save::build_dep_graph;
 pub use save::save_dep_graph;
 pub use save::save_work_product_index;
+pub use shared_cache::import_work_product;
 pub use work_product::copy_cgu_workproduct_to_incr_comp_cache_dir;
 pub use work_product::delete_workproduct_files;
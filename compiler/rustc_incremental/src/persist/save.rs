@@ -7,10 +7,13 @@
 use rustc_session::Session;
 use std::fs;
 
+use super::commandline_args;
 use super::data::*;
 use super::dirty_clean;
+use super::explain;
 use super::file_format;
 use super::fs::*;
+use super::shared_cache;
 use super::work_product;
 
 /// Saves and writes the [`DepGraph`] to the file system.
@@ -42,6 +45,9 @@ pub fn save_dep_graph(tcx: TyCtxt<'_>) {
         if sess.opts.unstable_opts.incremental_info {
             tcx.dep_graph.print_incremental_info()
         }
+        if let Some(format) = sess.opts.unstable_opts.incremental_explain {
+            explain::explain_reexecuted_queries(tcx, format);
+        }
 
         join(
             move || {
@@ -76,6 +82,8 @@ pub fn save_dep_graph(tcx: TyCtxt<'_>) {
                 });
             },
         );
+
+        shared_cache::save_codegen_unit_deps(tcx);
     })
 }
 
@@ -174,8 +182,10 @@ pub fn build_dep_graph(
 
     file_format::write_file_header(&mut encoder, sess.is_nightly_build());
 
-    // First encode the commandline arguments hash
-    sess.opts.dep_tracking_hash(false).encode(&mut encoder);
+    // First encode the commandline arguments hash, and the hashes of the individual options
+    // to explain a change of it
+    commandline_args::commandline_args_hash(sess).encode(&mut encoder);
+    commandline_args::commandline_args_hashes(sess).encode(&mut encoder);
 
     Some(DepGraph::new(
         &sess.prof,
@@ -184,5 +194,6 @@ pub fn build_dep_graph(
         encoder,
         sess.opts.unstable_opts.query_dep_graph,
         sess.opts.unstable_opts.incremental_info,
+        sess.opts.unstable_opts.incremental_shared_cache.is_some(),
     ))
 }
@@ -0,0 +1,689 @@
+//! This module implements an optional, content-addressed store that shares
+//! work products between checkouts and machines. It is enabled with
+//! `-Z incremental-shared-cache=<dir>`.
+//!
+//! The local incremental compilation directory keeps working as described in
+//! the `fs` module. In addition, every session that finalizes its session
+//! directory exports its work products to the shared store, together with the
+//! nodes of the dependency graph that their codegen units depend on and the
+//! fingerprints these nodes had. A later session that can't reuse a work
+//! product of its own imports one whose dependencies still have the same
+//! fingerprints, just like it would mark a codegen unit of the previous
+//! session green. Query results are not shared: they are cheaper to recompute
+//! than to verify one by one.
+//!
+//! The store has the following layout:
+//!
+//! - `objects/{xx}/{hash}`: the files of exported work products. Each file is
+//!   named by the SHA-256 hash of its contents, so files that are identical
+//!   across sessions are stored once, and files whose contents don't match
+//!   their name are detected.
+//! - `entries/{crate-name-and-disambiguator}-{key}/{cgu-name}/{hash}`: the
+//!   work products exported for a codegen unit, each one with the
+//!   dependencies it was verified against and the objects holding its files.
+//!   An entry is named by the SHA-256 hash of its contents too, so sessions
+//!   that produce the same work product from the same dependencies share an
+//!   entry, and the entries of sessions that differ live side by side. The
+//!   key is the SHA-256 hash of the compiler version and a path-independent
+//!   hash of the command line, see [`commandline_args_hash`].
+//! - `tmp/`: files that are still being written.
+//!
+//! Files in the store are never modified once they are in place: they are
+//! written to `tmp/` and then renamed to their final path, which is atomic.
+//! Thus, any number of sessions can read from the store concurrently without
+//! taking any locks.
+//!
+//! Objects are always copied into and out of the store, never hard-linked, so
+//! that a store entry that gets corrupted can't corrupt the session
+//! directories of the checkouts that exported or imported it, and the other
+//! way around. Every imported file is hashed again after it has been copied,
+//! and an entry with an object that doesn't match its hash is not imported;
+//! the object is deleted so that the next export replaces it.
+//!
+//! Once the store takes up more than `-Z incremental-shared-cache-limit` MiB,
+//! exporting a session evicts the least recently imported or exported
+//! entries, and the objects that no remaining entry refers to. Objects that
+//! were written within the last [`GRACE_PERIOD`] are kept either way, as a
+//! concurrent export may not have written the entry that refers to them yet.
+//! Entries that refer to objects which no longer exist are skipped when
+//! importing.
+
+use rustc_data_structures::fingerprint::Fingerprint;
+use rustc_data_structures::fx::{FxHashMap, FxHashSet};
+use rustc_data_structures::stack::ensure_sufficient_stack;
+use rustc_macros::{Decodable, Encodable};
+use rustc_middle::dep_graph::{
+    DepContext, DepKind, DepNode, DepNodeIndex, SerializedDepGraph, SerializedDepNodeIndex,
+    WorkProduct,
+};
+use rustc_middle::mir::mono::CodegenUnit;
+use rustc_middle::ty::TyCtxt;
+use rustc_serialize::opaque::{MemDecoder, MemEncoder};
+use rustc_serialize::{Decodable, Encodable};
+use rustc_session::config::Options;
+use rustc_session::Session;
+use rustc_span::RealFileName;
+
+use std::collections::BTreeMap;
+use std::fs as std_fs;
+use std::io::{self, Read};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
+
+use rand::{thread_rng, RngCore};
+use sha2::{Digest, Sha256};
+
+use super::commandline_args::commandline_args_hash;
+use super::data::SerializedWorkProduct;
+use super::file_format;
+use super::fs::*;
+
+#[cfg(test)]
+mod tests;
+
+/// How long objects and temporary files are protected from eviction after
+/// they were written.
+const GRACE_PERIOD: Duration = Duration::from_secs(60 * 60);
+
+/// Makes `opts` independent of the path of the checkout when sharing state, so that
+/// checkouts at different paths get the same [`commandline_args_hash`].
+///
+/// The local path of a remapped working directory is left out, and so are the parts of
+/// `--remap-path-prefix` mappings that lie inside of it. An unmapped working directory is
+/// kept, as it ends up in the debuginfo of work products.
+pub fn make_path_independent(sess: &Session, opts: &mut Options) {
+    if sess.opts.unstable_opts.incremental_shared_cache.is_none() {
+        return;
+    }
+
+    let RealFileName::Remapped { local_path: Some(local_path), virtual_name } =
+        &sess.opts.working_dir
+    else {
+        return;
+    };
+
+    opts.working_dir =
+        RealFileName::Remapped { local_path: None, virtual_name: virtual_name.clone() };
+    for (from, _) in &mut opts.remap_path_prefix {
+        if let Ok(relative) = from.strip_prefix(local_path) {
+            *from = relative.to_path_buf();
+        }
+    }
+}
+
+/// The part of the dependency graph that the `CompileCodegenUnit` node of a
+/// codegen unit depends on.
+#[derive(Debug, Encodable, Decodable)]
+struct CodegenUnitDeps {
+    /// The recorded nodes, starting with the `CompileCodegenUnit` node.
+    nodes: Vec<RecordedNode>,
+}
+
+#[derive(Debug, Encodable, Decodable)]
+struct RecordedNode {
+    node: DepNode,
+    fingerprint: Fingerprint,
+    /// The indices of the dependencies of the node in [`CodegenUnitDeps::nodes`],
+    /// in the order in which they were read. They are only recorded for nodes
+    /// that another session may not be able to recompute, see
+    /// [`is_checked_directly`].
+    edges: Option<Vec<u32>>,
+}
+
+/// A work product as it is stored in an entry of the shared store.
+#[derive(Debug, Encodable, Decodable)]
+struct SharedWorkProduct {
+    deps: CodegenUnitDeps,
+    /// The kinds of the saved files, sorted, with the hashes of the objects holding them.
+    saved_files: Vec<(String, ContentHash)>,
+}
+
+/// Records the dependencies of the codegen units of the current session, for
+/// [`export_work_products`] to share along with their work products.
+///
+/// This reads back the dependency graph that was just saved, so it has to be
+/// called after it is in place.
+pub fn save_codegen_unit_deps(tcx: TyCtxt<'_>) {
+    let sess = tcx.sess;
+    if sess.opts.unstable_opts.incremental_shared_cache.is_none()
+        || sess.opts.unstable_opts.no_codegen
+        || !sess.opts.output_types.should_codegen()
+    {
+        return;
+    }
+    let _timer = sess.timer("incr_comp_save_codegen_unit_deps");
+
+    let graph: SerializedDepGraph =
+        match file_format::read_file(false, &dep_graph_path(sess), sess.is_nightly_build()) {
+            Ok(Some((bytes, start_pos))) => {
+                let mut decoder = MemDecoder::new(&bytes, start_pos);
+                // Skip the command-line hashes that the graph starts with.
+                let _: u64 = Decodable::decode(&mut decoder);
+                let _: BTreeMap<String, u64> = Decodable::decode(&mut decoder);
+                Decodable::decode(&mut decoder)
+            }
+            // Failing to save the dependency graph has been reported already.
+            Ok(None) | Err(_) => return,
+        };
+
+    let codegen_units = tcx.collect_and_partition_mono_items(()).1;
+    let deps: Vec<(String, CodegenUnitDeps)> = codegen_units
+        .iter()
+        .filter_map(|cgu| {
+            let index = graph.node_to_index_opt(&cgu.codegen_dep_node(tcx))?;
+            let mut deps = CodegenUnitDeps { nodes: Vec::new() };
+            record_node(tcx, &graph, index, &mut deps, &mut FxHashMap::default());
+            Some((cgu.name().to_string(), deps))
+        })
+        .collect();
+
+    let path = codegen_unit_deps_path(sess);
+    file_format::save_in(sess, path, "codegen unit dependencies", |mut e| {
+        deps.encode(&mut e);
+        e.finish()
+    });
+}
+
+/// Adds the node at `index` of `graph` to `deps`, and with it the nodes it
+/// depends on unless it is checked directly, and returns its index in `deps`.
+fn record_node(
+    tcx: TyCtxt<'_>,
+    graph: &SerializedDepGraph,
+    index: SerializedDepNodeIndex,
+    deps: &mut CodegenUnitDeps,
+    recorded: &mut FxHashMap<SerializedDepNodeIndex, u32>,
+) -> u32 {
+    if let Some(&i) = recorded.get(&index) {
+        return i;
+    }
+
+    let node = graph.index_to_node(index);
+    let fingerprint = graph.fingerprint_by_index(index);
+    let i = deps.nodes.len() as u32;
+    deps.nodes.push(RecordedNode { node, fingerprint, edges: None });
+    recorded.insert(index, i);
+
+    if !is_checked_directly(tcx, node, fingerprint) {
+        let edges = graph
+            .edge_targets_from(index)
+            .iter()
+            .map(|&edge| ensure_sufficient_stack(|| record_node(tcx, graph, edge, deps, recorded)))
+            .collect();
+        deps.nodes[i as usize].edges = Some(edges);
+    }
+    i
+}
+
+/// Returns whether any session can compare the fingerprint of `node` with the
+/// one it had when it was recorded, as the node can be recomputed from its key.
+/// The dependencies of all other nodes are recorded, so that a session that
+/// didn't compute the node itself can check them instead.
+fn is_checked_directly(tcx: TyCtxt<'_>, node: DepNode, fingerprint: Fingerprint) -> bool {
+    fingerprint != Fingerprint::ZERO
+        && !matches!(node.kind, DepKind::Null | DepKind::Red)
+        && tcx.fingerprint_style(node.kind).reconstructible()
+        && tcx.dep_kind_info(node.kind).force_from_dep_node.is_some()
+}
+
+/// Imports a work product for `cgu` from the shared store, if the store has
+/// one whose dependencies are unchanged in the current session.
+///
+/// The saved files of the work product are copied to the session directory,
+/// and the `CompileCodegenUnit` node of `cgu` is created, depending on the
+/// nodes that were checked, so that the next session can mark it green.
+pub fn import_work_product(tcx: TyCtxt<'_>, cgu: &CodegenUnit<'_>) -> Option<WorkProduct> {
+    let sess = tcx.sess;
+    let store = SharedStore::new(sess)?;
+    let _timer = sess.timer("incr_comp_import_from_shared_cache");
+
+    let cgu_name = cgu.name().as_str();
+    let entry_dir = store.entry_dir(sess, &sess.incr_comp_session_dir()).join(cgu_name);
+    let mut entries: Vec<_> = read_dir_if_exists(&entry_dir)
+        .ok()?
+        .filter_map(|entry| {
+            // Another session may be evicting the entry.
+            let entry = entry.ok()?;
+            let modified = entry.metadata().and_then(|metadata| metadata.modified()).ok()?;
+            Some((modified, entry.path()))
+        })
+        .collect();
+    // Try the most recently used entries first.
+    entries.sort_by(|(a, _), (b, _)| b.cmp(a));
+
+    let dep_node = cgu.codegen_dep_node(tcx);
+    let work_product = entries
+        .into_iter()
+        .find_map(|(_, entry)| import_entry(tcx, &store, cgu_name, dep_node, &entry))?;
+
+    if sess.opts.unstable_opts.incremental_info {
+        eprintln!("[incremental] imported work product of `{}` from shared cache", cgu_name);
+    }
+    Some(work_product)
+}
+
+fn import_entry(
+    tcx: TyCtxt<'_>,
+    store: &SharedStore<'_>,
+    cgu_name: &str,
+    dep_node: DepNode,
+    entry: &Path,
+) -> Option<WorkProduct> {
+    let sess = tcx.sess;
+    let (bytes, shared) = read_entry(entry)?;
+    let inputs = unchanged_inputs(tcx, &shared.deps)?;
+
+    let mut saved_files = FxHashMap::default();
+    let mut copied = Vec::new();
+    let result = (|| -> io::Result<()> {
+        for (kind, hash) in &shared.saved_files {
+            // Don't let an entry refer to files outside of the session directory.
+            if kind.is_empty() || !kind.bytes().all(|b| b.is_ascii_alphanumeric()) {
+                return Err(io::Error::new(io::ErrorKind::InvalidData, "malformed entry"));
+            }
+            let file_name = format!("{cgu_name}.{kind}");
+            let path = in_incr_comp_dir_sess(sess, &file_name);
+            let object_path = store.object_path(hash);
+            copied.push(path.clone());
+            // Hash the copy rather than the object, so that the object can't change in between.
+            std_fs::copy(&object_path, &path)?;
+            if hash_file(&path)? != *hash {
+                // Make sure that the next export replaces the corrupted object.
+                let _ = std_fs::remove_file(&object_path);
+                return Err(io::Error::new(
+                    io::ErrorKind::InvalidData,
+                    format!("object `{}` does not match its hash", object_path.display()),
+                ));
+            }
+            saved_files.insert(kind.clone(), file_name);
+        }
+        // Mark the entry as recently used, to keep it from being evicted.
+        store.put_file(&bytes, entry)
+    })();
+
+    if let Err(err) = result {
+        debug!("import_entry: failed to import `{}`: {}", entry.display(), err);
+        for path in copied {
+            let _ = std_fs::remove_file(path);
+        }
+        if sess.opts.unstable_opts.incremental_info {
+            eprintln!("[incremental] ignoring incomplete shared cache entry: {}", err);
+        }
+        return None;
+    }
+
+    tcx.dep_graph.with_task(
+        dep_node,
+        tcx,
+        inputs,
+        |tcx, inputs| {
+            for index in inputs {
+                tcx.dep_graph.read_index(index);
+            }
+        },
+        None,
+    );
+    Some(WorkProduct { cgu_name: cgu_name.to_string(), saved_files })
+}
+
+/// Returns the nodes of the current session that the dependencies of the
+/// codegen unit were checked against, if none of them changed.
+fn unchanged_inputs(tcx: TyCtxt<'_>, deps: &CodegenUnitDeps) -> Option<Vec<DepNodeIndex>> {
+    let root = deps.nodes.first()?;
+    let mut inputs = Vec::new();
+    let mut unchanged = FxHashMap::default();
+    // Check the dependencies in the order in which they were read, like
+    // `DepGraph::try_mark_green` does: a node is only forced once the nodes
+    // read before it are known to be unchanged, e.g. the ones that tell
+    // whether the item it refers to still exists.
+    for &edge in root.edges.as_ref()? {
+        if !is_unchanged(tcx, deps, edge, &mut inputs, &mut unchanged) {
+            return None;
+        }
+    }
+    Some(inputs)
+}
+
+fn is_unchanged(
+    tcx: TyCtxt<'_>,
+    deps: &CodegenUnitDeps,
+    index: u32,
+    inputs: &mut Vec<DepNodeIndex>,
+    unchanged: &mut FxHashMap<u32, bool>,
+) -> bool {
+    if let Some(&result) = unchanged.get(&index) {
+        return result;
+    }
+    let Some(recorded) = deps.nodes.get(index as usize) else { return false };
+    // Don't loop on an entry with cyclic dependencies.
+    unchanged.insert(index, false);
+
+    let result = if recorded.node.kind == DepKind::Red {
+        false
+    } else if let Some((dep_node_index, fingerprint)) = current_fingerprint(tcx, recorded) {
+        inputs.push(dep_node_index);
+        fingerprint == recorded.fingerprint
+    } else if tcx.is_eval_always(recorded.node.kind) {
+        // The node depends on untracked state.
+        false
+    } else if let Some(edges) = &recorded.edges {
+        // The node's result only depends on the nodes it read, so it is
+        // unchanged if they are.
+        ensure_sufficient_stack(|| {
+            edges.iter().all(|&edge| is_unchanged(tcx, deps, edge, inputs, unchanged))
+        })
+    } else {
+        false
+    };
+    unchanged.insert(index, result);
+    result
+}
+
+/// Returns the index and the fingerprint of the node in the current session,
+/// recomputing it if it hasn't been computed yet and can be.
+fn current_fingerprint(
+    tcx: TyCtxt<'_>,
+    recorded: &RecordedNode,
+) -> Option<(DepNodeIndex, Fingerprint)> {
+    // Nodes without a fingerprint are checked by their dependencies.
+    if recorded.fingerprint == Fingerprint::ZERO {
+        return None;
+    }
+    let dep_graph = &tcx.dep_graph;
+    if !dep_graph.dep_node_exists(&recorded.node)
+        && tcx.fingerprint_style(recorded.node.kind).reconstructible()
+    {
+        tcx.try_force_from_dep_node(recorded.node);
+    }
+    let dep_node_index = dep_graph.dep_node_index_of_opt(&recorded.node)?;
+    Some((dep_node_index, dep_graph.current_fingerprint_of(&recorded.node)?))
+}
+
+/// Exports the work products of the session directory `session_dir` to the
+/// shared store, along with the dependencies recorded by
+/// [`save_codegen_unit_deps`].
+pub fn export_work_products(sess: &Session, session_dir: &Path) {
+    let Some(store) = SharedStore::new(sess) else { return };
+    let _timer = sess.timer("incr_comp_export_to_shared_cache");
+
+    let result = (|| -> io::Result<usize> {
+        let deps_path = codegen_unit_deps_path(sess);
+        let Some(deps) = read_file::<Vec<(String, CodegenUnitDeps)>>(sess, &deps_path)? else {
+            return Ok(0);
+        };
+        // The dependencies are only valid for the work products of this session.
+        std_fs::remove_file(&deps_path)?;
+        let Some(work_products) =
+            read_file::<Vec<SerializedWorkProduct>>(sess, &work_products_path(sess))?
+        else {
+            return Ok(0);
+        };
+
+        let mut deps: FxHashMap<_, _> = deps.into_iter().collect();
+        let entry_dir = store.entry_dir(sess, session_dir);
+        let mut count = 0;
+        for SerializedWorkProduct { work_product, .. } in work_products {
+            let Some(deps) = deps.remove(&work_product.cgu_name) else { continue };
+            let mut saved_files = work_product
+                .saved_files
+                .iter()
+                .map(|(kind, file_name)| {
+                    Ok((kind.clone(), store.put_object(&session_dir.join(file_name))?))
+                })
+                .collect::<io::Result<Vec<_>>>()?;
+            saved_files.sort();
+
+            let mut encoder = MemEncoder::new();
+            SharedWorkProduct { deps, saved_files }.encode(&mut encoder);
+            let bytes = encoder.finish();
+            let hash: ContentHash = Sha256::digest(&bytes).into();
+            store.put_file(&bytes, &entry_dir.join(&work_product.cgu_name).join(hex(&hash)))?;
+            count += 1;
+        }
+
+        store.evict(sess.opts.unstable_opts.incremental_shared_cache_limit * 1024 * 1024)?;
+        Ok(count)
+    })();
+
+    match result {
+        Ok(count) => {
+            if sess.opts.unstable_opts.incremental_info {
+                eprintln!("[incremental] exported {} work products to shared cache", count);
+            }
+        }
+        Err(err) => {
+            sess.warn(&format!(
+                "failed to export work products to shared cache `{}`: {}",
+                store.root.display(),
+                err
+            ));
+        }
+    }
+}
+
+/// Reads a file of the session directory written with `file_format::save_in`.
+fn read_file<T: for<'a> Decodable<MemDecoder<'a>>>(
+    sess: &Session,
+    path: &Path,
+) -> io::Result<Option<T>> {
+    let data = file_format::read_file(false, path, sess.is_nightly_build())?;
+    Ok(data.map(|(bytes, start_pos)| Decodable::decode(&mut MemDecoder::new(&bytes, start_pos))))
+}
+
+/// Reads the entry at `path`, returning its contents and the work product
+/// they encode. An entry whose contents don't match its name is deleted.
+fn read_entry(path: &Path) -> Option<(Vec<u8>, SharedWorkProduct)> {
+    let bytes = std_fs::read(path).ok()?;
+    let hash: ContentHash = Sha256::digest(&bytes).into();
+    if path.file_name().and_then(|name| name.to_str()) != Some(hex(&hash).as_str()) {
+        let _ = std_fs::remove_file(path);
+        return None;
+    }
+    let work_product = Decodable::decode(&mut MemDecoder::new(&bytes, 0));
+    Some((bytes, work_product))
+}
+
+struct SharedStore<'a> {
+    root: &'a Path,
+}
+
+impl<'a> SharedStore<'a> {
+    fn new(sess: &'a Session) -> Option<Self> {
+        let root = sess.opts.unstable_opts.incremental_shared_cache.as_deref()?;
+        Some(SharedStore { root })
+    }
+
+    /// The directory of the entries for the crate of `session_dir` and the
+    /// current command line.
+    fn entry_dir(&self, sess: &Session, session_dir: &Path) -> PathBuf {
+        // `{crate-name-and-disambiguator}` is path-independent, as the
+        // `StableCrateId` is only derived from the crate name and metadata.
+        let crate_dir_name = session_dir.parent().unwrap().file_name().unwrap();
+        let crate_dir_name = crate_dir_name.to_string_lossy();
+
+        let mut hasher = Sha256::new();
+        hasher.update(file_format::rustc_version(sess.is_nightly_build()).as_bytes());
+        hasher.update(commandline_args_hash(sess).to_le_bytes());
+        let key: ContentHash = hasher.finalize().into();
+
+        self.root.join("entries").join(format!("{}-{}", crate_dir_name, hex(&key)))
+    }
+
+    fn object_path(&self, hash: &ContentHash) -> PathBuf {
+        let hash = hex(hash);
+        self.root.join("objects").join(&hash[..2]).join(&hash[2..])
+    }
+
+    /// Adds a copy of the file at `path` to the objects of the store, unless an
+    /// identical file is already stored, and returns the object's hash.
+    fn put_object(&self, path: &Path) -> io::Result<ContentHash> {
+        let hash = hash_file(path)?;
+        let object_path = self.object_path(&hash);
+        // Replace an object that is about to leave its grace period, so that it
+        // can't be evicted before the entry that refers to it is in place.
+        let is_fresh = std_fs::metadata(&object_path)
+            .and_then(|metadata| metadata.modified())
+            .map_or(false, |modified| is_recent(modified, SystemTime::now(), GRACE_PERIOD / 2));
+        if !is_fresh {
+            let tmp_path = self.tmp_path()?;
+            std_fs::copy(path, &tmp_path)?;
+            self.rename_into_place(&tmp_path, &object_path)?;
+        }
+        Ok(hash)
+    }
+
+    /// Evicts the least recently used entries until the store takes up no
+    /// more than `limit` bytes, deleting the objects that no remaining entry
+    /// refers to, as well as temporary files left behind by crashed sessions.
+    ///
+    /// Objects and temporary files are only deleted once they are older than
+    /// [`GRACE_PERIOD`], as a concurrent export may still be writing them or
+    /// the entry that refers to them.
+    fn evict(&self, limit: u64) -> io::Result<()> {
+        let now = SystemTime::now();
+
+        for tmp in read_dir_if_exists(&self.root.join("tmp"))? {
+            let tmp = tmp?;
+            let modified = tmp.metadata().and_then(|metadata| metadata.modified());
+            if modified.map_or(false, |modified| !is_recent(modified, now, GRACE_PERIOD)) {
+                let _ = std_fs::remove_file(tmp.path());
+            }
+        }
+
+        let mut objects = FxHashMap::default();
+        let mut total_size = 0;
+        for dir in read_dir_if_exists(&self.root.join("objects"))? {
+            for object in read_dir_if_exists(&dir?.path())? {
+                let object = object?;
+                // Another session may be evicting the object.
+                let Ok(metadata) = object.metadata() else { continue };
+                total_size += metadata.len();
+                objects.insert(object.path(), (metadata.len(), metadata.modified()?));
+            }
+        }
+
+        // Count the references to each object, and order the entries from the
+        // least to the most recently used one.
+        let mut entries = Vec::new();
+        let mut references: FxHashMap<PathBuf, usize> = FxHashMap::default();
+        for crate_dir in read_dir_if_exists(&self.root.join("entries"))? {
+            for cgu_dir in read_dir_if_exists(&crate_dir?.path())? {
+                for entry in read_dir_if_exists(&cgu_dir?.path())? {
+                    let entry = entry?;
+                    let Ok(metadata) = entry.metadata() else { continue };
+                    total_size += metadata.len();
+                    // An entry that can't be read can't be imported either.
+                    let entry_objects: FxHashSet<PathBuf> = read_entry(&entry.path())
+                        .map(|(_, work_product)| {
+                            work_product
+                                .saved_files
+                                .iter()
+                                .map(|(_, hash)| self.object_path(hash))
+                                .collect()
+                        })
+                        .unwrap_or_default();
+                    for object in &entry_objects {
+                        *references.entry(object.clone()).or_default() += 1;
+                    }
+                    let modified = metadata.modified().unwrap_or(SystemTime::UNIX_EPOCH);
+                    entries.push((modified, entry.path(), metadata.len(), entry_objects));
+                }
+            }
+        }
+        if total_size <= limit {
+            return Ok(());
+        }
+        entries.sort_by_key(|&(modified, ..)| modified);
+
+        let remove_object = |object: &Path, total_size: &mut u64| {
+            let Some(&(size, modified)) = objects.get(object) else { return };
+            if !is_recent(modified, now, GRACE_PERIOD) && std_fs::remove_file(object).is_ok() {
+                *total_size = total_size.saturating_sub(size);
+            }
+        };
+
+        // Old objects that no entry refers to are left over from failed exports.
+        for object in objects.keys() {
+            if !references.contains_key(object) {
+                remove_object(object, &mut total_size);
+            }
+        }
+
+        for (_, entry, size, entry_objects) in entries {
+            if total_size <= limit {
+                break;
+            }
+            // Another session may be evicting the same entry.
+            if std_fs::remove_file(&entry).is_ok() {
+                total_size = total_size.saturating_sub(size);
+            }
+            for object in entry_objects {
+                let count = references.get_mut(&object).unwrap();
+                *count -= 1;
+                if *count == 0 {
+                    remove_object(&object, &mut total_size);
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Atomically writes `contents` to `path`, replacing any previous file.
+    fn put_file(&self, contents: &[u8], path: &Path) -> io::Result<()> {
+        let tmp_path = self.tmp_path()?;
+        std_fs::write(&tmp_path, contents)?;
+        self.rename_into_place(&tmp_path, path)
+    }
+
+    fn tmp_path(&self) -> io::Result<PathBuf> {
+        let tmp_dir = self.root.join("tmp");
+        std_fs::create_dir_all(&tmp_dir)?;
+        Ok(tmp_dir.join(format!("{}-{:x}", std::process::id(), thread_rng().next_u64())))
+    }
+
+    fn rename_into_place(&self, tmp_path: &Path, path: &Path) -> io::Result<()> {
+        let result = std_fs::create_dir_all(path.parent().unwrap())
+            .and_then(|()| std_fs::rename(tmp_path, path));
+        if result.is_err() {
+            let _ = std_fs::remove_file(tmp_path);
+        }
+        result
+    }
+}
+
+/// The SHA-256 hash of the contents of an object or an entry, or of the key of
+/// the entries of a crate.
+type ContentHash = [u8; 32];
+
+/// Returns whether a file that was last modified at `modified` is younger than
+/// `period` at `now`. Files from the future, e.g. written by a machine whose
+/// clock is ahead, count as recent.
+fn is_recent(modified: SystemTime, now: SystemTime, period: Duration) -> bool {
+    now.duration_since(modified).map_or(true, |age| age < period)
+}
+
+fn hash_file(path: &Path) -> io::Result<ContentHash> {
+    let mut file = std_fs::File::open(path)?;
+    let mut hasher = Sha256::new();
+    let mut buf = vec![0; 64 * 1024];
+    loop {
+        match file.read(&mut buf)? {
+            0 => return Ok(hasher.finalize().into()),
+            len => hasher.update(&buf[..len]),
+        }
+    }
+}
+
+/// Iterates over the directory at `path`, which is treated as empty if it doesn't exist.
+fn read_dir_if_exists(
+    path: &Path,
+) -> io::Result<impl Iterator<Item = io::Result<std_fs::DirEntry>>> {
+    match std_fs::read_dir(path) {
+        Ok(dir) => Ok(Some(dir).into_iter().flatten()),
+        Err(err) if err.kind() == io::ErrorKind::NotFound => Ok(None.into_iter().flatten()),
+        Err(err) => Err(err),
+    }
+}
+
+fn hex(hash: &ContentHash) -> String {
+    hash.iter().map(|byte| format!("{:02x}", byte)).collect()
+}
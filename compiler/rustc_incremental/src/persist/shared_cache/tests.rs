@@ -0,0 +1,47 @@
+use super::*;
+
+#[test]
+fn test_work_product_roundtrip() {
+    let node = DepNode { kind: DepKind::Null, hash: Fingerprint::ZERO.into() };
+    let fingerprint = Fingerprint::new(1, 2);
+    let work_product = SharedWorkProduct {
+        deps: CodegenUnitDeps {
+            nodes: vec![
+                RecordedNode { node, fingerprint: Fingerprint::ZERO, edges: Some(vec![1]) },
+                RecordedNode { node, fingerprint, edges: None },
+            ],
+        },
+        saved_files: vec![("o".to_string(), [0x12; 32])],
+    };
+
+    let mut encoder = MemEncoder::new();
+    work_product.encode(&mut encoder);
+    let bytes = encoder.finish();
+    let decoded: SharedWorkProduct = Decodable::decode(&mut MemDecoder::new(&bytes, 0));
+
+    assert_eq!(decoded.saved_files, work_product.saved_files);
+    let nodes = &decoded.deps.nodes;
+    assert_eq!(nodes.len(), 2);
+    assert_eq!(nodes[0].edges, Some(vec![1]));
+    assert_eq!((nodes[1].node, nodes[1].fingerprint, &nodes[1].edges), (node, fingerprint, &None));
+}
+
+#[test]
+fn test_is_recent() {
+    let now = SystemTime::now();
+    let minute = Duration::from_secs(60);
+    assert!(is_recent(now, now, GRACE_PERIOD));
+    assert!(is_recent(now - minute, now, GRACE_PERIOD));
+    assert!(!is_recent(now - GRACE_PERIOD, now, GRACE_PERIOD));
+    assert!(!is_recent(now - GRACE_PERIOD - minute, now, GRACE_PERIOD));
+    // Files from the future are recent.
+    assert!(is_recent(now + minute, now, GRACE_PERIOD));
+}
+
+#[test]
+fn test_hex() {
+    let mut hash = [0; 32];
+    hash[0] = 0x0f;
+    hash[31] = 0xa0;
+    assert_eq!(hex(&hash), format!("0f{}a0", "0".repeat(60)));
+}
@@ -8,6 +8,7 @@
 use rustc_data_structures::sync::Lrc;
 use rustc_data_structures::OnDrop;
 use rustc_errors::registry::Registry;
+use rustc_errors::snapshot::SnapshotPhase;
 use rustc_errors::{ErrorGuaranteed, Handler};
 use rustc_lint::LintStore;
 use rustc_middle::ty;
@@ -16,8 +17,7 @@
 use rustc_session::config::{self, CheckCfg, ErrorOutputType, Input, OutputFilenames};
 use rustc_session::lint;
 use rustc_session::parse::{CrateConfig, ParseSess};
-use rustc_session::Session;
-use rustc_session::{early_error, CompilerIO};
+use rustc_session::{early_error, CompilerIO, DiagnosticSnapshotCallback, Session};
 use rustc_span::source_map::{FileLoader, FileName};
 use rustc_span::symbol::sym;
 use std::path::PathBuf;
@@ -247,6 +247,17 @@ pub struct Config {
     pub make_codegen_backend:
         Option<Box<dyn FnOnce(&config::Options) -> Box<dyn CodegenBackend> + Send>>,
 
+    /// This is a callback from the driver that is called with the diagnostics emitted since
+    /// the previous call. Their messages are translated and their spans are resolved to file,
+    /// line and column ranges, so the driver doesn't have to install its own emitter to
+    /// present them.
+    ///
+    /// It is called once `Queries::parse` and `Queries::expansion` are done, and after
+    /// analysis in `Queries::ongoing_codegen` and in `rustc_driver`. It is always called one
+    /// last time when `run_compiler` returns or unwinds, with `SnapshotPhase::Finish` or
+    /// `SnapshotPhase::Abort`, so every diagnostic is passed to it exactly once.
+    pub diagnostic_snapshot: Option<DiagnosticSnapshotCallback>,
+
     /// Registry of diagnostics codes.
     pub registry: Registry,
 }
@@ -284,6 +295,11 @@ pub fn run_compiler<R: Send>(config: Config, f: impl FnOnce(&Compiler) -> R + Se
                 parse_sess_created(&mut sess.parse_sess);
             }
 
+            if let Some(diagnostic_snapshot) = config.diagnostic_snapshot {
+                sess.diagnostic().record_diagnostics();
+                sess.diagnostic_snapshot = Some(diagnostic_snapshot);
+            }
+
             let compiler = Compiler {
                 sess: Lrc::new(sess),
                 codegen_backend: Lrc::new(codegen_backend),
@@ -294,6 +310,14 @@ pub fn run_compiler<R: Send>(config: Config, f: impl FnOnce(&Compiler) -> R + Se
             rustc_span::with_source_map(compiler.sess.parse_sess.clone_source_map(), move || {
                 let r = {
                     let _sess_abort_error = OnDrop(|| {
+                        // Hand over what was not passed in the snapshot of a phase, as fatal
+                        // errors unwind past them, and drivers may not run every phase.
+                        if std::thread::panicking() {
+                            compiler.sess.snapshot_diagnostics(SnapshotPhase::Abort);
+                        } else {
+                            compiler.sess.diagnostic().emit_stashed_diagnostics();
+                            compiler.sess.snapshot_diagnostics(SnapshotPhase::Finish);
+                        }
                         compiler.sess.finish_diagnostics(registry);
                     });
 
@@ -214,11 +214,15 @@ pub fn register_plugins<'a>(
 
     let registrars =
         sess.time("plugin_loading", || plugin::load::load_plugins(sess, metadata_loader, &krate));
+    let lint_plugins = sess.time("lint_plugin_loading", || plugin::load::load_lint_plugins(sess));
     sess.time("plugin_registration", || {
         let mut registry = plugin::Registry { lint_store: &mut lint_store };
         for registrar in registrars {
             registrar(&mut registry);
         }
+        for lint_plugin in &lint_plugins {
+            lint_plugin.register(sess, &mut registry);
+        }
     });
 
     Ok((krate, lint_store))
@@ -590,6 +594,9 @@ fn write_out_deps(
         if let Some(ref profile_sample) = sess.opts.unstable_opts.profile_sample_use {
             files.push(normalize_path(profile_sample.as_path().to_path_buf()));
         }
+        if let Some(ref call_graph_profile) = sess.opts.unstable_opts.cgu_call_graph_profile {
+            files.push(normalize_path(call_graph_profile.path.clone()));
+        }
 
         if sess.binary_dep_depinfo() {
             if let Some(ref backend) = sess.opts.unstable_opts.codegen_backend {
@@ -816,6 +823,14 @@ pub fn create_global_ctxt<'tcx>(
 /// Runs the resolution, type-checking, region checking and other
 /// miscellaneous analysis passes on the crate.
 fn analysis(tcx: TyCtxt<'_>, (): ()) -> Result<()> {
+    let result = analysis_passes(tcx);
+    // Don't hold back the diagnostics of the parallel passes, or the ones held back for
+    // `-Z diagnostic-grouping`, until after codegen.
+    tcx.sess.diagnostic().emit_diagnostic_groups();
+    result
+}
+
+fn analysis_passes(tcx: TyCtxt<'_>) -> Result<()> {
     rustc_passes::hir_id_validator::check_crate(tcx);
 
     let sess = tcx.sess;
@@ -964,6 +979,8 @@ pub fn start_codegen<'tcx>(
         codegen_backend.codegen_crate(tcx, metadata, need_metadata_module)
     });
 
+    rustc_query_impl::print_self_profile_summary(tcx);
+
     // Don't run these test assertions when not doing codegen. Compiletest tries to build
     // build-fail tests in check mode first and expects it to not give an error in that case.
     if tcx.sess.opts.output_types.should_codegen() {
@@ -8,6 +8,7 @@
 use rustc_data_structures::steal::Steal;
 use rustc_data_structures::svh::Svh;
 use rustc_data_structures::sync::{Lrc, OnceCell, WorkerLocal};
+use rustc_errors::snapshot::SnapshotPhase;
 use rustc_hir::def_id::LOCAL_CRATE;
 use rustc_incremental::DepGraphFuture;
 use rustc_lint::LintStore;
@@ -128,8 +129,13 @@ fn dep_graph_future(&self) -> Result<QueryResult<'_, Option<DepGraphFuture>>> {
     }
 
     pub fn parse(&self) -> Result<QueryResult<'_, ast::Crate>> {
-        self.parse
-            .compute(|| passes::parse(self.session()).map_err(|mut parse_error| parse_error.emit()))
+        self.parse.compute(|| {
+            let krate =
+                passes::parse(self.session()).map_err(|mut parse_error| parse_error.emit());
+            self.session().diagnostic().emit_diagnostic_groups();
+            self.session().snapshot_diagnostics(SnapshotPhase::Parse);
+            krate
+        })
     }
 
     pub fn register_plugins(&self) -> Result<QueryResult<'_, (ast::Crate, Lrc<LintStore>)>> {
@@ -186,8 +192,10 @@ pub fn expansion(
             );
             let krate = resolver.access(|resolver| {
                 passes::configure_and_expand(sess, &lint_store, krate, crate_name, resolver)
-            })?;
-            Ok((Lrc::new(krate), Rc::new(RefCell::new(resolver)), lint_store))
+            });
+            sess.diagnostic().emit_diagnostic_groups();
+            sess.snapshot_diagnostics(SnapshotPhase::Expansion);
+            Ok((Lrc::new(krate?), Rc::new(RefCell::new(resolver)), lint_store))
         })
     }
 
@@ -250,6 +258,7 @@ pub fn ongoing_codegen(&'tcx self) -> Result<QueryResult<'_, Box<dyn Any>>> {
         self.ongoing_codegen.compute(|| {
             self.global_ctxt()?.enter(|tcx| {
                 tcx.analysis(()).ok();
+                self.session().snapshot_diagnostics(SnapshotPhase::Analysis);
 
                 // Don't do code generation if there were any errors
                 self.session().compile_status()?;
@@ -5,6 +5,7 @@
 use rustc_errors::{emitter::HumanReadableErrorType, registry, ColorConfig};
 use rustc_session::config::rustc_optgroups;
 use rustc_session::config::Input;
+use rustc_session::config::Polonius;
 use rustc_session::config::TraitSolver;
 use rustc_session::config::{build_configuration, build_session_options, to_crate_config};
 use rustc_session::config::{
@@ -12,9 +13,10 @@
     ProcMacroExecutionStrategy, SymbolManglingVersion, WasiExecModel,
 };
 use rustc_session::config::{CFGuard, ExternEntry, LinkerPluginLto, LtoCli, SwitchWithOptPath};
-use rustc_session::config::{DumpMonoStatsFormat, MirSpanview};
+use rustc_session::config::{CallGraphProfile, DumpMonoStatsFormat, MirSpanview};
 use rustc_session::config::{ErrorOutputType, ExternLocation, LocationDetail, Options, Strip};
-use rustc_session::config::{InstrumentCoverage, Passes};
+use rustc_session::config::{IncrementalExplainFormat, InstrumentCoverage, Passes};
+use rustc_session::config::{SelfProfileSummaryFormat, TraitProofTreeFormat};
 use rustc_session::lint::Level;
 use rustc_session::search_paths::SearchPath;
 use rustc_session::utils::{CanonicalizedPath, NativeLib, NativeLibKind};
@@ -189,6 +191,35 @@ fn test_output_types_tracking_hash_different_construction_order() {
     assert_same_hash(&v1, &v2);
 }
 
+#[test]
+fn test_output_types_for_incremental_state() {
+    let metadata = OutputTypes::new(&[(OutputType::Metadata, None)]);
+    let link = OutputTypes::new(&[(OutputType::Exe, None)]);
+    let pipelined = OutputTypes::new(&[
+        (OutputType::DepInfo, None),
+        (OutputType::Metadata, None),
+        (OutputType::Exe, None),
+    ]);
+    let asm = OutputTypes::new(&[(OutputType::Metadata, None), (OutputType::Assembly, None)]);
+
+    let options = |output_types: &OutputTypes| {
+        let mut options = Options::default();
+        options.output_types = output_types.for_incremental_state();
+        options
+    };
+
+    assert_same_hash(&options(&metadata), &options(&link));
+    assert_same_hash(&options(&metadata), &options(&pipelined));
+    assert_different_hash(&options(&metadata), &options(&asm));
+
+    // The crate hash doesn't depend on whether only metadata is emitted either.
+    let mut v1 = Options::default();
+    let mut v2 = Options::default();
+    v1.output_types = metadata;
+    v2.output_types = link;
+    assert_non_crate_hash_different(&v1, &v2);
+}
+
 #[test]
 fn test_externs_tracking_hash_different_construction_order() {
     let mut v1 = Options::default();
@@ -646,8 +677,10 @@ macro_rules! untracked {
     // Make sure that changing an [UNTRACKED] option leaves the hash unchanged.
     // tidy-alphabetical-start
     untracked!(assert_incr_state, Some(String::from("loaded")));
+    untracked!(const_eval_profile, true);
     untracked!(deduplicate_diagnostics, false);
     untracked!(dep_tasks, true);
+    untracked!(diagnostic_grouping, false);
     untracked!(dlltool, Some(PathBuf::from("custom_dlltool.exe")));
     untracked!(dont_buffer_diagnostics, true);
     untracked!(dump_dep_graph, true);
@@ -660,12 +693,18 @@ macro_rules! untracked {
     untracked!(dump_mir_spanview, Some(MirSpanview::Statement));
     untracked!(dump_mono_stats, SwitchWithOptPath::Enabled(Some("mono-items-dir/".into())));
     untracked!(dump_mono_stats_format, DumpMonoStatsFormat::Json);
+    untracked!(dump_trait_proof_tree, Some(String::from("abc")));
+    untracked!(dump_trait_proof_tree_format, TraitProofTreeFormat::Dot);
     untracked!(dylib_lto, true);
     untracked!(emit_stack_sizes, true);
     untracked!(future_incompat_test, true);
+    untracked!(highlight_snippets, true);
     untracked!(hir_stats, true);
     untracked!(identify_regions, true);
+    untracked!(incremental_explain, Some(IncrementalExplainFormat::Json));
     untracked!(incremental_info, true);
+    untracked!(incremental_shared_cache, Some(PathBuf::from("abc")));
+    untracked!(incremental_shared_cache_limit, 1);
     untracked!(incremental_verify_ich, true);
     untracked!(input_stats, true);
     untracked!(keep_hygiene_data, true);
@@ -693,6 +732,8 @@ macro_rules! untracked {
     untracked!(save_analysis, true);
     untracked!(self_profile, SwitchWithOptPath::Enabled(None));
     untracked!(self_profile_events, Some(vec![String::new()]));
+    untracked!(self_profile_summary, Some(SelfProfileSummaryFormat::Json));
+    untracked!(self_profile_summary_items, 20);
     untracked!(span_debug, true);
     untracked!(span_free_formats, true);
     untracked!(temps_dir, Some(String::from("abc")));
@@ -733,6 +774,7 @@ macro_rules! tracked {
             pac_ret: Some(PacRet { leaf: true, key: PAuthKey::B })
         })
     );
+    tracked!(cgu_call_graph_profile, Some(CallGraphProfile::new(PathBuf::from("abc"))));
     tracked!(codegen_backend, Some("abc".to_string()));
     tracked!(crate_attr, vec!["abc".to_string()]);
     tracked!(debug_info_for_profiling, true);
@@ -740,8 +782,10 @@ macro_rules! tracked {
     tracked!(dep_info_omit_d_target, true);
     tracked!(drop_tracking, true);
     tracked!(dual_proc_macros, true);
+    tracked!(dump_const_eval_locals, true);
     tracked!(dwarf_version, Some(5));
     tracked!(emit_thin_lto, false);
+    tracked!(explain_auto_traits, true);
     tracked!(export_executable_symbols, true);
     tracked!(fewer_names, Some(true));
     tracked!(force_unstable_if_unmarked, true);
@@ -756,6 +800,7 @@ macro_rules! tracked {
     tracked!(instrument_coverage, Some(InstrumentCoverage::All));
     tracked!(instrument_mcount, true);
     tracked!(link_only, true);
+    tracked!(lint_plugin, vec![(String::from("our_org"), PathBuf::from("liblints.so"))]);
     tracked!(llvm_plugins, vec![String::from("plugin_name")]);
     tracked!(location_detail, LocationDetail { file: true, line: false, column: false });
     tracked!(log_backtrace, Some("filter".to_string()));
@@ -773,12 +818,14 @@ macro_rules! tracked {
     tracked!(no_unique_section_names, true);
     tracked!(oom, OomStrategy::Panic);
     tracked!(osx_rpath_install_name, true);
+    tracked!(outline_cold_panics, true);
     tracked!(packed_bundled_libs, true);
     tracked!(panic_abort_tests, true);
     tracked!(panic_in_drop, PanicStrategy::Abort);
     tracked!(pick_stable_methods_before_any_unstable, false);
     tracked!(plt, Some(true));
-    tracked!(polonius, true);
+    tracked!(polonius, Polonius::Full);
+    tracked!(polymorphize_by_layout, true);
     tracked!(precise_enum_drop_elaboration, false);
     tracked!(print_fuel, Some("abc".to_string()));
     tracked!(profile, true);
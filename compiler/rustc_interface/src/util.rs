@@ -12,7 +12,7 @@
 use rustc_session::filesearch::sysroot_candidates;
 use rustc_session::lint::{self, BuiltinLintDiagnostics, LintBuffer};
 use rustc_session::parse::CrateConfig;
-use rustc_session::{early_error, filesearch, output, Session};
+use rustc_session::{early_error, early_error_with_help, filesearch, output, Session};
 use rustc_span::edition::Edition;
 use rustc_span::lev_distance::find_best_match_for_name;
 use rustc_span::source_map::FileLoader;
@@ -355,11 +355,27 @@ fn get_codegen_sysroot(maybe_sysroot: &Option<PathBuf>, backend_name: &str) -> M
         Some(ref s) => load_backend_from_dylib(s),
         None => {
             let err = format!("unsupported builtin codegen backend `{backend_name}`");
+            if let Some(component) = codegen_backend_component(backend_name) {
+                let help = format!(
+                    "the `{backend_name}` backend is shipped in the `{component}` component, \
+                     try `rustup component add {component}`"
+                );
+                early_error_with_help(ErrorOutputType::default(), &err, &help);
+            }
             early_error(ErrorOutputType::default(), &err);
         }
     }
 }
 
+/// The installer component which ships the builtin codegen backend `backend_name`, if it is not
+/// shipped together with rustc.
+fn codegen_backend_component(backend_name: &str) -> Option<&'static str> {
+    match backend_name {
+        "cranelift" => Some("rustc-codegen-cranelift-preview"),
+        _ => None,
+    }
+}
+
 pub(crate) fn check_attr_crate_type(
     sess: &Session,
     attrs: &[ast::Attribute],
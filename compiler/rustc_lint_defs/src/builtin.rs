@@ -1057,6 +1057,52 @@
     };
 }
 
+declare_lint! {
+    /// The `long_running_const_eval` lint reports constant evaluations that have been running
+    /// for a long time, and then again each time their running time doubles.
+    ///
+    /// ### Example
+    ///
+    /// ```rust,ignore (takes a while)
+    /// const FOO: u32 = {
+    ///     let mut i = 0;
+    ///     while i < 250_000 {
+    ///         i += 1;
+    ///     }
+    ///     i
+    /// };
+    /// ```
+    ///
+    /// This will produce:
+    ///
+    /// ```text
+    /// warning: constant evaluation is taking a long time
+    ///  --> src/lib.rs:1:1
+    ///   |
+    /// 1 | const FOO: u32 = {
+    ///   | ^^^^^^^^^^^^^^
+    ///   |
+    ///   = note: this evaluation has run for 500000 steps so far
+    ///   = help: compile with `-Z const-eval-profile` to see which `const fn`s take the longest
+    ///   = note: `#[warn(long_running_const_eval)]` on by default
+    /// ```
+    ///
+    /// ### Explanation
+    ///
+    /// Constant evaluation runs at compile time, so long-running evaluations slow down every
+    /// build of the crate, and running into `#![const_eval_limit]` only fails the build once
+    /// the limit has been reached. This lint reports such evaluations, counted in the same
+    /// steps as the limit: first after a quarter of the limit, but not before 500000 steps,
+    /// and then each time the number of steps doubles.
+    ///
+    /// The reports are emitted as they happen, so that an evaluation that doesn't seem to end
+    /// can be told apart from a hang. The step that reaches the limit is reported by the
+    /// resulting error instead.
+    pub LONG_RUNNING_CONST_EVAL,
+    Warn,
+    "constant evaluation that takes a long time"
+}
+
 declare_lint! {
     /// The `exported_private_dependencies` lint detects private dependencies
     /// that are exposed in a public interface.
@@ -3381,6 +3427,7 @@
         REPR_TRANSPARENT_EXTERNAL_PRIVATE_FIELDS,
         NAMED_ARGUMENTS_USED_POSITIONALLY,
         IMPLIED_BOUNDS_ENTAILMENT,
+        LONG_RUNNING_CONST_EVAL,
     ]
 }
 
@@ -6,6 +6,7 @@
 use rustc_ast as ast;
 use rustc_data_structures::captures::Captures;
 use rustc_data_structures::fx::FxHashMap;
+use rustc_data_structures::stable_hasher::StableHasher;
 use rustc_data_structures::svh::Svh;
 use rustc_data_structures::sync::{Lock, LockGuard, Lrc, OnceCell};
 use rustc_data_structures::unhash::UnhashMap;
@@ -35,6 +36,7 @@
 use rustc_span::{self, BytePos, ExpnId, Pos, Span, SyntaxContext, DUMMY_SP};
 
 use proc_macro::bridge::client::ProcMacro;
+use std::hash::Hash;
 use std::iter::TrustedLen;
 use std::num::NonZeroUsize;
 use std::path::Path;
@@ -722,8 +724,22 @@ pub(crate) fn name(&self) -> Symbol {
         self.name
     }
 
+    /// Returns the hash that identifies the crate to the crates that depend on it.
+    ///
+    /// The crate hash doesn't change when only metadata is emitted, so that such a build shares
+    /// its incremental state with a build that links, and so do the crates that depend on it,
+    /// which is why the `crate_hash` query returns it unchanged. The metadata of a build that
+    /// doesn't codegen has no exported symbols and no linkage of dylib dependencies though, so
+    /// it is identified by a different hash, to keep it from standing in for the metadata of a
+    /// build that links.
     pub(crate) fn hash(&self) -> Svh {
-        self.hash
+        if !self.metadata_only {
+            return self.hash;
+        }
+        let mut hasher = StableHasher::new();
+        self.hash.as_u64().hash(&mut hasher);
+        "metadata-only".hash(&mut hasher);
+        Svh::new(hasher.finish())
     }
 
     pub(crate) fn stable_crate_id(&self) -> StableCrateId {
@@ -1738,7 +1754,7 @@ pub(crate) fn stable_crate_id(&self) -> StableCrateId {
     }
 
     pub(crate) fn hash(&self) -> Svh {
-        self.root.hash
+        self.root.hash()
     }
 
     fn num_def_ids(&self) -> usize {
@@ -1,3 +1,4 @@
+use crate::creader::CStore;
 use crate::errors::{FailCreateFileEncoder, FailSeekFile, FailWriteFile};
 use crate::rmeta::def_path_hash_map::DefPathHashMapRef;
 use crate::rmeta::table::TableBuilder;
@@ -641,7 +642,12 @@ macro_rules! stat {
         // Encode exported symbols info. This is prefetched in `encode_metadata` so we encode
         // this as late as possible to give the prefetching as much time as possible to complete.
         let exported_symbols = stat!("exported-symbols", || {
-            self.encode_exported_symbols(&tcx.exported_symbols(LOCAL_CRATE))
+            let exported_symbols = if tcx.sess.opts.output_types.should_codegen() {
+                tcx.exported_symbols(LOCAL_CRATE)
+            } else {
+                &[]
+            };
+            self.encode_exported_symbols(exported_symbols)
         });
 
         // Encode the hygiene data.
@@ -684,6 +690,7 @@ macro_rules! stat {
                 panic_runtime: tcx.sess.contains_name(&attrs, sym::panic_runtime),
                 profiler_runtime: tcx.sess.contains_name(&attrs, sym::profiler_runtime),
                 symbol_mangling_version: tcx.sess.opts.get_symbol_mangling_version(),
+                metadata_only: !tcx.sess.opts.output_types.should_codegen(),
 
                 crate_deps,
                 dylib_dependency_formats,
@@ -1820,7 +1827,9 @@ fn encode_crate_deps(&mut self) -> LazyArray<CrateDep> {
             .map(|&cnum| {
                 let dep = CrateDep {
                     name: self.tcx.crate_name(cnum),
-                    hash: self.tcx.crate_hash(cnum),
+                    // Not `crate_hash`, which is the same for a build without codegen, see
+                    // `CrateRoot::hash`.
+                    hash: CStore::from_tcx(self.tcx).get_crate_data(cnum).hash(),
                     host_hash: self.tcx.crate_host_hash(cnum),
                     kind: self.tcx.dep_kind(cnum),
                     extra_filename: self.tcx.extra_filename(cnum).clone(),
@@ -1856,14 +1865,22 @@ fn encode_stability_implications(&mut self) -> LazyArray<(Symbol, Symbol)> {
         empty_proc_macro!(self);
         let tcx = self.tcx;
         let implications = tcx.stability_implications(LOCAL_CRATE);
-        self.lazy_array(implications.iter().map(|(k, v)| (*k, *v)))
+        // Sort by name rather than by the interned `Symbol`, whose order depends on the order
+        // in which a parallel front-end interned the symbols.
+        let mut implications: Vec<_> = implications.iter().map(|(k, v)| (*k, *v)).collect();
+        implications.sort_unstable_by(|a, b| a.0.as_str().cmp(b.0.as_str()));
+        self.lazy_array(implications)
     }
 
     fn encode_diagnostic_items(&mut self) -> LazyArray<(Symbol, DefIndex)> {
         empty_proc_macro!(self);
         let tcx = self.tcx;
         let diagnostic_items = &tcx.diagnostic_items(LOCAL_CRATE).name_to_id;
-        self.lazy_array(diagnostic_items.iter().map(|(&name, def_id)| (name, def_id.index)))
+        // Sorted by name for the same reason as the stability implications.
+        let mut diagnostic_items: Vec<_> =
+            diagnostic_items.iter().map(|(&name, def_id)| (name, def_id.index)).collect();
+        diagnostic_items.sort_unstable_by(|a, b| a.0.as_str().cmp(b.0.as_str()));
+        self.lazy_array(diagnostic_items)
     }
 
     fn encode_lang_items(&mut self) -> LazyArray<(DefIndex, LangItem)> {
@@ -1991,6 +2008,11 @@ fn encode_exported_symbols(
 
     fn encode_dylib_dependency_formats(&mut self) -> LazyArray<Option<LinkagePreference>> {
         empty_proc_macro!(self);
+        // Without codegen, there's nothing to link, and the dependencies may only be
+        // available as metadata.
+        if !self.tcx.sess.opts.output_types.should_codegen() {
+            return LazyArray::empty();
+        }
         let formats = self.tcx.dependency_formats(());
         for (ty, arr) in formats.iter() {
             if *ty != CrateType::Dylib {
@@ -2196,7 +2218,14 @@ pub fn encode_metadata(tcx: TyCtxt<'_>, path: &Path) {
             // Prefetch some queries used by metadata encoding.
             // This is not necessary for correctness, but is only done for performance reasons.
             // It can be removed if it turns out to cause trouble or be detrimental to performance.
-            join(|| prefetch_mir(tcx), || tcx.exported_symbols(LOCAL_CRATE));
+            join(
+                || prefetch_mir(tcx),
+                || {
+                    if tcx.sess.opts.output_types.should_codegen() {
+                        tcx.exported_symbols(LOCAL_CRATE);
+                    }
+                },
+            );
         },
     );
 }
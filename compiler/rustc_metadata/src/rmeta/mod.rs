@@ -262,6 +262,9 @@ pub(crate) struct CrateRoot {
     panic_runtime: bool,
     profiler_runtime: bool,
     symbol_mangling_version: SymbolManglingVersion,
+    /// Whether the crate was built without codegen, e.g. with `--emit=metadata`, which changes
+    /// the hash it is identified by, see `CrateRoot::hash`.
+    metadata_only: bool,
 }
 
 /// On-disk representation of `DefId`.
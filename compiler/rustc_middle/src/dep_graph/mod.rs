@@ -7,12 +7,13 @@
 mod dep_node;
 
 pub use rustc_query_system::dep_graph::{
-    debug::DepNodeFilter, hash_result, DepContext, DepNodeColor, DepNodeIndex,
+    debug::DepNodeFilter, hash_result, DepContext, DepNodeColor, DepNodeIndex, RedDependencyCause,
     SerializedDepNodeIndex, WorkProduct, WorkProductId,
 };
 
 pub use dep_node::{label_strs, DepKind, DepNode, DepNodeExt};
-pub(crate) use dep_node::{make_compile_codegen_unit, make_compile_mono_item};
+pub use dep_node::make_compile_codegen_unit;
+pub(crate) use dep_node::make_compile_mono_item;
 
 pub type DepGraph = rustc_query_system::dep_graph::DepGraph<DepKind>;
 
@@ -20,6 +21,7 @@
 pub type TaskDepsRef<'a> = rustc_query_system::dep_graph::TaskDepsRef<'a, DepKind>;
 pub type DepGraphQuery = rustc_query_system::dep_graph::DepGraphQuery<DepKind>;
 pub type SerializedDepGraph = rustc_query_system::dep_graph::SerializedDepGraph<DepKind>;
+pub type RedDependencyChain = rustc_query_system::dep_graph::RedDependencyChain<DepKind>;
 pub type EdgeFilter = rustc_query_system::dep_graph::debug::EdgeFilter<DepKind>;
 pub type DepKindStruct<'tcx> = rustc_query_system::dep_graph::DepKindStruct<TyCtxt<'tcx>>;
 
@@ -1854,6 +1854,14 @@
         separate_provide_extern
     }
 
+    query layout_only_generic_params(key: ty::InstanceDef<'tcx>) -> LayoutOnlyGenericParams {
+        cache_on_disk_if { key.def_id().is_local() }
+        desc {
+            |tcx| "determining which generic parameters of `{}` only matter by layout",
+                tcx.def_path_str(key.def_id())
+        }
+    }
+
     query backend_optimization_level(_: ()) -> OptLevel {
         desc { "optimization level used by backend" }
     }
@@ -1,10 +1,13 @@
 //! A subset of a mir body used for const evaluatability checking.
+use crate::mir;
+use crate::ty::util::int_size_and_signed;
 use crate::ty::{
-    self, Const, EarlyBinder, Ty, TyCtxt, TypeFoldable, TypeFolder, TypeSuperFoldable,
-    TypeVisitable,
+    self, Const, EarlyBinder, Expr, ScalarInt, Ty, TyCtxt, TypeFoldable, TypeFolder,
+    TypeSuperFoldable, TypeVisitable,
 };
 use rustc_errors::ErrorGuaranteed;
 use rustc_hir::def_id::DefId;
+use rustc_target::abi::Size;
 
 #[derive(Hash, Debug, Clone, Copy, Ord, PartialOrd, PartialEq, Eq)]
 #[derive(TyDecodable, TyEncodable, HashStable, TypeVisitable, TypeFoldable)]
@@ -81,4 +84,280 @@ fn fold_const(&mut self, c: Const<'tcx>) -> Const<'tcx> {
         }
         ac.fold_with(&mut Expander { tcx: self })
     }
+
+    /// Rewrites an expanded abstract const into a normal form, so that consts which only differ
+    /// in ways which cannot change their value unify with each other.
+    ///
+    /// Calls to the operator traits on integers are replaced by the corresponding operator,
+    /// subexpressions with concrete operands are evaluated and the operands of commutative
+    /// operators are sorted, with concrete values last. Sums of unsigned integers and bitwise
+    /// operations are also reassociated, as that cannot change whether they overflow. This
+    /// means that e.g. `N + 1 + 2`, `3 + N` and `Add::add(N, 3)` all simplify to `N + 3`.
+    ///
+    /// Subexpressions which overflow or divide by zero are left as is, as are consts which
+    /// mention inference variables.
+    pub fn simplify_abstract_const(self, ct: Const<'tcx>) -> Const<'tcx> {
+        if ct.has_non_region_infer() {
+            return ct;
+        }
+        ct.fold_with(&mut Simplifier { tcx: self })
+    }
+}
+
+struct Simplifier<'tcx> {
+    tcx: TyCtxt<'tcx>,
+}
+
+enum BuiltinOp {
+    Bin(mir::BinOp),
+    Un(mir::UnOp),
+}
+
+impl<'tcx> TypeFolder<'tcx> for Simplifier<'tcx> {
+    fn tcx(&self) -> TyCtxt<'tcx> {
+        self.tcx
+    }
+
+    fn fold_ty(&mut self, ty: Ty<'tcx>) -> Ty<'tcx> {
+        ty
+    }
+
+    fn fold_const(&mut self, c: Const<'tcx>) -> Const<'tcx> {
+        let c = c.super_fold_with(self);
+        let ty::ConstKind::Expr(expr) = c.kind() else { return c };
+        match expr {
+            Expr::Binop(op, lhs, rhs) => self.simplify_binop(c, op, lhs, rhs),
+            Expr::UnOp(op, operand) => self.eval_unop(op, operand, c.ty()).unwrap_or(c),
+            Expr::Cast(CastKind::Use, operand, ty) if operand.ty() == ty => operand,
+            Expr::Cast(_, operand, ty) => self.eval_cast(operand, ty).unwrap_or(c),
+            Expr::FunctionCall(func, args) => match self.builtin_op(func, args) {
+                Some(BuiltinOp::Bin(op)) => {
+                    let binop = self.tcx.mk_const(Expr::Binop(op, args[0], args[1]), c.ty());
+                    self.simplify_binop(binop, op, args[0], args[1])
+                }
+                Some(BuiltinOp::Un(op)) => {
+                    let unop = self.tcx.mk_const(Expr::UnOp(op, args[0]), c.ty());
+                    self.eval_unop(op, args[0], c.ty()).unwrap_or(unop)
+                }
+                None => c,
+            },
+        }
+    }
+}
+
+impl<'tcx> Simplifier<'tcx> {
+    /// Returns the operator a call to a method of one of the operator traits corresponds to, if
+    /// the call uses the builtin implementation of that trait for integers or `bool`.
+    fn builtin_op(&self, func: Const<'tcx>, args: &[Const<'tcx>]) -> Option<BuiltinOp> {
+        use mir::BinOp::*;
+
+        let ty::FnDef(def_id, _) = *func.ty().kind() else { return None };
+        let trait_def_id = Some(self.tcx.trait_of_item(def_id)?);
+        if !args.iter().all(|arg| arg.ty().is_integral() || arg.ty().is_bool()) {
+            return None;
+        }
+
+        let lang_items = self.tcx.lang_items();
+        match *args {
+            [_] if trait_def_id == lang_items.neg_trait() => Some(BuiltinOp::Un(mir::UnOp::Neg)),
+            [_] if trait_def_id == lang_items.not_trait() => Some(BuiltinOp::Un(mir::UnOp::Not)),
+            [lhs, rhs] => {
+                let op = [
+                    (lang_items.add_trait(), Add),
+                    (lang_items.sub_trait(), Sub),
+                    (lang_items.mul_trait(), Mul),
+                    (lang_items.div_trait(), Div),
+                    (lang_items.rem_trait(), Rem),
+                    (lang_items.bitxor_trait(), BitXor),
+                    (lang_items.bitand_trait(), BitAnd),
+                    (lang_items.bitor_trait(), BitOr),
+                    (lang_items.shl_trait(), Shl),
+                    (lang_items.shr_trait(), Shr),
+                ]
+                .into_iter()
+                .find_map(|(lang_item, op)| (lang_item == trait_def_id).then_some(op))?;
+                // Apart from shifts, the builtin implementations take operands of the same type.
+                if !matches!(op, Shl | Shr) && lhs.ty() != rhs.ty() {
+                    return None;
+                }
+                Some(BuiltinOp::Bin(op))
+            }
+            _ => None,
+        }
+    }
+
+    fn simplify_binop(
+        &self,
+        c: Const<'tcx>,
+        op: mir::BinOp,
+        lhs: Const<'tcx>,
+        rhs: Const<'tcx>,
+    ) -> Const<'tcx> {
+        use mir::BinOp::*;
+
+        if let Some(value) = self.eval_binop(op, lhs, rhs, c.ty()) {
+            return value;
+        }
+        if !matches!(op, Add | Mul | BitAnd | BitOr | BitXor | Eq | Ne) {
+            return c;
+        }
+
+        // Reassociating an operation could change whether an intermediate result overflows,
+        // which cannot happen for bitwise operations or sums of unsigned integers.
+        let associative = matches!(op, BitAnd | BitOr | BitXor)
+            || (op == Add && matches!(lhs.ty().kind(), ty::Uint(_)));
+        let mut operands = vec![];
+        if associative {
+            self.collect_operands(op, c.ty(), c, &mut operands);
+        } else {
+            operands.extend([lhs, rhs]);
+        }
+        operands
+            .sort_by_key(|&operand| (matches!(operand.kind(), ty::ConstKind::Value(_)), operand));
+
+        // Combine all concrete operands into a single value, unless doing so overflows.
+        if associative {
+            let first_value = operands
+                .iter()
+                .position(|operand| matches!(operand.kind(), ty::ConstKind::Value(_)))
+                .unwrap_or(operands.len());
+            if let [first, ref rest @ ..] = operands[first_value..]
+                && let Some(value) = rest
+                    .iter()
+                    .try_fold(first, |acc, &value| self.eval_binop(op, acc, value, c.ty()))
+            {
+                operands.truncate(first_value);
+                operands.push(value);
+            }
+        }
+
+        operands
+            .into_iter()
+            .reduce(|lhs, rhs| self.tcx.mk_const(Expr::Binop(op, lhs, rhs), c.ty()))
+            .unwrap()
+    }
+
+    /// Collects the operands of a chain of `op` operations, e.g. `[N, M, 1]` for `(N + M) + 1`.
+    fn collect_operands(
+        &self,
+        op: mir::BinOp,
+        ty: Ty<'tcx>,
+        c: Const<'tcx>,
+        operands: &mut Vec<Const<'tcx>>,
+    ) {
+        match c.kind() {
+            ty::ConstKind::Expr(Expr::Binop(c_op, lhs, rhs)) if c_op == op && c.ty() == ty => {
+                self.collect_operands(op, ty, lhs, operands);
+                self.collect_operands(op, ty, rhs, operands);
+            }
+            _ => operands.push(c),
+        }
+    }
+
+    /// Returns the size and signedness of integers and `bool`.
+    fn int_layout(&self, ty: Ty<'tcx>) -> Option<(Size, bool)> {
+        match ty.kind() {
+            ty::Bool => Some((Size::from_bytes(1), false)),
+            ty::Int(_) | ty::Uint(_) => Some(int_size_and_signed(self.tcx, ty)),
+            _ => None,
+        }
+    }
+
+    fn value(&self, scalar: ScalarInt, ty: Ty<'tcx>) -> Const<'tcx> {
+        self.tcx.mk_const(ty::ValTree::from_scalar_int(scalar), ty)
+    }
+
+    fn eval_binop(
+        &self,
+        op: mir::BinOp,
+        lhs: Const<'tcx>,
+        rhs: Const<'tcx>,
+        ty: Ty<'tcx>,
+    ) -> Option<Const<'tcx>> {
+        use mir::BinOp::*;
+
+        let (size, signed) = self.int_layout(lhs.ty())?;
+        let l = lhs.kind().try_to_scalar_int()?.to_bits(size).ok()?;
+        let (r_size, r_signed) = self.int_layout(rhs.ty())?;
+        let r = rhs.kind().try_to_scalar_int()?.to_bits(r_size).ok()?;
+
+        let bits = match op {
+            Eq | Ne | Lt | Le | Gt | Ge => {
+                let ordering = if signed {
+                    (size.sign_extend(l) as i128).cmp(&(size.sign_extend(r) as i128))
+                } else {
+                    l.cmp(&r)
+                };
+                let holds = match op {
+                    Eq => ordering.is_eq(),
+                    Ne => ordering.is_ne(),
+                    Lt => ordering.is_lt(),
+                    Le => ordering.is_le(),
+                    Gt => ordering.is_gt(),
+                    _ => ordering.is_ge(),
+                };
+                return Some(Const::from_bool(self.tcx, holds));
+            }
+            BitAnd => l & r,
+            BitOr => l | r,
+            BitXor => l ^ r,
+            Shl | Shr => {
+                // The shift amount may be of any integer type, but has to be smaller than the
+                // width of the shifted integer.
+                let amount =
+                    if r_signed { u128::try_from(r_size.sign_extend(r) as i128).ok()? } else { r };
+                if amount >= u128::from(size.bits()) {
+                    return None;
+                }
+                match op {
+                    Shl => size.truncate(l << amount),
+                    _ if signed => size.truncate(((size.sign_extend(l) as i128) >> amount) as u128),
+                    _ => l >> amount,
+                }
+            }
+            Add | Sub | Mul | Div | Rem if signed => {
+                let (l, r) = (size.sign_extend(l) as i128, size.sign_extend(r) as i128);
+                let value = match op {
+                    Add => l.checked_add(r),
+                    Sub => l.checked_sub(r),
+                    Mul => l.checked_mul(r),
+                    Div => l.checked_div(r),
+                    _ => l.checked_rem(r),
+                }?;
+                return Some(self.value(ScalarInt::try_from_int(value, size)?, ty));
+            }
+            Add | Sub | Mul | Div | Rem => match op {
+                Add => l.checked_add(r),
+                Sub => l.checked_sub(r),
+                Mul => l.checked_mul(r),
+                Div => l.checked_div(r),
+                _ => l.checked_rem(r),
+            }?,
+            Offset => return None,
+        };
+        Some(self.value(ScalarInt::try_from_uint(bits, size)?, ty))
+    }
+
+    fn eval_unop(&self, op: mir::UnOp, operand: Const<'tcx>, ty: Ty<'tcx>) -> Option<Const<'tcx>> {
+        let (size, signed) = self.int_layout(operand.ty())?;
+        let bits = operand.kind().try_to_scalar_int()?.to_bits(size).ok()?;
+        let scalar = match op {
+            mir::UnOp::Not if operand.ty().is_bool() => ScalarInt::try_from_uint(bits ^ 1, size),
+            mir::UnOp::Not => ScalarInt::try_from_uint(size.truncate(!bits), size),
+            mir::UnOp::Neg if signed => {
+                ScalarInt::try_from_int((size.sign_extend(bits) as i128).checked_neg()?, size)
+            }
+            mir::UnOp::Neg => None,
+        }?;
+        Some(self.value(scalar, ty))
+    }
+
+    /// Evaluates an `as` cast between integer types.
+    fn eval_cast(&self, operand: Const<'tcx>, ty: Ty<'tcx>) -> Option<Const<'tcx>> {
+        let (size, signed) = self.int_layout(operand.ty())?;
+        let bits = operand.kind().try_to_scalar_int()?.to_bits(size).ok()?;
+        let (target_size, _) = self.int_layout(ty).filter(|_| !ty.is_bool())?;
+        let bits = if signed { size.sign_extend(bits) } else { bits };
+        Some(self.value(ScalarInt::try_from_uint(target_size.truncate(bits), target_size)?, ty))
+    }
 }
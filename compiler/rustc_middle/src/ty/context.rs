@@ -29,7 +29,7 @@
 use rustc_data_structures::fx::{FxHashMap, FxHashSet};
 use rustc_data_structures::intern::Interned;
 use rustc_data_structures::memmap::Mmap;
-use rustc_data_structures::profiling::SelfProfilerRef;
+use rustc_data_structures::profiling::{SelfProfilerRef, SelfTimeGuard};
 use rustc_data_structures::sharded::{IntoPointer, ShardedHashMap};
 use rustc_data_structures::stable_hasher::{HashStable, StableHasher};
 use rustc_data_structures::steal::Steal;
@@ -74,6 +74,7 @@
 use std::iter;
 use std::mem;
 use std::ops::{Bound, Deref};
+use std::time::Duration;
 
 pub trait OnDiskCache<'tcx>: rustc_data_structures::sync::Sync {
     /// Creates a new `OnDiskCache` instance from the serialized data in `data`.
@@ -459,9 +460,21 @@ pub struct GlobalCtxt<'tcx> {
 
     /// Stores memory for globals (statics/consts).
     pub(crate) alloc_map: Lock<interpret::AllocMap<'tcx>>,
+
+    /// The self time spent building the backend IR for all instances of each item,
+    /// recorded for `-Z self-profile-summary`.
+    pub codegen_self_times: Lock<FxHashMap<DefId, Duration>>,
 }
 
 impl<'tcx> TyCtxt<'tcx> {
+    /// Attributes the self time measured by `guard` while building the backend IR of an
+    /// instance to `def_id`, the generic item the instance was created from.
+    pub fn record_codegen_self_time(self, def_id: DefId, guard: SelfTimeGuard) {
+        if let Some(self_time) = guard.finish() {
+            *self.codegen_self_times.lock().entry(def_id).or_default() += self_time;
+        }
+    }
+
     /// Expects a body and returns its codegen attributes.
     ///
     /// Unlike `codegen_fn_attrs`, this returns `CodegenFnAttrs::EMPTY` for
@@ -619,6 +632,7 @@ pub fn create_global_ctxt(
             evaluation_cache: Default::default(),
             data_layout,
             alloc_map: Lock::new(interpret::AllocMap::new()),
+            codegen_self_times: Default::default(),
         }
     }
 
@@ -633,7 +633,8 @@ pub fn try_subst_mir_and_normalize_erasing_regions<T>(
     }
 
     /// Returns a new `Instance` where generic parameters in `instance.substs` are replaced by
-    /// identity parameters if they are determined to be unused in `instance.def`.
+    /// identity parameters if they are determined to be unused in `instance.def`, and by a type
+    /// with the same layout if they are determined to be only used through their layout.
     pub fn polymorphize(self, tcx: TyCtxt<'tcx>) -> Self {
         debug!("polymorphize: running polymorphization analysis");
         if !tcx.sess.opts.unstable_opts.polymorphize {
@@ -654,6 +655,8 @@ fn polymorphize<'tcx>(
     debug!("polymorphize({:?}, {:?})", instance, substs);
     let unused = tcx.unused_generic_params(instance);
     debug!("polymorphize: unused={:?}", unused);
+    let layout_only = tcx.layout_only_generic_params(instance);
+    debug!("polymorphize: layout_only={:?}", layout_only);
 
     // If this is a closure or generator then we need to handle the case where another closure
     // from the function is captured as an upvar and hasn't been polymorphized. In this case,
@@ -738,12 +741,56 @@ fn fold_ty(&mut self, ty: Ty<'tcx>) -> Ty<'tcx> {
                     // ..then use the identity for this parameter.
                     tcx.mk_param_from_def(param),
 
+            // Layout case: If parameter is a type parameter that is only used through its
+            // layout, then share the instance with all types of the same layout..
+            ty::GenericParamDefKind::Type { .. } if layout_only.contains(param.index) => {
+                let ty = substs.type_at(param.index as usize);
+                match layout_representative(tcx, ty) {
+                    // ..by using a canonical type with that layout instead..
+                    Some(representative) => ty::GenericArg::from(representative),
+                    // ..unless the layout is not one that can be shared.
+                    None => substs[param.index as usize],
+                }
+            }
+
             // Otherwise, use the parameter as before.
             _ => substs[param.index as usize],
         }
     })
 }
 
+/// Returns the type that stands in for all types with the same layout as `ty` in instances
+/// that only use a generic parameter through its layout: an array of `MaybeUninit<uN>`, where
+/// `uN` has the alignment of `ty`.
+///
+/// The parameter may still appear behind references in the signature of the instance, so only
+/// types that are `Freeze` and `Unpin` like the representative are replaced, as the attributes
+/// of those references would differ otherwise.
+fn layout_representative<'tcx>(tcx: TyCtxt<'tcx>, ty: Ty<'tcx>) -> Option<Ty<'tcx>> {
+    if ty.has_non_region_param() {
+        return None;
+    }
+
+    let param_env = ty::ParamEnv::reveal_all();
+    if !ty.is_freeze(tcx, param_env) || !ty.is_unpin(tcx, param_env) {
+        return None;
+    }
+
+    let layout = tcx.layout_of(param_env.and(ty)).ok()?;
+    if layout.is_unsized() || layout.abi.is_uninhabited() {
+        return None;
+    }
+
+    let maybe_uninit = tcx.adt_def(tcx.lang_items().maybe_uninit()?);
+    let uints = [tcx.types.u8, tcx.types.u16, tcx.types.u32, tcx.types.u64, tcx.types.u128];
+    uints.into_iter().find_map(|uint| {
+        let element = tcx.mk_adt(maybe_uninit, tcx.intern_substs(&[uint.into()]));
+        let element_layout = tcx.layout_of(param_env.and(element)).ok()?;
+        (element_layout.align.abi == layout.align.abi)
+            .then(|| tcx.mk_array(element, layout.size.bytes() / element_layout.size.bytes()))
+    })
+}
+
 fn needs_fn_once_adapter_shim(
     actual_closure_kind: ty::ClosureKind,
     trait_closure_kind: ty::ClosureKind,
@@ -808,3 +855,30 @@ pub fn all_used(&self) -> bool {
         self.0.is_empty()
     }
 }
+
+// Set bits represent generic parameters that are only used through their size and alignment.
+// An empty set indicates that no parameter can be replaced by a type with the same layout.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Decodable, Encodable, HashStable)]
+pub struct LayoutOnlyGenericParams(FiniteBitSet<u32>);
+
+impl LayoutOnlyGenericParams {
+    pub fn new_empty() -> Self {
+        Self(FiniteBitSet::new_empty())
+    }
+
+    pub fn insert(&mut self, idx: u32) {
+        self.0.set(idx);
+    }
+
+    pub fn remove(&mut self, idx: u32) {
+        self.0.clear(idx);
+    }
+
+    pub fn contains(&self, idx: u32) -> bool {
+        self.0.contains(idx).unwrap_or(false)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+}
@@ -3,7 +3,9 @@
     self, ConstInt, DefIdTree, ParamConst, ScalarInt, Term, TermKind, Ty, TyCtxt, TypeFoldable,
     TypeSuperFoldable, TypeSuperVisitable, TypeVisitable,
 };
-use crate::ty::{GenericArg, GenericArgKind};
+use crate::ty::abstract_const::CastKind;
+use crate::ty::{Expr, GenericArg, GenericArgKind};
+use rustc_ast::util::parser::AssocOp;
 use rustc_apfloat::ieee::{Double, Single};
 use rustc_data_structures::fx::{FxHashMap, FxIndexMap};
 use rustc_data_structures::sso::SsoHashSet;
@@ -1324,14 +1326,67 @@ macro_rules! print_underscore {
                 self.pretty_print_bound_var(debruijn, bound_var)?
             }
             ty::ConstKind::Placeholder(placeholder) => p!(write("Placeholder({:?})", placeholder)),
-            // FIXME(generic_const_exprs):
-            // write out some legible representation of an abstract const?
-            ty::ConstKind::Expr(_) => p!("[const expr]"),
+            ty::ConstKind::Expr(expr) => return self.pretty_print_const_expr(expr),
             ty::ConstKind::Error(_) => p!("[const error]"),
         };
         Ok(self)
     }
 
+    fn pretty_print_const_expr(mut self, expr: Expr<'tcx>) -> Result<Self::Const, Self::Error> {
+        define_scoped_cx!(self);
+
+        fn precedence(op: crate::mir::BinOp) -> usize {
+            AssocOp::from_ast_binop(op.to_hir_binop().into()).precedence()
+        }
+        let cast_precedence = AssocOp::As.precedence();
+        let prefix_precedence = cast_precedence + 1;
+
+        // Operands which bind less tightly than the operator they are used with are
+        // parenthesized, as in `(N + 1) * 2` or `(N + 1) as u8`.
+        let operand_precedence = |ct: ty::Const<'tcx>| match ct.kind() {
+            ty::ConstKind::Expr(Expr::Binop(op, ..)) => precedence(op),
+            ty::ConstKind::Expr(Expr::Cast(CastKind::As, ..)) => cast_precedence,
+            ty::ConstKind::Expr(Expr::UnOp(..)) => prefix_precedence,
+            _ => usize::MAX,
+        };
+        macro_rules! print_operand {
+            ($ct:expr, $parenthesized:expr) => {{
+                if $parenthesized {
+                    p!("(", print($ct), ")");
+                } else {
+                    p!(print($ct));
+                }
+            }};
+        }
+
+        match expr {
+            Expr::Binop(op, lhs, rhs) => {
+                let op_precedence = precedence(op);
+                print_operand!(lhs, operand_precedence(lhs) < op_precedence);
+                p!(write(" {} ", op.to_hir_binop().as_str()));
+                print_operand!(rhs, operand_precedence(rhs) <= op_precedence);
+            }
+            Expr::UnOp(op, operand) => {
+                p!(write("{}", if op == crate::mir::UnOp::Not { "!" } else { "-" }));
+                print_operand!(operand, operand_precedence(operand) < prefix_precedence);
+            }
+            Expr::Cast(CastKind::As, operand, ty) => {
+                print_operand!(operand, operand_precedence(operand) < cast_precedence);
+                p!(" as ", print(ty));
+            }
+            // Coercions are implicit in the source.
+            Expr::Cast(CastKind::Use, operand, _) => p!(print(operand)),
+            Expr::FunctionCall(func, args) => {
+                match *func.ty().kind() {
+                    ty::FnDef(def_id, substs) => p!(print_value_path(def_id, substs)),
+                    _ => p!(print(func)),
+                }
+                p!("(", comma_sep(args.iter()), ")");
+            }
+        }
+        Ok(self)
+    }
+
     fn pretty_print_const_scalar(
         self,
         scalar: Scalar,
@@ -34,7 +34,10 @@
 use crate::ty::subst::{GenericArg, SubstsRef};
 use crate::ty::util::AlwaysRequiresDrop;
 use crate::ty::GeneratorDiagnosticData;
-use crate::ty::{self, CrateInherentImpls, ParamEnvAnd, Ty, TyCtxt, UnusedGenericParams};
+use crate::ty::{
+    self, CrateInherentImpls, LayoutOnlyGenericParams, ParamEnvAnd, Ty, TyCtxt,
+    UnusedGenericParams,
+};
 use rustc_ast as ast;
 use rustc_ast::expand::allocator::AllocatorKind;
 use rustc_attr as attr;
@@ -224,7 +227,13 @@ pub fn $name(self, key: query_helper_param_ty!($($K)*)) {
                 let key = key.into_query_param();
                 opt_remap_env_constness!([$($modifiers)*][key]);
 
-                let cached = try_get_cached(self.tcx, &self.tcx.query_caches.$name, &key, noop);
+                let cached = try_get_cached(
+                    self.tcx,
+                    &self.tcx.query_caches.$name,
+                    dep_graph::DepKind::$name,
+                    &key,
+                    noop,
+                );
 
                 match cached {
                     Ok(()) => return,
@@ -253,7 +262,13 @@ pub fn $name(self, key: query_helper_param_ty!($($K)*)) -> query_stored::$name<'
                 let key = key.into_query_param();
                 opt_remap_env_constness!([$($modifiers)*][key]);
 
-                let cached = try_get_cached(self.tcx, &self.tcx.query_caches.$name, &key, copy);
+                let cached = try_get_cached(
+                    self.tcx,
+                    &self.tcx.query_caches.$name,
+                    dep_graph::DepKind::$name,
+                    &key,
+                    copy,
+                );
 
                 match cached {
                     Ok(value) => return value,
@@ -352,7 +367,7 @@ pub fn $name(self, value: $V) -> query_stored::$name<'tcx> {
                 let tcx = self.tcx;
                 let cache = &tcx.query_caches.$name;
 
-                let cached = try_get_cached(tcx, cache, &key, copy);
+                let cached = try_get_cached(tcx, cache, dep_graph::DepKind::$name, &key, copy);
 
                 match cached {
                     Ok(old) => {
@@ -5,7 +5,7 @@
 //! subtyping, type equality, etc.
 
 use crate::ty::error::{ExpectedFound, TypeError};
-use crate::ty::{self, Expr, ImplSubject, Term, TermKind, Ty, TyCtxt, TypeFoldable};
+use crate::ty::{self, Expr, ImplSubject, Term, TermKind, Ty, TyCtxt, TypeFoldable, TypeVisitable};
 use crate::ty::{GenericArg, GenericArgKind, SubstsRef};
 use rustc_hir as ast;
 use rustc_hir::def_id::DefId;
@@ -663,48 +663,77 @@ pub fn super_relate_consts<'tcx, R: TypeRelation<'tcx>>(
         (ty::ConstKind::Expr(ae), ty::ConstKind::Expr(be)) => {
             let r = relation;
 
-            // FIXME(generic_const_exprs): is it possible to relate two consts which are not identical
-            // exprs? Should we care about that?
-            let expr = match (ae, be) {
-                (Expr::Binop(a_op, al, ar), Expr::Binop(b_op, bl, br))
-                    if a_op == b_op && al.ty() == bl.ty() && ar.ty() == br.ty() =>
-                {
-                    Expr::Binop(a_op, r.consts(al, bl)?, r.consts(ar, br)?)
-                }
-                (Expr::UnOp(a_op, av), Expr::UnOp(b_op, bv))
-                    if a_op == b_op && av.ty() == bv.ty() =>
-                {
-                    Expr::UnOp(a_op, r.consts(av, bv)?)
-                }
-                (Expr::Cast(ak, av, at), Expr::Cast(bk, bv, bt))
-                    if ak == bk && av.ty() == bv.ty() =>
-                {
-                    Expr::Cast(ak, r.consts(av, bv)?, r.tys(at, bt)?)
-                }
-                (Expr::FunctionCall(af, aa), Expr::FunctionCall(bf, ba))
-                    if aa.len() == ba.len()
-                        && af.ty() == bf.ty()
-                        && aa
-                            .iter()
-                            .zip(ba.iter())
-                            .all(|(a_arg, b_arg)| a_arg.ty() == b_arg.ty()) =>
-                {
-                    let func = r.consts(af, bf)?;
-                    let mut related_args = Vec::with_capacity(aa.len());
-                    for (a_arg, b_arg) in aa.iter().zip(ba.iter()) {
-                        related_args.push(r.consts(a_arg, b_arg)?);
+            // Exprs which are not structurally equal may still be equal once simplified,
+            // e.g. `N + 1` and `1 + N`.
+            let mut relate_exprs = || -> RelateResult<'tcx, Expr<'tcx>> {
+                Ok(match (ae, be) {
+                    (Expr::Binop(a_op, al, ar), Expr::Binop(b_op, bl, br))
+                        if a_op == b_op && al.ty() == bl.ty() && ar.ty() == br.ty() =>
+                    {
+                        Expr::Binop(a_op, r.consts(al, bl)?, r.consts(ar, br)?)
                     }
-                    let related_args = tcx.mk_const_list(related_args.iter());
-                    Expr::FunctionCall(func, related_args)
-                }
-                _ => return Err(TypeError::ConstMismatch(expected_found(r, a, b))),
+                    (Expr::UnOp(a_op, av), Expr::UnOp(b_op, bv))
+                        if a_op == b_op && av.ty() == bv.ty() =>
+                    {
+                        Expr::UnOp(a_op, r.consts(av, bv)?)
+                    }
+                    (Expr::Cast(ak, av, at), Expr::Cast(bk, bv, bt))
+                        if ak == bk && av.ty() == bv.ty() =>
+                    {
+                        Expr::Cast(ak, r.consts(av, bv)?, r.tys(at, bt)?)
+                    }
+                    (Expr::FunctionCall(af, aa), Expr::FunctionCall(bf, ba))
+                        if aa.len() == ba.len()
+                            && af.ty() == bf.ty()
+                            && aa
+                                .iter()
+                                .zip(ba.iter())
+                                .all(|(a_arg, b_arg)| a_arg.ty() == b_arg.ty()) =>
+                    {
+                        let func = r.consts(af, bf)?;
+                        let mut related_args = Vec::with_capacity(aa.len());
+                        for (a_arg, b_arg) in aa.iter().zip(ba.iter()) {
+                            related_args.push(r.consts(a_arg, b_arg)?);
+                        }
+                        let related_args = tcx.mk_const_list(related_args.iter());
+                        Expr::FunctionCall(func, related_args)
+                    }
+                    _ => return Err(TypeError::ConstMismatch(expected_found(r, a, b))),
+                })
+            };
+            return match relate_exprs() {
+                Ok(expr) => Ok(tcx.mk_const(ty::ConstKind::Expr(expr), a.ty())),
+                Err(err) => relate_simplified_consts(r, a, b).map_err(|_| err),
             };
-            let kind = ty::ConstKind::Expr(expr);
-            return Ok(tcx.mk_const(kind, a.ty()));
         }
         _ => false,
     };
-    if is_match { Ok(a) } else { Err(TypeError::ConstMismatch(expected_found(relation, a, b))) }
+    if is_match { Ok(a) } else { relate_simplified_consts(relation, a, b) }
+}
+
+/// Relates two expanded abstract consts which are not structurally equal by relating their
+/// simplified forms instead, so that e.g. `N + 1` and `1 + N` are related successfully.
+///
+/// Errors refer to the consts as written rather than to their simplified forms.
+fn relate_simplified_consts<'tcx, R: TypeRelation<'tcx>>(
+    relation: &mut R,
+    a: ty::Const<'tcx>,
+    b: ty::Const<'tcx>,
+) -> RelateResult<'tcx, ty::Const<'tcx>> {
+    let tcx = relation.tcx();
+    if tcx.features().generic_const_exprs
+        && !a.has_non_region_infer()
+        && !b.has_non_region_infer()
+    {
+        let simplified_a = tcx.simplify_abstract_const(a);
+        let simplified_b = tcx.simplify_abstract_const(b);
+        if (simplified_a, simplified_b) != (a, b)
+            && let Ok(ct) = relation.consts(simplified_a, simplified_b)
+        {
+            return Ok(ct);
+        }
+    }
+    Err(TypeError::ConstMismatch(expected_found(relation, a, b)))
 }
 
 impl<'tcx> Relate<'tcx> for &'tcx ty::List<ty::PolyExistentialPredicate<'tcx>> {
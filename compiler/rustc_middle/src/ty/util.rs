@@ -60,7 +60,7 @@ fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
     }
 }
 
-fn int_size_and_signed<'tcx>(tcx: TyCtxt<'tcx>, ty: Ty<'tcx>) -> (Size, bool) {
+pub(crate) fn int_size_and_signed<'tcx>(tcx: TyCtxt<'tcx>, ty: Ty<'tcx>) -> (Size, bool) {
     let (int, signed) = match *ty.kind() {
         ty::Int(ity) => (Integer::from_int_ty(&tcx, ity), true),
         ty::Uint(uty) => (Integer::from_uint_ty(&tcx, uty), false),
@@ -0,0 +1,533 @@
+//! Global value numbering.
+//!
+//! This pass finds rvalues that compute a value which is already stored in another local, and
+//! replaces them with a copy of that local. This catches repeated projections, `Len`
+//! computations, arithmetic and calls to pure intrinsics, which would otherwise only be merged
+//! by the codegen backend, if at all.
+//!
+//! Values are only tracked through locals that are assigned exactly once, whose assignment
+//! dominates all their uses and which are never borrowed (we call them SSA locals). As the value
+//! of such a local can never change after its assignment, two rvalues that are built from the
+//! same operations on the same SSA locals compute the same value. Memory is only read through
+//! shared references to `Freeze` types, which cannot be mutated while the reference is live.
+//!
+//! The replacement is only done when the assignment of the reused local dominates the replaced
+//! rvalue. Since the reused local is now live for longer than before, its storage statements are
+//! removed, and moves out of it are turned into copies.
+//!
+//! The pass runs at `mir-opt-level>=2`, and can be turned off with `-Zmir-enable-passes=-GVN`.
+
+use crate::MirPass;
+
+use rustc_data_structures::fx::{FxHashMap, FxIndexSet};
+use rustc_data_structures::graph::dominators::Dominators;
+use rustc_index::bit_set::BitSet;
+use rustc_index::vec::IndexVec;
+use rustc_middle::mir::visit::*;
+use rustc_middle::mir::*;
+use rustc_middle::ty::{self, Ty, TyCtxt};
+use rustc_span::symbol::sym;
+use smallvec::SmallVec;
+
+pub struct GVN;
+
+impl<'tcx> MirPass<'tcx> for GVN {
+    fn is_enabled(&self, sess: &rustc_session::Session) -> bool {
+        sess.mir_opt_level() >= 2
+    }
+
+    #[instrument(level = "trace", skip(self, tcx, body))]
+    fn run_pass(&self, tcx: TyCtxt<'tcx>, body: &mut Body<'tcx>) {
+        debug!(def_id = ?body.source.def_id());
+        let param_env = tcx.param_env_reveal_all_normalized(body.source.def_id());
+        let dominators = body.basic_blocks.dominators();
+        let ssa = SsaLocals::new(body, &dominators);
+
+        let mut state = VnState::new(tcx, param_env, body, &ssa, &dominators);
+        state.number_body();
+        let VnState { replacements, reused, .. } = state;
+        if replacements.is_empty() {
+            return;
+        }
+        debug!(?replacements, ?reused);
+
+        let mut replacer = Replacer { tcx, replacements, reused };
+        replacer.visit_body(body);
+    }
+}
+
+/// Where the value of an SSA local becomes available.
+#[derive(Copy, Clone, Debug)]
+enum Assignment {
+    /// The local is not assigned to (yet).
+    None,
+    /// The local is a function argument, which is available everywhere.
+    Argument,
+    /// The local is available from this location on.
+    At(Location),
+    /// The local is assigned to several times, or in a way we don't track.
+    Many,
+}
+
+struct SsaLocals {
+    assignments: IndexVec<Local, Assignment>,
+}
+
+impl SsaLocals {
+    fn new<'tcx>(body: &Body<'tcx>, dominators: &Dominators<BasicBlock>) -> SsaLocals {
+        let mut assignments = IndexVec::from_elem(Assignment::None, &body.local_decls);
+        for arg in body.args_iter() {
+            assignments[arg] = Assignment::Argument;
+        }
+        // The return place is read by the `Return` terminator.
+        assignments[RETURN_PLACE] = Assignment::Many;
+
+        let mut visitor = AssignmentVisitor { body, dominators, assignments };
+        visitor.visit_body(body);
+        let mut assignments = visitor.assignments;
+
+        // Check that the assignment of each local dominates all of its uses.
+        let mut visitor = UseVisitor { dominators, assignments: &mut assignments };
+        for (bb, data) in body.basic_blocks.iter_enumerated() {
+            if dominators.is_reachable(bb) {
+                visitor.visit_basic_block_data(bb, data);
+            }
+        }
+
+        SsaLocals { assignments }
+    }
+
+    fn is_ssa(&self, local: Local) -> bool {
+        matches!(self.assignments[local], Assignment::Argument | Assignment::At(_))
+    }
+
+    /// Whether the value of `local` is available at `location`.
+    fn is_available(
+        &self,
+        local: Local,
+        location: Location,
+        dominators: &Dominators<BasicBlock>,
+    ) -> bool {
+        match self.assignments[local] {
+            Assignment::Argument => true,
+            Assignment::At(assigned) => assigned.dominates(location, dominators),
+            Assignment::None | Assignment::Many => false,
+        }
+    }
+}
+
+struct AssignmentVisitor<'a, 'tcx> {
+    body: &'a Body<'tcx>,
+    dominators: &'a Dominators<BasicBlock>,
+    assignments: IndexVec<Local, Assignment>,
+}
+
+impl AssignmentVisitor<'_, '_> {
+    fn assign(&mut self, local: Local, available_from: Option<Location>) {
+        let assignment = &mut self.assignments[local];
+        *assignment = match (*assignment, available_from) {
+            (Assignment::None, Some(location)) if self.dominators.is_reachable(location.block) => {
+                Assignment::At(location)
+            }
+            _ => Assignment::Many,
+        };
+    }
+}
+
+impl<'tcx> Visitor<'tcx> for AssignmentVisitor<'_, 'tcx> {
+    fn visit_statement(&mut self, statement: &Statement<'tcx>, location: Location) {
+        if let StatementKind::Assign(box (place, rvalue)) = &statement.kind
+            && let Some(local) = place.as_local()
+        {
+            self.assign(local, Some(location.successor_within_block()));
+            self.visit_rvalue(rvalue, location);
+        } else {
+            self.super_statement(statement, location);
+        }
+    }
+
+    fn visit_terminator(&mut self, terminator: &Terminator<'tcx>, location: Location) {
+        if let TerminatorKind::Call { func, args, destination, target, .. } = &terminator.kind
+            && let Some(local) = destination.as_local()
+        {
+            // The destination is only written on the return edge, so it is available from the
+            // start of the target block if that block can only be reached through this call.
+            let available_from = target
+                .filter(|&target| self.body.basic_blocks.predecessors()[target].len() == 1)
+                .map(|target| Location { block: target, statement_index: 0 });
+            self.assign(local, available_from);
+            self.visit_operand(func, location);
+            for arg in args {
+                self.visit_operand(arg, location);
+            }
+        } else {
+            self.super_terminator(terminator, location);
+        }
+    }
+
+    fn visit_local(&mut self, local: Local, context: PlaceContext, _: Location) {
+        match context {
+            PlaceContext::NonMutatingUse(
+                NonMutatingUseContext::Copy
+                | NonMutatingUseContext::Move
+                | NonMutatingUseContext::Inspect
+                | NonMutatingUseContext::Projection,
+            )
+            | PlaceContext::NonUse(_) => {}
+            // Borrows, and writes we don't track.
+            _ => self.assignments[local] = Assignment::Many,
+        }
+    }
+}
+
+struct UseVisitor<'a> {
+    dominators: &'a Dominators<BasicBlock>,
+    assignments: &'a mut IndexVec<Local, Assignment>,
+}
+
+impl<'tcx> Visitor<'tcx> for UseVisitor<'_> {
+    fn visit_local(&mut self, local: Local, context: PlaceContext, location: Location) {
+        if context.is_use()
+            && let Assignment::At(assigned) = self.assignments[local]
+            && !assigned.dominates(location, self.dominators)
+        {
+            self.assignments[local] = Assignment::Many;
+        }
+    }
+}
+
+rustc_index::newtype_index! {
+    #[debug_format = "vn{}"]
+    struct VnIndex {}
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+enum Value<'tcx> {
+    /// The value of an SSA local that we don't know anything else about.
+    Opaque(Local),
+    Constant(ConstantKind<'tcx>),
+    /// The value of the place reached by applying the projection to the value.
+    Projection(VnIndex, ProjectionElem<VnIndex, Ty<'tcx>>),
+    /// The length of the slice or `str` behind the pointer.
+    Len(VnIndex),
+    Discriminant(VnIndex),
+    NullaryOp(NullOp, Ty<'tcx>),
+    UnaryOp(UnOp, VnIndex),
+    BinaryOp(BinOp, VnIndex, VnIndex),
+    CheckedBinaryOp(BinOp, VnIndex, VnIndex),
+    Cast(CastKind, VnIndex, Ty<'tcx>),
+    /// A call to a pure intrinsic, identified by the type of the function.
+    Intrinsic(Ty<'tcx>, SmallVec<[VnIndex; 2]>),
+}
+
+struct VnState<'body, 'tcx> {
+    tcx: TyCtxt<'tcx>,
+    param_env: ty::ParamEnv<'tcx>,
+    body: &'body Body<'tcx>,
+    ssa: &'body SsaLocals,
+    dominators: &'body Dominators<BasicBlock>,
+    values: FxIndexSet<Value<'tcx>>,
+    /// The value of each SSA local we have seen the assignment of.
+    locals: IndexVec<Local, Option<VnIndex>>,
+    /// The SSA locals that hold each value.
+    holders: FxHashMap<VnIndex, SmallVec<[Local; 1]>>,
+    /// The rvalues, or the results of intrinsic calls, to replace with a copy of a local.
+    replacements: FxHashMap<Location, Local>,
+    /// The locals that are now used in places where they were not used before.
+    reused: BitSet<Local>,
+}
+
+impl<'body, 'tcx> VnState<'body, 'tcx> {
+    fn new(
+        tcx: TyCtxt<'tcx>,
+        param_env: ty::ParamEnv<'tcx>,
+        body: &'body Body<'tcx>,
+        ssa: &'body SsaLocals,
+        dominators: &'body Dominators<BasicBlock>,
+    ) -> Self {
+        VnState {
+            tcx,
+            param_env,
+            body,
+            ssa,
+            dominators,
+            values: FxIndexSet::default(),
+            locals: IndexVec::from_elem(None, &body.local_decls),
+            holders: FxHashMap::default(),
+            replacements: FxHashMap::default(),
+            reused: BitSet::new_empty(body.local_decls.len()),
+        }
+    }
+
+    fn insert(&mut self, value: Value<'tcx>) -> VnIndex {
+        let (index, _) = self.values.insert_full(value);
+        VnIndex::from_usize(index)
+    }
+
+    /// Visits the blocks in reverse postorder, so the assignment of each SSA local is visited
+    /// before its uses.
+    fn number_body(&mut self) {
+        let body = self.body;
+        for (block, data) in traversal::reverse_postorder(body) {
+            for (statement_index, statement) in data.statements.iter().enumerate() {
+                let location = Location { block, statement_index };
+                if let StatementKind::Assign(box (place, rvalue)) = &statement.kind {
+                    self.number_assignment(*place, rvalue, location);
+                }
+            }
+
+            let location = Location { block, statement_index: data.statements.len() };
+            if let TerminatorKind::Call { func, args, destination, target: Some(_), .. } =
+                &data.terminator().kind
+            {
+                self.number_call(func, args, *destination, location);
+            }
+        }
+    }
+
+    fn number_assignment(
+        &mut self,
+        place: Place<'tcx>,
+        rvalue: &Rvalue<'tcx>,
+        location: Location,
+    ) {
+        let Some(value) = self.rvalue_value(rvalue, location) else { return };
+
+        // Constants and copies of locals are cheap, so there is nothing to gain from replacing
+        // them. Reads from memory are worth replacing.
+        let is_cheap = match rvalue {
+            Rvalue::Use(Operand::Constant(_)) => true,
+            Rvalue::Use(Operand::Copy(place) | Operand::Move(place)) => !place.is_indirect(),
+            _ => false,
+        };
+        if !is_cheap
+            && rvalue.ty(self.body, self.tcx).is_copy_modulo_regions(self.tcx, self.param_env)
+            && let Some(holder) = self.available_holder(value, location)
+        {
+            debug!("replacing {:?} at {:?} with a copy of {:?}", rvalue, location, holder);
+            self.replacements.insert(location, holder);
+            self.reused.insert(holder);
+        }
+
+        if let Some(local) = place.as_local() {
+            self.assign(local, value);
+        }
+    }
+
+    fn number_call(
+        &mut self,
+        func: &Operand<'tcx>,
+        args: &[Operand<'tcx>],
+        destination: Place<'tcx>,
+        location: Location,
+    ) {
+        let Some(local) = destination.as_local() else { return };
+        if !self.ssa.is_ssa(local) {
+            return;
+        }
+        let func_ty = func.ty(self.body, self.tcx);
+        let ty::FnDef(def_id, _) = *func_ty.kind() else { return };
+        if !self.tcx.is_intrinsic(def_id) || !is_pure_intrinsic(self.tcx.item_name(def_id)) {
+            return;
+        }
+        let Some(args) =
+            args.iter().map(|arg| self.operand_value(arg, location)).collect::<Option<_>>()
+        else {
+            return;
+        };
+        let value = self.insert(Value::Intrinsic(func_ty, args));
+
+        if let Some(holder) = self.available_holder(value, location) {
+            debug!("replacing call at {:?} with a copy of {:?}", location, holder);
+            self.replacements.insert(location, holder);
+            self.reused.insert(holder);
+        }
+        self.assign(local, value);
+    }
+
+    fn assign(&mut self, local: Local, value: VnIndex) {
+        if self.ssa.is_ssa(local) {
+            self.locals[local] = Some(value);
+            self.holders.entry(value).or_default().push(local);
+        }
+    }
+
+    /// Returns an SSA local holding `value` which is available at `location`.
+    fn available_holder(&self, value: VnIndex, location: Location) -> Option<Local> {
+        self.holders
+            .get(&value)?
+            .iter()
+            .copied()
+            .find(|&holder| self.ssa.is_available(holder, location, self.dominators))
+    }
+
+    fn local_value(&mut self, local: Local, location: Location) -> Option<VnIndex> {
+        if !self.ssa.is_ssa(local) || !self.ssa.is_available(local, location, self.dominators) {
+            return None;
+        }
+        Some(match self.locals[local] {
+            Some(value) => value,
+            None => {
+                let value = self.insert(Value::Opaque(local));
+                self.locals[local] = Some(value);
+                value
+            }
+        })
+    }
+
+    /// Returns the value stored in `place`, if it cannot change while the locals it mentions
+    /// are live.
+    fn place_value(&mut self, place: Place<'tcx>, location: Location) -> Option<VnIndex> {
+        let mut value = self.local_value(place.local, location)?;
+        for (base, elem) in place.as_ref().iter_projections() {
+            let elem = match elem {
+                ProjectionElem::Deref => {
+                    let base_ty = base.ty(self.body, self.tcx).ty;
+                    match *base_ty.kind() {
+                        ty::Ref(_, pointee, Mutability::Not)
+                            if pointee.is_freeze(self.tcx, self.param_env) =>
+                        {
+                            ProjectionElem::Deref
+                        }
+                        _ => return None,
+                    }
+                }
+                ProjectionElem::Index(index) => {
+                    ProjectionElem::Index(self.local_value(index, location)?)
+                }
+                ProjectionElem::Field(field, ty) => ProjectionElem::Field(field, ty),
+                ProjectionElem::ConstantIndex { offset, min_length, from_end } => {
+                    ProjectionElem::ConstantIndex { offset, min_length, from_end }
+                }
+                ProjectionElem::Subslice { from, to, from_end } => {
+                    ProjectionElem::Subslice { from, to, from_end }
+                }
+                ProjectionElem::Downcast(name, variant) => ProjectionElem::Downcast(name, variant),
+                ProjectionElem::OpaqueCast(ty) => ProjectionElem::OpaqueCast(ty),
+            };
+            value = self.insert(Value::Projection(value, elem));
+        }
+        Some(value)
+    }
+
+    fn operand_value(&mut self, operand: &Operand<'tcx>, location: Location) -> Option<VnIndex> {
+        match operand {
+            Operand::Constant(constant) => Some(self.insert(Value::Constant(constant.literal))),
+            Operand::Copy(place) | Operand::Move(place) => self.place_value(*place, location),
+        }
+    }
+
+    fn rvalue_value(&mut self, rvalue: &Rvalue<'tcx>, location: Location) -> Option<VnIndex> {
+        let value = match rvalue {
+            Rvalue::Use(operand) => return self.operand_value(operand, location),
+            Rvalue::Len(place) => {
+                // The length only depends on the metadata of the pointer, not on the memory
+                // behind it.
+                let [ProjectionElem::Deref] = place.projection[..] else { return None };
+                Value::Len(self.local_value(place.local, location)?)
+            }
+            Rvalue::Discriminant(place) => Value::Discriminant(self.place_value(*place, location)?),
+            Rvalue::NullaryOp(op, ty) => Value::NullaryOp(*op, *ty),
+            Rvalue::UnaryOp(op, operand) => {
+                Value::UnaryOp(*op, self.operand_value(operand, location)?)
+            }
+            Rvalue::BinaryOp(op, box (lhs, rhs)) => {
+                let lhs = self.operand_value(lhs, location)?;
+                let rhs = self.operand_value(rhs, location)?;
+                Value::BinaryOp(*op, lhs, rhs)
+            }
+            Rvalue::CheckedBinaryOp(op, box (lhs, rhs)) => {
+                let lhs = self.operand_value(lhs, location)?;
+                let rhs = self.operand_value(rhs, location)?;
+                Value::CheckedBinaryOp(*op, lhs, rhs)
+            }
+            Rvalue::Cast(kind, operand, ty) => {
+                Value::Cast(*kind, self.operand_value(operand, location)?, *ty)
+            }
+            Rvalue::CopyForDeref(place) => return self.place_value(*place, location),
+            // References and raw pointers created at different places are different values,
+            // and aggregates are not worth keeping around.
+            Rvalue::Ref(..)
+            | Rvalue::AddressOf(..)
+            | Rvalue::ThreadLocalRef(..)
+            | Rvalue::Repeat(..)
+            | Rvalue::Aggregate(..)
+            | Rvalue::ShallowInitBox(..) => return None,
+        };
+        Some(self.insert(value))
+    }
+}
+
+/// Intrinsics whose result only depends on their arguments, and which have no side effects.
+fn is_pure_intrinsic(name: rustc_span::Symbol) -> bool {
+    matches!(
+        name,
+        sym::bitreverse
+            | sym::bswap
+            | sym::ctlz
+            | sym::ctpop
+            | sym::cttz
+            | sym::rotate_left
+            | sym::rotate_right
+            | sym::saturating_add
+            | sym::saturating_sub
+    )
+}
+
+struct Replacer<'tcx> {
+    tcx: TyCtxt<'tcx>,
+    replacements: FxHashMap<Location, Local>,
+    reused: BitSet<Local>,
+}
+
+impl<'tcx> MutVisitor<'tcx> for Replacer<'tcx> {
+    fn tcx(&self) -> TyCtxt<'tcx> {
+        self.tcx
+    }
+
+    fn visit_basic_block_data(&mut self, block: BasicBlock, data: &mut BasicBlockData<'tcx>) {
+        let location = Location { block, statement_index: data.statements.len() };
+        if let Some(&holder) = self.replacements.get(&location) {
+            let terminator = data.terminator_mut();
+            let TerminatorKind::Call { destination, target: Some(target), .. } = terminator.kind
+            else {
+                bug!("replaced a terminator which is not a returning call")
+            };
+            let source_info = terminator.source_info;
+            terminator.kind = TerminatorKind::Goto { target };
+            data.statements.push(Statement {
+                source_info,
+                kind: StatementKind::Assign(Box::new((
+                    destination,
+                    Rvalue::Use(Operand::Copy(holder.into())),
+                ))),
+            });
+        }
+        self.super_basic_block_data(block, data);
+    }
+
+    fn visit_statement(&mut self, statement: &mut Statement<'tcx>, location: Location) {
+        if let StatementKind::StorageLive(local) | StatementKind::StorageDead(local) =
+            statement.kind
+            && self.reused.contains(local)
+        {
+            statement.make_nop();
+            return;
+        }
+        if let StatementKind::Assign(box (_, rvalue)) = &mut statement.kind
+            && let Some(&holder) = self.replacements.get(&location)
+        {
+            *rvalue = Rvalue::Use(Operand::Copy(holder.into()));
+        }
+        self.super_statement(statement, location);
+    }
+
+    fn visit_operand(&mut self, operand: &mut Operand<'tcx>, location: Location) {
+        if let Operand::Move(place) = *operand
+            && self.reused.contains(place.local)
+        {
+            *operand = Operand::Copy(place);
+        }
+        self.super_operand(operand, location);
+    }
+}
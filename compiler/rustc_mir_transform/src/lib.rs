@@ -69,6 +69,7 @@
 mod ffi_unwind_calls;
 mod function_item_references;
 mod generator;
+mod gvn;
 mod inline;
 mod instcombine;
 mod lower_intrinsics;
@@ -77,6 +78,7 @@
 mod multiple_return_terminators;
 mod normalize_array_len;
 mod nrvo;
+mod outline_cold_panics;
 mod remove_noop_landing_pads;
 mod remove_storage_markers;
 mod remove_uninit_drops;
@@ -569,6 +571,7 @@ fn o1<T>(x: T) -> WithMinOptLevel<T> {
             &simplify_comparison_integral::SimplifyComparisonIntegral,
             &simplify_try::SimplifyArmIdentity,
             &simplify_try::SimplifyBranchSame,
+            &gvn::GVN,
             &dead_store_elimination::DeadStoreElimination,
             &dest_prop::DestinationPropagation,
             &o1(simplify_branches::SimplifyConstCondition::new("final")),
@@ -577,6 +580,7 @@ fn o1<T>(x: T) -> WithMinOptLevel<T> {
             &nrvo::RenameReturnPlace,
             &simplify::SimplifyLocals::new("final"),
             &multiple_return_terminators::MultipleReturnTerminators,
+            &outline_cold_panics::OutlineColdPanics,
             &deduplicate_blocks::DeduplicateBlocks,
             // Some cleanup necessary at least for LLVM and potentially other codegen backends.
             &add_call_guards::CriticalCallEdges,
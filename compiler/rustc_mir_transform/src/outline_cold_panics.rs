@@ -0,0 +1,344 @@
+//! This pass rewrites the failure paths of a function into calls to functions that receive the
+//! caller location as an explicit constant argument instead of through `#[track_caller]`:
+//!
+//! - calls to the `panic` lang item with a constant message, like the failure path of
+//!   `Option::unwrap` or `unreachable!()`, and assertions with a constant message, like overflow
+//!   and division by zero checks, call the `panic_outlined` lang item,
+//! - bounds checks call the `panic_bounds_check_outlined` lang item,
+//! - calls to the failure path of `Option::expect` with a constant message call the
+//!   `panic_str_outlined` lang item.
+//!
+//! Once the location is a constant of the body, blocks of the same body that panic with the same
+//! message and location, e.g. the failure paths of a single `Option::unwrap` call inlined into
+//! several places, are merged into a single call site. All of them call the same cold function,
+//! so no code is duplicated per message or location. Bounds checks are never merged, as their
+//! index and length are only known at runtime.
+//!
+//! Calls to `panic_fmt` and to the failure paths of `Result::unwrap` and `Result::expect` are left
+//! alone: their arguments are built at runtime by the failure path itself, so there is nothing
+//! to share between two of them, and they already call a single cold function.
+//!
+//! The pass is only enabled with `-Z outline-cold-panics`.
+
+use crate::MirPass;
+
+use rustc_data_structures::fx::FxHashMap;
+use rustc_index::vec::IndexVec;
+use rustc_middle::mir::interpret::{Allocation, ConstValue};
+use rustc_middle::mir::visit::{PlaceContext, Visitor};
+use rustc_middle::mir::*;
+use rustc_middle::ty::{self, TyCtxt};
+use rustc_span::{Span, Symbol};
+
+use super::simplify::simplify_cfg;
+
+pub struct OutlineColdPanics;
+
+impl<'tcx> MirPass<'tcx> for OutlineColdPanics {
+    fn is_enabled(&self, sess: &rustc_session::Session) -> bool {
+        sess.opts.unstable_opts.outline_cold_panics
+    }
+
+    fn run_pass(&self, tcx: TyCtxt<'tcx>, body: &mut Body<'tcx>) {
+        let lang_items = tcx.lang_items();
+        let Some(panic_outlined) = lang_items.panic_outlined() else { return };
+
+        // A panic that is not inside of an inlined function reports the location of the
+        // caller of a `#[track_caller]` body, which is only known at runtime.
+        let body_tracks_caller = body.source.instance.requires_caller_location(tcx);
+
+        let local_uses = local_uses(body);
+
+        // Maps the outlined function, the message, the location and the unwind edge of a
+        // rewritten panic to the first block panicking with them that other blocks can be
+        // merged into.
+        let mut outlined: FxHashMap<_, BasicBlock> = FxHashMap::default();
+        let mut rewritten = false;
+
+        // The blocks added for assertions below are not visited, they are outlined already.
+        for bb in body.basic_blocks.indices() {
+            let data = &body.basic_blocks[bb];
+            if data.is_cleanup {
+                continue;
+            }
+            let source_info = data.terminator().source_info;
+
+            // The outlined function to call, its arguments other than the location, the unwind
+            // edge, and the message if the panic may share its call site with others.
+            let (outlined_fn, mut args, cleanup, msg) = match &data.terminator().kind {
+                TerminatorKind::Call { func, args, cleanup, .. } => {
+                    let Some((callee, _)) = func.const_fn_def() else { continue };
+                    let outlined_fn = if Some(callee) == lang_items.panic_fn() {
+                        panic_outlined
+                    } else if Some(callee) == lang_items.expect_failed_fn()
+                        && let Some(panic_str_outlined) = lang_items.panic_str_outlined()
+                    {
+                        panic_str_outlined
+                    } else {
+                        continue;
+                    };
+                    let [msg_operand] = &args[..] else { continue };
+                    let Operand::Constant(msg) = msg_operand else { continue };
+                    let Some(msg) = const_str(msg) else { continue };
+                    (outlined_fn, vec![msg_operand.clone()], *cleanup, Some(msg))
+                }
+                TerminatorKind::Assert {
+                    msg: AssertKind::BoundsCheck { len, index },
+                    cleanup,
+                    ..
+                } => {
+                    let Some(panic_bounds_check_outlined) =
+                        lang_items.panic_bounds_check_outlined()
+                    else {
+                        continue;
+                    };
+                    (panic_bounds_check_outlined, vec![index.clone(), len.clone()], *cleanup, None)
+                }
+                TerminatorKind::Assert { msg, cleanup, .. } => {
+                    // Codegen ignores these when overflow checks are disabled, as they come from
+                    // `#[rustc_inherit_overflow_checks]` functions inlined from other crates.
+                    if let AssertKind::OverflowNeg(_) = msg && !tcx.sess.overflow_checks() {
+                        continue;
+                    }
+                    let msg = msg.description();
+                    let msg_operand = str_operand(tcx, msg, source_info.span);
+                    (panic_outlined, vec![msg_operand], *cleanup, Some(msg))
+                }
+                _ => continue,
+            };
+            let Some(span) = caller_location_span(tcx, body, source_info, body_tracks_caller)
+            else {
+                continue;
+            };
+
+            let location = caller_location(tcx, span);
+            debug!("OutlineColdPanics: outlining panic in {:?} at {:?}", bb, location);
+            args.push(location_operand(tcx, location, source_info.span));
+            let func = Operand::function_handle(tcx, outlined_fn, [], source_info.span);
+            let key = msg.map(|msg| (outlined_fn, msg, location, cleanup));
+            rewritten = true;
+
+            if let TerminatorKind::Assert { .. } = body.basic_blocks[bb].terminator().kind {
+                // Branch to an earlier block panicking the same way, or to a new one.
+                let panic_bb = match key.and_then(|key| outlined.get(&key)) {
+                    Some(&first) => first,
+                    None => {
+                        let destination = body
+                            .local_decls
+                            .push(LocalDecl::new(tcx.types.never, source_info.span));
+                        let kind = TerminatorKind::Call {
+                            func,
+                            args,
+                            destination: destination.into(),
+                            target: None,
+                            cleanup,
+                            from_hir_call: false,
+                            fn_span: source_info.span,
+                        };
+                        let panic_bb = body
+                            .basic_blocks_mut()
+                            .push(BasicBlockData::new(Some(Terminator { source_info, kind })));
+                        if let Some(key) = key {
+                            outlined.insert(key, panic_bb);
+                        }
+                        panic_bb
+                    }
+                };
+                let terminator = body.basic_blocks_mut()[bb].terminator_mut();
+                let TerminatorKind::Assert { cond, expected, target, .. } = &terminator.kind else {
+                    bug!()
+                };
+                let (cond, target) = (cond.clone(), *target);
+                terminator.kind = if *expected {
+                    TerminatorKind::if_(cond, target, panic_bb)
+                } else {
+                    TerminatorKind::if_(cond, panic_bb, target)
+                };
+                continue;
+            }
+
+            let data = &mut body.basic_blocks_mut()[bb];
+            let TerminatorKind::Call { func: call_func, args: call_args, .. } =
+                &mut data.terminator_mut().kind
+            else {
+                bug!()
+            };
+            *call_func = func;
+            *call_args = args;
+
+            // Merging `bb` into `first` removes the statements of `bb` from its path, and runs
+            // those of `first` on it instead. That is only fine for storage markers of locals that
+            // nothing outside of the block uses.
+            let Some(key) = key else { continue };
+            if !has_only_private_storage_markers(data, bb, &local_uses) {
+                continue;
+            }
+            match outlined.get(&key) {
+                Some(&first) => {
+                    data.statements.clear();
+                    data.terminator_mut().kind = TerminatorKind::Goto { target: first };
+                }
+                None => {
+                    outlined.insert(key, bb);
+                }
+            }
+        }
+
+        if rewritten {
+            simplify_cfg(tcx, body);
+        }
+    }
+}
+
+/// Returns the value of a `&str` constant.
+fn const_str<'tcx>(constant: &Constant<'tcx>) -> Option<&'tcx str> {
+    let ConstantKind::Val(ConstValue::Slice { data, start, end }, ty) = constant.literal else {
+        return None;
+    };
+    if !matches!(ty.kind(), ty::Ref(_, inner, _) if inner.is_str()) {
+        return None;
+    }
+    std::str::from_utf8(data.inner().inspect_with_uninit_and_ptr_outside_interpreter(start..end))
+        .ok()
+}
+
+/// Returns the span that `Location::caller()` reports for a panic at `source_info`, or `None` if
+/// it depends on the caller of this body.
+///
+/// This mirrors the walk over inlined scopes done by codegen when it passes the location to a
+/// `#[track_caller]` function.
+fn caller_location_span<'tcx>(
+    tcx: TyCtxt<'tcx>,
+    body: &Body<'tcx>,
+    mut source_info: SourceInfo,
+    body_tracks_caller: bool,
+) -> Option<Span> {
+    loop {
+        let scope_data = &body.source_scopes[source_info.scope];
+
+        if let Some((callee, callsite_span)) = scope_data.inlined {
+            // Stop inside the most nested non-`#[track_caller]` function,
+            // before ever reaching its caller (which is irrelevant).
+            if !callee.def.requires_caller_location(tcx) {
+                return Some(source_info.span);
+            }
+            source_info.span = callsite_span;
+        }
+
+        // Skip past all of the parents with `inlined: None`.
+        match scope_data.inlined_parent_scope {
+            Some(parent) => source_info.scope = parent,
+            None => break,
+        }
+    }
+
+    if body_tracks_caller {
+        None
+    } else {
+        Some(source_info.span)
+    }
+}
+
+/// Returns the file, line and column that `Location::caller()` reports for `location_span`.
+///
+/// Keep in sync with `get_caller_location` in codegen.
+fn caller_location(tcx: TyCtxt<'_>, location_span: Span) -> (Symbol, u32, u32) {
+    let topmost = location_span.ctxt().outer_expn().expansion_cause().unwrap_or(location_span);
+    let caller = tcx.sess.source_map().lookup_char_pos(topmost.lo());
+    (
+        Symbol::intern(&caller.file.name.prefer_remapped().to_string_lossy()),
+        caller.line as u32,
+        caller.col_display as u32 + 1,
+    )
+}
+
+/// Builds the `&'static Location<'static>` constant for `location`.
+fn location_operand<'tcx>(
+    tcx: TyCtxt<'tcx>,
+    location: (Symbol, u32, u32),
+    span: Span,
+) -> Operand<'tcx> {
+    Operand::Constant(Box::new(Constant {
+        span,
+        user_ty: None,
+        literal: ConstantKind::Val(tcx.const_caller_location(location), tcx.caller_location_ty()),
+    }))
+}
+
+/// Builds a `&'static str` constant with the value `s`.
+fn str_operand<'tcx>(tcx: TyCtxt<'tcx>, s: &str, span: Span) -> Operand<'tcx> {
+    let allocation = Allocation::from_bytes_byte_aligned_immutable(s.as_bytes());
+    let allocation = tcx.intern_const_alloc(allocation);
+    let value = ConstValue::Slice { data: allocation, start: 0, end: s.len() };
+    Operand::Constant(Box::new(Constant {
+        span,
+        user_ty: None,
+        literal: ConstantKind::Val(value, tcx.mk_static_str()),
+    }))
+}
+
+/// Where a local is used.
+#[derive(Copy, Clone, PartialEq, Eq)]
+enum LocalUse {
+    Unused,
+    /// Only by the statements and the terminator of a single block.
+    Block(BasicBlock),
+    /// By several blocks, or by the debuginfo or the signature of the body.
+    Several,
+}
+
+/// Returns where each local of `body` is used.
+fn local_uses(body: &Body<'_>) -> IndexVec<Local, LocalUse> {
+    struct LocalUseVisitor {
+        uses: IndexVec<Local, LocalUse>,
+    }
+
+    impl<'tcx> Visitor<'tcx> for LocalUseVisitor {
+        fn visit_local(&mut self, local: Local, _context: PlaceContext, location: Location) {
+            let local_use = &mut self.uses[local];
+            *local_use = match *local_use {
+                LocalUse::Unused => LocalUse::Block(location.block),
+                LocalUse::Block(bb) if bb == location.block => LocalUse::Block(bb),
+                _ => LocalUse::Several,
+            };
+        }
+    }
+
+    let mut visitor =
+        LocalUseVisitor { uses: IndexVec::from_elem(LocalUse::Unused, &body.local_decls) };
+    // The return place and the arguments are used by the caller as well.
+    for local in (0..=body.arg_count).map(Local::new) {
+        visitor.uses[local] = LocalUse::Several;
+    }
+    for var_debug_info in &body.var_debug_info {
+        match &var_debug_info.value {
+            VarDebugInfoContents::Place(place) => visitor.uses[place.local] = LocalUse::Several,
+            VarDebugInfoContents::Composite { fragments, .. } => {
+                for fragment in fragments {
+                    visitor.uses[fragment.contents.local] = LocalUse::Several;
+                }
+            }
+            VarDebugInfoContents::Const(_) => {}
+        }
+    }
+    for (bb, data) in body.basic_blocks.iter_enumerated() {
+        visitor.visit_basic_block_data(bb, data);
+    }
+    visitor.uses
+}
+
+/// Whether the statements of `bb` are all storage markers of locals that no other block uses,
+/// which can neither be observed by a panic nor by its unwind path.
+fn has_only_private_storage_markers(
+    data: &BasicBlockData<'_>,
+    bb: BasicBlock,
+    local_uses: &IndexVec<Local, LocalUse>,
+) -> bool {
+    data.statements.iter().all(|statement| match statement.kind {
+        StatementKind::StorageLive(local) | StatementKind::StorageDead(local) => {
+            local_uses[local] == LocalUse::Block(bb)
+        }
+        StatementKind::Nop => true,
+        _ => false,
+    })
+}
@@ -0,0 +1,110 @@
+//! Placing hot callees in the codegen units of their callers, as given by a call-graph
+//! profile (`-Z cgu-call-graph-profile`).
+//!
+//! LLVM can only inline a call if the callee is defined in the module of the caller, or if
+//! it is `#[inline]` and therefore copied into every codegen unit that uses it. Codegen units
+//! are formed from source-level modules, so a hot call into another module is never inlined.
+//!
+//! The profile is a text file with one edge of the call graph per line, of the form
+//! `caller callee count`. Callers and callees are given by their symbol names, or by their
+//! paths as printed by `-Z print-mono-items`, and `count` is how often the call was observed,
+//! e.g. by a sampling profiler or in a run instrumented with `-C profile-generate`. Empty lines
+//! and lines starting with `#` are ignored, and so are functions the profile mentions that
+//! are not defined in this crate.
+//!
+//! Before the codegen units are merged, the hottest edges are visited first, and the callee
+//! of each edge is moved into the codegen unit of the caller, or the other way around if the
+//! callee was already placed for a hotter edge. Merging only ever combines codegen units, so
+//! the pairs placed here end up together.
+
+use rustc_data_structures::fx::{FxHashMap, FxHashSet};
+use rustc_middle::mir::mono::MonoItem;
+use rustc_middle::ty::print::with_no_trimmed_paths;
+use rustc_middle::ty::TyCtxt;
+use rustc_session::config::CallGraphProfile;
+use rustc_span::symbol::Symbol;
+
+use super::{PartitioningCx, PreInliningPartitioning};
+
+/// Edges with a count below the count of the hottest edge divided by this are not hot enough
+/// to be worth placing.
+const HOT_EDGE_DIVISOR: u64 = 100;
+
+pub fn place_hot_callees<'tcx>(
+    cx: &PartitioningCx<'_, 'tcx>,
+    partitioning: &mut PreInliningPartitioning<'tcx>,
+    profile: &CallGraphProfile,
+) {
+    let tcx = cx.tcx;
+
+    let mut placements: FxHashMap<MonoItem<'tcx>, Symbol> = FxHashMap::default();
+    let mut items_by_name: FxHashMap<String, MonoItem<'tcx>> = FxHashMap::default();
+    for cgu in &partitioning.codegen_units {
+        for &item in cgu.items().keys() {
+            placements.insert(item, cgu.name());
+            items_by_name.insert(item.symbol_name(tcx).name.to_string(), item);
+            if let Some(path) = item_path(tcx, item) {
+                items_by_name.insert(path, item);
+            }
+        }
+    }
+
+    let mut edges: Vec<_> = profile
+        .edges
+        .iter()
+        .filter_map(|edge| {
+            let caller = *items_by_name.get(&edge.caller)?;
+            let callee = *items_by_name.get(&edge.callee)?;
+            (caller != callee).then_some((edge, caller, callee))
+        })
+        .collect();
+    let max_count = edges.iter().map(|(edge, ..)| edge.count).max().unwrap_or(0);
+    edges.retain(|(edge, ..)| edge.count.saturating_mul(HOT_EDGE_DIVISOR) >= max_count);
+    // Visit the hottest edges first, and break ties by name to be deterministic.
+    edges.sort_by(|(a, ..), (b, ..)| {
+        b.count.cmp(&a.count).then_with(|| (&a.caller, &a.callee).cmp(&(&b.caller, &b.callee)))
+    });
+
+    // Items that are placed together with a hotter caller or callee, and stay where they are.
+    let mut pinned = FxHashSet::default();
+    let mut moves = Vec::new();
+    for (edge, caller, callee) in edges {
+        let (item, to) = if placements[&caller] == placements[&callee] {
+            pinned.extend([caller, callee]);
+            continue;
+        } else if !pinned.contains(&callee) {
+            (callee, placements[&caller])
+        } else if !pinned.contains(&caller) {
+            (caller, placements[&callee])
+        } else {
+            continue;
+        };
+        debug!("placing {} in {} for {:?}", item, to, edge);
+        let from = placements.insert(item, to).unwrap();
+        pinned.extend([caller, callee]);
+        moves.push((item, from, to));
+    }
+
+    if moves.is_empty() {
+        return;
+    }
+
+    let codegen_units = &mut partitioning.codegen_units;
+    let indices: FxHashMap<Symbol, usize> =
+        codegen_units.iter().enumerate().map(|(index, cgu)| (cgu.name(), index)).collect();
+    for (item, from, to) in moves {
+        let linkage_and_visibility = codegen_units[indices[&from]].items_mut().remove(&item);
+        codegen_units[indices[&to]].items_mut().insert(item, linkage_and_visibility.unwrap());
+    }
+    codegen_units.retain(|cgu| !cgu.items().is_empty());
+    codegen_units.iter_mut().for_each(|cgu| cgu.estimate_size(tcx));
+}
+
+/// The path of `item` as printed by `-Z print-mono-items`.
+fn item_path<'tcx>(tcx: TyCtxt<'tcx>, item: MonoItem<'tcx>) -> Option<String> {
+    match item {
+        MonoItem::Fn(instance) => Some(with_no_trimmed_paths!(instance.to_string())),
+        MonoItem::Static(def_id) => Some(with_no_trimmed_paths!(tcx.def_path_str(def_id))),
+        MonoItem::GlobalAsm(..) => None,
+    }
+}
@@ -92,6 +92,7 @@
 //! source-level module, functions from the same module will be available for
 //! inlining, even when they are not marked `#[inline]`.
 
+mod call_graph;
 mod default;
 mod merging;
 
@@ -184,6 +185,13 @@ pub fn partition<'tcx>(
 
     debug_dump(tcx, "INITIAL PARTITIONING:", initial_partitioning.codegen_units.iter());
 
+    // Place hot callees in the codegen units of their callers, so LLVM can inline them.
+    if let Some(profile) = &tcx.sess.opts.unstable_opts.cgu_call_graph_profile {
+        let _prof_timer = tcx.prof.generic_activity("cgu_partitioning_place_hot_callees");
+        call_graph::place_hot_callees(cx, &mut initial_partitioning, profile);
+        debug_dump(tcx, "POST CALL-GRAPH PLACEMENT:", initial_partitioning.codegen_units.iter());
+    }
+
     // Merge until we have at most `max_cgu_count` codegen units.
     {
         let _prof_timer = tcx.prof.generic_activity("cgu_partitioning_merge_cgus");
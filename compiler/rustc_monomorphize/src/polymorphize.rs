@@ -2,23 +2,30 @@
 //! =========================
 //!
 //! This module implements an analysis of functions, methods and closures to determine which
-//! generic parameters are unused (and eventually, in what ways generic parameters are used - only
-//! for their size, offset of a field, etc.).
+//! generic parameters are unused, and an analysis of functions and inherent methods to determine
+//! which generic type parameters are only used through their size and alignment.
+//!
+//! Instances are shared between all types of a parameter that is unused, and with
+//! `-Z polymorphize-by-layout`, between all types of the same layout for a parameter that is
+//! only used through its layout.
 
+use rustc_data_structures::fx::FxHashSet;
 use rustc_hir::{def::DefKind, def_id::DefId, ConstContext};
 use rustc_middle::mir::{
     self,
-    visit::{TyContext, Visitor},
-    Constant, ConstantKind, Local, LocalDecl, Location,
+    visit::{MutatingUseContext, PlaceContext, TyContext, Visitor},
+    Body, CastKind, Constant, ConstantKind, Local, LocalDecl, Location, Rvalue, Terminator,
+    TerminatorKind,
 };
 use rustc_middle::ty::{
     self,
+    adjustment::PointerCast,
     query::Providers,
-    subst::SubstsRef,
+    subst::{GenericArgKind, SubstsRef},
     visit::{TypeSuperVisitable, TypeVisitable, TypeVisitor},
-    Const, Ty, TyCtxt, UnusedGenericParams,
+    Const, LayoutOnlyGenericParams, Ty, TyCtxt, UnusedGenericParams,
 };
-use rustc_span::symbol::sym;
+use rustc_span::symbol::{sym, Symbol};
 use std::ops::ControlFlow;
 
 use crate::errors::UnusedGenericParamsHint;
@@ -26,6 +33,7 @@
 /// Provide implementations of queries relating to polymorphization analysis.
 pub fn provide(providers: &mut Providers) {
     providers.unused_generic_params = unused_generic_params;
+    providers.layout_only_generic_params = layout_only_generic_params;
 }
 
 /// Determine which generic parameters are used by the instance.
@@ -347,3 +355,341 @@ fn visit_ty(&mut self, ty: Ty<'tcx>) -> ControlFlow<Self::BreakTy> {
         }
     }
 }
+
+/// Intrinsics whose behaviour only depends on the layout of their generic parameter.
+const LAYOUT_ONLY_INTRINSICS: &[Symbol] = &[
+    sym::size_of,
+    sym::min_align_of,
+    sym::size_of_val,
+    sym::min_align_of_val,
+    sym::copy,
+    sym::copy_nonoverlapping,
+    sym::write_bytes,
+    sym::offset,
+    sym::arith_offset,
+    sym::ptr_offset_from,
+    sym::ptr_offset_from_unsigned,
+];
+
+/// Determine which generic type parameters are only used through their size and alignment by
+/// the instance, so that the instance can be shared between all types with the same layout.
+///
+/// Such a parameter may only appear in the signature behind pointers, which keeps the ABI the
+/// same for all types with the same layout. In the body, it may only appear in locals of the
+/// type itself, arrays, slices and pointers of it and `MaybeUninit` or `ManuallyDrop` wrappers
+/// of those, be passed to size and alignment intrinsics, memory copies and pointer offsets, and
+/// be passed on to other functions that only use it through its layout. It must not be dropped,
+/// be bounded by traits other than `Sized` and `Copy` or be used for trait dispatch, and
+/// constants must not depend on it.
+fn layout_only_generic_params<'tcx>(
+    tcx: TyCtxt<'tcx>,
+    instance: ty::InstanceDef<'tcx>,
+) -> LayoutOnlyGenericParams {
+    let opts = &tcx.sess.opts.unstable_opts;
+    if !opts.polymorphize || !opts.polymorphize_by_layout {
+        return LayoutOnlyGenericParams::new_empty();
+    }
+
+    let ty::InstanceDef::Item(def) = instance else {
+        return LayoutOnlyGenericParams::new_empty();
+    };
+    let def_id = def.did;
+    if !is_layout_analyzed(tcx, def_id) {
+        return LayoutOnlyGenericParams::new_empty();
+    }
+
+    let generics = tcx.generics_of(def_id);
+    let mut candidates = LayoutOnlyGenericParams::new_empty();
+    for index in 0..generics.count() {
+        let param = generics.param_at(index, tcx);
+        if let ty::GenericParamDefKind::Type { .. } = param.kind {
+            candidates.insert(param.index);
+        }
+    }
+    if candidates.is_empty() {
+        return candidates;
+    }
+
+    let body = tcx.optimized_mir(def_id);
+    // Recursive calls assume that the candidates are only used through their layout, so check
+    // again with fewer candidates until none of them is rejected.
+    loop {
+        let mut vis = MarkNonLayoutGenericParams {
+            tcx,
+            def_id,
+            body,
+            candidates,
+            layout_only: candidates,
+        };
+        vis.visit_predicates();
+        vis.visit_body(body);
+        debug!(?def_id, ?candidates, layout_only = ?vis.layout_only);
+        if vis.layout_only == candidates {
+            break;
+        }
+        candidates = vis.layout_only;
+    }
+    debug!(?instance, layout_only = ?candidates);
+    candidates
+}
+
+/// Returns `true` if `layout_only_generic_params` analyzes the body of `def_id`.
+fn is_layout_analyzed(tcx: TyCtxt<'_>, def_id: DefId) -> bool {
+    // Only functions and inherent methods are called directly: calls to trait methods are
+    // resolved using the types of their parameters, and closures capture their environment.
+    let is_directly_called = match tcx.def_kind(def_id) {
+        DefKind::Fn => true,
+        DefKind::AssocFn => tcx
+            .impl_of_method(def_id)
+            .map_or(false, |impl_def_id| tcx.trait_id_of_impl(impl_def_id).is_none()),
+        _ => false,
+    };
+    is_directly_called && tcx.is_mir_available(def_id)
+}
+
+/// Returns `true` if `layout_only_generic_params` of `callee` would depend on the one of
+/// `caller`, i.e. if `callee` passes generic parameters on to a chain of calls that ends in
+/// `caller`.
+///
+/// The analysis of such a cycle would have to consider all of its functions at once. Instead,
+/// the callees that call back into the caller are treated as using all of their parameters,
+/// so that the query never depends on itself.
+fn calls_back(tcx: TyCtxt<'_>, callee: DefId, caller: DefId) -> bool {
+    let mut visited = FxHashSet::default();
+    let mut pending = vec![callee];
+    while let Some(def_id) = pending.pop() {
+        if !visited.insert(def_id) || !is_layout_analyzed(tcx, def_id) {
+            continue;
+        }
+        let body = tcx.optimized_mir(def_id);
+        let param_env = tcx.param_env_reveal_all_normalized(def_id);
+        for bb_data in body.basic_blocks.iter() {
+            let TerminatorKind::Call { func, .. } = &bb_data.terminator().kind else { continue };
+            let ty::FnDef(next, substs) = *func.ty(body, tcx).kind() else { continue };
+            // Only calls that are passed generic parameters are followed by the analysis.
+            if !substs.has_non_region_param() {
+                continue;
+            }
+            let Ok(Some(next)) = ty::Instance::resolve(tcx, param_env, next, substs) else {
+                continue;
+            };
+            if let ty::InstanceDef::Item(next) = next.def {
+                if next.did == caller {
+                    return true;
+                }
+                pending.push(next.did);
+            }
+        }
+    }
+    false
+}
+
+/// Visitor used to remove generic parameters that are not only used through their layout.
+struct MarkNonLayoutGenericParams<'tcx> {
+    tcx: TyCtxt<'tcx>,
+    def_id: DefId,
+    body: &'tcx Body<'tcx>,
+    /// Parameters assumed to be only used through their layout in this round.
+    candidates: LayoutOnlyGenericParams,
+    /// Candidates that have not been rejected so far.
+    layout_only: LayoutOnlyGenericParams,
+}
+
+impl<'tcx> MarkNonLayoutGenericParams<'tcx> {
+    /// Returns the candidates that are mentioned by `value`.
+    fn mentioned(&self, value: impl TypeVisitable<'tcx>) -> LayoutOnlyGenericParams {
+        let found = LayoutOnlyGenericParams::new_empty();
+        let mut collector = CollectParams { candidates: self.candidates, found };
+        value.visit_with(&mut collector);
+        collector.found
+    }
+
+    /// Rejects the candidates that are mentioned by `value`.
+    fn reject(&mut self, value: impl TypeVisitable<'tcx>) {
+        let mentioned = self.mentioned(value);
+        for index in 0..u32::BITS {
+            if mentioned.contains(index) {
+                self.layout_only.remove(index);
+            }
+        }
+    }
+
+    /// Returns `true` if the layout of `ty` only depends on the layout of the candidates it
+    /// mentions.
+    fn is_layout_determined(&self, ty: Ty<'tcx>) -> bool {
+        if self.mentioned(ty).is_empty() {
+            return true;
+        }
+
+        match *ty.kind() {
+            ty::Param(_) => true,
+            ty::Array(ty, _) | ty::Slice(ty) => self.is_layout_determined(ty),
+            ty::Ref(_, ty, _) | ty::RawPtr(ty::TypeAndMut { ty, .. }) => {
+                self.is_layout_determined(ty)
+            }
+            ty::Adt(def, substs)
+                if self.tcx.lang_items().maybe_uninit() == Some(def.did())
+                    || self.tcx.lang_items().manually_drop() == Some(def.did()) =>
+            {
+                self.is_layout_determined(substs.type_at(0))
+            }
+            _ => false,
+        }
+    }
+
+    /// Returns `true` if the candidates mentioned by `ty` only appear behind pointers, and so
+    /// don't affect how a value of `ty` is passed.
+    fn is_behind_pointer(&self, ty: Ty<'tcx>) -> bool {
+        if self.mentioned(ty).is_empty() {
+            return true;
+        }
+
+        match *ty.kind() {
+            ty::Ref(_, ty, _) | ty::RawPtr(ty::TypeAndMut { ty, .. }) => {
+                self.is_layout_determined(ty)
+            }
+            _ => false,
+        }
+    }
+
+    /// Rejects the candidates mentioned by the bounds of the item, other than `Sized`, `Copy`
+    /// and outlives bounds, which the types standing in for them satisfy as well.
+    fn visit_predicates(&mut self) {
+        let lang_items = self.tcx.lang_items();
+        let predicates = self.tcx.predicates_of(self.def_id).instantiate_identity(self.tcx);
+        for predicate in predicates.predicates {
+            let is_structural = match predicate.kind().skip_binder() {
+                ty::PredicateKind::Clause(ty::Clause::Trait(trait_predicate)) => {
+                    let trait_def_id = Some(trait_predicate.def_id());
+                    trait_def_id == lang_items.sized_trait()
+                        || trait_def_id == lang_items.copy_trait()
+                }
+                ty::PredicateKind::Clause(
+                    ty::Clause::TypeOutlives(_) | ty::Clause::RegionOutlives(_),
+                ) => true,
+                _ => false,
+            };
+            if !is_structural {
+                self.reject(predicate);
+            }
+        }
+    }
+
+    fn visit_callee(&mut self, def_id: DefId, substs: SubstsRef<'tcx>) {
+        if self.mentioned(substs).is_empty() {
+            return;
+        }
+
+        let param_env = self.tcx.param_env_reveal_all_normalized(self.def_id);
+        let callee = match ty::Instance::resolve(self.tcx, param_env, def_id, substs) {
+            Ok(Some(callee)) => callee,
+            // The callee depends on the parameters.
+            Ok(None) | Err(_) => return self.reject(substs),
+        };
+        let callee_layout_only = match callee.def {
+            ty::InstanceDef::Intrinsic(def_id) => {
+                if !LAYOUT_ONLY_INTRINSICS.contains(&self.tcx.item_name(def_id)) {
+                    self.reject(substs);
+                }
+                return;
+            }
+            ty::InstanceDef::Item(def) if def.did == self.def_id => self.candidates,
+            ty::InstanceDef::Item(def) if calls_back(self.tcx, def.did, self.def_id) => {
+                return self.reject(substs);
+            }
+            ty::InstanceDef::Item(_) => self.tcx.layout_only_generic_params(callee.def),
+            // Shims like drop glue depend on the parameters.
+            _ => return self.reject(substs),
+        };
+        debug!(?callee, ?callee_layout_only);
+        for (index, arg) in callee.substs.iter().enumerate() {
+            let is_layout_only = match arg.unpack() {
+                GenericArgKind::Type(ty) => {
+                    callee_layout_only.contains(index as u32) && self.is_layout_determined(ty)
+                }
+                GenericArgKind::Lifetime(_) | GenericArgKind::Const(_) => false,
+            };
+            if !is_layout_only {
+                self.reject(arg);
+            }
+        }
+    }
+}
+
+impl<'tcx> Visitor<'tcx> for MarkNonLayoutGenericParams<'tcx> {
+    fn visit_local_decl(&mut self, local: Local, local_decl: &LocalDecl<'tcx>) {
+        let is_layout_only = if local.as_usize() <= self.body.arg_count {
+            // The return place and the arguments make up the signature.
+            self.is_behind_pointer(local_decl.ty)
+        } else {
+            self.is_layout_determined(local_decl.ty)
+        };
+        if !is_layout_only {
+            self.reject(local_decl.ty);
+        }
+    }
+
+    fn visit_constant(&mut self, ct: &Constant<'tcx>, _: Location) {
+        // Constants that mention a parameter may be evaluated differently for each type.
+        self.reject(ct.literal);
+    }
+
+    fn visit_rvalue(&mut self, rvalue: &Rvalue<'tcx>, location: Location) {
+        if let Rvalue::Cast(CastKind::Pointer(PointerCast::Unsize) | CastKind::DynStar, op, ty) =
+            rvalue
+        {
+            // Unsizing to a trait object creates a vtable for the parameter.
+            if self.mentioned(*ty).is_empty() {
+                self.reject(op.ty(self.body, self.tcx));
+            }
+        }
+
+        self.super_rvalue(rvalue, location);
+    }
+
+    fn visit_terminator(&mut self, terminator: &Terminator<'tcx>, location: Location) {
+        match &terminator.kind {
+            TerminatorKind::Call { func, args, destination, .. } => {
+                match *func.ty(self.body, self.tcx).kind() {
+                    ty::FnDef(def_id, substs) if func.constant().is_some() => {
+                        self.visit_callee(def_id, substs)
+                    }
+                    _ => self.visit_operand(func, location),
+                }
+                for arg in args {
+                    self.visit_operand(arg, location);
+                }
+                let context = PlaceContext::MutatingUse(MutatingUseContext::Call);
+                self.visit_place(destination, context, location);
+            }
+            TerminatorKind::Drop { place, .. } | TerminatorKind::DropAndReplace { place, .. } => {
+                // Dropping calls the drop glue of the parameter.
+                self.reject(place.ty(self.body, self.tcx).ty);
+                self.super_terminator(terminator, location);
+            }
+            _ => self.super_terminator(terminator, location),
+        }
+    }
+}
+
+/// Visitor used to collect the candidates mentioned by a type.
+struct CollectParams {
+    candidates: LayoutOnlyGenericParams,
+    found: LayoutOnlyGenericParams,
+}
+
+impl<'tcx> TypeVisitor<'tcx> for CollectParams {
+    fn visit_ty(&mut self, ty: Ty<'tcx>) -> ControlFlow<Self::BreakTy> {
+        if !ty.has_non_region_param() {
+            return ControlFlow::Continue(());
+        }
+
+        match *ty.kind() {
+            ty::Param(param) if self.candidates.contains(param.index) => {
+                self.found.insert(param.index);
+                ControlFlow::Continue(())
+            }
+            _ => ty.super_visit_with(self),
+        }
+    }
+}
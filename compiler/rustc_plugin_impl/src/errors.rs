@@ -18,3 +18,44 @@ pub struct MalformedPluginAttribute {
     #[label]
     pub span: Span,
 }
+
+#[derive(Diagnostic)]
+#[diag(plugin_impl_load_lint_plugin_error)]
+pub struct LoadLintPluginError {
+    pub path: String,
+    pub msg: String,
+}
+
+#[derive(Diagnostic)]
+#[diag(plugin_impl_lint_plugin_api_mismatch)]
+#[help]
+pub struct LintPluginApiMismatch {
+    pub path: String,
+    pub found: u32,
+    pub expected: u32,
+}
+
+#[derive(Diagnostic)]
+#[diag(plugin_impl_lint_plugin_rustc_mismatch)]
+#[help]
+pub struct LintPluginRustcMismatch {
+    pub path: String,
+    pub found: &'static str,
+    pub expected: &'static str,
+}
+
+#[derive(Diagnostic)]
+#[diag(plugin_impl_lint_plugin_tool_mismatch)]
+pub struct LintPluginToolMismatch {
+    pub path: String,
+    pub declared: &'static str,
+    pub requested: String,
+}
+
+#[derive(Diagnostic)]
+#[diag(plugin_impl_lint_plugin_lint_not_named_after_tool)]
+pub struct LintPluginLintNotNamedAfterTool {
+    pub path: String,
+    pub lint: String,
+    pub tool: String,
+}
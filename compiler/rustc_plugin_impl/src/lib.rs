@@ -5,6 +5,12 @@
 //! See the [`plugin`
 //! feature](https://doc.rust-lang.org/nightly/unstable-book/language-features/plugin.html)
 //! of the Unstable Book for some examples.
+//!
+//! Lint plugins are their replacement for custom lints: dylibs that are loaded with
+//! `-Z lint-plugin=tool=path`, export a [`LintPluginDeclaration`] with [`declare_lint_plugin!`]
+//! and register lints named `tool::lint_name`, whose levels can be controlled like those of
+//! any other tool lint. They are not a stable interface: they use the internal types of rustc,
+//! so a plugin only works with the rustc that built it.
 
 #![doc(html_root_url = "https://doc.rust-lang.org/nightly/nightly-rustc/")]
 #![recursion_limit = "256"]
@@ -16,6 +22,60 @@
 mod errors;
 pub mod load;
 
+/// The version of the layout of [`LintPluginDeclaration`], bumped whenever it changes, so that
+/// the declaration of a plugin with another layout is rejected before its other fields are read.
+/// This doesn't version anything else: plugins also have to match [`RUSTC_VERSION`].
+pub const LINT_PLUGIN_API_VERSION: u32 = 2;
+
+/// The version of rustc, including its commit hash, which lint plugins must have been built
+/// with. They are handed types of rustc that have no stable layout, like `LintStore` and
+/// `LateLintPass`, so loading a plugin built by another rustc is undefined behavior.
+pub const RUSTC_VERSION: &str = match option_env!("CFG_VERSION") {
+    Some(version) => version,
+    None => "unknown version",
+};
+
+/// Describes a lint plugin to the rustc that loads it. Lint plugins export one, named
+/// `__rustc_lint_plugin`, with [`declare_lint_plugin!`].
+///
+/// `api_version` is the first field of a `repr(C)` struct, so that it can be checked before
+/// anything else is read, even from a plugin built against another version of this struct.
+/// `rustc_version` must then match exactly, as long as plugins use the internal types of rustc.
+#[repr(C)]
+pub struct LintPluginDeclaration {
+    pub api_version: u32,
+    pub rustc_version: &'static str,
+    /// The tool that the lints of the plugin are named after.
+    pub tool: &'static str,
+    /// Registers the lints and lint passes of the plugin.
+    pub register: fn(&mut Registry<'_>),
+}
+
+/// Exports the [`LintPluginDeclaration`] of a lint plugin, e.g.
+///
+/// ```ignore (needs a dylib)
+/// declare_tool_lint!(pub our_org::NO_UNWRAP, Warn, "use of `unwrap`");
+///
+/// declare_lint_plugin!(our_org, |reg| {
+///     reg.lint_store.register_lints(&[&NO_UNWRAP]);
+///     reg.lint_store.register_late_pass(|_| Box::new(NoUnwrap));
+/// });
+/// ```
+#[macro_export]
+macro_rules! declare_lint_plugin {
+    ($tool:ident, $register:expr) => {
+        #[no_mangle]
+        #[allow(non_upper_case_globals)]
+        pub static __rustc_lint_plugin: $crate::LintPluginDeclaration =
+            $crate::LintPluginDeclaration {
+                api_version: $crate::LINT_PLUGIN_API_VERSION,
+                rustc_version: $crate::RUSTC_VERSION,
+                tool: stringify!($tool),
+                register: $register,
+            };
+    };
+}
+
 /// Structure used to register plugins.
 ///
 /// A plugin registrar function takes an `&mut Registry` and should call
@@ -1,7 +1,10 @@
 //! Used by `rustc` when loading a plugin.
 
-use crate::errors::{LoadPluginError, MalformedPluginAttribute};
-use crate::Registry;
+use crate::errors::{
+    LintPluginApiMismatch, LintPluginLintNotNamedAfterTool, LintPluginRustcMismatch,
+    LintPluginToolMismatch, LoadLintPluginError, LoadPluginError, MalformedPluginAttribute,
+};
+use crate::{LintPluginDeclaration, Registry, LINT_PLUGIN_API_VERSION, RUSTC_VERSION};
 use libloading::Library;
 use rustc_ast::Crate;
 use rustc_metadata::locator;
@@ -11,7 +14,7 @@
 
 use std::env;
 use std::mem;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
 /// Pointer to a registrar function.
 type PluginRegistrarFn = fn(&mut Registry<'_>);
@@ -76,3 +79,94 @@ fn dylink_registrar(lib_path: PathBuf) -> Result<PluginRegistrarFn, libloading::
 
     Ok(*registrar_sym)
 }
+
+/// A lint plugin given with `-Z lint-plugin`, whose declaration has been checked.
+pub struct LintPlugin {
+    tool: String,
+    path: PathBuf,
+    register: PluginRegistrarFn,
+}
+
+impl LintPlugin {
+    /// Registers the lints of the plugin, which must all be named after its tool so that their
+    /// levels can be controlled.
+    pub fn register(&self, sess: &Session, registry: &mut Registry<'_>) {
+        let registered = registry.lint_store.get_lints().len();
+        (self.register)(registry);
+        let prefix = format!("{}::", self.tool);
+        for lint in &registry.lint_store.get_lints()[registered..] {
+            if !lint.name_lower().starts_with(&prefix) {
+                sess.emit_err(LintPluginLintNotNamedAfterTool {
+                    path: self.path.display().to_string(),
+                    lint: lint.name_lower(),
+                    tool: self.tool.clone(),
+                });
+            }
+        }
+    }
+}
+
+/// Dynamically load the lint plugins given with `-Z lint-plugin`.
+pub fn load_lint_plugins(sess: &Session) -> Vec<LintPlugin> {
+    sess.opts
+        .unstable_opts
+        .lint_plugin
+        .iter()
+        .map(|(tool, path)| load_lint_plugin(sess, tool, path))
+        .collect()
+}
+
+fn load_lint_plugin(sess: &Session, tool: &str, path: &Path) -> LintPlugin {
+    let display_path = || path.display().to_string();
+    let declaration = dylink_lint_plugin_declaration(path).unwrap_or_else(|err| {
+        // Like for other plugins, this is fatal: the lints of the plugin are almost certainly
+        // mentioned in the crate, so continuing would spew "unknown lint" warnings.
+        sess.emit_fatal(LoadLintPluginError { path: display_path(), msg: err.to_string() });
+    });
+
+    if declaration.api_version != LINT_PLUGIN_API_VERSION {
+        sess.emit_fatal(LintPluginApiMismatch {
+            path: display_path(),
+            found: declaration.api_version,
+            expected: LINT_PLUGIN_API_VERSION,
+        });
+    }
+    // Only read the other fields once we know the layout of the declaration. The plugin is
+    // handed the internal types of rustc, so it must have been built by this very rustc.
+    if declaration.rustc_version != RUSTC_VERSION {
+        sess.emit_fatal(LintPluginRustcMismatch {
+            path: display_path(),
+            found: declaration.rustc_version,
+            expected: RUSTC_VERSION,
+        });
+    }
+    if declaration.tool != tool {
+        sess.emit_fatal(LintPluginToolMismatch {
+            path: display_path(),
+            declared: declaration.tool,
+            requested: tool.to_string(),
+        });
+    }
+
+    LintPlugin { tool: tool.to_string(), path: path.to_owned(), register: declaration.register }
+}
+
+/// Dynamically link the declaration of a lint plugin into the compiler process.
+fn dylink_lint_plugin_declaration(
+    lib_path: &Path,
+) -> Result<&'static LintPluginDeclaration, libloading::Error> {
+    // Make sure the path contains a / or the linker will search for it.
+    let lib_path = env::current_dir().unwrap().join(lib_path);
+
+    let lib = unsafe { Library::new(&lib_path) }?;
+
+    let declaration_sym =
+        unsafe { lib.get::<*const LintPluginDeclaration>(b"__rustc_lint_plugin") }?;
+
+    // Intentionally leak the dynamic library, see `dylink_registrar`. This is also what makes
+    // the declaration `'static`.
+    let declaration = unsafe { &**declaration_sym };
+    mem::forget(lib);
+
+    Ok(declaration)
+}
@@ -41,6 +41,9 @@
 mod profiling_support;
 pub use self::profiling_support::alloc_self_profile_query_strings;
 
+mod self_profile_summary;
+pub use self::self_profile_summary::print_self_profile_summary;
+
 rustc_query_append! { define_queries! }
 
 impl<'tcx> Queries<'tcx> {
@@ -0,0 +1,126 @@
+//! Support for `-Z self-profile-summary`, which prints the items with the highest
+//! self time in a few of the most expensive per-item activities, without the need
+//! for any tools to post-process the self-profile.
+
+use rustc_data_structures::fx::FxHashMap;
+use rustc_hir::def_id::DefId;
+use rustc_middle::ty::print::with_no_trimmed_paths;
+use rustc_middle::ty::TyCtxt;
+use rustc_query_system::query::QueryCache;
+use rustc_session::config::SelfProfileSummaryFormat;
+use std::cmp;
+use std::io::{self, Write};
+use std::time::Duration;
+
+#[derive(serde::Serialize)]
+struct Activity {
+    name: &'static str,
+    /// What the self times of the activity leave out, if anything.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    note: Option<&'static str>,
+    total_self_time_ms: f64,
+    items: Vec<Item>,
+}
+
+#[derive(serde::Serialize)]
+struct Item {
+    item: String,
+    self_time_ms: f64,
+}
+
+/// Sums up the self time of the invocations of a query per item. Invocations whose
+/// result was loaded from the incremental cache have no self time.
+fn query_self_times<C: QueryCache>(
+    tcx: TyCtxt<'_>,
+    query_cache: &C,
+    key_def_id: impl Fn(&C::Key) -> DefId,
+) -> FxHashMap<DefId, Duration> {
+    let mut self_times: FxHashMap<DefId, Duration> = FxHashMap::default();
+    query_cache.iter(&mut |key, _, dep_node_index| {
+        if let Some(self_time) = tcx.prof.query_self_time(dep_node_index.into()) {
+            *self_times.entry(key_def_id(key)).or_default() += self_time;
+        }
+    });
+    self_times
+}
+
+/// Codegen is measured per item only while building the backend IR of the item. The
+/// backend optimizes and emits machine code per codegen unit, often on other threads, which
+/// can't be attributed to single items.
+const CODEGEN_NOTE: &str = "only the time spent building the backend IR of each item is \
+    included, not the time the backend spends optimizing and emitting code for whole codegen \
+    units on other threads";
+
+fn millis(duration: Duration) -> f64 {
+    duration.as_secs_f64() * 1000.0
+}
+
+/// Prints the summary requested with `-Z self-profile-summary` to stderr. This
+/// needs to be called after codegen, so that all of the activities have run.
+pub fn print_self_profile_summary(tcx: TyCtxt<'_>) {
+    let Some(format) = tcx.sess.opts.unstable_opts.self_profile_summary else { return };
+    let items_per_activity = tcx.sess.opts.unstable_opts.self_profile_summary_items;
+
+    let caches = &tcx.query_caches;
+    // Codegen is recorded per instance and attributed to the generic item the
+    // instance was created from.
+    let codegen_self_times = tcx.codegen_self_times.lock().clone();
+    let self_times = [
+        ("typeck", None, query_self_times(tcx, &caches.typeck, |key| key.to_def_id())),
+        ("mir_borrowck", None, query_self_times(tcx, &caches.mir_borrowck, |key| key.to_def_id())),
+        ("optimized_mir", None, query_self_times(tcx, &caches.optimized_mir, |&key| key)),
+        ("codegen", Some(CODEGEN_NOTE), codegen_self_times),
+    ];
+
+    let activities: Vec<_> = self_times
+        .into_iter()
+        .map(|(name, note, self_times)| {
+            let total_self_time = self_times.values().sum();
+            let mut self_times: Vec<_> = self_times.into_iter().collect();
+            self_times.sort_by_key(|&(_, self_time)| cmp::Reverse(self_time));
+            let items = self_times
+                .into_iter()
+                .take(items_per_activity)
+                .map(|(def_id, self_time)| Item {
+                    item: with_no_trimmed_paths!(tcx.def_path_str(def_id)),
+                    self_time_ms: millis(self_time),
+                })
+                .collect();
+            Activity { name, note, total_self_time_ms: millis(total_self_time), items }
+        })
+        .collect();
+
+    // Failing to write to stderr is not worth an error.
+    let _ = write_summary(format, &activities, &mut io::stderr().lock());
+}
+
+fn write_summary(
+    format: SelfProfileSummaryFormat,
+    activities: &[Activity],
+    out: &mut dyn Write,
+) -> io::Result<()> {
+    match format {
+        SelfProfileSummaryFormat::Json => {
+            serde_json::to_writer(&mut *out, activities)?;
+            writeln!(out)
+        }
+        SelfProfileSummaryFormat::Table => {
+            for activity in activities {
+                writeln!(
+                    out,
+                    "{} (total self time: {:.3}ms)",
+                    activity.name, activity.total_self_time_ms
+                )?;
+                if let Some(note) = activity.note {
+                    writeln!(out, "note: {note}")?;
+                }
+                writeln!(out, "{:>14}  item", "self time")?;
+                for item in &activity.items {
+                    writeln!(out, "{:>12.3}ms  {}", item.self_time_ms, item.item)?;
+                }
+                writeln!(out)?;
+            }
+            Ok(())
+        }
+    }
+}
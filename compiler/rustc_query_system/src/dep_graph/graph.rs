@@ -115,6 +115,7 @@ pub fn new(
         encoder: FileEncoder,
         record_graph: bool,
         record_stats: bool,
+        record_fingerprints: bool,
     ) -> DepGraph<K> {
         let prev_graph_node_count = prev_graph.node_count();
 
@@ -124,6 +125,7 @@ pub fn new(
             encoder,
             record_graph,
             record_stats,
+            record_fingerprints,
         );
 
         let colors = DepNodeColorMap::new(prev_graph_node_count);
@@ -601,6 +603,12 @@ pub fn prev_fingerprint_of(&self, dep_node: &DepNode<K>) -> Option<Fingerprint>
         self.data.as_ref().unwrap().previous.fingerprint_of(dep_node)
     }
 
+    /// Returns the fingerprint of `dep_node` in the current session, if the node exists and
+    /// the dep-graph was created to record fingerprints.
+    pub fn current_fingerprint_of(&self, dep_node: &DepNode<K>) -> Option<Fingerprint> {
+        self.data.as_ref()?.current.encoder.borrow().fingerprint_of(dep_node)
+    }
+
     /// Checks whether a previous work product exists for `v` and, if
     /// so, return the path that leads to it. Used to skip doing work.
     pub fn previous_work_product(&self, v: &WorkProductId) -> Option<WorkProduct> {
@@ -878,6 +886,68 @@ pub fn is_green(&self, dep_node: &DepNode<K>) -> bool {
         self.node_color(dep_node).map_or(false, |c| c.is_green())
     }
 
+    /// Returns the dep-nodes of the previous session that were re-executed in this session
+    /// and whose results changed.
+    pub fn red_nodes(&self) -> Vec<DepNode<K>> {
+        let Some(data) = &self.data else { return Vec::new() };
+        data.colors
+            .values
+            .indices()
+            .filter(|&index| data.colors.get(index) == Some(DepNodeColor::Red))
+            .map(|index| data.previous.index_to_node(index))
+            .collect()
+    }
+
+    /// Explains why `dep_node` could not be marked green, by walking the previous
+    /// dep-graph from it to the dependency that changed.
+    ///
+    /// Returns `None` if `dep_node` did not exist in the previous session.
+    pub fn red_dependency_chain<Tcx: DepContext<DepKind = K>>(
+        &self,
+        tcx: Tcx,
+        dep_node: &DepNode<K>,
+    ) -> Option<RedDependencyChain<K>> {
+        let data = self.data.as_ref()?;
+        let mut prev_index = data.previous.node_to_index_opt(dep_node)?;
+        let mut nodes = vec![*dep_node];
+
+        loop {
+            // `try_mark_previous_green` stops at the first dependency that it fails to mark
+            // green, so that dependency is the one to blame.
+            let Some(&dep_index) = data
+                .previous
+                .edge_targets_from(prev_index)
+                .iter()
+                .find(|&&dep_index| {
+                    !matches!(data.colors.get(dep_index), Some(DepNodeColor::Green(_)))
+                })
+            else {
+                return Some(RedDependencyChain { nodes, cause: RedDependencyCause::Changed });
+            };
+
+            let dep_dep_node = data.previous.index_to_node(dep_index);
+            nodes.push(dep_dep_node);
+            if data.colors.get(dep_index) == Some(DepNodeColor::Red) {
+                // Eval-always nodes are re-executed without looking at their dependencies, so
+                // a red one is to blame itself.
+                if tcx.is_eval_always(dep_dep_node.kind) {
+                    return Some(RedDependencyChain { nodes, cause: RedDependencyCause::Changed });
+                }
+                prev_index = dep_index;
+                continue;
+            }
+
+            // The dependency is left without a color if it could not be forced. For a
+            // dependency that can be forced in general, that means its key no longer exists.
+            let cause = if tcx.dep_kind_info(dep_dep_node.kind).force_from_dep_node.is_some() {
+                RedDependencyCause::Removed
+            } else {
+                RedDependencyCause::Unknown
+            };
+            return Some(RedDependencyChain { nodes, cause });
+        }
+    }
+
     /// This method loads all on-disk cacheable query results into memory, so
     /// they can be written out to the new cache file again. Most query results
     /// will already be in memory but in the case where we marked something as
@@ -970,6 +1040,29 @@ pub struct WorkProduct {
     pub saved_files: FxHashMap<String, String>,
 }
 
+/// The chain of dependencies that kept a dep-node from being marked green, see
+/// [`DepGraph::red_dependency_chain`].
+#[derive(Debug)]
+pub struct RedDependencyChain<K: DepKind> {
+    /// The dep-node that could not be marked green, followed by the dependencies that could
+    /// not be marked green because of the next one.
+    pub nodes: Vec<DepNode<K>>,
+    /// What happened to the last node of the chain.
+    pub cause: RedDependencyCause,
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum RedDependencyCause {
+    /// The last node was recomputed and its result changed, while none of its
+    /// dependencies did.
+    Changed,
+    /// The last node could not be recomputed because what it refers to no longer exists.
+    Removed,
+    /// The last node can only be recomputed by the query that depends on it and was not
+    /// recomputed, so what happened to it is unknown.
+    Unknown,
+}
+
 // Index type for `DepNodeData`'s edges.
 rustc_index::newtype_index! {
     struct EdgeIndex {}
@@ -1045,6 +1138,7 @@ fn new(
         encoder: FileEncoder,
         record_graph: bool,
         record_stats: bool,
+        record_fingerprints: bool,
     ) -> CurrentDepGraph<K> {
         use std::time::{SystemTime, UNIX_EPOCH};
 
@@ -1080,6 +1174,7 @@ fn new(
                 prev_graph_node_count,
                 record_graph,
                 record_stats,
+                record_fingerprints,
             )),
             new_node_to_index: Sharded::new(|| {
                 FxHashMap::with_capacity_and_hasher(
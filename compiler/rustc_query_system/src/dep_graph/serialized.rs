@@ -233,6 +233,7 @@ fn finish(self, profiler: &SelfProfilerRef) -> FileEncodeResult {
 pub struct GraphEncoder<K: DepKind> {
     status: Lock<EncoderState<K>>,
     record_graph: Option<Lock<DepGraphQuery<K>>>,
+    record_fingerprints: Option<Lock<FxHashMap<DepNode<K>, Fingerprint>>>,
 }
 
 impl<K: DepKind + Encodable<FileEncoder>> GraphEncoder<K> {
@@ -241,11 +242,13 @@ pub fn new(
         prev_node_count: usize,
         record_graph: bool,
         record_stats: bool,
+        record_fingerprints: bool,
     ) -> Self {
         let record_graph =
             if record_graph { Some(Lock::new(DepGraphQuery::new(prev_node_count))) } else { None };
+        let record_fingerprints = record_fingerprints.then(|| Lock::new(FxHashMap::default()));
         let status = Lock::new(EncoderState::new(encoder, record_stats));
-        GraphEncoder { status, record_graph }
+        GraphEncoder { status, record_graph, record_fingerprints }
     }
 
     pub(crate) fn with_query(&self, f: impl Fn(&DepGraphQuery<K>)) {
@@ -254,6 +257,11 @@ pub(crate) fn with_query(&self, f: impl Fn(&DepGraphQuery<K>)) {
         }
     }
 
+    /// Returns the fingerprint that `node` was encoded with, if fingerprints are recorded.
+    pub(crate) fn fingerprint_of(&self, node: &DepNode<K>) -> Option<Fingerprint> {
+        self.record_fingerprints.as_ref()?.lock().get(node).copied()
+    }
+
     pub(crate) fn print_incremental_info(
         &self,
         total_read_count: u64,
@@ -314,6 +322,9 @@ pub(crate) fn send(
         edges: SmallVec<[DepNodeIndex; 8]>,
     ) -> DepNodeIndex {
         let _prof_timer = profiler.generic_activity("incr_comp_encode_dep_graph");
+        if let Some(record_fingerprints) = &self.record_fingerprints {
+            record_fingerprints.lock().insert(node, fingerprint);
+        }
         let node = NodeInfo { node, fingerprint, edges };
         self.status.lock().encode_node(&node, &self.record_graph)
     }
@@ -2,7 +2,7 @@
 //! generate the actual methods on tcx which find and execute the provider,
 //! manage the caches, and so forth.
 
-use crate::dep_graph::{DepContext, DepKind, DepNode, DepNodeIndex};
+use crate::dep_graph::{DepContext, DepKind, DepNode, DepNodeIndex, DepNodeParams};
 use crate::ich::StableHashingContext;
 use crate::query::caches::QueryCache;
 use crate::query::job::{report_cycle, QueryInfo, QueryJob, QueryJobId, QueryJobInfo};
@@ -14,6 +14,8 @@
 #[cfg(parallel_compiler)]
 use rustc_data_structures::profiling::TimingGuard;
 #[cfg(parallel_compiler)]
+use rustc_data_structures::sequential_order::{self, QueryIdentity};
+#[cfg(parallel_compiler)]
 use rustc_data_structures::sharded::Sharded;
 use rustc_data_structures::sync::Lock;
 use rustc_errors::{DiagnosticBuilder, ErrorGuaranteed, FatalError};
@@ -342,15 +344,26 @@ enum TryGetJob<'tcx, K, D>
 pub fn try_get_cached<Tcx, C, R, OnHit>(
     tcx: Tcx,
     cache: &C,
+    // The dep kind of the query, to identify its requests for `sequential_order`.
+    #[cfg_attr(not(parallel_compiler), allow(unused_variables))] kind: Tcx::DepKind,
     key: &C::Key,
     // `on_hit` can be called while holding a lock to the query cache
     on_hit: OnHit,
 ) -> Result<R, ()>
 where
     C: QueryCache,
+    C::Key: DepNodeParams<Tcx>,
     Tcx: DepContext,
     OnHit: FnOnce(&C::Stored) -> R,
 {
+    // This is the hottest path of the query system, so only track requests when they are
+    // needed to order diagnostics.
+    #[cfg(parallel_compiler)]
+    if tcx.sess().sequential_diagnostic_order() {
+        sequential_order::request_query(|| {
+            query_identity(cache, DepNode::construct(tcx, kind, key))
+        });
+    }
     cache.lookup(&key, |value, index| {
         if std::intrinsics::unlikely(tcx.profiler().enabled()) {
             tcx.profiler().query_cache_hit(index.into());
@@ -360,6 +373,13 @@ pub fn try_get_cached<Tcx, C, R, OnHit>(
     })
 }
 
+/// Identifies the invocation of the query with the given `cache` and `dep_node` for
+/// `sequential_order`. Unlike hashes of the keys, dep nodes of different keys don't collide.
+#[cfg(parallel_compiler)]
+fn query_identity<C: QueryCache, K: DepKind>(cache: &C, dep_node: DepNode<K>) -> QueryIdentity {
+    (cache as *const C as usize, dep_node.hash.into())
+}
+
 fn try_execute_query<Q, Qcx>(
     qcx: Qcx,
     state: &QueryState<Q::Key, Qcx::DepKind>,
@@ -374,8 +394,23 @@ fn try_execute_query<Q, Qcx>(
 {
     match JobOwner::<'_, Q::Key, Qcx::DepKind>::try_start(&qcx, state, span, key.clone()) {
         TryGetJob::NotYetStarted(job) => {
-            let (result, dep_node_index) =
-                execute_job::<Q, Qcx>(qcx, key.clone(), dep_node, job.id);
+            let execute = || execute_job::<Q, Qcx>(qcx, key.clone(), dep_node, job.id);
+            // What the query emits is positioned relative to its earliest request.
+            #[cfg(parallel_compiler)]
+            let tracks_order = qcx.dep_context().sess().sequential_diagnostic_order();
+            #[cfg(parallel_compiler)]
+            let (result, dep_node_index) = if tracks_order {
+                let identity = || {
+                    let dep_node =
+                        dep_node.unwrap_or_else(|| Q::construct_dep_node(*qcx.dep_context(), &key));
+                    query_identity(cache, dep_node)
+                };
+                sequential_order::run_query(identity, execute)
+            } else {
+                execute()
+            };
+            #[cfg(not(parallel_compiler))]
+            let (result, dep_node_index) = execute();
             if Q::FEEDABLE {
                 // We may have put a value inside the cache from inside the execution.
                 // Verify that it has the same hash as what we have now, to ensure consistency.
@@ -400,6 +435,13 @@ fn try_execute_query<Q, Qcx>(
         }
         #[cfg(parallel_compiler)]
         TryGetJob::JobCompleted(query_blocked_prof_timer) => {
+            if qcx.dep_context().sess().sequential_diagnostic_order() {
+                sequential_order::request_query_again(|| {
+                    let dep_node =
+                        dep_node.unwrap_or_else(|| Q::construct_dep_node(*qcx.dep_context(), &key));
+                    query_identity(cache, dep_node)
+                });
+            }
             let (v, index) = cache
                 .lookup(&key, |value, index| (value.clone(), index))
                 .unwrap_or_else(|_| panic!("value must be in cache after waiting"));
@@ -429,10 +471,12 @@ fn execute_job<Q, Qcx>(
     // Fast path for when incr. comp. is off.
     if !dep_graph.is_fully_enabled() {
         let prof_timer = qcx.dep_context().profiler().query_provider();
+        let self_time = qcx.dep_context().profiler().self_time();
         let result = qcx.start_query(job_id, Q::DEPTH_LIMIT, None, || {
             Q::compute(qcx, &key)(*qcx.dep_context(), key)
         });
         let dep_node_index = dep_graph.next_virtual_depnode_index();
+        qcx.dep_context().profiler().finish_query_self_time(self_time, dep_node_index.into());
         prof_timer.finish_with_query_invocation_id(dep_node_index.into());
         return (result, dep_node_index);
     }
@@ -452,6 +496,7 @@ fn execute_job<Q, Qcx>(
     }
 
     let prof_timer = qcx.dep_context().profiler().query_provider();
+    let self_time = qcx.dep_context().profiler().self_time();
     let diagnostics = Lock::new(ThinVec::new());
 
     let (result, dep_node_index) =
@@ -470,6 +515,7 @@ fn execute_job<Q, Qcx>(
             dep_graph.with_task(dep_node, *qcx.dep_context(), key, task, Q::HASH_RESULT)
         });
 
+    qcx.dep_context().profiler().finish_query_self_time(self_time, dep_node_index.into());
     prof_timer.finish_with_query_invocation_id(dep_node_index.into());
 
     let diagnostics = diagnostics.into_inner();
@@ -556,10 +602,12 @@ fn try_load_from_disk_and_cache_in_memory<Q, Qcx>(
     // We could not load a result from the on-disk cache, so
     // recompute.
     let prof_timer = qcx.dep_context().profiler().query_provider();
+    let self_time = qcx.dep_context().profiler().self_time();
 
     // The dep-graph for this computation is already in-place.
     let result = dep_graph.with_ignore(|| Q::compute(qcx, key)(*qcx.dep_context(), key.clone()));
 
+    qcx.dep_context().profiler().finish_query_self_time(self_time, dep_node_index.into());
     prof_timer.finish_with_query_invocation_id(dep_node_index.into());
 
     // Verify that re-running the query produced a result with the expected hash
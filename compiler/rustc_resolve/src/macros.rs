@@ -137,6 +137,10 @@ pub(crate) fn registered_tools(sess: &Session, attrs: &[ast::Attribute]) -> FxHa
     // but it's not an error to register them explicitly.
     let predefined_tools = [sym::clippy, sym::rustfmt];
     registered_tools.extend(predefined_tools.iter().cloned().map(Ident::with_dummy_span));
+    // The tools of lint plugins are known too, so that their lints can be controlled with
+    // `#[allow(tool::lint)]` and friends.
+    let lint_plugin_tools = sess.opts.unstable_opts.lint_plugin.iter().map(|(tool, _)| tool);
+    registered_tools.extend(lint_plugin_tools.map(|tool| Ident::from_str(tool)));
     registered_tools
 }
 
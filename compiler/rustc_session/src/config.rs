@@ -10,7 +10,7 @@
 
 use rustc_data_structures::fx::{FxHashMap, FxHashSet};
 
-use rustc_data_structures::stable_hasher::{StableOrd, ToStableHashKey};
+use rustc_data_structures::stable_hasher::{HashStable, StableHasher, StableOrd, ToStableHashKey};
 use rustc_target::abi::Align;
 use rustc_target::spec::{PanicStrategy, SanitizerSet, SplitDebuginfo};
 use rustc_target::spec::{Target, TargetTriple, TargetWarnings, TARGETS};
@@ -458,6 +458,24 @@ pub fn should_link(&self) -> bool {
             OutputType::Exe => true,
         })
     }
+
+    /// Returns the output types with metadata and dep-info left out, treating a build that
+    /// emits nothing else like one that emits the default linked artifact.
+    ///
+    /// This is what incremental compilation state is keyed on, so that a build that only
+    /// emits metadata and a later one that also links can share their analysis.
+    pub fn for_incremental_state(&self) -> OutputTypes {
+        let mut output_types: BTreeMap<_, _> = self
+            .0
+            .iter()
+            .filter(|(k, _)| !matches!(k, OutputType::Metadata | OutputType::DepInfo))
+            .map(|(&k, v)| (k, v.clone()))
+            .collect();
+        if output_types.is_empty() {
+            output_types.insert(OutputType::Exe, None);
+        }
+        OutputTypes(output_types)
+    }
 }
 
 /// Use tree-based collections to cheaply get a deterministic `Hash` implementation.
@@ -565,6 +583,26 @@ pub enum TraitSolver {
     Next,
 }
 
+/// The modes of `-Z polonius`.
+#[derive(Debug, Copy, Clone, Hash, PartialEq, Eq)]
+pub enum Polonius {
+    /// Borrow checking with NLL only.
+    Off,
+    /// Borrow checking with NLL, where the borrow conflicts reported by NLL are then checked with
+    /// the location-insensitive Polonius analysis, and only if it doesn't rule them out, with the
+    /// full Polonius analysis.
+    Hybrid,
+    /// Borrow checking with the full Polonius analysis.
+    Full,
+}
+
+impl Polonius {
+    /// Whether the Polonius facts are computed.
+    pub fn is_enabled(self) -> bool {
+        self != Polonius::Off
+    }
+}
+
 pub enum Input {
     /// Load source code from a file.
     File(PathBuf),
@@ -611,7 +649,7 @@ pub fn opt_path(&self) -> Option<&Path> {
     }
 }
 
-#[derive(Clone, Hash, Debug, HashStable_Generic)]
+#[derive(Clone, Hash, Debug)]
 pub struct OutputFilenames {
     pub out_directory: PathBuf,
     filestem: String,
@@ -620,6 +658,26 @@ pub struct OutputFilenames {
     pub outputs: OutputTypes,
 }
 
+impl<HCX: HashStableContext> HashStable<HCX> for OutputFilenames {
+    fn hash_stable(&self, hcx: &mut HCX, hasher: &mut StableHasher) {
+        let OutputFilenames {
+            out_directory,
+            filestem,
+            single_output_file,
+            temps_directory,
+            outputs,
+        } = self;
+        out_directory.hash_stable(hcx, hasher);
+        filestem.hash_stable(hcx, hasher);
+        single_output_file.hash_stable(hcx, hasher);
+        temps_directory.hash_stable(hcx, hasher);
+        // Like the command-line hash of incremental state, this doesn't change when only
+        // metadata is emitted, so that the work products of a build that codegens stay valid
+        // across a build that doesn't.
+        outputs.for_incremental_state().hash_stable(hcx, hasher);
+    }
+}
+
 pub const RLINK_EXT: &str = "rlink";
 pub const RUST_CGU_EXT: &str = "rcgu";
 pub const DWARF_OBJECT_EXT: &str = "dwo";
@@ -751,6 +809,7 @@ fn default() -> Options {
             debuginfo: DebugInfo::None,
             lint_opts: Vec::new(),
             lint_cap: None,
+            warnings_per_lint_cap: None,
             describe_lints: false,
             output_types: OutputTypes(BTreeMap::new()),
             search_paths: vec![],
@@ -834,6 +893,9 @@ pub fn diagnostic_handler_flags(&self, can_emit_warnings: bool) -> HandlerFlags
             macro_backtrace: self.macro_backtrace,
             deduplicate_diagnostics: self.deduplicate_diagnostics,
             track_diagnostics: self.track_diagnostics,
+            diagnostic_grouping: false,
+            warnings_per_lint_cap: None,
+            sequential_diagnostic_order: false,
         }
     }
 }
@@ -1479,6 +1541,13 @@ pub fn rustc_optgroups() -> Vec<RustcOptGroup> {
             "Remap source names in all output (compiler messages and output files)",
             "FROM=TO",
         ),
+        opt::opt(
+            "",
+            "cap-warnings-per-lint",
+            "Set the number of warnings each lint may emit. \
+             The remaining warnings are folded into a summary note",
+            "N",
+        ),
     ]);
     opts
 }
@@ -2288,6 +2357,10 @@ pub fn build_session_options(matches: &getopts::Matches) -> Options {
     let mut unstable_opts = UnstableOptions::build(matches, error_format);
     let (lint_opts, describe_lints, lint_cap) = get_cmd_lint_options(matches, error_format);
 
+    let warnings_per_lint_cap = matches.opt_get("cap-warnings-per-lint").unwrap_or_else(|_| {
+        early_error(error_format, "`--cap-warnings-per-lint` must be a non-negative integer");
+    });
+
     check_error_format_stability(&unstable_opts, error_format, json_rendered);
 
     if !unstable_opts.unstable_options && json_unused_externs.is_enabled() {
@@ -2529,6 +2602,7 @@ pub fn build_session_options(matches: &getopts::Matches) -> Options {
         debuginfo,
         lint_opts,
         lint_cap,
+        warnings_per_lint_cap,
         describe_lints,
         output_types,
         search_paths,
@@ -2798,10 +2872,10 @@ pub fn needs_analysis(&self) -> bool {
 /// how the hash should be calculated when adding a new command-line argument.
 pub(crate) mod dep_tracking {
     use super::{
-        BranchProtection, CFGuard, CFProtection, CrateType, DebugInfo, ErrorOutputType,
-        InstrumentCoverage, LdImpl, LinkerPluginLto, LocationDetail, LtoCli, OomStrategy, OptLevel,
-        OutputType, OutputTypes, Passes, SourceFileHashAlgorithm, SplitDwarfKind,
-        SwitchWithOptPath, SymbolManglingVersion, TraitSolver, TrimmedDefPaths,
+        BranchProtection, CFGuard, CFProtection, CallGraphProfile, CrateType, DebugInfo,
+        ErrorOutputType, InstrumentCoverage, LdImpl, LinkerPluginLto, LocationDetail, LtoCli,
+        OomStrategy, OptLevel, OutputType, OutputTypes, Passes, Polonius, SourceFileHashAlgorithm,
+        SplitDwarfKind, SwitchWithOptPath, SymbolManglingVersion, TraitSolver, TrimmedDefPaths,
     };
     use crate::lint;
     use crate::options::WasiExecModel;
@@ -2816,7 +2890,7 @@ pub(crate) mod dep_tracking {
     };
     use std::collections::hash_map::DefaultHasher;
     use std::collections::BTreeMap;
-    use std::hash::Hash;
+    use std::hash::{Hash, Hasher};
     use std::num::NonZeroUsize;
     use std::path::PathBuf;
 
@@ -2902,6 +2976,8 @@ fn hash(
         OomStrategy,
         LanguageIdentifier,
         TraitSolver,
+        Polonius,
+        CallGraphProfile,
     );
 
     impl<T1, T2> DepTrackingHash for (T1, T2)
@@ -2965,12 +3041,20 @@ fn hash(
             error_format: ErrorOutputType,
             for_crate_hash: bool,
         ) {
+            if for_crate_hash {
+                // The crate hash doesn't change when only metadata is emitted, so that it can be
+                // shared with the incremental state of a build that codegens.
+                let output_types = self.for_incremental_state();
+                Hash::hash(&output_types.0.len(), hasher);
+                for key in output_types.0.keys() {
+                    DepTrackingHash::hash(key, hasher, error_format, for_crate_hash);
+                }
+                return;
+            }
             Hash::hash(&self.0.len(), hasher);
             for (key, val) in &self.0 {
                 DepTrackingHash::hash(key, hasher, error_format, for_crate_hash);
-                if !for_crate_hash {
-                    DepTrackingHash::hash(val, hasher, error_format, for_crate_hash);
-                }
+                DepTrackingHash::hash(val, hasher, error_format, for_crate_hash);
             }
         }
     }
@@ -2990,6 +3074,24 @@ pub(crate) fn stable_hash(
             sub_hash.hash(hasher, error_format, for_crate_hash);
         }
     }
+
+    /// Hashes each option on its own, so that a change of the [`stable_hash`] can be blamed
+    /// on the options that changed. `prefix` is prepended to the option names.
+    pub(crate) fn stable_hashes(
+        sub_hashes: BTreeMap<&'static str, &dyn DepTrackingHash>,
+        prefix: &str,
+        error_format: ErrorOutputType,
+        for_crate_hash: bool,
+    ) -> BTreeMap<String, u64> {
+        sub_hashes
+            .into_iter()
+            .map(|(key, sub_hash)| {
+                let mut hasher = DefaultHasher::new();
+                sub_hash.hash(&mut hasher, error_format, for_crate_hash);
+                (format!("{prefix}{}", key.replace('_', "-")), hasher.finish())
+            })
+            .collect()
+    }
 }
 
 /// Default behavior to use in out-of-memory situations.
@@ -3040,3 +3142,86 @@ pub fn extension(self) -> &'static str {
         }
     }
 }
+
+/// Which format to use for `-Z dump-trait-proof-tree`
+#[derive(Clone, Copy, PartialEq, Hash, Debug)]
+pub enum TraitProofTreeFormat {
+    /// Print the tree as indented text
+    Text,
+    /// Emit a graphviz graph
+    Dot,
+}
+
+/// Which format to use for `-Z incremental-explain`
+#[derive(Clone, Copy, PartialEq, Hash, Debug)]
+pub enum IncrementalExplainFormat {
+    /// Print human-readable causal chains
+    Text,
+    /// Emit one JSON object per line
+    Json,
+}
+
+/// Which format to use for `-Z self-profile-summary`
+#[derive(Clone, Copy, PartialEq, Hash, Debug)]
+pub enum SelfProfileSummaryFormat {
+    /// Print a plain text table
+    Table,
+    /// Emit a single JSON object
+    Json,
+}
+
+/// The call-graph profile given with `-Z cgu-call-graph-profile`.
+#[derive(Clone, PartialEq, Hash, Debug)]
+pub struct CallGraphProfile {
+    pub path: PathBuf,
+    /// The edges of the profile, read by [`CallGraphProfile::load`] when the session is built.
+    /// They are part of the dep-tracking hash, so that it changes with the profile and not
+    /// just with its path.
+    pub edges: Vec<CallGraphEdge>,
+}
+
+/// A line of a [`CallGraphProfile`]: `caller` called `callee` `count` times.
+#[derive(Clone, PartialEq, Eq, Hash, Debug)]
+pub struct CallGraphEdge {
+    pub caller: String,
+    pub callee: String,
+    pub count: u64,
+}
+
+/// Why a [`CallGraphProfile`] could not be loaded.
+#[derive(Debug)]
+pub enum CallGraphProfileError {
+    Io(std::io::Error),
+    /// The number of the first malformed line.
+    Malformed(usize),
+}
+
+impl CallGraphProfile {
+    pub fn new(path: PathBuf) -> CallGraphProfile {
+        CallGraphProfile { path, edges: Vec::new() }
+    }
+
+    /// Reads and parses the profile. Empty lines and lines starting with `#` are ignored.
+    pub fn load(&mut self) -> Result<(), CallGraphProfileError> {
+        let profile = std::fs::read_to_string(&self.path).map_err(CallGraphProfileError::Io)?;
+        self.edges = profile
+            .lines()
+            .enumerate()
+            .map(|(index, line)| (index + 1, line.trim()))
+            .filter(|(_, line)| !line.is_empty() && !line.starts_with('#'))
+            .map(|(line_number, line)| {
+                let mut fields = line.split_whitespace();
+                let (Some(caller), Some(callee), Some(count), None) =
+                    (fields.next(), fields.next(), fields.next(), fields.next())
+                else {
+                    return Err(CallGraphProfileError::Malformed(line_number));
+                };
+                let Ok(count) = count.parse() else {
+                    return Err(CallGraphProfileError::Malformed(line_number));
+                };
+                Ok(CallGraphEdge { caller: caller.to_string(), callee: callee.to_string(), count })
+            })
+            .collect::<Result<_, _>>()?;
+        Ok(())
+    }
+}
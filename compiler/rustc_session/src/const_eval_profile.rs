@@ -0,0 +1,161 @@
+//! Support for `-Z const-eval-profile`, which prints the const items and functions that took
+//! the most interpreter steps during const evaluation, and the calls between them, from the
+//! body of an item or a function to the function it called, that the steps were spent in.
+
+use rustc_data_structures::fx::FxHashMap;
+use rustc_data_structures::sync::Lock;
+use std::cmp::Reverse;
+use std::io::{self, Write};
+use std::ops::AddAssign;
+
+/// How many const items and functions are printed.
+const PRINTED_ENTRIES: usize = 20;
+
+/// The work done by the interpreter.
+#[derive(Copy, Clone, Default, Debug)]
+pub struct ConstEvalCost {
+    /// The number of terminators evaluated, the unit of `#![const_eval_limit]`.
+    pub steps: u64,
+    pub allocations: u64,
+    pub allocated_bytes: u64,
+}
+
+impl AddAssign for ConstEvalCost {
+    fn add_assign(&mut self, other: ConstEvalCost) {
+        self.steps += other.steps;
+        self.allocations += other.allocations;
+        self.allocated_bytes += other.allocated_bytes;
+    }
+}
+
+/// The work done by the interpreter on behalf of a function.
+#[derive(Copy, Clone, Default, Debug)]
+pub struct ConstFnProfile {
+    pub calls: u64,
+    /// The work done in the function itself, not in its callees.
+    pub self_cost: ConstEvalCost,
+    /// The steps taken in the function and in its callees. Recursive calls are only counted
+    /// once.
+    pub total_steps: u64,
+}
+
+impl AddAssign for ConstFnProfile {
+    fn add_assign(&mut self, other: ConstFnProfile) {
+        self.calls += other.calls;
+        self.self_cost += other.self_cost;
+        self.total_steps += other.total_steps;
+    }
+}
+
+/// The calls from the body of an item or a function to another function.
+#[derive(Copy, Clone, Default, Debug)]
+pub struct ConstCallProfile {
+    pub calls: u64,
+    /// The steps taken in the callee and in its callees. Calls made while a call of the same
+    /// caller to the same callee is already on the stack are only counted once.
+    pub total_steps: u64,
+}
+
+impl AddAssign for ConstCallProfile {
+    fn add_assign(&mut self, other: ConstCallProfile) {
+        self.calls += other.calls;
+        self.total_steps += other.total_steps;
+    }
+}
+
+#[derive(Copy, Clone, Default)]
+struct ConstItemProfile {
+    evaluations: u64,
+    cost: ConstEvalCost,
+}
+
+/// The cost of const evaluation over the whole session, keyed by the paths of the items.
+#[derive(Default)]
+pub struct ConstEvalProfile {
+    items: Lock<FxHashMap<String, ConstItemProfile>>,
+    fns: Lock<FxHashMap<String, ConstFnProfile>>,
+    /// Keyed by the paths of the caller and of the callee.
+    calls: Lock<FxHashMap<(String, String), ConstCallProfile>>,
+}
+
+impl ConstEvalProfile {
+    /// Records one evaluation of the const item or static `item`, including the functions it
+    /// called.
+    pub fn record_item(&self, item: String, cost: ConstEvalCost) {
+        let mut items = self.items.lock();
+        let profile = items.entry(item).or_default();
+        profile.evaluations += 1;
+        profile.cost += cost;
+    }
+
+    pub fn record_fn(&self, function: String, profile: ConstFnProfile) {
+        *self.fns.lock().entry(function).or_default() += profile;
+    }
+
+    /// Records the calls from `caller`, a const item, static or function, to `callee`.
+    pub fn record_call(&self, caller: String, callee: String, profile: ConstCallProfile) {
+        *self.calls.lock().entry((caller, callee)).or_default() += profile;
+    }
+
+    /// Prints the most expensive const items and functions to stderr.
+    pub fn print(&self) {
+        // Failing to write to stderr is not worth an error.
+        let _ = self.write(&mut io::stderr().lock());
+    }
+
+    fn write(&self, out: &mut dyn Write) -> io::Result<()> {
+        let mut items: Vec<_> = self.items.lock().clone().into_iter().collect();
+        items.sort_by_key(|(item, profile)| (Reverse(profile.cost.steps), item.clone()));
+        let total_steps: u64 = items.iter().map(|(_, profile)| profile.cost.steps).sum();
+
+        writeln!(out, "const items (total steps: {total_steps})")?;
+        writeln!(
+            out,
+            "{:>12}  {:>11}  {:>11}  {:>11}  item",
+            "steps", "allocations", "bytes", "evaluations"
+        )?;
+        for (item, profile) in items.iter().take(PRINTED_ENTRIES) {
+            let ConstItemProfile { evaluations, cost } = profile;
+            writeln!(
+                out,
+                "{:>12}  {:>11}  {:>11}  {:>11}  {item}",
+                cost.steps, cost.allocations, cost.allocated_bytes, evaluations
+            )?;
+        }
+        writeln!(out)?;
+
+        let mut fns: Vec<_> = self.fns.lock().clone().into_iter().collect();
+        fns.sort_by_key(|(function, profile)| (Reverse(profile.self_cost.steps), function.clone()));
+
+        writeln!(out, "functions")?;
+        writeln!(
+            out,
+            "{:>12}  {:>12}  {:>11}  {:>11}  {:>11}  function",
+            "self steps", "total steps", "allocations", "bytes", "calls"
+        )?;
+        for (function, profile) in fns.iter().take(PRINTED_ENTRIES) {
+            let ConstFnProfile { calls, self_cost, total_steps } = profile;
+            writeln!(
+                out,
+                "{:>12}  {:>12}  {:>11}  {:>11}  {:>11}  {function}",
+                self_cost.steps,
+                total_steps,
+                self_cost.allocations,
+                self_cost.allocated_bytes,
+                calls
+            )?;
+        }
+        writeln!(out)?;
+
+        let mut calls: Vec<_> = self.calls.lock().clone().into_iter().collect();
+        calls.sort_by_key(|(edge, profile)| (Reverse(profile.total_steps), edge.clone()));
+
+        writeln!(out, "calls")?;
+        writeln!(out, "{:>12}  {:>11}  caller -> callee", "total steps", "calls")?;
+        for ((caller, callee), profile) in calls.iter().take(PRINTED_ENTRIES) {
+            let ConstCallProfile { calls, total_steps } = profile;
+            writeln!(out, "{:>12}  {:>11}  {caller} -> {callee}", total_steps, calls)?;
+        }
+        writeln!(out)
+    }
+}
@@ -67,6 +67,21 @@ pub struct ProfileSampleUseFileDoesNotExist<'a> {
     pub path: &'a std::path::Path,
 }
 
+#[derive(Diagnostic)]
+#[diag(session_couldnt_read_call_graph_profile)]
+pub struct CouldntReadCallGraphProfile<'a> {
+    pub path: &'a std::path::Path,
+    pub error: String,
+}
+
+#[derive(Diagnostic)]
+#[diag(session_malformed_call_graph_profile)]
+#[note]
+pub struct MalformedCallGraphProfile<'a> {
+    pub path: &'a std::path::Path,
+    pub line: usize,
+}
+
 #[derive(Diagnostic)]
 #[diag(session_target_requires_unwind_tables)]
 pub struct TargetRequiresUnwindTables;
@@ -25,6 +25,7 @@
 pub mod parse;
 
 mod code_stats;
+mod const_eval_profile;
 #[macro_use]
 pub mod config;
 pub mod cstore;
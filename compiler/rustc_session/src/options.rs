@@ -12,6 +12,7 @@
 
 use rustc_feature::UnstableFeatures;
 use rustc_span::edition::Edition;
+use rustc_span::symbol::Ident;
 use rustc_span::RealFileName;
 use rustc_span::SourceFileHashAlgorithm;
 
@@ -59,6 +60,15 @@ macro_rules! hash_substruct {
     };
 }
 
+macro_rules! hashes_substruct {
+    ($opt_expr:expr, $error_format:expr, $for_crate_hash:expr, $hashes:expr, [UNTRACKED]) => {{}};
+    ($opt_expr:expr, $error_format:expr, $for_crate_hash:expr, $hashes:expr, [TRACKED]) => {{}};
+    ($opt_expr:expr, $error_format:expr, $for_crate_hash:expr, $hashes:expr, [TRACKED_NO_CRATE_HASH]) => {{}};
+    ($opt_expr:expr, $error_format:expr, $for_crate_hash:expr, $hashes:expr, [SUBSTRUCT]) => {
+        $hashes.extend($opt_expr.dep_tracking_hashes($for_crate_hash, $error_format));
+    };
+}
+
 macro_rules! top_level_options {
     ( $( #[$top_level_attr:meta] )* pub struct Options { $(
         $( #[$attr:meta] )*
@@ -98,6 +108,31 @@ pub fn dep_tracking_hash(&self, for_crate_hash: bool) -> u64 {
                 })*
                 hasher.finish()
             }
+
+            /// The hashes of the individual options that make up [`Options::dep_tracking_hash`],
+            /// by option name.
+            pub fn dep_tracking_hashes(&self, for_crate_hash: bool) -> BTreeMap<String, u64> {
+                let mut sub_hashes = BTreeMap::new();
+                $({
+                    hash_opt!($opt,
+                                &self.$opt,
+                                &mut sub_hashes,
+                                for_crate_hash,
+                                [$dep_tracking_marker]);
+                })*
+                let mut hashes = dep_tracking::stable_hashes(sub_hashes,
+                                                             "",
+                                                             self.error_format,
+                                                             for_crate_hash);
+                $({
+                    hashes_substruct!(&self.$opt,
+                        self.error_format,
+                        for_crate_hash,
+                        &mut hashes,
+                        [$dep_tracking_marker]);
+                })*
+                hashes
+            }
         }
     );
 }
@@ -140,6 +175,9 @@ pub struct Options {
         debuginfo: DebugInfo [TRACKED],
         lint_opts: Vec<(String, lint::Level)> [TRACKED_NO_CRATE_HASH],
         lint_cap: Option<lint::Level> [TRACKED_NO_CRATE_HASH],
+        /// The number of warnings each lint may emit before the remaining ones are folded
+        /// into a summary note. (rustc: see `--cap-warnings-per-lint`)
+        warnings_per_lint_cap: Option<usize> [UNTRACKED],
         describe_lints: bool [UNTRACKED],
         output_types: OutputTypes [TRACKED],
         search_paths: Vec<SearchPath> [UNTRACKED],
@@ -265,6 +303,25 @@ fn dep_tracking_hash(&self, for_crate_hash: bool, error_format: ErrorOutputType)
                                         );
             hasher.finish()
         }
+
+        fn dep_tracking_hashes(
+            &self,
+            for_crate_hash: bool,
+            error_format: ErrorOutputType,
+        ) -> BTreeMap<String, u64> {
+            let mut sub_hashes = BTreeMap::new();
+            $({
+                hash_opt!($opt,
+                            &self.$opt,
+                            &mut sub_hashes,
+                            for_crate_hash,
+                            [$dep_tracking_marker]);
+            })*
+            dep_tracking::stable_hashes(sub_hashes,
+                                        concat!("-", $prefix, " "),
+                                        error_format,
+                                        for_crate_hash)
+        }
     }
 
     pub const $stat: OptionDescrs<$struct_name> =
@@ -376,14 +433,21 @@ mod desc {
     pub const parse_strip: &str = "either `none`, `debuginfo`, or `symbols`";
     pub const parse_linker_flavor: &str = ::rustc_target::spec::LinkerFlavorCli::one_of();
     pub const parse_optimization_fuel: &str = "crate=integer";
+    pub const parse_lint_plugin: &str =
+        "`tool=path`, where `tool` is an identifier and `path` is a lint plugin dylib";
     pub const parse_mir_spanview: &str = "`statement` (default), `terminator`, or `block`";
     pub const parse_dump_mono_stats: &str = "`markdown` (default) or `json`";
+    pub const parse_call_graph_profile: &str = parse_opt_pathbuf;
+    pub const parse_trait_proof_tree_format: &str = "`text` (default) or `dot`";
+    pub const parse_incremental_explain: &str = "`text` (default) or `json`";
+    pub const parse_self_profile_summary: &str = "`table` (default) or `json`";
     pub const parse_instrument_coverage: &str =
         "`all` (default), `except-unused-generics`, `except-unused-functions`, or `off`";
     pub const parse_unpretty: &str = "`string` or `string=string`";
     pub const parse_treat_err_as_bug: &str = "either no value or a number bigger than 0";
     pub const parse_trait_solver: &str =
         "one of the supported solver modes (`classic`, `chalk`, or `next`)";
+    pub const parse_polonius: &str = "either no value (same as `full`), `full`, `hybrid` or `off`";
     pub const parse_lto: &str =
         "either a boolean (`yes`, `no`, `on`, `off`, etc), `thin`, `fat`, or omitted";
     pub const parse_linker_plugin_lto: &str =
@@ -789,6 +853,24 @@ pub(crate) fn parse_optimization_fuel(
         }
     }
 
+    pub(crate) fn parse_lint_plugin(slot: &mut Vec<(String, PathBuf)>, v: Option<&str>) -> bool {
+        // The tool is registered with `Ident::from_str` (see `registered_tools`), so it has to
+        // be an identifier that can start the path of a lint.
+        let is_tool_name = |tool: &str| {
+            rustc_lexer::is_ident(tool)
+                && rustc_span::create_default_session_if_not_set_then(|_| {
+                    !Ident::from_str(tool).is_reserved()
+                })
+        };
+        match v.and_then(|s| s.split_once('=')) {
+            Some((tool, path)) if is_tool_name(tool) && !path.is_empty() => {
+                slot.push((tool.to_string(), PathBuf::from(path)));
+                true
+            }
+            _ => false,
+        }
+    }
+
     pub(crate) fn parse_unpretty(slot: &mut Option<String>, v: Option<&str>) -> bool {
         match v {
             None => false,
@@ -838,6 +920,55 @@ pub(crate) fn parse_dump_mono_stats(slot: &mut DumpMonoStatsFormat, v: Option<&s
         }
     }
 
+    pub(crate) fn parse_trait_proof_tree_format(
+        slot: &mut TraitProofTreeFormat,
+        v: Option<&str>,
+    ) -> bool {
+        match v {
+            None | Some("text") => *slot = TraitProofTreeFormat::Text,
+            Some("dot") => *slot = TraitProofTreeFormat::Dot,
+            Some(_) => return false,
+        }
+        true
+    }
+
+    pub(crate) fn parse_call_graph_profile(
+        slot: &mut Option<CallGraphProfile>,
+        v: Option<&str>,
+    ) -> bool {
+        match v {
+            Some(s) => {
+                *slot = Some(CallGraphProfile::new(PathBuf::from(s)));
+                true
+            }
+            None => false,
+        }
+    }
+
+    pub(crate) fn parse_incremental_explain(
+        slot: &mut Option<IncrementalExplainFormat>,
+        v: Option<&str>,
+    ) -> bool {
+        match v {
+            None | Some("text") => *slot = Some(IncrementalExplainFormat::Text),
+            Some("json") => *slot = Some(IncrementalExplainFormat::Json),
+            Some(_) => return false,
+        }
+        true
+    }
+
+    pub(crate) fn parse_self_profile_summary(
+        slot: &mut Option<SelfProfileSummaryFormat>,
+        v: Option<&str>,
+    ) -> bool {
+        match v {
+            None | Some("table") => *slot = Some(SelfProfileSummaryFormat::Table),
+            Some("json") => *slot = Some(SelfProfileSummaryFormat::Json),
+            Some(_) => return false,
+        }
+        true
+    }
+
     pub(crate) fn parse_instrument_coverage(
         slot: &mut Option<InstrumentCoverage>,
         v: Option<&str>,
@@ -882,6 +1013,16 @@ pub(crate) fn parse_treat_err_as_bug(slot: &mut Option<NonZeroUsize>, v: Option<
         }
     }
 
+    pub(crate) fn parse_polonius(slot: &mut Polonius, v: Option<&str>) -> bool {
+        match v {
+            None | Some("full") => *slot = Polonius::Full,
+            Some("hybrid") => *slot = Polonius::Hybrid,
+            Some("off") => *slot = Polonius::Off,
+            _ => return false,
+        }
+        true
+    }
+
     pub(crate) fn parse_trait_solver(slot: &mut TraitSolver, v: Option<&str>) -> bool {
         match v {
             Some("classic") => *slot = TraitSolver::Classic,
@@ -1261,12 +1402,18 @@ pub(crate) fn parse_proc_macro_execution_strategy(
         "set options for branch target identification and pointer authentication on AArch64"),
     cf_protection: CFProtection = (CFProtection::None, parse_cfprotection, [TRACKED],
         "instrument control-flow architecture protection"),
+    cgu_call_graph_profile: Option<CallGraphProfile> = (None, parse_call_graph_profile, [TRACKED],
+        "place hot callees in the codegen units of their callers, as given by a call-graph \
+        profile with lines of the form `caller callee count`"),
     cgu_partitioning_strategy: Option<String> = (None, parse_opt_string, [TRACKED],
         "the codegen unit partitioning strategy to use"),
     codegen_backend: Option<String> = (None, parse_opt_string, [TRACKED],
         "the backend to use"),
     combine_cgu: bool = (false, parse_bool, [TRACKED],
         "combine CGUs into a single one"),
+    const_eval_profile: bool = (false, parse_bool, [UNTRACKED],
+        "print the const items and functions that took the most interpreter steps and \
+        allocations during const evaluation (default: no)"),
     crate_attr: Vec<String> = (Vec::new(), parse_string_push, [TRACKED],
         "inject the given attribute in the crate"),
     debug_info_for_profiling: bool = (false, parse_bool, [TRACKED],
@@ -1281,6 +1428,9 @@ pub(crate) fn parse_proc_macro_execution_strategy(
     dep_tasks: bool = (false, parse_bool, [UNTRACKED],
         "print tasks that execute and the color their dep node gets (requires debug build) \
         (default: no)"),
+    diagnostic_grouping: bool = (true, parse_bool, [UNTRACKED],
+        "report the errors of a pass with the same code and primary cause as a single error, \
+        followed by the locations of the others; never applies to JSON output (default: yes)"),
     diagnostic_width: Option<usize> = (None, parse_opt_number, [UNTRACKED],
         "set the current output width for diagnostic truncation"),
     dlltool: Option<PathBuf> = (None, parse_opt_pathbuf, [UNTRACKED],
@@ -1292,6 +1442,9 @@ pub(crate) fn parse_proc_macro_execution_strategy(
         "enables drop tracking in generators (default: no)"),
     dual_proc_macros: bool = (false, parse_bool, [TRACKED],
         "load proc macros for both target and host, but only link to the target (default: no)"),
+    dump_const_eval_locals: bool = (false, parse_bool, [TRACKED],
+        "when const evaluation fails, show the values of the locals of every frame \
+        (default: no)"),
     dump_dep_graph: bool = (false, parse_bool, [UNTRACKED],
         "dump the dependency graph to $RUST_DEP_GRAPH (default: /tmp/dep_graph.gv) \
         (default: no)"),
@@ -1326,6 +1479,12 @@ pub(crate) fn parse_proc_macro_execution_strategy(
         "output statistics about monomorphization collection"),
     dump_mono_stats_format: DumpMonoStatsFormat = (DumpMonoStatsFormat::Markdown, parse_dump_mono_stats, [UNTRACKED],
         "the format to use for -Z dump-mono-stats (`markdown` (default) or `json`)"),
+    dump_trait_proof_tree: Option<String> = (None, parse_opt_string, [UNTRACKED],
+        "print the candidate assembly, confirmation and nested obligations of trait selection \
+        for obligations in the given item (default: no)"),
+    dump_trait_proof_tree_format: TraitProofTreeFormat = (TraitProofTreeFormat::Text,
+        parse_trait_proof_tree_format, [UNTRACKED],
+        "the format to use for -Z dump-trait-proof-tree (`text` (default) or `dot`)"),
     dwarf_version: Option<u32> = (None, parse_opt_number, [TRACKED],
         "version of DWARF debug information to emit (default: 2 or 4, depending on platform)"),
     dylib_lto: bool = (false, parse_bool, [UNTRACKED],
@@ -1334,6 +1493,9 @@ pub(crate) fn parse_proc_macro_execution_strategy(
         "emit a section containing stack size metadata (default: no)"),
     emit_thin_lto: bool = (true, parse_bool, [TRACKED],
         "emit the bc module with thin LTO info (default: yes)"),
+    explain_auto_traits: bool = (false, parse_bool, [TRACKED],
+        "when a type does not implement an auto trait, note the full chain of types \
+        through which the obligation was derived (default: no)"),
     export_executable_symbols: bool = (false, parse_bool, [TRACKED],
         "export symbols from executables, as if they were dynamic libraries"),
     extra_const_ub_checks: bool = (false, parse_bool, [TRACKED],
@@ -1356,12 +1518,18 @@ pub(crate) fn parse_proc_macro_execution_strategy(
     graphviz_font: String = ("Courier, monospace".to_string(), parse_string, [UNTRACKED],
         "use the given `fontname` in graphviz output; can be overridden by setting \
         environment variable `RUSTC_GRAPHVIZ_FONT` (default: `Courier, monospace`)"),
+    highlight_snippets: bool = (false, parse_bool, [UNTRACKED],
+        "syntax-highlight the source code quoted in human-readable diagnostics; \
+        disabled by `--color=never` and the `NO_COLOR` environment variable (default: no)"),
     hir_stats: bool = (false, parse_bool, [UNTRACKED],
         "print some statistics about AST and HIR (default: no)"),
     human_readable_cgu_names: bool = (false, parse_bool, [TRACKED],
         "generate human-readable, predictable names for codegen units (default: no)"),
     identify_regions: bool = (false, parse_bool, [UNTRACKED],
         "display unnamed regions as `'<id>`, using a non-ident unique id (default: no)"),
+    incremental_explain: Option<IncrementalExplainFormat> = (None, parse_incremental_explain, [UNTRACKED],
+        "explain why codegen units are recompiled and queries re-executed, by printing the chain \
+        of dependencies from each of them to the input that changed; `text` (default) or `json`"),
     incremental_ignore_spans: bool = (false, parse_bool, [TRACKED],
         "ignore spans during ICH computation -- used for testing (default: no)"),
     incremental_info: bool = (false, parse_bool, [UNTRACKED],
@@ -1370,6 +1538,13 @@ pub(crate) fn parse_proc_macro_execution_strategy(
     #[rustc_lint_opt_deny_field_access("use `Session::incremental_relative_spans` instead of this field")]
     incremental_relative_spans: bool = (false, parse_bool, [TRACKED],
         "hash spans relative to their parent item for incr. comp. (default: no)"),
+    incremental_shared_cache: Option<PathBuf> = (None, parse_opt_pathbuf, [UNTRACKED],
+        "share the work products of incremental compilation through a content-addressed store \
+        in the given directory; checkouts at different paths can share them if the working \
+        directory is remapped with `--remap-path-prefix`"),
+    incremental_shared_cache_limit: u64 = (10240, parse_number, [UNTRACKED],
+        "the size in MiB above which `-Z incremental-shared-cache` evicts the least recently \
+        used work products (default: 10240)"),
     incremental_verify_ich: bool = (false, parse_bool, [UNTRACKED],
         "verify incr. comp. hashes of green query instances (default: no)"),
     inline_in_all_cgus: Option<bool> = (None, parse_opt_bool, [TRACKED],
@@ -1403,6 +1578,9 @@ pub(crate) fn parse_proc_macro_execution_strategy(
         "link native libraries in the linker invocation (default: yes)"),
     link_only: bool = (false, parse_bool, [TRACKED],
         "link the `.rlink` file generated by `-Z no-link` (default: no)"),
+    lint_plugin: Vec<(String, PathBuf)> = (Vec::new(), parse_lint_plugin, [TRACKED],
+        "load the lint plugin dylib at `path`, whose lints are named `tool::lint_name` \
+        (can be given multiple times)"),
     llvm_plugins: Vec<String> = (Vec::new(), parse_list, [TRACKED],
         "a list LLVM plugins to enable (space separated)"),
     llvm_time_trace: bool = (false, parse_bool, [UNTRACKED],
@@ -1469,6 +1647,9 @@ pub(crate) fn parse_proc_macro_execution_strategy(
         "panic strategy for out-of-memory handling"),
     osx_rpath_install_name: bool = (false, parse_bool, [TRACKED],
         "pass `-install_name @rpath/...` to the macOS linker (default: no)"),
+    outline_cold_panics: bool = (false, parse_bool, [TRACKED],
+        "merge panics with the same constant message and location into a single call to a \
+        shared cold function (default: no)"),
     packed_bundled_libs: bool = (false, parse_bool, [TRACKED],
         "change rlib format to store native libraries as archives"),
     panic_abort_tests: bool = (false, parse_bool, [TRACKED],
@@ -1485,10 +1666,14 @@ pub(crate) fn parse_proc_macro_execution_strategy(
         "whether to use the PLT when calling into shared libraries;
         only has effect for PIC code on systems with ELF binaries
         (default: PLT is disabled if full relro is enabled)"),
-    polonius: bool = (false, parse_bool, [TRACKED],
-        "enable polonius-based borrow-checker (default: no)"),
+    polonius: Polonius = (Polonius::Off, parse_polonius, [TRACKED],
+        "enable polonius-based borrow-checker: `full` uses only polonius, `hybrid` only checks \
+        the borrow conflicts found by NLL with polonius (default: off)"),
     polymorphize: bool = (false, parse_bool, [TRACKED],
           "perform polymorphization analysis"),
+    polymorphize_by_layout: bool = (false, parse_bool, [TRACKED],
+        "with `-Z polymorphize`, also share instances between all types with the same layout \
+        if they only use a type parameter through its layout (default: no)"),
     pre_link_arg: (/* redirected to pre_link_args */) = ((), parse_string_push, [UNTRACKED],
         "a single extra argument to prepend the linker invocation (can be used several times)"),
     pre_link_args: Vec<String> = (Vec::new(), parse_list, [UNTRACKED],
@@ -1560,6 +1745,11 @@ pub(crate) fn parse_proc_macro_execution_strategy(
         for example: `-Z self-profile-events=default,query-keys`
         all options: none, all, default, generic-activity, query-provider, query-cache-hit
                      query-blocked, incr-cache-load, incr-result-hashing, query-keys, function-args, args, llvm, artifact-sizes"),
+    self_profile_summary: Option<SelfProfileSummaryFormat> = (None, parse_self_profile_summary, [UNTRACKED],
+        "print the items with the highest self time in `typeck`, `mir_borrowck`, `optimized_mir` \
+        and building the backend IR, as a `table` (default) or as `json`"),
+    self_profile_summary_items: usize = (10, parse_number, [UNTRACKED],
+        "number of items printed per activity by `-Z self-profile-summary` (default: 10)"),
     share_generics: Option<bool> = (None, parse_opt_bool, [TRACKED],
         "make the current crate share its generic instantiations"),
     show_span: Option<String> = (None, parse_opt_string, [TRACKED],
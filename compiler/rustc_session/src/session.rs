@@ -1,15 +1,20 @@
 use crate::cgu_reuse_tracker::CguReuseTracker;
 use crate::code_stats::CodeStats;
+use crate::const_eval_profile::ConstEvalProfile;
 pub use crate::code_stats::{DataTypeKind, FieldInfo, SizeKind, VariantInfo};
+pub use crate::const_eval_profile::{ConstCallProfile, ConstEvalCost, ConstFnProfile};
 use crate::config::Input;
-use crate::config::{self, CrateType, InstrumentCoverage, OptLevel, OutputType, SwitchWithOptPath};
+use crate::config::{
+    self, CallGraphProfileError, CrateType, InstrumentCoverage, OptLevel, OutputType,
+    SwitchWithOptPath,
+};
 use crate::errors::{
     BranchProtectionRequiresAArch64, CannotEnableCrtStaticLinux, CannotMixAndMatchSanitizers,
-    LinkerPluginToWindowsNotSupported, NotCircumventFeature, ProfileSampleUseFileDoesNotExist,
-    ProfileUseFileDoesNotExist, SanitizerCfiEnabled, SanitizerNotSupported, SanitizersNotSupported,
-    SkippingConstChecks, SplitDebugInfoUnstablePlatform, StackProtectorNotSupportedForTarget,
-    TargetRequiresUnwindTables, UnleashedFeatureHelp, UnstableVirtualFunctionElimination,
-    UnsupportedDwarfVersion,
+    CouldntReadCallGraphProfile, LinkerPluginToWindowsNotSupported, MalformedCallGraphProfile,
+    NotCircumventFeature, ProfileSampleUseFileDoesNotExist, ProfileUseFileDoesNotExist,
+    SanitizerCfiEnabled, SanitizerNotSupported, SanitizersNotSupported, SkippingConstChecks,
+    SplitDebugInfoUnstablePlatform, StackProtectorNotSupportedForTarget, TargetRequiresUnwindTables,
+    UnleashedFeatureHelp, UnstableVirtualFunctionElimination, UnsupportedDwarfVersion,
 };
 use crate::parse::{add_feature_diagnostics, ParseSess};
 use crate::search_paths::{PathKind, SearchPath};
@@ -28,6 +33,7 @@
 use rustc_errors::emitter::{Emitter, EmitterWriter, HumanReadableErrorType};
 use rustc_errors::json::JsonEmitter;
 use rustc_errors::registry::Registry;
+use rustc_errors::snapshot::{ResolvedDiagnostic, SnapshotPhase};
 use rustc_errors::{
     error_code, fallback_fluent_bundle, DiagnosticBuilder, DiagnosticId, DiagnosticMessage,
     ErrorGuaranteed, FluentBundle, IntoDiagnostic, LazyFallbackBundle, MultiSpan, Noted,
@@ -145,6 +151,11 @@ pub struct CompilerIO {
     pub temps_dir: Option<PathBuf>,
 }
 
+/// Receives the diagnostics emitted during a phase of compilation, see
+/// [`Session::snapshot_diagnostics`].
+pub type DiagnosticSnapshotCallback =
+    Box<dyn Fn(SnapshotPhase, Vec<ResolvedDiagnostic>) + Send + Sync>;
+
 /// Represents the data associated with a compilation
 /// session for a single crate.
 pub struct Session {
@@ -182,6 +193,13 @@ pub struct Session {
     /// Data about code being compiled, gathered during compilation.
     pub code_stats: CodeStats,
 
+    /// The cost of const evaluation, gathered with `-Z const-eval-profile`.
+    pub const_eval_profile: ConstEvalProfile,
+
+    /// The obligations whose trait proof tree `-Z dump-trait-proof-tree` already printed, as
+    /// the item they come from and their predicate.
+    pub dumped_trait_proof_trees: Lock<FxHashSet<(String, String)>>,
+
     /// Tracks fuel info if `-zfuel=crate=n` is specified.
     optimization_fuel: Lock<OptimizationFuel>,
 
@@ -217,6 +235,13 @@ pub struct Session {
 
     /// Set of enabled features for the current target, including unstable ones.
     pub unstable_target_features: FxHashSet<Symbol>,
+
+    /// Set by drivers that want a snapshot of the emitted diagnostics after parsing,
+    /// expansion and analysis.
+    pub diagnostic_snapshot: Option<DiagnosticSnapshotCallback>,
+
+    /// The phases that `diagnostic_snapshot` was already called for.
+    diagnostic_snapshot_phases: Lock<Vec<SnapshotPhase>>,
 }
 
 pub struct PerfStats {
@@ -269,10 +294,31 @@ fn check_miri_unleashed_features(&self) {
     /// Invoked all the way at the end to finish off diagnostics printing.
     pub fn finish_diagnostics(&self, registry: &Registry) {
         self.check_miri_unleashed_features();
+        if self.opts.unstable_opts.const_eval_profile {
+            // Printed here rather than after codegen, since running into the step limit is
+            // usually the reason to profile const evaluation in the first place.
+            self.const_eval_profile.print();
+        }
         self.diagnostic().print_error_count(registry);
         self.emit_future_breakage();
     }
 
+    /// Passes the diagnostics emitted since the previous snapshot to the driver's
+    /// `diagnostic_snapshot` callback, if there is one and it wasn't called for `phase` yet.
+    ///
+    /// Must not be called from within a query, as the callback may run arbitrary code.
+    pub fn snapshot_diagnostics(&self, phase: SnapshotPhase) {
+        let Some(callback) = &self.diagnostic_snapshot else { return };
+        {
+            let mut phases = self.diagnostic_snapshot_phases.lock();
+            if phases.contains(&phase) {
+                return;
+            }
+            phases.push(phase);
+        }
+        callback(phase, self.diagnostic().take_diagnostic_snapshot());
+    }
+
     fn emit_future_breakage(&self) {
         if !self.opts.json_future_incompat {
             return;
@@ -1167,6 +1213,13 @@ pub fn threads(&self) -> usize {
         self.opts.unstable_opts.threads
     }
 
+    /// Returns whether diagnostics emitted from parallel sections are put into the order in
+    /// which a single-threaded compiler would have emitted them. Only then do queries have to
+    /// keep track of where they were requested from.
+    pub fn sequential_diagnostic_order(&self) -> bool {
+        self.threads() > 1
+    }
+
     /// Returns the number of codegen units that should be used for this
     /// compilation
     pub fn codegen_units(&self) -> usize {
@@ -1275,6 +1328,10 @@ fn default_emitter(
 ) -> Box<dyn Emitter + sync::Send> {
     let macro_backtrace = sopts.unstable_opts.macro_backtrace;
     let track_diagnostics = sopts.unstable_opts.track_diagnostics;
+    // See https://no-color.org: a non-empty `NO_COLOR` opts out of any optional coloring.
+    let highlight_snippets = sopts.unstable_opts.highlight_snippets
+        && std::env::var_os("NO_COLOR").map_or(true, |no_color| no_color.is_empty());
+    let syntax_highlighting = highlight_snippets.then_some(sopts.edition);
     match sopts.error_format {
         config::ErrorOutputType::HumanReadable(kind) => {
             let (short, color_config) = kind.unzip();
@@ -1300,7 +1357,11 @@ fn default_emitter(
                     macro_backtrace,
                     track_diagnostics,
                 );
-                Box::new(emitter.ui_testing(sopts.unstable_opts.ui_testing))
+                Box::new(
+                    emitter
+                        .ui_testing(sopts.unstable_opts.ui_testing)
+                        .syntax_highlighting(syntax_highlighting),
+                )
             }
         }
         config::ErrorOutputType::Json { pretty, json_rendered } => Box::new(
@@ -1315,7 +1376,8 @@ fn default_emitter(
                 macro_backtrace,
                 track_diagnostics,
             )
-            .ui_testing(sopts.unstable_opts.ui_testing),
+            .ui_testing(sopts.unstable_opts.ui_testing)
+            .syntax_highlighting(syntax_highlighting),
         ),
     }
 }
@@ -1323,7 +1385,7 @@ fn default_emitter(
 // JUSTIFICATION: literally session construction
 #[allow(rustc::bad_opt_access)]
 pub fn build_session(
-    sopts: config::Options,
+    mut sopts: config::Options,
     io: CompilerIO,
     bundle: Option<Lrc<rustc_errors::FluentBundle>>,
     registry: rustc_errors::registry::Registry,
@@ -1376,10 +1438,34 @@ pub fn build_session(
     );
     let emitter = default_emitter(&sopts, registry, source_map.clone(), bundle, fallback_bundle);
 
-    let span_diagnostic = rustc_errors::Handler::with_emitter_and_flags(
-        emitter,
-        sopts.unstable_opts.diagnostic_handler_flags(can_emit_warnings),
-    );
+    let mut handler_flags = sopts.unstable_opts.diagnostic_handler_flags(can_emit_warnings);
+    // Tools consuming JSON expect every diagnostic separately, so only group human readable
+    // output.
+    handler_flags.diagnostic_grouping = match sopts.error_format {
+        config::ErrorOutputType::HumanReadable(_) => sopts.unstable_opts.diagnostic_grouping,
+        config::ErrorOutputType::Json { .. } => false,
+    };
+    handler_flags.warnings_per_lint_cap = sopts.warnings_per_lint_cap;
+    // Emit diagnostics in the same order no matter how many threads are used.
+    // Keep in sync with `Session::sequential_diagnostic_order`.
+    handler_flags.sequential_diagnostic_order = sopts.unstable_opts.threads > 1;
+    let span_diagnostic = rustc_errors::Handler::with_emitter_and_flags(emitter, handler_flags);
+
+    // The call-graph profile is read only once, here, so that the edges that are hashed into
+    // the dep-tracking hash are the ones that the partitioning uses.
+    if let Some(profile) = &mut sopts.unstable_opts.cgu_call_graph_profile {
+        match profile.load() {
+            Ok(()) => {}
+            Err(CallGraphProfileError::Io(error)) => {
+                let path = &profile.path;
+                span_diagnostic
+                    .emit_err(CouldntReadCallGraphProfile { path, error: error.to_string() });
+            }
+            Err(CallGraphProfileError::Malformed(line)) => {
+                span_diagnostic.emit_err(MalformedCallGraphProfile { path: &profile.path, line });
+            }
+        }
+    }
 
     let self_profiler = if let SwitchWithOptPath::Enabled(ref d) = sopts.unstable_opts.self_profile
     {
@@ -1429,7 +1515,11 @@ pub fn build_session(
         CguReuseTracker::new_disabled()
     };
 
-    let prof = SelfProfilerRef::new(self_profiler, sopts.unstable_opts.time_passes);
+    let prof = SelfProfilerRef::new(
+        self_profiler,
+        sopts.unstable_opts.time_passes,
+        sopts.unstable_opts.self_profile_summary.is_some(),
+    );
 
     let ctfe_backtrace = Lock::new(match env::var("RUSTC_CTFE_BACKTRACE") {
         Ok(ref val) if val == "immediate" => CtfeBacktrace::Immediate,
@@ -1462,6 +1552,8 @@ pub fn build_session(
             normalize_projection_ty: AtomicUsize::new(0),
         },
         code_stats: Default::default(),
+        const_eval_profile: Default::default(),
+        dumped_trait_proof_trees: Default::default(),
         optimization_fuel,
         print_fuel,
         jobserver: jobserver::client(),
@@ -1471,6 +1563,8 @@ pub fn build_session(
         asm_arch,
         target_features: FxHashSet::default(),
         unstable_target_features: FxHashSet::default(),
+        diagnostic_snapshot: None,
+        diagnostic_snapshot_phases: Lock::new(Vec::new()),
     };
 
     validate_commandline_args_with_session_available(&sess);
@@ -1651,6 +1745,14 @@ pub fn early_error(output: config::ErrorOutputType, msg: &str) -> ! {
     early_error_handler(output).struct_fatal(msg).emit()
 }
 
+#[allow(rustc::untranslatable_diagnostic)]
+#[allow(rustc::diagnostic_outside_of_impl)]
+pub fn early_error_with_help(output: config::ErrorOutputType, msg: &str, help: &str) -> ! {
+    let mut err = early_error_handler(output).struct_fatal(msg);
+    err.help(help);
+    err.emit()
+}
+
 #[allow(rustc::untranslatable_diagnostic)]
 #[allow(rustc::diagnostic_outside_of_impl)]
 pub fn early_warn(output: config::ErrorOutputType, msg: &str) {
@@ -4,7 +4,8 @@
 //!
 //! # Note
 //!
-//! This API is still completely unstable and subject to change.
+//! This API is still completely unstable and subject to change, except for the Stable MIR model
+//! in [`stable_mir`], which is versioned on its own.
 
 #![doc(
     html_root_url = "https://doc.rust-lang.org/nightly/nightly-rustc/",
@@ -16,4 +17,8 @@
 
 pub mod mir;
 
+pub mod rustc_internal;
+pub mod rustc_smir;
+pub mod stable_mir;
+
 pub mod very_unstable;
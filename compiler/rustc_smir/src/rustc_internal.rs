@@ -0,0 +1,49 @@
+//! Entry point for tools that analyze the Stable MIR of a crate.
+//!
+//! This is the only part of the crate that drives the compiler: tools call [`run_analysis`]
+//! from their `main` instead of implementing `rustc_driver::Callbacks` themselves.
+
+use crate::rustc_smir;
+use crate::stable_mir::CrateItem;
+use crate::very_unstable::driver::{Callbacks, Compilation, RunCompiler};
+use crate::very_unstable::interface::interface::{self, Compiler};
+use crate::very_unstable::interface::Queries;
+
+/// Runs the compiler with the command-line arguments `args`, where `args[0]` is the name of the
+/// program as usual, and calls `callback` with the Stable MIR of every local item with a body.
+///
+/// Items are converted one at a time, right before they are passed to `callback`. Compilation
+/// stops after analysis, so nothing is codegened or written out, and if analysis reports any
+/// errors, `callback` isn't called at all.
+pub fn run_analysis<F>(args: &[String], callback: F) -> interface::Result<()>
+where
+    F: FnMut(&CrateItem) + Send,
+{
+    let mut callbacks = StableMirCallbacks { callback };
+    RunCompiler::new(args, &mut callbacks).run()
+}
+
+struct StableMirCallbacks<F> {
+    callback: F,
+}
+
+impl<F> Callbacks for StableMirCallbacks<F>
+where
+    F: FnMut(&CrateItem) + Send,
+{
+    fn after_analysis<'tcx>(
+        &mut self,
+        compiler: &Compiler,
+        queries: &'tcx Queries<'tcx>,
+    ) -> Compilation {
+        compiler.session().abort_if_errors();
+        queries.global_ctxt().unwrap().enter(|tcx| {
+            for &def_id in tcx.mir_keys(()) {
+                if let Some(item) = rustc_smir::crate_item(tcx, def_id) {
+                    (self.callback)(&item);
+                }
+            }
+        });
+        Compilation::Stop
+    }
+}
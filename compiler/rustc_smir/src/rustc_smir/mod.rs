@@ -0,0 +1,851 @@
+//! Module that implements the rustc side of Stable MIR.
+//!
+//! This module is responsible for building Stable MIR components from internal components,
+//! see [`Stable`].
+
+use crate::stable_mir::{self, mir as smir, ty as sty};
+use crate::very_unstable::ast;
+use crate::very_unstable::hir::def::DefKind;
+use crate::very_unstable::hir::def_id::{DefId, LocalDefId, LOCAL_CRATE};
+use crate::very_unstable::hir::{self, Movability};
+use crate::very_unstable::middle;
+use crate::very_unstable::middle::mir::interpret::{ConstValue, Scalar};
+use crate::very_unstable::middle::mir::{self, Mutability};
+use crate::very_unstable::middle::ty::adjustment::PointerCast;
+use crate::very_unstable::middle::ty::print::with_no_trimmed_paths;
+use crate::very_unstable::middle::ty::{self, GenericArgKind, ScalarInt, SubstsRef, TyCtxt};
+use crate::very_unstable::span::Span;
+
+/// Builds the Stable MIR of all local items with a body.
+pub fn crate_model(tcx: TyCtxt<'_>) -> stable_mir::Crate {
+    stable_mir::Crate {
+        version: stable_mir::VERSION,
+        name: tcx.crate_name(LOCAL_CRATE).to_string(),
+        items: tcx.mir_keys(()).iter().filter_map(|&def_id| crate_item(tcx, def_id)).collect(),
+    }
+}
+
+/// Builds the Stable MIR of the local item `def_id`, or returns `None` if it has no body.
+pub fn crate_item(tcx: TyCtxt<'_>, def_id: LocalDefId) -> Option<stable_mir::CrateItem> {
+    let def_id = def_id.to_def_id();
+    let kind = match tcx.def_kind(def_id) {
+        DefKind::Fn => stable_mir::ItemKind::Fn,
+        DefKind::AssocFn => stable_mir::ItemKind::AssocFn,
+        DefKind::Closure => stable_mir::ItemKind::Closure,
+        DefKind::Generator => stable_mir::ItemKind::Generator,
+        DefKind::Const => stable_mir::ItemKind::Const,
+        DefKind::AssocConst => stable_mir::ItemKind::AssocConst,
+        DefKind::AnonConst => stable_mir::ItemKind::AnonConst,
+        DefKind::InlineConst => stable_mir::ItemKind::InlineConst,
+        DefKind::Static(mutability) => stable_mir::ItemKind::Static(mutability.stable(tcx)),
+        DefKind::Ctor(..) => stable_mir::ItemKind::Ctor,
+        _ => return None,
+    };
+
+    // This is the MIR used for codegen of functions, and the MIR used for const evaluation
+    // of constants and statics.
+    let body = tcx.instance_mir(ty::InstanceDef::Item(ty::WithOptConstParam::unknown(def_id)));
+    Some(stable_mir::CrateItem {
+        def_id: stable_def_id(tcx, def_id),
+        kind,
+        span: tcx.def_span(def_id).stable(tcx),
+        generics: tcx.generics_of(def_id).stable(tcx),
+        body: body.stable(tcx),
+        promoted: tcx.promoted_mir(def_id).iter().map(|body| body.stable(tcx)).collect(),
+    })
+}
+
+/// Builds the Stable MIR of a body.
+pub fn body<'tcx>(tcx: TyCtxt<'tcx>, body: &mir::Body<'tcx>) -> smir::Body {
+    body.stable(tcx)
+}
+
+fn stable_def_id(tcx: TyCtxt<'_>, def_id: DefId) -> stable_mir::DefId {
+    let (hash_lo, hash_hi) = tcx.def_path_hash(def_id).0.as_value();
+    stable_mir::DefId {
+        krate: tcx.crate_name(def_id.krate).to_string(),
+        path: with_no_trimmed_paths!(tcx.def_path_str(def_id)),
+        hash: [hash_lo, hash_hi],
+    }
+}
+
+fn stable_scalar(int: ScalarInt) -> sty::ConstValue {
+    sty::ConstValue::Scalar { bits: int.assert_bits(int.size()), size: int.size().bytes() }
+}
+
+/// Converts an internal compiler item into its Stable MIR counterpart.
+pub trait Stable<'tcx> {
+    /// The Stable MIR representation of the item.
+    type T;
+
+    fn stable(&self, tcx: TyCtxt<'tcx>) -> Self::T;
+}
+
+impl<'tcx> Stable<'tcx> for Span {
+    type T = stable_mir::Span;
+    fn stable(&self, tcx: TyCtxt<'tcx>) -> Self::T {
+        let source_map = tcx.sess.source_map();
+        let lo = source_map.lookup_char_pos(self.lo());
+        let hi = source_map.lookup_char_pos(self.hi());
+        stable_mir::Span {
+            file: lo.file.name.prefer_remapped().to_string_lossy().into_owned(),
+            lo: stable_mir::LineColumn { line: lo.line, col: lo.col.0 },
+            hi: stable_mir::LineColumn { line: hi.line, col: hi.col.0 },
+        }
+    }
+}
+
+impl<'tcx> Stable<'tcx> for mir::Body<'tcx> {
+    type T = smir::Body;
+    fn stable(&self, tcx: TyCtxt<'tcx>) -> Self::T {
+        smir::Body {
+            blocks: self
+                .basic_blocks
+                .iter()
+                .map(|block| smir::BasicBlock {
+                    statements: block.statements.iter().map(|stmt| stmt.stable(tcx)).collect(),
+                    terminator: block.terminator().stable(tcx),
+                    is_cleanup: block.is_cleanup,
+                })
+                .collect(),
+            locals: self
+                .local_decls
+                .iter()
+                .map(|decl| smir::LocalDecl {
+                    ty: decl.ty.stable(tcx),
+                    mutability: decl.mutability.stable(tcx),
+                    span: decl.source_info.span.stable(tcx),
+                })
+                .collect(),
+            arg_count: self.arg_count,
+            var_debug_info: self.var_debug_info.iter().map(|info| info.stable(tcx)).collect(),
+            span: self.span.stable(tcx),
+        }
+    }
+}
+
+impl<'tcx> Stable<'tcx> for mir::VarDebugInfo<'tcx> {
+    type T = smir::VarDebugInfo;
+    fn stable(&self, tcx: TyCtxt<'tcx>) -> Self::T {
+        let value = match &self.value {
+            mir::VarDebugInfoContents::Place(place) => {
+                smir::VarDebugInfoContents::Place(place.stable(tcx))
+            }
+            mir::VarDebugInfoContents::Const(constant) => {
+                smir::VarDebugInfoContents::Const(constant.stable(tcx))
+            }
+            mir::VarDebugInfoContents::Composite { ty, fragments } => {
+                smir::VarDebugInfoContents::Composite {
+                    ty: ty.stable(tcx),
+                    fragments: fragments
+                        .iter()
+                        .map(|fragment| {
+                            let projection =
+                                fragment.projection.iter().map(|elem| elem.stable(tcx)).collect();
+                            (projection, fragment.contents.stable(tcx))
+                        })
+                        .collect(),
+                }
+            }
+        };
+        smir::VarDebugInfo {
+            name: self.name.to_string(),
+            span: self.source_info.span.stable(tcx),
+            value,
+        }
+    }
+}
+
+impl<'tcx> Stable<'tcx> for mir::Statement<'tcx> {
+    type T = smir::Statement;
+    fn stable(&self, tcx: TyCtxt<'tcx>) -> Self::T {
+        smir::Statement { kind: self.kind.stable(tcx), span: self.source_info.span.stable(tcx) }
+    }
+}
+
+impl<'tcx> Stable<'tcx> for mir::StatementKind<'tcx> {
+    type T = smir::StatementKind;
+    fn stable(&self, tcx: TyCtxt<'tcx>) -> Self::T {
+        use mir::StatementKind::*;
+        match self {
+            Assign(assign) => {
+                let (place, rvalue) = &**assign;
+                smir::StatementKind::Assign(place.stable(tcx), rvalue.stable(tcx))
+            }
+            FakeRead(fake_read) => {
+                let (cause, place) = &**fake_read;
+                smir::StatementKind::FakeRead(cause.stable(tcx), place.stable(tcx))
+            }
+            SetDiscriminant { place, variant_index } => smir::StatementKind::SetDiscriminant {
+                place: place.stable(tcx),
+                variant_index: variant_index.as_usize(),
+            },
+            Deinit(place) => smir::StatementKind::Deinit(place.stable(tcx)),
+            StorageLive(local) => smir::StatementKind::StorageLive(local.as_usize()),
+            StorageDead(local) => smir::StatementKind::StorageDead(local.as_usize()),
+            Retag(kind, place) => smir::StatementKind::Retag(kind.stable(tcx), place.stable(tcx)),
+            AscribeUserType(ascription, _) => {
+                smir::StatementKind::AscribeUserType(ascription.0.stable(tcx))
+            }
+            Coverage(_) => smir::StatementKind::Coverage,
+            Intrinsic(intrinsic) => smir::StatementKind::Intrinsic(match &**intrinsic {
+                mir::NonDivergingIntrinsic::Assume(op) => {
+                    smir::NonDivergingIntrinsic::Assume(op.stable(tcx))
+                }
+                mir::NonDivergingIntrinsic::CopyNonOverlapping(copy) => {
+                    smir::NonDivergingIntrinsic::CopyNonOverlapping {
+                        src: copy.src.stable(tcx),
+                        dst: copy.dst.stable(tcx),
+                        count: copy.count.stable(tcx),
+                    }
+                }
+            }),
+            Nop => smir::StatementKind::Nop,
+        }
+    }
+}
+
+impl<'tcx> Stable<'tcx> for mir::FakeReadCause {
+    type T = smir::FakeReadCause;
+    fn stable(&self, _: TyCtxt<'tcx>) -> Self::T {
+        use mir::FakeReadCause::*;
+        match self {
+            ForMatchGuard => smir::FakeReadCause::ForMatchGuard,
+            ForMatchedPlace(_) => smir::FakeReadCause::ForMatchedPlace,
+            ForGuardBinding => smir::FakeReadCause::ForGuardBinding,
+            ForLet(_) => smir::FakeReadCause::ForLet,
+            ForIndex => smir::FakeReadCause::ForIndex,
+        }
+    }
+}
+
+impl<'tcx> Stable<'tcx> for mir::RetagKind {
+    type T = smir::RetagKind;
+    fn stable(&self, _: TyCtxt<'tcx>) -> Self::T {
+        use mir::RetagKind::*;
+        match self {
+            FnEntry => smir::RetagKind::FnEntry,
+            TwoPhase => smir::RetagKind::TwoPhase,
+            Raw => smir::RetagKind::Raw,
+            Default => smir::RetagKind::Default,
+        }
+    }
+}
+
+impl<'tcx> Stable<'tcx> for mir::Terminator<'tcx> {
+    type T = smir::Terminator;
+    fn stable(&self, tcx: TyCtxt<'tcx>) -> Self::T {
+        smir::Terminator { kind: self.kind.stable(tcx), span: self.source_info.span.stable(tcx) }
+    }
+}
+
+impl<'tcx> Stable<'tcx> for mir::TerminatorKind<'tcx> {
+    type T = smir::TerminatorKind;
+    fn stable(&self, tcx: TyCtxt<'tcx>) -> Self::T {
+        use mir::TerminatorKind::*;
+        let bb = |bb: &mir::BasicBlock| bb.as_usize();
+        match self {
+            Goto { target } => smir::TerminatorKind::Goto { target: bb(target) },
+            SwitchInt { discr, targets } => smir::TerminatorKind::SwitchInt {
+                discr: discr.stable(tcx),
+                targets: targets.iter().map(|(value, target)| (value, bb(&target))).collect(),
+                otherwise: bb(&targets.otherwise()),
+            },
+            Resume => smir::TerminatorKind::Resume,
+            Abort => smir::TerminatorKind::Abort,
+            Return => smir::TerminatorKind::Return,
+            Unreachable => smir::TerminatorKind::Unreachable,
+            Drop { place, target, unwind } => smir::TerminatorKind::Drop {
+                place: place.stable(tcx),
+                target: bb(target),
+                unwind: unwind.as_ref().map(bb),
+            },
+            DropAndReplace { place, value, target, unwind } => {
+                smir::TerminatorKind::DropAndReplace {
+                    place: place.stable(tcx),
+                    value: value.stable(tcx),
+                    target: bb(target),
+                    unwind: unwind.as_ref().map(bb),
+                }
+            }
+            Call { func, args, destination, target, cleanup, .. } => smir::TerminatorKind::Call {
+                func: func.stable(tcx),
+                args: args.iter().map(|arg| arg.stable(tcx)).collect(),
+                destination: destination.stable(tcx),
+                target: target.as_ref().map(bb),
+                cleanup: cleanup.as_ref().map(bb),
+            },
+            Assert { cond, expected, msg, target, cleanup } => smir::TerminatorKind::Assert {
+                cond: cond.stable(tcx),
+                expected: *expected,
+                msg: msg.stable(tcx),
+                target: bb(target),
+                cleanup: cleanup.as_ref().map(bb),
+            },
+            Yield { value, resume, resume_arg, drop } => smir::TerminatorKind::Yield {
+                value: value.stable(tcx),
+                resume: bb(resume),
+                resume_arg: resume_arg.stable(tcx),
+                drop: drop.as_ref().map(bb),
+            },
+            GeneratorDrop => smir::TerminatorKind::GeneratorDrop,
+            FalseEdge { real_target, imaginary_target } => smir::TerminatorKind::FalseEdge {
+                real_target: bb(real_target),
+                imaginary_target: bb(imaginary_target),
+            },
+            FalseUnwind { real_target, unwind } => smir::TerminatorKind::FalseUnwind {
+                real_target: bb(real_target),
+                unwind: unwind.as_ref().map(bb),
+            },
+            InlineAsm { template, operands, destination, cleanup, .. } => {
+                smir::TerminatorKind::InlineAsm {
+                    template: ast::InlineAsmTemplatePiece::to_string(template),
+                    operands: operands.iter().map(|op| op.stable(tcx)).collect(),
+                    destination: destination.as_ref().map(bb),
+                    cleanup: cleanup.as_ref().map(bb),
+                }
+            }
+        }
+    }
+}
+
+impl<'tcx> Stable<'tcx> for mir::AssertMessage<'tcx> {
+    type T = smir::AssertMessage;
+    fn stable(&self, tcx: TyCtxt<'tcx>) -> Self::T {
+        use mir::AssertKind::*;
+        match self {
+            BoundsCheck { len, index } => smir::AssertMessage::BoundsCheck {
+                len: len.stable(tcx),
+                index: index.stable(tcx),
+            },
+            Overflow(op, lhs, rhs) => {
+                smir::AssertMessage::Overflow(op.stable(tcx), lhs.stable(tcx), rhs.stable(tcx))
+            }
+            OverflowNeg(op) => smir::AssertMessage::OverflowNeg(op.stable(tcx)),
+            DivisionByZero(op) => smir::AssertMessage::DivisionByZero(op.stable(tcx)),
+            RemainderByZero(op) => smir::AssertMessage::RemainderByZero(op.stable(tcx)),
+            ResumedAfterReturn(kind) => smir::AssertMessage::ResumedAfterReturn(kind.stable(tcx)),
+            ResumedAfterPanic(kind) => smir::AssertMessage::ResumedAfterPanic(kind.stable(tcx)),
+        }
+    }
+}
+
+impl<'tcx> Stable<'tcx> for hir::GeneratorKind {
+    type T = smir::GeneratorKind;
+    fn stable(&self, _: TyCtxt<'tcx>) -> Self::T {
+        match self {
+            hir::GeneratorKind::Async(_) => smir::GeneratorKind::Async,
+            hir::GeneratorKind::Gen => smir::GeneratorKind::Gen,
+        }
+    }
+}
+
+impl<'tcx> Stable<'tcx> for mir::InlineAsmOperand<'tcx> {
+    type T = smir::InlineAsmOperand;
+    fn stable(&self, tcx: TyCtxt<'tcx>) -> Self::T {
+        use mir::InlineAsmOperand::*;
+        let (in_value, out_place) = match self {
+            In { value, .. } => (Some(value.stable(tcx)), None),
+            Out { place, .. } => (None, place.as_ref().map(|place| place.stable(tcx))),
+            InOut { in_value, out_place, .. } => {
+                (Some(in_value.stable(tcx)), out_place.as_ref().map(|place| place.stable(tcx)))
+            }
+            Const { .. } | SymFn { .. } | SymStatic { .. } => (None, None),
+        };
+        smir::InlineAsmOperand { in_value, out_place, raw_repr: format!("{self:?}") }
+    }
+}
+
+impl<'tcx> Stable<'tcx> for mir::Place<'tcx> {
+    type T = smir::Place;
+    fn stable(&self, tcx: TyCtxt<'tcx>) -> Self::T {
+        smir::Place {
+            local: self.local.as_usize(),
+            projection: self.projection.iter().map(|elem| elem.stable(tcx)).collect(),
+        }
+    }
+}
+
+impl<'tcx> Stable<'tcx> for mir::PlaceElem<'tcx> {
+    type T = smir::ProjectionElem;
+    fn stable(&self, tcx: TyCtxt<'tcx>) -> Self::T {
+        use mir::ProjectionElem::*;
+        match *self {
+            Deref => smir::ProjectionElem::Deref,
+            Field(field, ty) => smir::ProjectionElem::Field(field.as_usize(), ty.stable(tcx)),
+            Index(local) => smir::ProjectionElem::Index(local.as_usize()),
+            ConstantIndex { offset, min_length, from_end } => {
+                smir::ProjectionElem::ConstantIndex { offset, min_length, from_end }
+            }
+            Subslice { from, to, from_end } => {
+                smir::ProjectionElem::Subslice { from, to, from_end }
+            }
+            Downcast(_, variant) => smir::ProjectionElem::Downcast(variant.as_usize()),
+            OpaqueCast(ty) => smir::ProjectionElem::OpaqueCast(ty.stable(tcx)),
+        }
+    }
+}
+
+impl<'tcx> Stable<'tcx> for mir::Operand<'tcx> {
+    type T = smir::Operand;
+    fn stable(&self, tcx: TyCtxt<'tcx>) -> Self::T {
+        match self {
+            mir::Operand::Copy(place) => smir::Operand::Copy(place.stable(tcx)),
+            mir::Operand::Move(place) => smir::Operand::Move(place.stable(tcx)),
+            mir::Operand::Constant(constant) => smir::Operand::Constant(constant.stable(tcx)),
+        }
+    }
+}
+
+impl<'tcx> Stable<'tcx> for mir::Constant<'tcx> {
+    type T = smir::Constant;
+    fn stable(&self, tcx: TyCtxt<'tcx>) -> Self::T {
+        smir::Constant { span: self.span.stable(tcx), literal: self.literal.stable(tcx) }
+    }
+}
+
+impl<'tcx> Stable<'tcx> for mir::Rvalue<'tcx> {
+    type T = smir::Rvalue;
+    fn stable(&self, tcx: TyCtxt<'tcx>) -> Self::T {
+        use mir::Rvalue::*;
+        match self {
+            Use(op) => smir::Rvalue::Use(op.stable(tcx)),
+            Repeat(op, len) => smir::Rvalue::Repeat(op.stable(tcx), len.stable(tcx)),
+            Ref(region, kind, place) => {
+                smir::Rvalue::Ref(region.stable(tcx), kind.stable(tcx), place.stable(tcx))
+            }
+            ThreadLocalRef(def_id) => smir::Rvalue::ThreadLocalRef(stable_def_id(tcx, *def_id)),
+            AddressOf(mutability, place) => {
+                smir::Rvalue::AddressOf(mutability.stable(tcx), place.stable(tcx))
+            }
+            Len(place) => smir::Rvalue::Len(place.stable(tcx)),
+            Cast(kind, op, ty) => {
+                smir::Rvalue::Cast(kind.stable(tcx), op.stable(tcx), ty.stable(tcx))
+            }
+            BinaryOp(bin_op, ops) => {
+                let (lhs, rhs) = &**ops;
+                smir::Rvalue::BinaryOp(bin_op.stable(tcx), lhs.stable(tcx), rhs.stable(tcx))
+            }
+            CheckedBinaryOp(bin_op, ops) => {
+                let (lhs, rhs) = &**ops;
+                smir::Rvalue::CheckedBinaryOp(bin_op.stable(tcx), lhs.stable(tcx), rhs.stable(tcx))
+            }
+            NullaryOp(null_op, ty) => smir::Rvalue::NullaryOp(null_op.stable(tcx), ty.stable(tcx)),
+            UnaryOp(un_op, op) => smir::Rvalue::UnaryOp(un_op.stable(tcx), op.stable(tcx)),
+            Discriminant(place) => smir::Rvalue::Discriminant(place.stable(tcx)),
+            Aggregate(kind, ops) => smir::Rvalue::Aggregate(
+                kind.stable(tcx),
+                ops.iter().map(|op| op.stable(tcx)).collect(),
+            ),
+            ShallowInitBox(op, ty) => smir::Rvalue::ShallowInitBox(op.stable(tcx), ty.stable(tcx)),
+            CopyForDeref(place) => smir::Rvalue::CopyForDeref(place.stable(tcx)),
+        }
+    }
+}
+
+impl<'tcx> Stable<'tcx> for mir::AggregateKind<'tcx> {
+    type T = smir::AggregateKind;
+    fn stable(&self, tcx: TyCtxt<'tcx>) -> Self::T {
+        match self {
+            mir::AggregateKind::Array(ty) => smir::AggregateKind::Array(ty.stable(tcx)),
+            mir::AggregateKind::Tuple => smir::AggregateKind::Tuple,
+            mir::AggregateKind::Adt(def_id, variant, substs, _, active_field) => {
+                smir::AggregateKind::Adt(
+                    stable_def_id(tcx, *def_id),
+                    variant.as_usize(),
+                    substs.stable(tcx),
+                    *active_field,
+                )
+            }
+            mir::AggregateKind::Closure(def_id, substs) => smir::AggregateKind::Closure(
+                stable_def_id(tcx, def_id.to_def_id()),
+                substs.stable(tcx),
+            ),
+            mir::AggregateKind::Generator(def_id, substs, movability) => {
+                smir::AggregateKind::Generator(
+                    stable_def_id(tcx, def_id.to_def_id()),
+                    substs.stable(tcx),
+                    movability.stable(tcx),
+                )
+            }
+        }
+    }
+}
+
+impl<'tcx> Stable<'tcx> for mir::BorrowKind {
+    type T = smir::BorrowKind;
+    fn stable(&self, _: TyCtxt<'tcx>) -> Self::T {
+        match *self {
+            mir::BorrowKind::Shared => smir::BorrowKind::Shared,
+            mir::BorrowKind::Shallow => smir::BorrowKind::Shallow,
+            mir::BorrowKind::Unique => smir::BorrowKind::Unique,
+            mir::BorrowKind::Mut { allow_two_phase_borrow } => {
+                smir::BorrowKind::Mut { allow_two_phase_borrow }
+            }
+        }
+    }
+}
+
+impl<'tcx> Stable<'tcx> for mir::CastKind {
+    type T = smir::CastKind;
+    fn stable(&self, tcx: TyCtxt<'tcx>) -> Self::T {
+        use mir::CastKind::*;
+        match self {
+            PointerExposeAddress => smir::CastKind::PointerExposeAddress,
+            PointerFromExposedAddress => smir::CastKind::PointerFromExposedAddress,
+            Pointer(cast) => smir::CastKind::Pointer(cast.stable(tcx)),
+            DynStar => smir::CastKind::DynStar,
+            IntToInt => smir::CastKind::IntToInt,
+            FloatToInt => smir::CastKind::FloatToInt,
+            FloatToFloat => smir::CastKind::FloatToFloat,
+            IntToFloat => smir::CastKind::IntToFloat,
+            PtrToPtr => smir::CastKind::PtrToPtr,
+            FnPtrToPtr => smir::CastKind::FnPtrToPtr,
+        }
+    }
+}
+
+impl<'tcx> Stable<'tcx> for PointerCast {
+    type T = smir::PointerCast;
+    fn stable(&self, tcx: TyCtxt<'tcx>) -> Self::T {
+        match self {
+            PointerCast::ReifyFnPointer => smir::PointerCast::ReifyFnPointer,
+            PointerCast::UnsafeFnPointer => smir::PointerCast::UnsafeFnPointer,
+            PointerCast::ClosureFnPointer(unsafety) => {
+                smir::PointerCast::ClosureFnPointer(unsafety.stable(tcx))
+            }
+            PointerCast::MutToConstPointer => smir::PointerCast::MutToConstPointer,
+            PointerCast::ArrayToPointer => smir::PointerCast::ArrayToPointer,
+            PointerCast::Unsize => smir::PointerCast::Unsize,
+        }
+    }
+}
+
+impl<'tcx> Stable<'tcx> for mir::NullOp {
+    type T = smir::NullOp;
+    fn stable(&self, _: TyCtxt<'tcx>) -> Self::T {
+        match self {
+            mir::NullOp::SizeOf => smir::NullOp::SizeOf,
+            mir::NullOp::AlignOf => smir::NullOp::AlignOf,
+        }
+    }
+}
+
+impl<'tcx> Stable<'tcx> for mir::UnOp {
+    type T = smir::UnOp;
+    fn stable(&self, _: TyCtxt<'tcx>) -> Self::T {
+        match self {
+            mir::UnOp::Not => smir::UnOp::Not,
+            mir::UnOp::Neg => smir::UnOp::Neg,
+        }
+    }
+}
+
+impl<'tcx> Stable<'tcx> for mir::BinOp {
+    type T = smir::BinOp;
+    fn stable(&self, _: TyCtxt<'tcx>) -> Self::T {
+        use mir::BinOp::*;
+        match self {
+            Add => smir::BinOp::Add,
+            Sub => smir::BinOp::Sub,
+            Mul => smir::BinOp::Mul,
+            Div => smir::BinOp::Div,
+            Rem => smir::BinOp::Rem,
+            BitXor => smir::BinOp::BitXor,
+            BitAnd => smir::BinOp::BitAnd,
+            BitOr => smir::BinOp::BitOr,
+            Shl => smir::BinOp::Shl,
+            Shr => smir::BinOp::Shr,
+            Eq => smir::BinOp::Eq,
+            Lt => smir::BinOp::Lt,
+            Le => smir::BinOp::Le,
+            Ne => smir::BinOp::Ne,
+            Ge => smir::BinOp::Ge,
+            Gt => smir::BinOp::Gt,
+            Offset => smir::BinOp::Offset,
+        }
+    }
+}
+
+impl<'tcx> Stable<'tcx> for Mutability {
+    type T = sty::Mutability;
+    fn stable(&self, _: TyCtxt<'tcx>) -> Self::T {
+        match self {
+            Mutability::Not => sty::Mutability::Not,
+            Mutability::Mut => sty::Mutability::Mut,
+        }
+    }
+}
+
+impl<'tcx> Stable<'tcx> for Movability {
+    type T = sty::Movability;
+    fn stable(&self, _: TyCtxt<'tcx>) -> Self::T {
+        match self {
+            Movability::Static => sty::Movability::Static,
+            Movability::Movable => sty::Movability::Movable,
+        }
+    }
+}
+
+impl<'tcx> Stable<'tcx> for hir::Unsafety {
+    type T = sty::Safety;
+    fn stable(&self, _: TyCtxt<'tcx>) -> Self::T {
+        match self {
+            hir::Unsafety::Unsafe => sty::Safety::Unsafe,
+            hir::Unsafety::Normal => sty::Safety::Normal,
+        }
+    }
+}
+
+impl<'tcx> Stable<'tcx> for ty::Ty<'tcx> {
+    type T = sty::Ty;
+    fn stable(&self, tcx: TyCtxt<'tcx>) -> Self::T {
+        let kind = match *self.kind() {
+            ty::Bool => sty::TyKind::Bool,
+            ty::Char => sty::TyKind::Char,
+            ty::Int(int_ty) => sty::TyKind::Int(int_ty.stable(tcx)),
+            ty::Uint(uint_ty) => sty::TyKind::Uint(uint_ty.stable(tcx)),
+            ty::Float(float_ty) => sty::TyKind::Float(float_ty.stable(tcx)),
+            ty::Adt(adt_def, substs) => {
+                sty::TyKind::Adt(stable_def_id(tcx, adt_def.did()), substs.stable(tcx))
+            }
+            ty::Foreign(def_id) => sty::TyKind::Foreign(stable_def_id(tcx, def_id)),
+            ty::Str => sty::TyKind::Str,
+            ty::Array(ty, len) => sty::TyKind::Array(ty.stable(tcx), len.stable(tcx)),
+            ty::Slice(ty) => sty::TyKind::Slice(ty.stable(tcx)),
+            ty::RawPtr(ty::TypeAndMut { ty, mutbl }) => {
+                sty::TyKind::RawPtr(ty.stable(tcx), mutbl.stable(tcx))
+            }
+            ty::Ref(region, ty, mutbl) => {
+                sty::TyKind::Ref(region.stable(tcx), ty.stable(tcx), mutbl.stable(tcx))
+            }
+            ty::FnDef(def_id, substs) => {
+                sty::TyKind::FnDef(stable_def_id(tcx, def_id), substs.stable(tcx))
+            }
+            ty::FnPtr(sig) => sty::TyKind::FnPtr(sig.skip_binder().stable(tcx)),
+            ty::Dynamic(predicates, region, _) => sty::TyKind::Dynamic(
+                predicates
+                    .principal_def_id()
+                    .into_iter()
+                    .chain(predicates.auto_traits())
+                    .map(|def_id| stable_def_id(tcx, def_id))
+                    .collect(),
+                region.stable(tcx),
+            ),
+            ty::Closure(def_id, substs) => {
+                sty::TyKind::Closure(stable_def_id(tcx, def_id), substs.stable(tcx))
+            }
+            ty::Generator(def_id, substs, movability) => sty::TyKind::Generator(
+                stable_def_id(tcx, def_id),
+                substs.stable(tcx),
+                movability.stable(tcx),
+            ),
+            ty::GeneratorWitness(_) => sty::TyKind::GeneratorWitness,
+            ty::Never => sty::TyKind::Never,
+            ty::Tuple(tys) => sty::TyKind::Tuple(tys.iter().map(|ty| ty.stable(tcx)).collect()),
+            ty::Alias(kind, alias_ty) => {
+                let kind = match kind {
+                    ty::Projection => sty::AliasKind::Projection,
+                    ty::Opaque => sty::AliasKind::Opaque,
+                };
+                sty::TyKind::Alias(
+                    kind,
+                    stable_def_id(tcx, alias_ty.def_id),
+                    alias_ty.substs.stable(tcx),
+                )
+            }
+            ty::Param(param) => sty::TyKind::Param(sty::ParamTy {
+                index: param.index,
+                name: param.name.to_string(),
+            }),
+            ty::Bound(debruijn, bound_ty) => {
+                sty::TyKind::Bound(debruijn.as_usize(), bound_ty.var.as_usize())
+            }
+            ty::Error(_) => sty::TyKind::Error,
+            ty::Placeholder(..) | ty::Infer(_) => {
+                middle::bug!("unexpected type in the MIR of an item: {:?}", self)
+            }
+        };
+        sty::Ty::new(kind)
+    }
+}
+
+impl<'tcx> Stable<'tcx> for ty::IntTy {
+    type T = sty::IntTy;
+    fn stable(&self, _: TyCtxt<'tcx>) -> Self::T {
+        match self {
+            ty::IntTy::Isize => sty::IntTy::Isize,
+            ty::IntTy::I8 => sty::IntTy::I8,
+            ty::IntTy::I16 => sty::IntTy::I16,
+            ty::IntTy::I32 => sty::IntTy::I32,
+            ty::IntTy::I64 => sty::IntTy::I64,
+            ty::IntTy::I128 => sty::IntTy::I128,
+        }
+    }
+}
+
+impl<'tcx> Stable<'tcx> for ty::UintTy {
+    type T = sty::UintTy;
+    fn stable(&self, _: TyCtxt<'tcx>) -> Self::T {
+        match self {
+            ty::UintTy::Usize => sty::UintTy::Usize,
+            ty::UintTy::U8 => sty::UintTy::U8,
+            ty::UintTy::U16 => sty::UintTy::U16,
+            ty::UintTy::U32 => sty::UintTy::U32,
+            ty::UintTy::U64 => sty::UintTy::U64,
+            ty::UintTy::U128 => sty::UintTy::U128,
+        }
+    }
+}
+
+impl<'tcx> Stable<'tcx> for ty::FloatTy {
+    type T = sty::FloatTy;
+    fn stable(&self, _: TyCtxt<'tcx>) -> Self::T {
+        match self {
+            ty::FloatTy::F32 => sty::FloatTy::F32,
+            ty::FloatTy::F64 => sty::FloatTy::F64,
+        }
+    }
+}
+
+impl<'tcx> Stable<'tcx> for ty::FnSig<'tcx> {
+    type T = sty::FnSig;
+    fn stable(&self, tcx: TyCtxt<'tcx>) -> Self::T {
+        sty::FnSig {
+            inputs: self.inputs().iter().map(|ty| ty.stable(tcx)).collect(),
+            output: self.output().stable(tcx),
+            c_variadic: self.c_variadic,
+            safety: self.unsafety.stable(tcx),
+            abi: self.abi.name().to_string(),
+        }
+    }
+}
+
+impl<'tcx> Stable<'tcx> for ty::Region<'tcx> {
+    type T = sty::Region;
+    fn stable(&self, _: TyCtxt<'tcx>) -> Self::T {
+        match self.kind() {
+            ty::ReEarlyBound(region) => {
+                sty::Region::EarlyBound { index: region.index, name: region.name.to_string() }
+            }
+            ty::ReLateBound(debruijn, region) => {
+                sty::Region::LateBound { debruijn: debruijn.as_usize(), var: region.var.as_usize() }
+            }
+            ty::ReStatic => sty::Region::Static,
+            ty::ReErased => sty::Region::Erased,
+            _ => sty::Region::Other(self.to_string()),
+        }
+    }
+}
+
+impl<'tcx> Stable<'tcx> for SubstsRef<'tcx> {
+    type T = sty::GenericArgs;
+    fn stable(&self, tcx: TyCtxt<'tcx>) -> Self::T {
+        sty::GenericArgs(
+            self.iter()
+                .map(|arg| match arg.unpack() {
+                    GenericArgKind::Lifetime(region) => {
+                        sty::GenericArgKind::Lifetime(region.stable(tcx))
+                    }
+                    GenericArgKind::Type(ty) => sty::GenericArgKind::Type(ty.stable(tcx)),
+                    GenericArgKind::Const(ct) => sty::GenericArgKind::Const(ct.stable(tcx)),
+                })
+                .collect(),
+        )
+    }
+}
+
+impl<'tcx> Stable<'tcx> for ty::Const<'tcx> {
+    type T = sty::Const;
+    fn stable(&self, tcx: TyCtxt<'tcx>) -> Self::T {
+        let kind = match self.kind() {
+            ty::ConstKind::Param(param) => sty::ConstKind::Param(sty::ParamConst {
+                index: param.index,
+                name: param.name.to_string(),
+            }),
+            ty::ConstKind::Unevaluated(uv) => sty::ConstKind::Unevaluated {
+                def_id: stable_def_id(tcx, uv.def.did),
+                args: uv.substs.stable(tcx),
+                promoted: None,
+            },
+            ty::ConstKind::Value(ty::ValTree::Leaf(int)) => {
+                sty::ConstKind::Value(stable_scalar(int))
+            }
+            ty::ConstKind::Value(ty::ValTree::Branch(_)) => {
+                sty::ConstKind::Value(sty::ConstValue::Opaque(self.to_string()))
+            }
+            ty::ConstKind::Expr(_) => sty::ConstKind::Expr(self.to_string()),
+            ty::ConstKind::Error(_) => sty::ConstKind::Error,
+            ty::ConstKind::Infer(_) | ty::ConstKind::Bound(..) | ty::ConstKind::Placeholder(_) => {
+                middle::bug!("unexpected constant in the MIR of an item: {:?}", self)
+            }
+        };
+        sty::Const { ty: self.ty().stable(tcx), kind }
+    }
+}
+
+impl<'tcx> Stable<'tcx> for mir::ConstantKind<'tcx> {
+    type T = sty::Const;
+    fn stable(&self, tcx: TyCtxt<'tcx>) -> Self::T {
+        match *self {
+            mir::ConstantKind::Ty(ct) => ct.stable(tcx),
+            mir::ConstantKind::Unevaluated(uv, ty) => sty::Const {
+                ty: ty.stable(tcx),
+                kind: sty::ConstKind::Unevaluated {
+                    def_id: stable_def_id(tcx, uv.def.did),
+                    args: uv.substs.stable(tcx),
+                    promoted: uv.promoted.map(|promoted| promoted.as_usize()),
+                },
+            },
+            mir::ConstantKind::Val(val, ty) => {
+                let value = match val {
+                    ConstValue::Scalar(Scalar::Int(int)) => stable_scalar(int),
+                    ConstValue::ZeroSized => sty::ConstValue::ZeroSized,
+                    ConstValue::Slice { data, start, end } => sty::ConstValue::Slice(
+                        data.inner()
+                            .inspect_with_uninit_and_ptr_outside_interpreter(start..end)
+                            .to_vec(),
+                    ),
+                    ConstValue::Scalar(Scalar::Ptr(..)) | ConstValue::ByRef { .. } => {
+                        sty::ConstValue::Opaque(self.to_string())
+                    }
+                };
+                sty::Const { ty: ty.stable(tcx), kind: sty::ConstKind::Value(value) }
+            }
+        }
+    }
+}
+
+impl<'tcx> Stable<'tcx> for ty::Generics {
+    type T = sty::Generics;
+    fn stable(&self, tcx: TyCtxt<'tcx>) -> Self::T {
+        sty::Generics {
+            parent: self.parent.map(|parent| stable_def_id(tcx, parent)),
+            parent_count: self.parent_count,
+            params: self.params.iter().map(|param| param.stable(tcx)).collect(),
+            has_self: self.has_self,
+        }
+    }
+}
+
+impl<'tcx> Stable<'tcx> for ty::GenericParamDef {
+    type T = sty::GenericParamDef;
+    fn stable(&self, tcx: TyCtxt<'tcx>) -> Self::T {
+        let kind = match self.kind {
+            ty::GenericParamDefKind::Lifetime => sty::GenericParamDefKind::Lifetime,
+            ty::GenericParamDefKind::Type { has_default, synthetic } => {
+                sty::GenericParamDefKind::Type { has_default, synthetic }
+            }
+            ty::GenericParamDefKind::Const { has_default } => {
+                sty::GenericParamDefKind::Const { has_default }
+            }
+        };
+        sty::GenericParamDef {
+            name: self.name.to_string(),
+            def_id: stable_def_id(tcx, self.def_id),
+            index: self.index,
+            kind,
+            span: tcx.def_span(self.def_id).stable(tcx),
+        }
+    }
+}
@@ -0,0 +1,364 @@
+//! Bodies, basic blocks, statements and terminators of the Stable MIR model.
+//!
+//! This mirrors the MIR of rustc closely, except that locals, basic blocks and variants are
+//! plain indices and that everything is owned.
+
+use super::ty::{Const, GenericArgs, Movability, Mutability, Region, Safety, Ty};
+use super::{DefId, Span};
+use serde::{Deserialize, Serialize};
+
+/// The index of a local in [`Body::locals`]. The return place is local 0, followed by the
+/// arguments.
+pub type Local = usize;
+
+/// The index of a basic block in [`Body::blocks`].
+pub type BasicBlockIdx = usize;
+
+/// The index of a variant of an enum, or 0 for other ADTs.
+pub type VariantIdx = usize;
+
+/// The index of a field of a struct, union, enum variant, tuple or closure.
+pub type FieldIdx = usize;
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Body {
+    pub blocks: Vec<BasicBlock>,
+    pub locals: Vec<LocalDecl>,
+    /// The number of arguments, which are the locals `1..=arg_count`.
+    pub arg_count: usize,
+    pub var_debug_info: Vec<VarDebugInfo>,
+    pub span: Span,
+}
+
+impl Body {
+    /// The type of the return place.
+    pub fn ret_ty(&self) -> &Ty {
+        &self.locals[0].ty
+    }
+
+    /// The locals of the arguments.
+    pub fn args(&self) -> &[LocalDecl] {
+        &self.locals[1..=self.arg_count]
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct LocalDecl {
+    pub ty: Ty,
+    pub mutability: Mutability,
+    pub span: Span,
+}
+
+/// Describes a user variable for debuginfo.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct VarDebugInfo {
+    pub name: String,
+    pub span: Span,
+    pub value: VarDebugInfoContents,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub enum VarDebugInfoContents {
+    Place(Place),
+    Const(Constant),
+    /// The variable was split up into disjoint places, each given with the projection of the
+    /// variable it holds.
+    Composite { ty: Ty, fragments: Vec<(Vec<ProjectionElem>, Place)> },
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct BasicBlock {
+    pub statements: Vec<Statement>,
+    pub terminator: Terminator,
+    /// Whether this block is only reached while unwinding.
+    pub is_cleanup: bool,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Statement {
+    pub kind: StatementKind,
+    pub span: Span,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub enum StatementKind {
+    Assign(Place, Rvalue),
+    FakeRead(FakeReadCause, Place),
+    SetDiscriminant { place: Place, variant_index: VariantIdx },
+    Deinit(Place),
+    StorageLive(Local),
+    StorageDead(Local),
+    Retag(RetagKind, Place),
+    AscribeUserType(Place),
+    Coverage,
+    Intrinsic(NonDivergingIntrinsic),
+    Nop,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum FakeReadCause {
+    ForMatchGuard,
+    ForMatchedPlace,
+    ForGuardBinding,
+    ForLet,
+    ForIndex,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum RetagKind {
+    FnEntry,
+    TwoPhase,
+    Raw,
+    Default,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub enum NonDivergingIntrinsic {
+    Assume(Operand),
+    CopyNonOverlapping { src: Operand, dst: Operand, count: Operand },
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Terminator {
+    pub kind: TerminatorKind,
+    pub span: Span,
+}
+
+impl Terminator {
+    /// The blocks that control may flow to from this terminator, including cleanup blocks.
+    pub fn successors(&self) -> Vec<BasicBlockIdx> {
+        use TerminatorKind::*;
+        match &self.kind {
+            Goto { target } => vec![*target],
+            SwitchInt { targets, otherwise, .. } => {
+                targets.iter().map(|&(_, bb)| bb).chain(Some(*otherwise)).collect()
+            }
+            Resume | Abort | Return | Unreachable | GeneratorDrop => vec![],
+            Drop { target, unwind, .. } | DropAndReplace { target, unwind, .. } => {
+                Some(*target).into_iter().chain(*unwind).collect()
+            }
+            Call { target, cleanup, .. } => target.iter().chain(cleanup).copied().collect(),
+            Assert { target, cleanup, .. } => Some(*target).into_iter().chain(*cleanup).collect(),
+            Yield { resume, drop, .. } => Some(*resume).into_iter().chain(*drop).collect(),
+            FalseEdge { real_target, imaginary_target } => vec![*real_target, *imaginary_target],
+            FalseUnwind { real_target, unwind } => {
+                Some(*real_target).into_iter().chain(*unwind).collect()
+            }
+            InlineAsm { destination, cleanup, .. } => {
+                destination.iter().chain(cleanup).copied().collect()
+            }
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub enum TerminatorKind {
+    Goto {
+        target: BasicBlockIdx,
+    },
+    SwitchInt {
+        discr: Operand,
+        targets: Vec<(u128, BasicBlockIdx)>,
+        otherwise: BasicBlockIdx,
+    },
+    Resume,
+    Abort,
+    Return,
+    Unreachable,
+    Drop {
+        place: Place,
+        target: BasicBlockIdx,
+        unwind: Option<BasicBlockIdx>,
+    },
+    DropAndReplace {
+        place: Place,
+        value: Operand,
+        target: BasicBlockIdx,
+        unwind: Option<BasicBlockIdx>,
+    },
+    Call {
+        func: Operand,
+        args: Vec<Operand>,
+        destination: Place,
+        target: Option<BasicBlockIdx>,
+        cleanup: Option<BasicBlockIdx>,
+    },
+    Assert {
+        cond: Operand,
+        expected: bool,
+        msg: AssertMessage,
+        target: BasicBlockIdx,
+        cleanup: Option<BasicBlockIdx>,
+    },
+    Yield {
+        value: Operand,
+        resume: BasicBlockIdx,
+        resume_arg: Place,
+        drop: Option<BasicBlockIdx>,
+    },
+    GeneratorDrop,
+    FalseEdge {
+        real_target: BasicBlockIdx,
+        imaginary_target: BasicBlockIdx,
+    },
+    FalseUnwind {
+        real_target: BasicBlockIdx,
+        unwind: Option<BasicBlockIdx>,
+    },
+    InlineAsm {
+        template: String,
+        operands: Vec<InlineAsmOperand>,
+        destination: Option<BasicBlockIdx>,
+        cleanup: Option<BasicBlockIdx>,
+    },
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub enum AssertMessage {
+    BoundsCheck { len: Operand, index: Operand },
+    Overflow(BinOp, Operand, Operand),
+    OverflowNeg(Operand),
+    DivisionByZero(Operand),
+    RemainderByZero(Operand),
+    ResumedAfterReturn(GeneratorKind),
+    ResumedAfterPanic(GeneratorKind),
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum GeneratorKind {
+    Async,
+    Gen,
+}
+
+/// An operand of an inline assembly block. The register or register class is only given as
+/// part of the rendering of the whole operand.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct InlineAsmOperand {
+    pub in_value: Option<Operand>,
+    pub out_place: Option<Place>,
+    pub raw_repr: String,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Place {
+    pub local: Local,
+    pub projection: Vec<ProjectionElem>,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub enum ProjectionElem {
+    Deref,
+    Field(FieldIdx, Ty),
+    Index(Local),
+    ConstantIndex { offset: u64, min_length: u64, from_end: bool },
+    Subslice { from: u64, to: u64, from_end: bool },
+    Downcast(VariantIdx),
+    OpaqueCast(Ty),
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub enum Operand {
+    Copy(Place),
+    Move(Place),
+    Constant(Constant),
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Constant {
+    pub span: Span,
+    pub literal: Const,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub enum Rvalue {
+    Use(Operand),
+    Repeat(Operand, Const),
+    Ref(Region, BorrowKind, Place),
+    ThreadLocalRef(DefId),
+    AddressOf(Mutability, Place),
+    Len(Place),
+    Cast(CastKind, Operand, Ty),
+    BinaryOp(BinOp, Operand, Operand),
+    CheckedBinaryOp(BinOp, Operand, Operand),
+    NullaryOp(NullOp, Ty),
+    UnaryOp(UnOp, Operand),
+    Discriminant(Place),
+    Aggregate(AggregateKind, Vec<Operand>),
+    ShallowInitBox(Operand, Ty),
+    CopyForDeref(Place),
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub enum AggregateKind {
+    Array(Ty),
+    Tuple,
+    /// An ADT with the variant and, for unions, the field that is initialized.
+    Adt(DefId, VariantIdx, GenericArgs, Option<FieldIdx>),
+    Closure(DefId, GenericArgs),
+    Generator(DefId, GenericArgs, Movability),
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum BorrowKind {
+    Shared,
+    Shallow,
+    Unique,
+    Mut { allow_two_phase_borrow: bool },
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum CastKind {
+    PointerExposeAddress,
+    PointerFromExposedAddress,
+    Pointer(PointerCast),
+    DynStar,
+    IntToInt,
+    FloatToInt,
+    FloatToFloat,
+    IntToFloat,
+    PtrToPtr,
+    FnPtrToPtr,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum PointerCast {
+    ReifyFnPointer,
+    UnsafeFnPointer,
+    ClosureFnPointer(Safety),
+    MutToConstPointer,
+    ArrayToPointer,
+    Unsize,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum NullOp {
+    SizeOf,
+    AlignOf,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum UnOp {
+    Not,
+    Neg,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum BinOp {
+    Add,
+    Sub,
+    Mul,
+    Div,
+    Rem,
+    BitXor,
+    BitAnd,
+    BitOr,
+    Shl,
+    Shr,
+    Eq,
+    Lt,
+    Le,
+    Ne,
+    Ge,
+    Gt,
+    Offset,
+}
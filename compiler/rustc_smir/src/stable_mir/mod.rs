@@ -0,0 +1,117 @@
+//! Module that implements the public interface to the Stable MIR.
+//!
+//! This module shall contain all type definitions and APIs that we expect 3P tools to invoke to
+//! interact with the compiler.
+//!
+//! The model is owned and serializable: it doesn't borrow from the compiler, doesn't mention
+//! any type of `rustc_middle` and can be written out with `serde` to be consumed by tools that
+//! don't link against rustc at all.
+//!
+//! The model is versioned with [`VERSION`], independently of the compiler version, following
+//! semver: additions bump the minor version (or the patch version before 1.0), and anything that
+//! could break a consumer of the serialized model bumps the major version (or the minor version
+//! before 1.0).
+
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+pub mod mir;
+pub mod ty;
+
+/// The version of the Stable MIR model implemented by this crate.
+pub const VERSION: Version = Version { major: 0, minor: 1, patch: 0 };
+
+/// A semver version of the Stable MIR model.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub struct Version {
+    pub major: u32,
+    pub minor: u32,
+    pub patch: u32,
+}
+
+impl Version {
+    /// Whether a model of this version can be consumed by a tool written against `required`,
+    /// following the rules of semver (and of Cargo for versions before 1.0).
+    pub fn is_compatible_with(self, required: Version) -> bool {
+        if self.major != required.major {
+            return false;
+        }
+        if self.major == 0 && self.minor != required.minor {
+            return false;
+        }
+        self >= required
+    }
+}
+
+impl fmt::Display for Version {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}.{}.{}", self.major, self.minor, self.patch)
+    }
+}
+
+/// The Stable MIR of all local items of a crate that have a body.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Crate {
+    /// The version of the model, always [`VERSION`] when produced by this crate.
+    pub version: Version,
+    pub name: String,
+    pub items: Vec<CrateItem>,
+}
+
+/// A local item with a MIR body, e.g. a function, a closure or a constant.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct CrateItem {
+    pub def_id: DefId,
+    pub kind: ItemKind,
+    pub span: Span,
+    pub generics: ty::Generics,
+    /// The MIR that is used for codegen, or for const evaluation if the item is a constant or a
+    /// static.
+    pub body: mir::Body,
+    /// The bodies of the promoted constants of `body`, referred to by
+    /// [`ty::ConstKind::Unevaluated`] with a `promoted` index.
+    pub promoted: Vec<mir::Body>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum ItemKind {
+    Fn,
+    AssocFn,
+    Closure,
+    Generator,
+    Const,
+    AssocConst,
+    AnonConst,
+    InlineConst,
+    Static(ty::Mutability),
+    /// The constructor function of a tuple struct or tuple variant.
+    Ctor,
+}
+
+/// Identifies an item, of this crate or of another one.
+///
+/// The hash is stable across compilation sessions as long as the path of the item doesn't
+/// change, so it can be used to match up the items of models produced by different sessions.
+#[derive(Clone, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct DefId {
+    /// The name of the crate that defines the item.
+    pub krate: String,
+    /// The path of the item, meant for humans.
+    pub path: String,
+    pub hash: [u64; 2],
+}
+
+/// A span in the source code of the crate, or of another crate if it was inlined from there.
+#[derive(Clone, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct Span {
+    pub file: String,
+    pub lo: LineColumn,
+    pub hi: LineColumn,
+}
+
+/// A position in a source file. Lines are 1-based, columns are 0-based and counted in chars.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub struct LineColumn {
+    pub line: usize,
+    pub col: usize,
+}
@@ -0,0 +1,199 @@
+//! Types, constants and generics of the Stable MIR model.
+
+use super::{DefId, Span};
+use serde::{Deserialize, Serialize};
+
+/// A type. Types are trees, they are not interned.
+#[derive(Clone, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct Ty(pub Box<TyKind>);
+
+impl Ty {
+    pub fn new(kind: TyKind) -> Ty {
+        Ty(Box::new(kind))
+    }
+
+    pub fn kind(&self) -> &TyKind {
+        &self.0
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum TyKind {
+    Bool,
+    Char,
+    Int(IntTy),
+    Uint(UintTy),
+    Float(FloatTy),
+    Adt(DefId, GenericArgs),
+    Foreign(DefId),
+    Str,
+    Array(Ty, Const),
+    Slice(Ty),
+    RawPtr(Ty, Mutability),
+    Ref(Region, Ty, Mutability),
+    FnDef(DefId, GenericArgs),
+    FnPtr(FnSig),
+    /// A trait object, with the principal trait (if any) followed by the auto traits.
+    Dynamic(Vec<DefId>, Region),
+    Closure(DefId, GenericArgs),
+    Generator(DefId, GenericArgs, Movability),
+    GeneratorWitness,
+    Never,
+    Tuple(Vec<Ty>),
+    Alias(AliasKind, DefId, GenericArgs),
+    Param(ParamTy),
+    /// A type bound by an enclosing binder, e.g. of a function pointer type.
+    Bound(usize, usize),
+    Error,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum IntTy {
+    Isize,
+    I8,
+    I16,
+    I32,
+    I64,
+    I128,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum UintTy {
+    Usize,
+    U8,
+    U16,
+    U32,
+    U64,
+    U128,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum FloatTy {
+    F32,
+    F64,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum Mutability {
+    Not,
+    Mut,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum Movability {
+    Static,
+    Movable,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum Safety {
+    Unsafe,
+    Normal,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum AliasKind {
+    Projection,
+    Opaque,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct ParamTy {
+    pub index: u32,
+    pub name: String,
+}
+
+/// The signature of a function pointer type.
+#[derive(Clone, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct FnSig {
+    pub inputs: Vec<Ty>,
+    pub output: Ty,
+    pub c_variadic: bool,
+    pub safety: Safety,
+    pub abi: String,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum Region {
+    EarlyBound { index: u32, name: String },
+    /// A region bound by an enclosing binder, e.g. of a function pointer type.
+    LateBound { debruijn: usize, var: usize },
+    Static,
+    /// Regions are erased in the MIR of most items.
+    Erased,
+    /// Any other region, rendered by rustc.
+    Other(String),
+}
+
+/// The generic arguments of an item or type.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct GenericArgs(pub Vec<GenericArgKind>);
+
+#[derive(Clone, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum GenericArgKind {
+    Lifetime(Region),
+    Type(Ty),
+    Const(Const),
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct Const {
+    pub ty: Ty,
+    pub kind: ConstKind,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum ConstKind {
+    Param(ParamConst),
+    /// A constant that still needs to be evaluated. If `promoted` is set, this refers to a
+    /// promoted constant of the body that `def_id` belongs to.
+    Unevaluated { def_id: DefId, args: GenericArgs, promoted: Option<usize> },
+    Value(ConstValue),
+    /// A generic constant expression, rendered by rustc.
+    Expr(String),
+    Error,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct ParamConst {
+    pub index: u32,
+    pub name: String,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum ConstValue {
+    /// An integer, `bool`, `char` or float, given by its bits and its size in bytes.
+    Scalar { bits: u128, size: u64 },
+    ZeroSized,
+    /// The contents of a `&str` or `&[u8]` constant.
+    Slice(Vec<u8>),
+    /// Any other value, e.g. one that contains pointers, rendered by rustc.
+    Opaque(String),
+}
+
+/// The generic parameters of an item, not including the ones of its parent.
+#[derive(Clone, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct Generics {
+    pub parent: Option<DefId>,
+    pub parent_count: usize,
+    pub params: Vec<GenericParamDef>,
+    pub has_self: bool,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct GenericParamDef {
+    pub name: String,
+    pub def_id: DefId,
+    pub index: u32,
+    pub kind: GenericParamDefKind,
+    pub span: Span,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum GenericParamDefKind {
+    Lifetime,
+    Type { has_default: bool, synthetic: bool },
+    Const { has_default: bool },
+}
@@ -15,6 +15,7 @@ macro_rules! crates {
 }
 
 crates! {
+    rustc_ast -> ast,
     rustc_borrowck -> borrowck,
     rustc_driver -> driver,
     rustc_hir -> hir,
@@ -23,5 +24,6 @@ macro_rules! crates {
     rustc_mir_dataflow -> dataflow,
     rustc_mir_transform -> transform,
     rustc_serialize -> serialize,
+    rustc_span -> span,
     rustc_trait_selection -> trait_selection,
 }
@@ -670,6 +670,7 @@
         exp2f32,
         exp2f64,
         expect,
+        expect_failed,
         expected,
         expf32,
         expf64,
@@ -1046,6 +1047,7 @@
         panic_2021,
         panic_abort,
         panic_bounds_check,
+        panic_bounds_check_outlined,
         panic_cannot_unwind,
         panic_display,
         panic_fmt,
@@ -1055,8 +1057,10 @@
         panic_info,
         panic_location,
         panic_nounwind,
+        panic_outlined,
         panic_runtime,
         panic_str,
+        panic_str_outlined,
         panic_unwind,
         panicking,
         param_attrs,
@@ -1120,6 +1124,7 @@
         ptr_null_mut,
         ptr_offset_from,
         ptr_offset_from_unsigned,
+        ptr_unique,
         pub_macro_rules,
         pub_restricted,
         public,
@@ -88,3 +88,9 @@ fn into_diagnostic(
         diag
     }
 }
+
+#[derive(Diagnostic)]
+#[diag(trait_selection_couldnt_dump_trait_proof_tree)]
+pub struct CouldntDumpTraitProofTree {
+    pub error: String,
+}
@@ -10,12 +10,15 @@
 //! generic constants mentioned in the `caller_bounds` of the current environment.
 use rustc_hir::def::DefKind;
 use rustc_infer::infer::InferCtxt;
-use rustc_middle::mir::interpret::ErrorHandled;
+use rustc_middle::mir::interpret::{ConstValue, ErrorHandled, Scalar};
+use rustc_middle::mir::{AssertKind, BinOp, UnOp};
 
 use rustc_middle::traits::ObligationCause;
 use rustc_middle::ty::abstract_const::NotConstEvaluatable;
-use rustc_middle::ty::{self, TyCtxt, TypeVisitable, TypeVisitor};
+use rustc_middle::ty::fold::BottomUpFolder;
+use rustc_middle::ty::{self, GenericArgKind, TyCtxt, TypeFoldable, TypeVisitable, TypeVisitor};
 
+use rustc_errors::ErrorGuaranteed;
 use rustc_span::Span;
 use std::ops::ControlFlow;
 
@@ -62,13 +65,37 @@ pub fn is_const_evaluatable<'tcx>(
 
         match unexpanded_ct.kind() {
             ty::ConstKind::Expr(_) => {
-                // FIXME(generic_const_exprs): we have a `ConstKind::Expr` which is fully concrete, but
-                // currently it is not possible to evaluate `ConstKind::Expr` so we are unable to tell if it
-                // is evaluatable or not. For now we just ICE until this is implemented.
-                Err(NotConstEvaluatable::Error(tcx.sess.delay_span_bug(
-                    span,
-                    "evaluating `ConstKind::Expr` is not currently supported",
-                )))
+                // We have a `ConstKind::Expr` which is fully concrete, so evaluate any constants
+                // and calls it mentions and then fold it into a value.
+                let ct = ct.fold_with(&mut BottomUpFolder {
+                    tcx,
+                    ty_op: |ty| ty,
+                    lt_op: |lt| lt,
+                    ct_op: |c| match c.kind() {
+                        ty::ConstKind::Unevaluated(_) => c.eval(tcx, param_env),
+                        ty::ConstKind::Expr(ty::Expr::FunctionCall(..)) => {
+                            eval_function_call(tcx, param_env, c, span)
+                        }
+                        _ => c,
+                    },
+                });
+                let simplified = tcx.simplify_abstract_const(ct);
+                match simplified.kind() {
+                    ty::ConstKind::Value(_) => Ok(()),
+                    ty::ConstKind::Error(e) => Err(NotConstEvaluatable::Error(e)),
+                    _ => match failed_operation(simplified) {
+                        Some(failed) if !is_function_call(failed) => Err(
+                            NotConstEvaluatable::Error(report_failed_evaluation(tcx, failed, span)),
+                        ),
+                        // FIXME(generic_const_exprs): calls of `const fn`s with arguments can't
+                        // be evaluated yet, so we are unable to tell if this is evaluatable or
+                        // not. For now we just ICE until this is implemented.
+                        _ => Err(NotConstEvaluatable::Error(tcx.sess.delay_span_bug(
+                            span,
+                            "evaluating `ConstKind::Expr` is not currently supported",
+                        ))),
+                    },
+                }
             }
             ty::ConstKind::Unevaluated(uv) => {
                 let concrete = infcx.const_eval_resolve(param_env, uv, Some(span));
@@ -153,6 +180,92 @@ pub fn is_const_evaluatable<'tcx>(
     }
 }
 
+/// Evaluates a concrete call of a `const fn` without arguments, like `size_of::<u32>()`, to an
+/// integer. Other calls are returned unchanged.
+fn eval_function_call<'tcx>(
+    tcx: TyCtxt<'tcx>,
+    param_env: ty::ParamEnv<'tcx>,
+    ct: ty::Const<'tcx>,
+    span: Span,
+) -> ty::Const<'tcx> {
+    let ty::ConstKind::Expr(ty::Expr::FunctionCall(func, args)) = ct.kind() else { return ct };
+    let ty::FnDef(def_id, substs) = *func.ty().kind() else { return ct };
+    if !args.is_empty() {
+        return ct;
+    }
+    let Ok(Some(instance)) = ty::Instance::resolve(tcx, param_env, def_id, substs) else {
+        return ct;
+    };
+    match tcx.const_eval_instance(param_env, instance, Some(span)) {
+        Ok(ConstValue::Scalar(Scalar::Int(int))) => {
+            tcx.mk_const(ty::ValTree::from_scalar_int(int), ct.ty())
+        }
+        Err(ErrorHandled::Reported(e)) => tcx.const_error_with_guaranteed(ct.ty(), e),
+        Ok(_) | Err(ErrorHandled::TooGeneric) => ct,
+    }
+}
+
+fn is_function_call(ct: ty::Const<'_>) -> bool {
+    matches!(ct.kind(), ty::ConstKind::Expr(ty::Expr::FunctionCall(..)))
+}
+
+/// Returns the innermost operation of a simplified `ConstKind::Expr` whose evaluation failed.
+fn failed_operation<'tcx>(ct: ty::Const<'tcx>) -> Option<ty::Const<'tcx>> {
+    // Simplification evaluates every operation whose operands are values, so the last such
+    // operation is one whose evaluation failed.
+    let is_value = |c: ty::Const<'_>| matches!(c.kind(), ty::ConstKind::Value(_));
+    let consts = ct.walk().filter_map(|arg| match arg.unpack() {
+        GenericArgKind::Const(c) => Some(c),
+        _ => None,
+    });
+    consts
+        .filter(|c| match c.kind() {
+            ty::ConstKind::Expr(ty::Expr::Binop(_, lhs, rhs)) => is_value(lhs) && is_value(rhs),
+            ty::ConstKind::Expr(ty::Expr::UnOp(_, operand) | ty::Expr::Cast(_, operand, _)) => {
+                is_value(operand)
+            }
+            ty::ConstKind::Expr(ty::Expr::FunctionCall(_, args)) => args.iter().all(is_value),
+            _ => false,
+        })
+        .last()
+}
+
+/// Reports that a concrete `ConstKind::Expr` could not be evaluated because of the `failed`
+/// operation, see `failed_operation`.
+fn report_failed_evaluation<'tcx>(
+    tcx: TyCtxt<'tcx>,
+    failed: ty::Const<'tcx>,
+    span: Span,
+) -> ErrorGuaranteed {
+    let mut err = tcx.sess.struct_span_err(span, "failed to evaluate generic const expression");
+    let reason = match failed.kind() {
+        ty::ConstKind::Expr(ty::Expr::Binop(op @ (BinOp::Div | BinOp::Rem), _, rhs))
+            if rhs.kind().try_to_scalar_int().map_or(false, |rhs| rhs.is_null()) =>
+        {
+            let kind = if op == BinOp::Div {
+                AssertKind::DivisionByZero(())
+            } else {
+                AssertKind::RemainderByZero(())
+            };
+            Some(kind.description())
+        }
+        ty::ConstKind::Expr(ty::Expr::Binop(op, ..))
+            if op.is_checkable() || matches!(op, BinOp::Div | BinOp::Rem) =>
+        {
+            Some(AssertKind::Overflow(op, (), ()).description())
+        }
+        ty::ConstKind::Expr(ty::Expr::UnOp(UnOp::Neg, _)) => {
+            Some(AssertKind::OverflowNeg(()).description())
+        }
+        _ => None,
+    };
+    match reason {
+        Some(reason) => err.note(&format!("{reason} in `{failed}`")),
+        None => err.note(&format!("`{failed}` cannot be evaluated in a const expression")),
+    };
+    err.emit()
+}
+
 #[instrument(skip(infcx, tcx), level = "debug")]
 fn satisfied_from_param_env<'tcx>(
     tcx: TyCtxt<'tcx>,
@@ -838,6 +838,12 @@ fn report_selection_error(
                             // If it has a custom `#[rustc_on_unimplemented]` note, let's display it
                             err.note(s.as_str());
                         }
+                        if Some(trait_ref.def_id()) == tcx.lang_items().transmute_trait()
+                            && let Some(explanation) =
+                                self.explain_transmute_rejection(&obligation, trait_ref)
+                        {
+                            err.note(&explanation);
+                        }
                         if let Some(ref s) = parent_label {
                             let body = tcx
                                 .hir()
@@ -1402,16 +1408,30 @@ fn report_selection_error(
 
                 match obligation.predicate.kind().skip_binder() {
                     ty::PredicateKind::ConstEvaluatable(ct) => {
-                        let ty::ConstKind::Unevaluated(uv) = ct.kind() else {
-                            bug!("const evaluatable failed for non-unevaluated const `{ct:?}`");
-                        };
+                        let ct = self.resolve_vars_if_possible(ct);
                         let mut err =
                             self.tcx.sess.struct_span_err(span, "unconstrained generic constant");
-                        let const_span = self.tcx.def_span(uv.def.did);
-                        match self.tcx.sess.source_map().span_to_snippet(const_span) {
-                            Ok(snippet) => err.help(&format!(
+                        let expression = match ct.kind() {
+                            ty::ConstKind::Unevaluated(uv) => {
+                                // The source of a constant is only meaningful in the current item
+                                // if it is used with its own generic parameters, so otherwise try
+                                // to print it with its generic arguments substituted.
+                                let expanded = self.tcx.expand_abstract_consts(ct);
+                                let identity =
+                                    ty::InternalSubsts::identity_for_item(self.tcx, uv.def.did);
+                                if uv.substs != identity && is_printable_abstract_const(expanded) {
+                                    Some(expanded.to_string())
+                                } else {
+                                    let const_span = self.tcx.def_span(uv.def.did);
+                                    self.tcx.sess.source_map().span_to_snippet(const_span).ok()
+                                }
+                            }
+                            _ => Some(ct.to_string()),
+                        };
+                        match expression {
+                            Some(expression) => err.help(&format!(
                                 "try adding a `where` bound using this expression: `where [(); {}]:`",
-                                snippet
+                                expression
                             )),
                             _ => err.help("consider adding a `where` bound using this expression"),
                         };
@@ -1564,6 +1584,12 @@ fn is_recursive_obligation(
         obligated_types: &mut Vec<Ty<'tcx>>,
         cause_code: &ObligationCauseCode<'tcx>,
     ) -> bool;
+
+    fn explain_transmute_rejection(
+        &self,
+        obligation: &PredicateObligation<'tcx>,
+        trait_ref: ty::PolyTraitRef<'tcx>,
+    ) -> Option<String>;
 }
 
 impl<'tcx> InferCtxtPrivExt<'tcx> for TypeErrCtxt<'_, 'tcx> {
@@ -2685,6 +2711,9 @@ fn note_obligation_cause(&self, err: &mut Diagnostic, obligation: &PredicateObli
             );
             self.suggest_unsized_bound_if_applicable(err, obligation);
         }
+        if self.tcx.sess.opts.unstable_opts.explain_auto_traits {
+            self.note_auto_trait_derivation_chain(err, obligation);
+        }
     }
 
     #[instrument(level = "debug", skip_all)]
@@ -2818,6 +2847,74 @@ fn is_recursive_obligation(
         }
         false
     }
+
+    /// Explains why the source type of a `BikeshedIntrinsicFrom` obligation can't be transmuted
+    /// into its destination type.
+    fn explain_transmute_rejection(
+        &self,
+        obligation: &PredicateObligation<'tcx>,
+        trait_ref: ty::PolyTraitRef<'tcx>,
+    ) -> Option<String> {
+        use rustc_transmute::{Answer, Reason};
+
+        let trait_ref = self.tcx.erase_late_bound_regions(self.resolve_vars_if_possible(trait_ref));
+        let (dst, src) = (trait_ref.substs.type_at(0), trait_ref.substs.type_at(1));
+        let scope = trait_ref.substs.type_at(2);
+        let assume = rustc_transmute::Assume::from_const(
+            self.tcx,
+            obligation.param_env,
+            trait_ref.substs.const_at(3),
+        )?;
+
+        let answer = rustc_transmute::TransmuteTypeEnv::new(self.infcx).is_transmutable(
+            obligation.cause.clone(),
+            ty::Binder::dummy(rustc_transmute::Types { src, dst }),
+            scope,
+            assume,
+        );
+        let Answer::No(reason) = answer else { return None };
+
+        Some(match reason {
+            Reason::SrcIsUnspecified => format!("`{src}` does not have a well-specified layout"),
+            Reason::DstIsUnspecified => format!("`{dst}` does not have a well-specified layout"),
+            Reason::SrcHasUnsizedReferent => format!(
+                "`{src}` contains a reference to an unsized type other than a slice, \
+                 and such references are not supported"
+            ),
+            Reason::DstHasUnsizedReferent => format!(
+                "`{dst}` contains a reference to an unsized type other than a slice, \
+                 and such references are not supported"
+            ),
+            Reason::DstIsBitIncompatible { offset } => format!(
+                "at byte offset {offset}, `{src}` may hold a value that is not valid for `{dst}`"
+            ),
+            Reason::SrcHasPadding { offset } => format!(
+                "at byte offset {offset}, `{src}` may be padding, \
+                 but `{dst}` requires an initialized byte"
+            ),
+            Reason::DstIsPrivate => format!(
+                "`{dst}` has private fields or variants, so it cannot be safely constructed"
+            ),
+            Reason::DstIsTooBig { offset } => format!(
+                "`{dst}` is larger than `{src}`, \
+                 and requires an initialized byte at offset {offset}"
+            ),
+            Reason::DstHasStricterAlignment { offset, src_min_align, dst_min_align } => format!(
+                "the reference at byte offset {offset} of `{dst}` requires an alignment of \
+                 {dst_min_align}, but the referent of the reference in `{src}` only has an \
+                 alignment of {src_min_align}"
+            ),
+            Reason::DstIsMoreUnique { offset } => format!(
+                "the shared reference at byte offset {offset} of `{src}` \
+                 cannot be transmuted into a mutable reference"
+            ),
+            Reason::DstHasDifferentElementSize { offset, src_elem_size, dst_elem_size } => format!(
+                "the reference at byte offset {offset} of `{dst}` refers to elements of size \
+                 {dst_elem_size}, but the reference in `{src}` refers to elements of size \
+                 {src_elem_size}"
+            ),
+        })
+    }
 }
 
 /// Crude way of getting back an `Expr` from a `Span`.
@@ -2943,3 +3040,18 @@ pub enum DefIdOrName {
     DefId(DefId),
     Name(&'static str),
 }
+
+/// Whether an expanded abstract const only consists of operations on generic parameters and
+/// values, which means that it can be printed as an expression that is valid in any item.
+fn is_printable_abstract_const(ct: ty::Const<'_>) -> bool {
+    match ct.kind() {
+        ty::ConstKind::Param(_) | ty::ConstKind::Value(_) => true,
+        ty::ConstKind::Expr(ty::Expr::Binop(_, lhs, rhs)) => {
+            is_printable_abstract_const(lhs) && is_printable_abstract_const(rhs)
+        }
+        ty::ConstKind::Expr(ty::Expr::UnOp(_, operand) | ty::Expr::Cast(_, operand, _)) => {
+            is_printable_abstract_const(operand)
+        }
+        _ => false,
+    }
+}
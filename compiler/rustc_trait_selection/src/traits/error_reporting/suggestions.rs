@@ -34,6 +34,7 @@
     IsSuggestable, ToPredicate, Ty, TyCtxt, TypeAndMut, TypeFoldable, TypeFolder,
     TypeSuperFoldable, TypeVisitable, TypeckResults,
 };
+use rustc_session::config::ErrorOutputType;
 use rustc_span::symbol::{sym, Ident, Symbol};
 use rustc_span::{BytePos, DesugaringKind, ExpnKind, MacroKind, Span, DUMMY_SP};
 use rustc_target::spec::abi;
@@ -319,6 +320,12 @@ fn note_obligation_cause_for_async_await(
         next_code: Option<&ObligationCauseCode<'tcx>>,
     );
 
+    fn note_auto_trait_derivation_chain(
+        &self,
+        err: &mut Diagnostic,
+        obligation: &PredicateObligation<'tcx>,
+    );
+
     fn note_obligation_cause_code<T>(
         &self,
         err: &mut Diagnostic,
@@ -2649,6 +2656,138 @@ fn note_obligation_cause_for_async_await(
         );
     }
 
+    /// Adds the full chain of types through which an auto trait obligation was derived, from
+    /// the type named in the failing bound down to the leaf type that does not implement the
+    /// auto trait. This is enabled with `-Z explain-auto-traits`.
+    ///
+    /// In human readable output, the chain is rendered as a single compact tree:
+    ///
+    /// ```text
+    /// = note: `Rc<()>` is not `Send`, which is required by the following chain:
+    ///         `Outer`
+    ///         └─ `Middle` (field)
+    ///            └─ `Rc<()>` (field)
+    /// ```
+    ///
+    /// In JSON output, every step of the chain is emitted as its own child note, pointing at
+    /// the definition of the type when there is one, so that tools can walk the chain without
+    /// parsing the rendered message.
+    #[instrument(level = "debug", skip_all, fields(?obligation.predicate))]
+    fn note_auto_trait_derivation_chain(
+        &self,
+        err: &mut Diagnostic,
+        obligation: &PredicateObligation<'tcx>,
+    ) {
+        let tcx = self.tcx;
+        let ty::PredicateKind::Clause(ty::Clause::Trait(trait_pred)) = obligation.predicate.kind().skip_binder() else {
+            return;
+        };
+        if !tcx.trait_is_auto(trait_pred.def_id()) {
+            return;
+        }
+
+        // Walk the derived obligations outwards, starting with the leaf type. Only the part of
+        // the chain that requires the same auto trait is structural; anything after that (e.g.
+        // a user-written `T: Send` bound on an impl) is explained by the regular notes.
+        let mut chain = vec![self.resolve_vars_if_possible(trait_pred.self_ty())];
+        let mut code = obligation.cause.code();
+        while let Some((parent_code, parent_trait_pred)) = code.parent() {
+            if let Some(parent_trait_pred) = parent_trait_pred {
+                let parent_trait_pred = self.resolve_vars_if_possible(parent_trait_pred);
+                if parent_trait_pred.references_error() {
+                    return;
+                }
+                if parent_trait_pred.def_id() != trait_pred.def_id() {
+                    break;
+                }
+                let ty = parent_trait_pred.skip_binder().self_ty();
+                // Recursive types show up more than once; stop at the first repetition.
+                if chain.contains(&ty) {
+                    break;
+                }
+                chain.push(ty);
+            }
+            code = parent_code;
+        }
+        debug!(?chain);
+
+        // A chain of one type is already fully explained by the primary message.
+        if chain.len() < 2 {
+            return;
+        }
+        chain.reverse();
+
+        // Describes how `chain[idx]` is contained in the type before it.
+        let component_descr = |idx: usize| -> &'static str {
+            let parent = chain[idx - 1];
+            match parent.kind() {
+                ty::Adt(def, _) if def.is_enum() => "variant field",
+                ty::Adt(..) => "field",
+                ty::Generator(..) | ty::Closure(..) => match chain[idx].kind() {
+                    ty::Tuple(..) => "captured upvars",
+                    ty::GeneratorWitness(..) => "generator witness",
+                    _ => "captured upvar",
+                },
+                ty::Tuple(..)
+                    if idx >= 2
+                        && matches!(chain[idx - 2].kind(), ty::Generator(..) | ty::Closure(..)) =>
+                {
+                    "captured upvar"
+                }
+                ty::Tuple(..) => "tuple element",
+                ty::GeneratorWitness(..) => "value held across an await or yield point",
+                ty::Alias(ty::Opaque, ..) => "hidden type",
+                ty::Array(..) | ty::Slice(..) => "element",
+                ty::Ref(..) | ty::RawPtr(..) => "referent",
+                _ => "component",
+            }
+        };
+
+        let trait_name = with_forced_trimmed_paths!(tcx.def_path_str(trait_pred.def_id()));
+        let leaf = chain[chain.len() - 1];
+        let header = with_forced_trimmed_paths!(format!(
+            "`{leaf}` is not `{trait_name}`, which is required by the following chain:"
+        ));
+
+        if let ErrorOutputType::Json { .. } = tcx.sess.opts.error_format {
+            err.note(&header);
+            for (idx, &ty) in chain.iter().enumerate() {
+                let msg = if idx == 0 {
+                    with_forced_trimmed_paths!(format!("`{ty}`"))
+                } else {
+                    with_forced_trimmed_paths!(format!("`{ty}` ({})", component_descr(idx)))
+                };
+                let span = match *ty.kind() {
+                    ty::Adt(def, _) => tcx.opt_item_ident(def.did()).map(|ident| ident.span),
+                    ty::Generator(def_id, ..)
+                    | ty::Closure(def_id, _)
+                    | ty::Alias(ty::Opaque, ty::AliasTy { def_id, .. }) => {
+                        Some(tcx.def_span(def_id))
+                    }
+                    _ => None,
+                };
+                match span {
+                    Some(span) => err.span_note(span, &msg),
+                    None => err.note(&msg),
+                };
+            }
+        } else {
+            let mut msg = header;
+            for (idx, &ty) in chain.iter().enumerate() {
+                msg.push('\n');
+                if idx == 0 {
+                    with_forced_trimmed_paths!(msg.push_str(&format!("`{ty}`")));
+                } else {
+                    msg.push_str(&"   ".repeat(idx - 1));
+                    with_forced_trimmed_paths!(
+                        msg.push_str(&format!("└─ `{ty}` ({})", component_descr(idx)))
+                    );
+                }
+            }
+            err.note(&msg);
+        }
+    }
+
     fn note_obligation_cause_code<T>(
         &self,
         err: &mut Diagnostic,
@@ -276,6 +276,70 @@ fn confirm_transmutability_candidate(
         &mut self,
         obligation: &TraitObligation<'tcx>,
     ) -> Result<ImplSourceBuiltinData<PredicateObligation<'tcx>>, SelectionError<'tcx>> {
+        use rustc_transmute::{Answer, Assume};
+
+        /// Turns the conditions of an answer into the nested obligations of `obligation`.
+        fn flatten_answer_tree<'tcx>(
+            tcx: TyCtxt<'tcx>,
+            obligation: &TraitObligation<'tcx>,
+            assume: &Assume,
+            answer: Answer<rustc_transmute::Ref<'tcx>>,
+        ) -> Result<Vec<PredicateObligation<'tcx>>, SelectionError<'tcx>> {
+            match answer {
+                Answer::Yes => Ok(vec![]),
+                Answer::No(_) => Err(Unimplemented),
+                Answer::IfAll(answers) => {
+                    let mut nested = vec![];
+                    for answer in answers {
+                        nested.extend(flatten_answer_tree(tcx, obligation, assume, answer)?);
+                    }
+                    Ok(nested)
+                }
+                // Disjunctions can't be expressed as nested obligations, so they only hold if
+                // one of their alternatives holds unconditionally.
+                Answer::IfAny(answers) => answers
+                    .into_iter()
+                    .find_map(|answer| {
+                        flatten_answer_tree(tcx, obligation, assume, answer)
+                            .ok()
+                            .filter(|nested| nested.is_empty())
+                    })
+                    .ok_or(Unimplemented),
+                Answer::IfTransmutable { src, dst } => {
+                    let trait_ref = obligation.predicate.skip_binder().trait_ref;
+                    let (scope, assume_const) = (trait_ref.substs[2], trait_ref.substs[3]);
+                    let transmutable =
+                        |src: Ty<'tcx>, dst: Ty<'tcx>| -> PredicateObligation<'tcx> {
+                            let trait_ref = tcx.mk_trait_ref(
+                                trait_ref.def_id,
+                                [dst.into(), src.into(), scope, assume_const],
+                            );
+                            obligation.with(tcx, obligation.predicate.rebind(trait_ref))
+                        };
+
+                    // The length of a reference to a slice counts elements, which are of the same
+                    // size in both referents, so the slices are transmutable if their elements are.
+                    let referent = |r: rustc_transmute::Ref<'tcx>| match r.ty.kind() {
+                        ty::Slice(elem) => *elem,
+                        _ => r.ty,
+                    };
+                    let (src_referent, dst_referent) = (referent(src), referent(dst));
+
+                    // The referent of `src` must be transmutable into the referent of `dst`, and
+                    // since a mutable `dst` can be written through, also the other way around.
+                    let mut nested = vec![transmutable(src_referent, dst_referent)];
+                    if dst.mutability.is_mut() {
+                        nested.push(transmutable(dst_referent, src_referent));
+                    }
+                    if !assume.lifetimes {
+                        let outlives = ty::OutlivesPredicate(src.lifetime, dst.lifetime);
+                        nested.push(obligation.with(tcx, obligation.predicate.rebind(outlives)));
+                    }
+                    Ok(nested)
+                }
+            }
+        }
+
         debug!(?obligation, "confirm_transmutability_candidate");
 
         let predicate = obligation.predicate;
@@ -291,7 +355,7 @@ fn confirm_transmutability_candidate(
         let scope = type_at(2).skip_binder();
 
         let Some(assume) =
-            rustc_transmute::Assume::from_const(self.infcx.tcx, obligation.param_env, const_at(3)) else {
+            Assume::from_const(self.infcx.tcx, obligation.param_env, const_at(3)) else {
                 return Err(Unimplemented);
             };
 
@@ -299,14 +363,12 @@ fn confirm_transmutability_candidate(
 
         let mut transmute_env = rustc_transmute::TransmuteTypeEnv::new(self.infcx);
 
-        let maybe_transmutable = transmute_env.is_transmutable(cause, src_and_dst, scope, assume);
+        let maybe_transmutable =
+            transmute_env.is_transmutable(cause, src_and_dst, scope, assume);
 
-        use rustc_transmute::Answer;
-
-        match maybe_transmutable {
-            Answer::Yes => Ok(ImplSourceBuiltinData { nested: vec![] }),
-            _ => Err(Unimplemented),
-        }
+        debug!(?maybe_transmutable);
+        let nested = flatten_answer_tree(self.tcx(), obligation, &assume, maybe_transmutable)?;
+        Ok(ImplSourceBuiltinData { nested })
     }
 
     /// This handles the case where an `auto trait Foo` impl is being used.
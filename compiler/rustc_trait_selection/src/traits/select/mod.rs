@@ -59,6 +59,12 @@
 
 mod candidate_assembly;
 mod confirmation;
+mod proof_tree;
+
+use self::proof_tree::{
+    describe_candidate, describe_evaluation, describe_selection, describe_selection_error,
+    ProofNodeKind, ProofTreeBuilder,
+};
 
 #[derive(Clone, Debug, Eq, PartialEq, Hash)]
 pub enum IntercrateAmbiguityCause {
@@ -123,6 +129,9 @@ pub struct SelectionContext<'cx, 'tcx> {
     /// policy. In essence, canonicalized queries need their errors propagated
     /// rather than immediately reported because we do not have accurate spans.
     query_mode: TraitQueryMode,
+
+    /// The proof tree being recorded for `-Z dump-trait-proof-tree`, if any.
+    proof_tree: Option<ProofTreeBuilder>,
 }
 
 // A stack that walks back up the stack frame.
@@ -210,6 +219,7 @@ pub fn new(infcx: &'cx InferCtxt<'tcx>) -> SelectionContext<'cx, 'tcx> {
             freshener: infcx.freshener_keep_static(),
             intercrate_ambiguity_causes: None,
             query_mode: TraitQueryMode::Standard,
+            proof_tree: None,
         }
     }
 
@@ -267,6 +277,19 @@ pub fn is_intercrate(&self) -> bool {
     pub fn select(
         &mut self,
         obligation: &TraitObligation<'tcx>,
+    ) -> SelectionResult<'tcx, Selection<'tcx>> {
+        let started = self.proof_tree_start(&obligation.cause, obligation.predicate);
+        let opened = self
+            .proof_tree_open(ProofNodeKind::Goal, || format!("select `{}`", obligation.predicate));
+        let result = self.select_inner(obligation);
+        self.proof_tree_close(opened, || describe_selection(&result));
+        self.proof_tree_finish(started);
+        result
+    }
+
+    fn select_inner(
+        &mut self,
+        obligation: &TraitObligation<'tcx>,
     ) -> SelectionResult<'tcx, Selection<'tcx>> {
         let candidate = match self.select_from_obligation(obligation) {
             Err(SelectionError::Overflow(OverflowError::Canonical)) => {
@@ -284,7 +307,22 @@ pub fn select(
             Ok(Some(candidate)) => candidate,
         };
 
-        match self.confirm_candidate(obligation, candidate) {
+        let opened = self.proof_tree_open(ProofNodeKind::Candidate, || {
+            format!("confirm {}", describe_candidate(self.tcx(), &candidate))
+        });
+        let result = self.confirm_candidate(obligation, candidate);
+        if let Ok(selection) = &result {
+            for nested in selection.borrow_nested_obligations() {
+                self.proof_tree_leaf(ProofNodeKind::Nested, || {
+                    format!("nested `{}`", nested.predicate)
+                });
+            }
+        }
+        self.proof_tree_close(opened, || match &result {
+            Ok(_) => "confirmed".to_string(),
+            Err(e) => describe_selection_error(e),
+        });
+        match result {
             Err(SelectionError::Overflow(OverflowError::Canonical)) => {
                 assert!(self.query_mode == TraitQueryMode::Canonical);
                 Err(SelectionError::Overflow(OverflowError::Canonical))
@@ -327,6 +365,12 @@ fn candidate_from_obligation<'o>(
             self.check_candidate_cache(stack.obligation.param_env, cache_fresh_trait_pred)
         {
             debug!("CACHE HIT");
+            self.proof_tree_leaf(ProofNodeKind::Cached, || match &c {
+                Ok(Some(candidate)) => {
+                    format!("cached: {}", describe_candidate(self.tcx(), candidate))
+                }
+                _ => format!("cached: {}", describe_selection(&c)),
+            });
             return c;
         }
 
@@ -400,6 +444,20 @@ fn candidate_from_obligation_no_cache<'o>(
 
         let candidate_set = self.assemble_candidates(stack)?;
 
+        let opened = self.proof_tree_open(ProofNodeKind::Assembly, || {
+            "assemble candidates".to_string()
+        });
+        for candidate in &candidate_set.vec {
+            self.proof_tree_leaf(ProofNodeKind::Candidate, || {
+                describe_candidate(self.tcx(), candidate)
+            });
+        }
+        self.proof_tree_close(opened, || match candidate_set.vec.len() {
+            _ if candidate_set.ambiguous => "ambiguous".to_string(),
+            1 => "1 candidate".to_string(),
+            n => format!("{} candidates", n),
+        });
+
         if candidate_set.ambiguous {
             debug!("candidate set contains ambig");
             return Ok(None);
@@ -543,12 +601,15 @@ pub fn evaluate_root_obligation(
         &mut self,
         obligation: &PredicateObligation<'tcx>,
     ) -> Result<EvaluationResult, OverflowError> {
-        self.evaluation_probe(|this| {
+        let started = self.proof_tree_start(&obligation.cause, obligation.predicate);
+        let result = self.evaluation_probe(|this| {
             this.evaluate_predicate_recursively(
                 TraitObligationStackList::empty(&ProvisionalEvaluationCache::default()),
                 obligation.clone(),
             )
-        })
+        });
+        self.proof_tree_finish(started);
+        result
     }
 
     fn evaluation_probe(
@@ -620,7 +681,10 @@ fn evaluate_predicate_recursively<'o>(
             None => self.check_recursion_limit(&obligation, &obligation)?,
         }
 
-        ensure_sufficient_stack(|| {
+        let opened = self.proof_tree_open(ProofNodeKind::Goal, || {
+            format!("evaluate `{}`", obligation.predicate)
+        });
+        let result = ensure_sufficient_stack(|| {
             let bound_predicate = obligation.predicate.kind();
             match bound_predicate.skip_binder() {
                 ty::PredicateKind::Clause(ty::Clause::Trait(t)) => {
@@ -964,7 +1028,9 @@ fn evaluate_predicate_recursively<'o>(
                 }
                 ty::PredicateKind::Ambiguous => Ok(EvaluatedToAmbig),
             }
-        })
+        });
+        self.proof_tree_close(opened, || describe_evaluation(&result));
+        result
     }
 
     #[instrument(skip(self, previous_stack), level = "debug", ret)]
@@ -999,11 +1065,17 @@ fn evaluate_trait_predicate_recursively<'o>(
         // then we know it holds without cycles.
         if let Some(result) = self.check_evaluation_cache(param_env, fresh_trait_pred) {
             debug!("CACHE HIT");
+            self.proof_tree_leaf(ProofNodeKind::Cached, || {
+                format!("cached: {}", describe_evaluation(&Ok(result)))
+            });
             return Ok(result);
         }
 
         if let Some(result) = stack.cache().get_provisional(fresh_trait_pred) {
             debug!("PROVISIONAL CACHE HIT");
+            self.proof_tree_leaf(ProofNodeKind::Cached, || {
+                format!("provisionally cached: {}", describe_evaluation(&Ok(result.result)))
+            });
             stack.update_reached_depth(result.reached_depth);
             return Ok(result.result);
         }
@@ -1014,6 +1086,10 @@ fn evaluate_trait_predicate_recursively<'o>(
         // cache (which is meant for things that have completed but
         // for a "backedge" -- this result *is* the backedge).
         if let Some(cycle_result) = self.check_evaluation_cycle(&stack) {
+            self.proof_tree_leaf(ProofNodeKind::Cycle, || {
+                let kind = if cycle_result == EvaluatedToOk { "coinductive" } else { "inductive" };
+                format!("`{}` is already on the stack ({} cycle)", obligation.predicate, kind)
+            });
             return Ok(cycle_result);
         }
 
@@ -1147,6 +1223,12 @@ fn evaluate_stack<'o>(
             })
         {
             debug!("evaluate_stack --> unbound argument, recursive --> giving up",);
+            self.proof_tree_leaf(ProofNodeKind::Cycle, || {
+                format!(
+                    "`{}` matches a goal on the stack up to inference variables",
+                    stack.obligation.predicate
+                )
+            });
             return Ok(EvaluatedToUnknown);
         }
 
@@ -1187,6 +1269,19 @@ fn evaluate_candidate<'o>(
         &mut self,
         stack: &TraitObligationStack<'o, 'tcx>,
         candidate: &SelectionCandidate<'tcx>,
+    ) -> Result<EvaluationResult, OverflowError> {
+        let opened = self.proof_tree_open(ProofNodeKind::Candidate, || {
+            format!("evaluate {}", describe_candidate(self.tcx(), candidate))
+        });
+        let result = self.evaluate_candidate_inner(stack, candidate);
+        self.proof_tree_close(opened, || describe_evaluation(&result));
+        result
+    }
+
+    fn evaluate_candidate_inner<'o>(
+        &mut self,
+        stack: &TraitObligationStack<'o, 'tcx>,
+        candidate: &SelectionCandidate<'tcx>,
     ) -> Result<EvaluationResult, OverflowError> {
         let mut result = self.evaluation_probe(|this| {
             let candidate = (*candidate).clone();
@@ -1308,6 +1403,8 @@ fn check_recursion_depth<T>(
                     if let Some(e) = self.infcx.tainted_by_errors() {
                         return Err(OverflowError::Error(e));
                     }
+                    // Reporting overflow aborts, so this is the last chance to print the tree.
+                    self.proof_tree_print_incomplete();
                     self.infcx.err_ctxt().report_overflow_obligation(error_obligation, true);
                 }
                 TraitQueryMode::Canonical => {
@@ -1331,6 +1428,14 @@ fn check_recursion_limit<T: Display + TypeFoldable<'tcx>, V>(
     where
         V: ToPredicate<'tcx> + Clone,
     {
+        if !self.infcx.tcx.recursion_limit().value_within_limit(obligation.recursion_depth) {
+            self.proof_tree_leaf(ProofNodeKind::Overflow, || {
+                format!(
+                    "`{}` at depth {} exceeds the recursion limit",
+                    obligation.predicate, obligation.recursion_depth
+                )
+            });
+        }
         self.check_recursion_depth(obligation.recursion_depth, error_obligation)
     }
 
@@ -0,0 +1,358 @@
+//! Recording of the selection search for `-Z dump-trait-proof-tree`.
+//!
+//! When that option names the item an obligation comes from, the selection
+//! context records each step taken to select or evaluate the obligation: the
+//! candidates that were assembled, the candidates that were evaluated or
+//! confirmed, and the nested obligations that were evaluated recursively.
+//! Cycles and overflow are recorded as marked leaves of the tree.
+//!
+//! The nested obligations that confirming the selected candidate returns are
+//! recorded as leaves below that candidate. They aren't evaluated there, because
+//! that could change the evaluation cache or overflow where compilation wouldn't.
+//! Instead, each of them gets a tree of its own once the fulfillment context
+//! selects it, which names the obligation it is nested in.
+//!
+//! Once the root obligation has been selected or evaluated, the tree is
+//! printed to stdout, either as indented text or as a graphviz graph. The
+//! fulfillment context selects ambiguous obligations again each time it makes
+//! progress, so the tree of each predicate is only printed once.
+
+use super::{EvaluationResult, OverflowError, SelectionCandidate, SelectionContext};
+use crate::errors::CouldntDumpTraitProofTree;
+use crate::traits::{ObligationCause, SelectionError, SelectionResult};
+
+use rustc_graphviz as dot;
+use rustc_middle::ty::{TyCtxt, TypeFoldable};
+use rustc_session::config::TraitProofTreeFormat;
+use rustc_session::Session;
+
+use std::borrow::Cow;
+use std::cell::RefCell;
+use std::fmt;
+use std::io::{self, Write};
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub(super) enum ProofNodeKind {
+    /// Selecting or evaluating an obligation.
+    Goal,
+    /// The candidates that were assembled for the enclosing goal.
+    Assembly,
+    /// A single candidate, which is either evaluated or confirmed.
+    Candidate,
+    /// A nested obligation that confirmation returns to the caller.
+    Nested,
+    /// A result that was taken from one of the caches.
+    Cached,
+    /// A goal that is already on the stack.
+    Cycle,
+    /// A goal that exceeds the recursion limit.
+    Overflow,
+}
+
+#[derive(Debug)]
+struct ProofNode {
+    kind: ProofNodeKind,
+    label: String,
+    /// The result of the node, once it has been computed. Leaves have no outcome.
+    outcome: Option<String>,
+    children: Vec<usize>,
+}
+
+impl ProofNode {
+    fn text_label(&self) -> Cow<'_, str> {
+        match self.kind {
+            ProofNodeKind::Cycle => format!("[cycle] {}", self.label).into(),
+            ProofNodeKind::Overflow => format!("[overflow] {}", self.label).into(),
+            _ => self.label.as_str().into(),
+        }
+    }
+}
+
+/// The proof tree of a single root obligation.
+#[derive(Debug)]
+pub(super) struct ProofTreeBuilder {
+    /// The item the root obligation comes from.
+    item: String,
+    /// The obligation the root obligation is nested in, if any.
+    parent: Option<String>,
+    nodes: RefCell<Vec<ProofNode>>,
+    /// The nodes without a parent.
+    roots: RefCell<Vec<usize>>,
+    /// The nodes which are still being computed, innermost last.
+    open: RefCell<Vec<usize>>,
+}
+
+impl ProofTreeBuilder {
+    fn new(item: String, parent: Option<String>) -> ProofTreeBuilder {
+        ProofTreeBuilder {
+            item,
+            parent,
+            nodes: RefCell::new(vec![]),
+            roots: RefCell::new(vec![]),
+            open: RefCell::new(vec![]),
+        }
+    }
+
+    /// Adds a node below the innermost open node, and returns its index.
+    fn add(&self, kind: ProofNodeKind, label: String) -> usize {
+        let mut nodes = self.nodes.borrow_mut();
+        let index = nodes.len();
+        nodes.push(ProofNode { kind, label, outcome: None, children: vec![] });
+        match self.open.borrow().last() {
+            Some(&parent) => nodes[parent].children.push(index),
+            None => self.roots.borrow_mut().push(index),
+        }
+        index
+    }
+
+    fn open(&self, kind: ProofNodeKind, label: String) {
+        let index = self.add(kind, label);
+        self.open.borrow_mut().push(index);
+    }
+
+    fn close(&self, outcome: String) {
+        let index = self.open.borrow_mut().pop().expect("no open proof tree node to close");
+        self.nodes.borrow_mut()[index].outcome = Some(outcome);
+    }
+
+    fn print(&self, sess: &Session) {
+        let stdout = io::stdout();
+        let mut out = stdout.lock();
+        let result = match sess.opts.unstable_opts.dump_trait_proof_tree_format {
+            TraitProofTreeFormat::Text => self.write_text(&mut out),
+            TraitProofTreeFormat::Dot => dot::render(self, &mut out),
+        };
+        if let Err(error) = result.and_then(|()| out.flush()) {
+            sess.emit_err(CouldntDumpTraitProofTree { error: error.to_string() });
+        }
+    }
+
+    fn write_text(&self, out: &mut impl Write) -> io::Result<()> {
+        match &self.parent {
+            Some(parent) => writeln!(
+                out,
+                "trait proof tree for an obligation in `{}`, nested in `{}`:",
+                self.item, parent
+            )?,
+            None => writeln!(out, "trait proof tree for an obligation in `{}`:", self.item)?,
+        }
+        let nodes = self.nodes.borrow();
+        let open = self.open.borrow();
+        let mut worklist: Vec<_> = self.roots.borrow().iter().rev().map(|&n| (n, 1)).collect();
+        while let Some((index, depth)) = worklist.pop() {
+            let node = &nodes[index];
+            write!(out, "{:indent$}{}", "", node.text_label(), indent = depth * 2)?;
+            match &node.outcome {
+                Some(outcome) => writeln!(out, " => {}", outcome)?,
+                // The tree is printed before this node was computed, because of a fatal error.
+                None if open.contains(&index) => writeln!(out, " => ...")?,
+                None => writeln!(out)?,
+            }
+            worklist.extend(node.children.iter().rev().map(|&child| (child, depth + 1)));
+        }
+        Ok(())
+    }
+}
+
+impl<'a> dot::Labeller<'a> for ProofTreeBuilder {
+    type Node = usize;
+    type Edge = (usize, usize);
+
+    fn graph_id(&'a self) -> dot::Id<'a> {
+        dot::Id::new("trait_proof_tree").unwrap()
+    }
+
+    fn node_id(&'a self, n: &usize) -> dot::Id<'a> {
+        dot::Id::new(format!("n{}", n)).unwrap()
+    }
+
+    fn node_label(&'a self, n: &usize) -> dot::LabelText<'a> {
+        let node = &self.nodes.borrow()[*n];
+        let label = match &node.outcome {
+            Some(outcome) => format!("{} => {}", node.text_label(), outcome),
+            None => node.text_label().into_owned(),
+        };
+        dot::LabelText::LabelStr(label.into())
+    }
+
+    fn node_shape(&'a self, n: &usize) -> Option<dot::LabelText<'a>> {
+        let shape = match self.nodes.borrow()[*n].kind {
+            ProofNodeKind::Goal => "box",
+            ProofNodeKind::Cycle | ProofNodeKind::Overflow => "octagon",
+            _ => "ellipse",
+        };
+        Some(dot::LabelText::LabelStr(shape.into()))
+    }
+
+    fn node_style(&'a self, n: &usize) -> dot::Style {
+        match self.nodes.borrow()[*n].kind {
+            ProofNodeKind::Cycle | ProofNodeKind::Overflow => dot::Style::Bold,
+            ProofNodeKind::Cached | ProofNodeKind::Nested => dot::Style::Dashed,
+            _ => dot::Style::None,
+        }
+    }
+}
+
+impl<'a> dot::GraphWalk<'a> for ProofTreeBuilder {
+    type Node = usize;
+    type Edge = (usize, usize);
+
+    fn nodes(&'a self) -> dot::Nodes<'a, usize> {
+        (0..self.nodes.borrow().len()).collect()
+    }
+
+    fn edges(&'a self) -> dot::Edges<'a, (usize, usize)> {
+        self.nodes
+            .borrow()
+            .iter()
+            .enumerate()
+            .flat_map(|(parent, node)| node.children.iter().map(move |&child| (parent, child)))
+            .collect()
+    }
+
+    fn source(&'a self, edge: &(usize, usize)) -> usize {
+        edge.0
+    }
+
+    fn target(&'a self, edge: &(usize, usize)) -> usize {
+        edge.1
+    }
+}
+
+impl<'cx, 'tcx> SelectionContext<'cx, 'tcx> {
+    /// Starts recording a proof tree for a root obligation with the given `cause` and
+    /// `predicate`, if no tree is being recorded yet, the obligation comes from the item named
+    /// by `-Z dump-trait-proof-tree`, and the tree of the same predicate wasn't printed yet.
+    ///
+    /// Returns whether recording was started, in which case the tree must be printed with
+    /// [`Self::proof_tree_finish`] once the root obligation is done.
+    pub(super) fn proof_tree_start(
+        &mut self,
+        cause: &ObligationCause<'tcx>,
+        predicate: impl TypeFoldable<'tcx> + fmt::Display,
+    ) -> bool {
+        let tcx = self.tcx();
+        let Some(wanted) = &tcx.sess.opts.unstable_opts.dump_trait_proof_tree else {
+            return false;
+        };
+        if self.proof_tree.is_some() {
+            return false;
+        }
+        // Match either the full path of the item, or only its name.
+        let owner = cause.body_id.owner.to_def_id();
+        let item = tcx.def_path_str(owner);
+        if item != *wanted && tcx.opt_item_name(owner).map_or(true, |name| name.as_str() != wanted)
+        {
+            return false;
+        }
+        let predicate = self.infcx.resolve_vars_if_possible(predicate).to_string();
+        if !tcx.sess.dumped_trait_proof_trees.lock().insert((item.clone(), predicate)) {
+            return false;
+        }
+        let parent = match cause.code().parent() {
+            Some((_, Some(parent))) => {
+                Some(self.infcx.resolve_vars_if_possible(parent).to_string())
+            }
+            _ => None,
+        };
+        self.proof_tree = Some(ProofTreeBuilder::new(item, parent));
+        true
+    }
+
+    /// Prints and stops recording the proof tree started by [`Self::proof_tree_start`].
+    pub(super) fn proof_tree_finish(&mut self, started: bool) {
+        if started {
+            let tree = self.proof_tree.take().unwrap();
+            tree.print(self.tcx().sess);
+        }
+    }
+
+    /// Adds a node below the innermost open node of the proof tree being recorded, if any.
+    ///
+    /// Returns whether a node was opened, in which case it must be closed with
+    /// [`Self::proof_tree_close`].
+    pub(super) fn proof_tree_open(
+        &self,
+        kind: ProofNodeKind,
+        label: impl FnOnce() -> String,
+    ) -> bool {
+        match &self.proof_tree {
+            Some(tree) => {
+                tree.open(kind, label());
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Closes the node opened by [`Self::proof_tree_open`], recording its outcome.
+    pub(super) fn proof_tree_close(&self, opened: bool, outcome: impl FnOnce() -> String) {
+        if opened {
+            self.proof_tree.as_ref().unwrap().close(outcome());
+        }
+    }
+
+    /// Adds a leaf below the innermost open node of the proof tree being recorded, if any.
+    pub(super) fn proof_tree_leaf(&self, kind: ProofNodeKind, label: impl FnOnce() -> String) {
+        if let Some(tree) = &self.proof_tree {
+            tree.add(kind, label());
+        }
+    }
+
+    /// Prints the proof tree being recorded, if any, before it is complete. This is used
+    /// right before reporting overflow, which aborts compilation.
+    pub(super) fn proof_tree_print_incomplete(&self) {
+        if let Some(tree) = &self.proof_tree {
+            tree.print(self.tcx().sess);
+        }
+    }
+}
+
+/// Describes a candidate without printing `DefId`s.
+pub(super) fn describe_candidate<'tcx>(
+    tcx: TyCtxt<'tcx>,
+    candidate: &SelectionCandidate<'tcx>,
+) -> String {
+    match candidate {
+        SelectionCandidate::ImplCandidate(def_id) => {
+            format!("impl candidate `{}`", tcx.def_path_str(*def_id))
+        }
+        SelectionCandidate::ParamCandidate(predicate) => {
+            format!("where-clause candidate `{}`", predicate)
+        }
+        SelectionCandidate::ConstDestructCandidate(Some(def_id)) => {
+            format!("const destruct candidate `{}`", tcx.def_path_str(*def_id))
+        }
+        _ => format!("{:?}", candidate),
+    }
+}
+
+pub(super) fn describe_selection<T>(result: &SelectionResult<'_, T>) -> String {
+    match result {
+        Ok(Some(_)) => "selected".to_string(),
+        Ok(None) => "ambiguous".to_string(),
+        Err(err) => describe_selection_error(err),
+    }
+}
+
+pub(super) fn describe_selection_error(err: &SelectionError<'_>) -> String {
+    match err {
+        SelectionError::Unimplemented => "unimplemented".to_string(),
+        SelectionError::Overflow(_) => "overflow".to_string(),
+        _ => format!("error: {:?}", err),
+    }
+}
+
+pub(super) fn describe_evaluation(result: &Result<EvaluationResult, OverflowError>) -> String {
+    match result {
+        Ok(EvaluationResult::EvaluatedToOk) => "holds",
+        Ok(EvaluationResult::EvaluatedToOkModuloRegions) => "holds modulo regions",
+        Ok(EvaluationResult::EvaluatedToOkModuloOpaqueTypes) => "holds modulo opaque types",
+        Ok(EvaluationResult::EvaluatedToAmbig) => "ambiguous",
+        Ok(EvaluationResult::EvaluatedToUnknown) => "unknown",
+        Ok(EvaluationResult::EvaluatedToRecur) => "recursive",
+        Ok(EvaluationResult::EvaluatedToErr) => "does not hold",
+        Err(_) => "overflow",
+    }
+    .to_string()
+}
@@ -30,10 +30,38 @@ fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
 }
 
 pub(crate) trait Def: Debug + Hash + Eq + PartialEq + Copy + Clone {}
-pub trait Ref: Debug + Hash + Eq + PartialEq + Copy + Clone {}
+pub trait Ref: Debug + Hash + Eq + PartialEq + Copy + Clone {
+    /// The alignment that the referent is guaranteed to have, in bytes.
+    fn min_align(&self) -> usize;
+
+    /// The size of the reference itself, in bytes.
+    fn size(&self) -> usize;
+
+    /// The size of the elements of the referent in bytes, if the referent is a slice. A
+    /// reference to a slice is wide: besides the address, it holds the number of elements.
+    fn elem_size(&self) -> Option<usize>;
+
+    fn is_mutable(&self) -> bool;
+}
 
 impl Def for ! {}
-impl Ref for ! {}
+impl Ref for ! {
+    fn min_align(&self) -> usize {
+        *self
+    }
+
+    fn size(&self) -> usize {
+        *self
+    }
+
+    fn elem_size(&self) -> Option<usize> {
+        *self
+    }
+
+    fn is_mutable(&self) -> bool {
+        *self
+    }
+}
 
 #[cfg(feature = "rustc")]
 pub(crate) mod rustc {
@@ -45,16 +73,33 @@ pub(crate) mod rustc {
     /// A reference in the layout.
     #[derive(Debug, Hash, Eq, PartialEq, PartialOrd, Ord, Clone, Copy)]
     pub struct Ref<'tcx> {
-        lifetime: Region<'tcx>,
-        ty: Ty<'tcx>,
-        mutability: Mutability,
+        pub lifetime: Region<'tcx>,
+        /// The type of the referent.
+        pub ty: Ty<'tcx>,
+        pub mutability: Mutability,
+        /// The ABI alignment of the referent.
+        pub align: usize,
+        /// The size of the reference.
+        pub size: usize,
+        /// The size of the elements of the referent, if it is a slice.
+        pub elem_size: Option<usize>,
     }
 
-    impl<'tcx> super::Ref for Ref<'tcx> {}
+    impl<'tcx> super::Ref for Ref<'tcx> {
+        fn min_align(&self) -> usize {
+            self.align
+        }
+
+        fn size(&self) -> usize {
+            self.size
+        }
+
+        fn elem_size(&self) -> Option<usize> {
+            self.elem_size
+        }
 
-    impl<'tcx> Ref<'tcx> {
-        pub fn min_align(&self) -> usize {
-            todo!()
+        fn is_mutable(&self) -> bool {
+            self.mutability == Mutability::Mut
         }
     }
 
@@ -173,6 +173,9 @@ pub(crate) enum Err {
     Unspecified,
     /// This error will be surfaced elsewhere by rustc, so don't surface it.
     Unknown,
+    /// The type contains a reference to an unsized type other than a slice, which is not
+    /// supported yet.
+    UnsizedReferent,
 }
 
 #[cfg(feature = "rustc")]
@@ -181,16 +184,14 @@ pub(crate) mod rustc {
     use crate::layout::rustc::{Def, Ref};
 
     use rustc_middle::ty;
-    use rustc_middle::ty::layout::LayoutError;
+    use rustc_middle::ty::layout::{LayoutCx, LayoutError, LayoutOf, TyAndLayout};
     use rustc_middle::ty::util::Discr;
-    use rustc_middle::ty::AdtDef;
     use rustc_middle::ty::ParamEnv;
     use rustc_middle::ty::SubstsRef;
     use rustc_middle::ty::Ty;
     use rustc_middle::ty::TyCtxt;
     use rustc_middle::ty::VariantDef;
-    use rustc_target::abi::Align;
-    use std::alloc;
+    use rustc_target::abi::{Size, TagEncoding, Variants};
 
     impl<'tcx> From<LayoutError<'tcx>> for Err {
         fn from(err: LayoutError<'tcx>) -> Self {
@@ -201,59 +202,6 @@ fn from(err: LayoutError<'tcx>) -> Self {
         }
     }
 
-    trait LayoutExt {
-        fn clamp_align(&self, min_align: Align, max_align: Align) -> Self;
-    }
-
-    impl LayoutExt for alloc::Layout {
-        fn clamp_align(&self, min_align: Align, max_align: Align) -> Self {
-            let min_align = min_align.bytes().try_into().unwrap();
-            let max_align = max_align.bytes().try_into().unwrap();
-            Self::from_size_align(self.size(), self.align().clamp(min_align, max_align)).unwrap()
-        }
-    }
-
-    struct LayoutSummary {
-        total_align: Align,
-        total_size: usize,
-        discriminant_size: usize,
-        discriminant_align: Align,
-    }
-
-    impl LayoutSummary {
-        fn from_ty<'tcx>(ty: Ty<'tcx>, ctx: TyCtxt<'tcx>) -> Result<Self, LayoutError<'tcx>> {
-            use rustc_middle::ty::ParamEnvAnd;
-            use rustc_target::abi::{TyAndLayout, Variants};
-
-            let param_env = ParamEnv::reveal_all();
-            let param_env_and_type = ParamEnvAnd { param_env, value: ty };
-            let TyAndLayout { layout, .. } = ctx.layout_of(param_env_and_type)?;
-
-            let total_size: usize = layout.size().bytes_usize();
-            let total_align: Align = layout.align().abi;
-            let discriminant_align: Align;
-            let discriminant_size: usize;
-
-            if let Variants::Multiple { tag, .. } = layout.variants() {
-                discriminant_align = tag.align(&ctx).abi;
-                discriminant_size = tag.size(&ctx).bytes_usize();
-            } else {
-                discriminant_align = Align::ONE;
-                discriminant_size = 0;
-            };
-
-            Ok(Self { total_align, total_size, discriminant_align, discriminant_size })
-        }
-
-        fn into(&self) -> alloc::Layout {
-            alloc::Layout::from_size_align(
-                self.total_size,
-                self.total_align.bytes().try_into().unwrap(),
-            )
-            .unwrap()
-        }
-    }
-
     impl<'tcx> Tree<Def<'tcx>, Ref<'tcx>> {
         pub fn from_ty(ty: Ty<'tcx>, tcx: TyCtxt<'tcx>) -> Result<Self, Err> {
             use rustc_middle::ty::FloatTy::*;
@@ -292,6 +240,28 @@ pub fn from_ty(ty: Ty<'tcx>, tcx: TyCtxt<'tcx>) -> Result<Self, Err> {
                         .fold(Tree::unit(), |tree, elt| tree.then(elt)))
                 }
 
+                ty::Ref(lifetime, referent, mutability) => {
+                    // Besides thin references, only references to slices are supported, whose
+                    // metadata is the number of elements; the layout of other metadata, and what
+                    // it means for the referent, aren't modeled yet.
+                    let elem_size = match referent.kind() {
+                        ty::Slice(elem) => Some(layout_of(tcx, *elem)?.size.bytes_usize()),
+                        _ if tcx.erase_regions(*referent).is_sized(tcx, ParamEnv::reveal_all()) => {
+                            None
+                        }
+                        _ => return Err(Err::UnsizedReferent),
+                    };
+                    let referent_layout = layout_of(tcx, *referent)?;
+                    Ok(Tree::Ref(Ref {
+                        lifetime: *lifetime,
+                        ty: *referent,
+                        mutability: *mutability,
+                        align: referent_layout.align.abi.bytes_usize(),
+                        size: layout_of(tcx, ty)?.size.bytes_usize(),
+                        elem_size,
+                    }))
+                }
+
                 ty::Adt(adt_def, substs_ref) => {
                     use rustc_middle::ty::AdtKind;
 
@@ -300,35 +270,53 @@ pub fn from_ty(ty: Ty<'tcx>, tcx: TyCtxt<'tcx>) -> Result<Self, Err> {
                         return Err(Err::Unspecified);
                     }
 
-                    // Compute a summary of the type's layout.
-                    let layout_summary = LayoutSummary::from_ty(ty, tcx)?;
+                    let layout = layout_of(tcx, ty)?;
 
                     // The layout begins with this adt's visibility.
                     let vis = Self::def(Def::Adt(*adt_def));
 
                     // And is followed the layout(s) of its variants
                     Ok(vis.then(match adt_def.adt_kind() {
-                        AdtKind::Struct => Self::from_repr_c_variant(
-                            ty,
-                            *adt_def,
-                            substs_ref,
-                            &layout_summary,
+                        AdtKind::Struct => Self::from_variant(
+                            layout,
+                            layout.size,
                             None,
                             adt_def.non_enum_variant(),
+                            substs_ref,
                             tcx,
                         )?,
                         AdtKind::Enum => {
                             trace!(?adt_def, "treeifying enum");
+                            let cx = LayoutCx { tcx, param_env: ParamEnv::reveal_all() };
                             let mut tree = Tree::uninhabited();
 
                             for (idx, discr) in adt_def.discriminants(tcx) {
-                                tree = tree.or(Self::from_repr_c_variant(
-                                    ty,
-                                    *adt_def,
-                                    substs_ref,
-                                    &layout_summary,
-                                    Some(discr),
+                                // The tag, if any, is the same for all variants, but its value
+                                // is that of the variant.
+                                let tag = match layout.variants {
+                                    Variants::Single { index } if index != idx => continue,
+                                    Variants::Single { .. } => None,
+                                    Variants::Multiple {
+                                        tag,
+                                        tag_encoding: TagEncoding::Direct,
+                                        tag_field,
+                                        ..
+                                    } => {
+                                        let size = tag.size(&tcx);
+                                        let bytes =
+                                            Self::from_discr(discr, tcx, size.bytes_usize());
+                                        Some((layout.fields.offset(tag_field), size, bytes))
+                                    }
+                                    // Niches are not used by enums with a well-defined layout.
+                                    Variants::Multiple { .. } => return Err(Err::Unspecified),
+                                };
+
+                                tree = tree.or(Self::from_variant(
+                                    layout.for_variant(&cx, idx),
+                                    layout.size,
+                                    tag,
                                     adt_def.variant(idx),
+                                    substs_ref,
                                     tcx,
                                 )?);
                             }
@@ -341,17 +329,15 @@ pub fn from_ty(ty: Ty<'tcx>, tcx: TyCtxt<'tcx>) -> Result<Self, Err> {
                                 return Err(Err::Unspecified);
                             }
 
-                            let ty_layout = layout_of(tcx, ty)?;
-
                             let mut tree = Tree::uninhabited();
 
                             for field in adt_def.all_fields() {
                                 let variant_ty = field.ty(tcx, substs_ref);
                                 let variant_layout = layout_of(tcx, variant_ty)?;
-                                let padding_needed = ty_layout.size() - variant_layout.size();
+                                let padding_needed = layout.size - variant_layout.size;
                                 let variant = Self::def(Def::Field(field))
                                     .then(Self::from_ty(variant_ty, tcx)?)
-                                    .then(Self::padding(padding_needed));
+                                    .then(Self::padding(padding_needed.bytes_usize()));
 
                                 tree = tree.or(variant);
                             }
@@ -364,85 +350,45 @@ pub fn from_ty(ty: Ty<'tcx>, tcx: TyCtxt<'tcx>) -> Result<Self, Err> {
             }
         }
 
-        fn from_repr_c_variant(
-            ty: Ty<'tcx>,
-            adt_def: AdtDef<'tcx>,
-            substs_ref: SubstsRef<'tcx>,
-            layout_summary: &LayoutSummary,
-            discr: Option<Discr<'tcx>>,
+        /// Lays out the tag, if any, and the fields of a variant at the offsets that rustc
+        /// computed for them, with padding in between and up to `total_size`.
+        fn from_variant(
+            layout: TyAndLayout<'tcx>,
+            total_size: Size,
+            tag: Option<(Size, Size, Self)>,
             variant_def: &'tcx VariantDef,
+            substs_ref: SubstsRef<'tcx>,
             tcx: TyCtxt<'tcx>,
         ) -> Result<Self, Err> {
-            let mut tree = Tree::unit();
+            let _variant_span = trace_span!("treeifying variant", ?layout).entered();
 
-            let repr = adt_def.repr();
-            let min_align = repr.align.unwrap_or(Align::ONE);
-            let max_align = repr.pack.unwrap_or(Align::MAX);
-
-            let clamp =
-                |align: Align| align.clamp(min_align, max_align).bytes().try_into().unwrap();
-
-            let variant_span = trace_span!(
-                "treeifying variant",
-                min_align = ?min_align,
-                max_align = ?max_align,
-            )
-            .entered();
-
-            let mut variant_layout = alloc::Layout::from_size_align(
-                0,
-                layout_summary.total_align.bytes().try_into().unwrap(),
-            )
-            .unwrap();
-
-            // The layout of the variant is prefixed by the discriminant, if any.
-            if let Some(discr) = discr {
-                trace!(?discr, "treeifying discriminant");
-                let discr_layout = alloc::Layout::from_size_align(
-                    layout_summary.discriminant_size,
-                    clamp(layout_summary.discriminant_align),
-                )
-                .unwrap();
-                trace!(?discr_layout, "computed discriminant layout");
-                variant_layout = variant_layout.extend(discr_layout).unwrap().0;
-                tree = tree.then(Self::from_discr(discr, tcx, layout_summary.discriminant_size));
-            }
+            // The tag and the fields, with their offsets and sizes.
+            let mut parts = Vec::with_capacity(variant_def.fields.len() + 1);
+            parts.extend(tag);
 
-            // Next come fields.
-            let fields_span = trace_span!("treeifying fields").entered();
-            for field_def in variant_def.fields.iter() {
+            for (i, field_def) in variant_def.fields.iter().enumerate() {
                 let field_ty = field_def.ty(tcx, substs_ref);
                 let _span = trace_span!("treeifying field", field = ?field_ty).entered();
 
-                // begin with the field's visibility
-                tree = tree.then(Self::def(Def::Field(field_def)));
-
-                // compute the field's layout characteristics
-                let field_layout = layout_of(tcx, field_ty)?.clamp_align(min_align, max_align);
+                let field_size = layout_of(tcx, field_ty)?.size;
 
-                // next comes the field's padding
-                let padding_needed = variant_layout.padding_needed_for(field_layout.align());
-                if padding_needed > 0 {
-                    tree = tree.then(Self::padding(padding_needed));
-                }
+                // The field's layout begins with its visibility.
+                let field = Self::def(Def::Field(field_def)).then(Self::from_ty(field_ty, tcx)?);
+                parts.push((layout.fields.offset(i), field_size, field));
+            }
 
-                // finally, the field's layout
-                tree = tree.then(Self::from_ty(field_ty, tcx)?);
+            // Fields may be laid out in a different order than they are declared in.
+            parts.sort_by_key(|&(offset, ..)| offset);
 
-                // extend the variant layout with the field layout
-                variant_layout = variant_layout.extend(field_layout).unwrap().0;
+            let mut tree = Tree::unit();
+            let mut end = Size::ZERO;
+            for (offset, size, part) in parts {
+                tree = tree.then(Self::padding((offset - end).bytes_usize())).then(part);
+                end = offset + size;
             }
-            drop(fields_span);
 
-            // finally: padding
-            let padding_span = trace_span!("adding trailing padding").entered();
-            if layout_summary.total_size > variant_layout.size() {
-                let padding_needed = layout_summary.total_size - variant_layout.size();
-                tree = tree.then(Self::padding(padding_needed));
-            };
-            drop(padding_span);
-            drop(variant_span);
-            Ok(tree)
+            // finally: trailing padding
+            Ok(tree.then(Self::padding((total_size - end).bytes_usize())))
         }
 
         pub fn from_discr(discr: Discr<'tcx>, tcx: TyCtxt<'tcx>, size: usize) -> Self {
@@ -464,20 +410,12 @@ pub fn from_discr(discr: Discr<'tcx>, tcx: TyCtxt<'tcx>, size: usize) -> Self {
     }
 
     fn layout_of<'tcx>(
-        ctx: TyCtxt<'tcx>,
+        tcx: TyCtxt<'tcx>,
         ty: Ty<'tcx>,
-    ) -> Result<alloc::Layout, LayoutError<'tcx>> {
-        use rustc_middle::ty::ParamEnvAnd;
-        use rustc_target::abi::TyAndLayout;
-
-        let param_env = ParamEnv::reveal_all();
-        let param_env_and_type = ParamEnvAnd { param_env, value: ty };
-        let TyAndLayout { layout, .. } = ctx.layout_of(param_env_and_type)?;
-        let layout = alloc::Layout::from_size_align(
-            layout.size().bytes_usize(),
-            layout.align().abi.bytes().try_into().unwrap(),
-        )
-        .unwrap();
+    ) -> Result<TyAndLayout<'tcx>, LayoutError<'tcx>> {
+        // The regions of `ty` don't matter to its layout, and may be inference variables.
+        let ty = tcx.erase_regions(ty);
+        let layout = LayoutCx { tcx, param_env: ParamEnv::reveal_all() }.layout_of(ty)?;
         trace!(?ty, ?layout, "computed layout for type");
         Ok(layout)
     }
@@ -11,7 +11,7 @@
 pub(crate) mod layout;
 pub(crate) mod maybe_transmutable;
 
-#[derive(Default)]
+#[derive(Default, Clone, Copy, Debug)]
 pub struct Assume {
     pub alignment: bool,
     pub lifetimes: bool,
@@ -48,18 +48,40 @@ pub enum Reason {
     SrcIsUnspecified,
     /// The layout of the destination type is unspecified.
     DstIsUnspecified,
-    /// The layout of the destination type is bit-incompatible with the source type.
-    DstIsBitIncompatible,
+    /// The source type contains a reference to an unsized type other than a slice.
+    SrcHasUnsizedReferent,
+    /// The destination type contains a reference to an unsized type other than a slice.
+    DstHasUnsizedReferent,
+    /// At byte `offset`, the source type may hold a byte that is not a valid byte of the
+    /// destination type, or a reference where the destination type has none.
+    DstIsBitIncompatible { offset: usize },
+    /// At byte `offset`, the source type may have padding, but the destination type requires an
+    /// initialized byte.
+    SrcHasPadding { offset: usize },
     /// There aren't any public constructors for `Dst`.
     DstIsPrivate,
-    /// `Dst` is larger than `Src`, and the excess bytes were not exclusively uninitialized.
-    DstIsTooBig,
+    /// `Dst` is larger than `Src`, and requires an initialized byte at `offset`, past the end of
+    /// `Src`.
+    DstIsTooBig { offset: usize },
+    /// The reference at byte `offset` of `Dst` requires its referent to be aligned to
+    /// `dst_min_align`, but the referent of the reference in `Src` is only aligned to
+    /// `src_min_align`.
+    DstHasStricterAlignment { offset: usize, src_min_align: usize, dst_min_align: usize },
+    /// The reference at byte `offset` of `Dst` is mutable, but the reference in `Src` is shared.
+    DstIsMoreUnique { offset: usize },
+    /// The reference at byte `offset` of `Dst` refers to a slice with elements of
+    /// `dst_elem_size` bytes, but the reference in `Src` refers to a slice with elements of
+    /// `src_elem_size` bytes. As the length of a slice counts its elements, the referents would
+    /// be of different sizes.
+    DstHasDifferentElementSize { offset: usize, src_elem_size: usize, dst_elem_size: usize },
 }
 
 #[cfg(feature = "rustc")]
 mod rustc {
     use super::*;
 
+    pub use crate::layout::rustc::Ref;
+
     use rustc_hir::lang_items::LangItem;
     use rustc_infer::infer::InferCtxt;
     use rustc_macros::{TypeFoldable, TypeVisitable};
@@ -7,7 +7,7 @@
 mod query_context;
 use query_context::QueryContext;
 
-use crate::layout::{self, dfa, Byte, Dfa, Nfa, Tree, Uninhabited};
+use crate::layout::{self, dfa, Byte, Dfa, Nfa, Ref, Tree, Uninhabited};
 pub(crate) struct MaybeTransmutableQuery<L, C>
 where
     C: QueryContext,
@@ -76,11 +76,13 @@ pub fn answer(self) -> Answer<<TyCtxt<'tcx> as QueryContext>::Ref> {
                     // rustc. No need to spam the user with more errors.
                     Err::Unknown => Answer::Yes,
                     Err::Unspecified => Answer::No(Reason::SrcIsUnspecified),
+                    Err::UnsizedReferent => Answer::No(Reason::SrcHasUnsizedReferent),
                 })?;
 
                 let dst = Tree::from_ty(dst, context).map_err(|err| match err {
                     Err::Unknown => Answer::Yes,
                     Err::Unspecified => Answer::No(Reason::DstIsUnspecified),
+                    Err::UnsizedReferent => Answer::No(Reason::DstHasUnsizedReferent),
                 })?;
 
                 Ok((src, dst))
@@ -188,9 +190,11 @@ impl<'l, C> MaybeTransmutableQuery<&'l Dfa<<C as QueryContext>::Ref>, C>
     C: QueryContext,
 {
     pub(crate) fn answer(&mut self) -> Answer<<C as QueryContext>::Ref> {
-        self.answer_memo(&mut Map::default(), self.src.start, self.dst.start)
+        self.answer_memo(&mut Map::default(), self.src.start, self.dst.start, 0)
     }
 
+    /// Answers whether the layout of `src` after `src_state` is transmutable into the layout of
+    /// `dst` after `dst_state`. Both states are at byte `offset` of their layouts.
     #[inline(always)]
     #[instrument(level = "debug", skip(self))]
     fn answer_memo(
@@ -198,6 +202,7 @@ fn answer_memo(
         cache: &mut Map<(dfa::State, dfa::State), Answer<<C as QueryContext>::Ref>>,
         src_state: dfa::State,
         dst_state: dfa::State,
+        offset: usize,
     ) -> Answer<<C as QueryContext>::Ref> {
         if let Some(answer) = cache.get(&(src_state, dst_state)) {
             answer.clone()
@@ -208,9 +213,9 @@ fn answer_memo(
             } else if src_state == self.src.accepting {
                 // extension: `size_of(Src) >= size_of(Dst)`
                 if let Some(dst_state_prime) = self.dst.byte_from(dst_state, Byte::Uninit) {
-                    self.answer_memo(cache, src_state, dst_state_prime)
+                    self.answer_memo(cache, src_state, dst_state_prime, offset + 1)
                 } else {
-                    Answer::No(Reason::DstIsTooBig)
+                    Answer::No(Reason::DstIsTooBig { offset })
                 }
             } else {
                 let src_quantification = if self.assume.validity {
@@ -225,25 +230,100 @@ fn answer_memo(
                     for_all
                 };
 
-                src_quantification(
-                    self.src.bytes_from(src_state).unwrap_or(&Map::default()),
-                    |(&src_validity, &src_state_prime)| {
+                let src_bytes = self.src.bytes_from(src_state).filter(|bytes| !bytes.is_empty());
+                let bytes_answer = src_bytes.map(|src_bytes| {
+                    src_quantification(src_bytes, |(&src_validity, &src_state_prime)| {
                         if let Some(dst_state_prime) = self.dst.byte_from(dst_state, src_validity) {
-                            self.answer_memo(cache, src_state_prime, dst_state_prime)
+                            self.answer_memo(cache, src_state_prime, dst_state_prime, offset + 1)
                         } else if let Some(dst_state_prime) =
                             self.dst.byte_from(dst_state, Byte::Uninit)
                         {
-                            self.answer_memo(cache, src_state_prime, dst_state_prime)
+                            self.answer_memo(cache, src_state_prime, dst_state_prime, offset + 1)
+                        } else if src_validity == Byte::Uninit {
+                            Answer::No(Reason::SrcHasPadding { offset })
                         } else {
-                            Answer::No(Reason::DstIsBitIncompatible)
+                            Answer::No(Reason::DstIsBitIncompatible { offset })
                         }
-                    },
-                )
+                    })
+                });
+
+                let src_refs = self.src.refs_from(src_state).filter(|refs| !refs.is_empty());
+                let refs_answer = src_refs.map(|src_refs| {
+                    src_quantification(src_refs, |(&src_ref, &src_state_prime)| {
+                        // A reference can only be transmuted into another reference.
+                        let Some(dst_refs) =
+                            self.dst.refs_from(dst_state).filter(|refs| !refs.is_empty())
+                        else {
+                            return Answer::No(Reason::DstIsBitIncompatible { offset });
+                        };
+                        there_exists(dst_refs, |(&dst_ref, &dst_state_prime)| {
+                            match self.answer_ref(src_ref, dst_ref, offset) {
+                                // The rest of the layouts may not even line up.
+                                Answer::No(reason) => Answer::No(reason),
+                                answer => answer.and(self.answer_memo(
+                                    cache,
+                                    src_state_prime,
+                                    dst_state_prime,
+                                    offset + src_ref.size(),
+                                )),
+                            }
+                        })
+                    })
+                });
+
+                match (bytes_answer, refs_answer) {
+                    (Some(bytes_answer), Some(refs_answer)) if self.assume.validity => {
+                        bytes_answer.or(refs_answer)
+                    }
+                    (Some(bytes_answer), Some(refs_answer)) => bytes_answer.and(refs_answer),
+                    (Some(answer), None) | (None, Some(answer)) => answer,
+                    // No value of `src` reaches `src_state`.
+                    (None, None) => Answer::Yes,
+                }
             };
             cache.insert((src_state, dst_state), answer.clone());
             answer
         }
     }
+
+    /// Answers whether the reference `src` at byte `offset` is transmutable into `dst`, which
+    /// depends on whether their referents are transmutable.
+    fn answer_ref(
+        &self,
+        src: <C as QueryContext>::Ref,
+        dst: <C as QueryContext>::Ref,
+        offset: usize,
+    ) -> Answer<<C as QueryContext>::Ref> {
+        match (src.elem_size(), dst.elem_size()) {
+            // A reference to a slice can only be transmuted into another one.
+            _ if src.size() != dst.size() => {
+                return Answer::No(Reason::DstIsBitIncompatible { offset });
+            }
+            (Some(_), None) | (None, Some(_)) => {
+                return Answer::No(Reason::DstIsBitIncompatible { offset });
+            }
+            (Some(src_elem_size), Some(dst_elem_size)) if src_elem_size != dst_elem_size => {
+                return Answer::No(Reason::DstHasDifferentElementSize {
+                    offset,
+                    src_elem_size,
+                    dst_elem_size,
+                });
+            }
+            _ => {}
+        }
+
+        if dst.is_mutable() && !src.is_mutable() {
+            Answer::No(Reason::DstIsMoreUnique { offset })
+        } else if !self.assume.alignment && src.min_align() < dst.min_align() {
+            Answer::No(Reason::DstHasStricterAlignment {
+                offset,
+                src_min_align: src.min_align(),
+                dst_min_align: dst.min_align(),
+            })
+        } else {
+            Answer::IfTransmutable { src, dst }
+        }
+    }
 }
 
 impl<R> Answer<R>
@@ -309,12 +389,17 @@ pub fn there_exists<R, I, F>(iter: I, f: F) -> Answer<R>
     F: FnMut(<I as IntoIterator>::Item) -> Answer<R>,
 {
     use std::ops::ControlFlow::{Break, Continue};
-    let (Continue(result) | Break(result)) = iter.into_iter().map(f).try_fold(
-        Answer::No(Reason::DstIsBitIncompatible),
-        |constraints, constraint| match constraint.or(constraints) {
-            Answer::Yes => Break(Answer::Yes),
-            maybe => Continue(maybe),
-        },
-    );
-    result
+    // There must be at least one alternative, so that there is a reason to answer `No` with.
+    let (Continue(result) | Break(result)) =
+        iter.into_iter().map(f).try_fold(None, |constraints, constraint| {
+            let constraints = match constraints {
+                Some(constraints) => constraint.or(constraints),
+                None => constraint,
+            };
+            match constraints {
+                Answer::Yes => Break(Some(Answer::Yes)),
+                maybe => Continue(Some(maybe)),
+            }
+        });
+    result.expect("`there_exists` needs at least one alternative")
 }
@@ -8,8 +8,6 @@ pub(crate) trait QueryContext {
 
     /// Is `def` accessible from the defining module of `scope`?
     fn is_accessible_from(&self, def: Self::Def, scope: Self::Scope) -> bool;
-
-    fn min_align(&self, reference: Self::Ref) -> usize;
 }
 
 #[cfg(test)]
@@ -34,10 +32,6 @@ impl QueryContext for UltraMinimal {
         fn is_accessible_from(&self, def: Def, scope: ()) -> bool {
             matches!(Def::Visible, def)
         }
-
-        fn min_align(&self, reference: !) -> usize {
-            unimplemented!()
-        }
     }
 }
 
@@ -81,9 +75,5 @@ fn is_accessible_from(&self, def: Self::Def, scope: Self::Scope) -> bool {
             trace!(?ret, "ret");
             ret
         }
-
-        fn min_align(&self, reference: Self::Ref) -> usize {
-            unimplemented!()
-        }
     }
 }
@@ -95,7 +95,7 @@ fn should_permit_validity_expansion_and_reject_contraction() {
                     );
                 } else {
                     assert_eq!(
-                        Answer::No(Reason::DstIsBitIncompatible),
+                        Answer::No(Reason::DstIsBitIncompatible { offset: 0 }),
                         MaybeTransmutableQuery::new(
                             src_layout.clone(),
                             dst_layout.clone(),
@@ -113,3 +113,32 @@ fn should_permit_validity_expansion_and_reject_contraction() {
         }
     }
 }
+
+mod reason {
+    use super::*;
+
+    fn answer(src: layout::Tree<Def, !>, dst: layout::Tree<Def, !>) -> Answer<!> {
+        MaybeTransmutableQuery::new(src, dst, (), crate::Assume::default(), UltraMinimal).answer()
+    }
+
+    #[test]
+    fn should_locate_bit_incompatibility() {
+        let src = layout::Tree::u8().then(layout::Tree::u8());
+        let dst = layout::Tree::u8().then(layout::Tree::bool());
+        assert_eq!(answer(src, dst), Answer::No(Reason::DstIsBitIncompatible { offset: 1 }));
+    }
+
+    #[test]
+    fn should_locate_padding() {
+        let src = layout::Tree::u8().then(layout::Tree::uninit());
+        let dst = layout::Tree::number(2);
+        assert_eq!(answer(src, dst), Answer::No(Reason::SrcHasPadding { offset: 1 }));
+    }
+
+    #[test]
+    fn should_locate_extension() {
+        let src = layout::Tree::number(2);
+        let dst = layout::Tree::number(2).then(layout::Tree::bool());
+        assert_eq!(answer(src, dst), Answer::No(Reason::DstIsTooBig { offset: 2 }));
+    }
+}
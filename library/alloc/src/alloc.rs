@@ -169,12 +169,12 @@ pub unsafe fn alloc_zeroed(layout: Layout) -> *mut u8 {
 #[cfg(not(test))]
 impl Global {
     #[inline]
-    fn alloc_impl(&self, layout: Layout, zeroed: bool) -> Result<NonNull<[u8]>, AllocError> {
+    const fn alloc_impl(&self, layout: Layout, zeroed: bool) -> Result<NonNull<[u8]>, AllocError> {
         match layout.size() {
             0 => Ok(NonNull::slice_from_raw_parts(layout.dangling(), 0)),
             // SAFETY: `layout` is non-zero in size,
             size => unsafe {
-                let raw_ptr = if zeroed { alloc_zeroed(layout) } else { alloc(layout) };
+                let raw_ptr = intrinsics::const_eval_select((layout, zeroed), ct_alloc, rt_alloc);
                 let ptr = NonNull::new(raw_ptr).ok_or(AllocError)?;
                 Ok(NonNull::slice_from_raw_parts(ptr, size))
             },
@@ -183,7 +183,7 @@ fn alloc_impl(&self, layout: Layout, zeroed: bool) -> Result<NonNull<[u8]>, Allo
 
     // SAFETY: Same as `Allocator::grow`
     #[inline]
-    unsafe fn grow_impl(
+    const unsafe fn grow_impl(
         &self,
         ptr: NonNull<u8>,
         old_layout: Layout,
@@ -206,7 +206,11 @@ unsafe fn grow_impl(
                 // `realloc` probably checks for `new_size >= old_layout.size()` or something similar.
                 intrinsics::assume(new_size >= old_layout.size());
 
-                let raw_ptr = realloc(ptr.as_ptr(), old_layout, new_size);
+                let raw_ptr = intrinsics::const_eval_select(
+                    (ptr.as_ptr(), old_layout, new_size),
+                    ct_realloc,
+                    rt_realloc,
+                );
                 let ptr = NonNull::new(raw_ptr).ok_or(AllocError)?;
                 if zeroed {
                     raw_ptr.add(old_size).write_bytes(0, new_size - old_size);
@@ -229,9 +233,64 @@ unsafe fn grow_impl(
     }
 }
 
+// During const evaluation, `Global` allocates on the heap of the interpreter. Allocations that are
+// still reachable from the final value of a constant or static are interned along with it, see
+// `rustc_const_eval::interpret::intern`.
+
+#[cfg(not(test))]
+const fn ct_alloc(layout: Layout, zeroed: bool) -> *mut u8 {
+    // SAFETY: the alignment of a `Layout` is a power of two.
+    let ptr = unsafe { intrinsics::const_allocate(layout.size(), layout.align()) };
+    if zeroed {
+        // SAFETY: `ptr` was just allocated with room for `layout.size()` bytes.
+        unsafe { ptr.write_bytes(0, layout.size()) };
+    }
+    ptr
+}
+
+#[cfg(not(test))]
+fn rt_alloc(layout: Layout, zeroed: bool) -> *mut u8 {
+    // SAFETY: `layout` is non-zero in size, as checked by the caller.
+    unsafe { if zeroed { alloc_zeroed(layout) } else { alloc(layout) } }
+}
+
+#[cfg(not(test))]
+const fn ct_dealloc(ptr: *mut u8, layout: Layout) {
+    // SAFETY: the caller upholds the contract of `Allocator::deallocate`.
+    unsafe { intrinsics::const_deallocate(ptr, layout.size(), layout.align()) }
+}
+
+#[cfg(not(test))]
+fn rt_dealloc(ptr: *mut u8, layout: Layout) {
+    // SAFETY: the caller upholds the contract of `Allocator::deallocate`.
+    unsafe { dealloc(ptr, layout) }
+}
+
+/// The interpreter can't resize allocations in place, so this moves the contents to a new
+/// allocation.
+#[cfg(not(test))]
+const fn ct_realloc(ptr: *mut u8, old_layout: Layout, new_size: usize) -> *mut u8 {
+    // SAFETY: `new_size` is non-zero and, rounded up to the alignment, doesn't overflow, as
+    // required from the callers of `grow` and `shrink`.
+    let new_layout = unsafe { Layout::from_size_align_unchecked(new_size, old_layout.align()) };
+    let new_ptr = ct_alloc(new_layout, false);
+    let copied = if new_size < old_layout.size() { new_size } else { old_layout.size() };
+    // SAFETY: both allocations are valid for `copied` bytes and don't overlap.
+    unsafe { ptr::copy_nonoverlapping(ptr, new_ptr, copied) };
+    ct_dealloc(ptr, old_layout);
+    new_ptr
+}
+
+#[cfg(not(test))]
+fn rt_realloc(ptr: *mut u8, old_layout: Layout, new_size: usize) -> *mut u8 {
+    // SAFETY: the caller upholds the contract of `Allocator::grow` or `Allocator::shrink`.
+    unsafe { realloc(ptr, old_layout, new_size) }
+}
+
 #[unstable(feature = "allocator_api", issue = "32838")]
+#[rustc_const_unstable(feature = "const_global_allocator", issue = "none")]
 #[cfg(not(test))]
-unsafe impl Allocator for Global {
+unsafe impl const Allocator for Global {
     #[inline]
     fn allocate(&self, layout: Layout) -> Result<NonNull<[u8]>, AllocError> {
         self.alloc_impl(layout, false)
@@ -247,7 +306,9 @@ unsafe fn deallocate(&self, ptr: NonNull<u8>, layout: Layout) {
         if layout.size() != 0 {
             // SAFETY: `layout` is non-zero in size,
             // other conditions must be upheld by the caller
-            unsafe { dealloc(ptr.as_ptr(), layout) }
+            unsafe {
+                intrinsics::const_eval_select((ptr.as_ptr(), layout), ct_dealloc, rt_dealloc)
+            }
         }
     }
 
@@ -297,7 +358,11 @@ unsafe fn shrink(
                 // `realloc` probably checks for `new_size <= old_layout.size()` or something similar.
                 intrinsics::assume(new_size <= old_layout.size());
 
-                let raw_ptr = realloc(ptr.as_ptr(), old_layout, new_size);
+                let raw_ptr = intrinsics::const_eval_select(
+                    (ptr.as_ptr(), old_layout, new_size),
+                    ct_realloc,
+                    rt_realloc,
+                );
                 let ptr = NonNull::new(raw_ptr).ok_or(AllocError)?;
                 Ok(NonNull::slice_from_raw_parts(ptr, new_size))
             },
@@ -105,6 +105,15 @@
 #![feature(const_convert)]
 #![feature(const_size_of_val)]
 #![feature(const_align_of_val)]
+#![feature(const_assume)]
+#![feature(const_heap)]
+#![feature(const_global_allocator)]
+#![feature(const_vec_leak)]
+#![feature(const_nonnull_new)]
+#![feature(const_nonnull_slice_from_raw_parts)]
+#![feature(const_option_ext)]
+#![feature(const_slice_from_raw_parts_mut)]
+#![feature(const_str_from_utf8_unchecked_mut)]
 #![feature(const_ptr_read)]
 #![feature(const_maybe_uninit_zeroed)]
 #![feature(const_maybe_uninit_write)]
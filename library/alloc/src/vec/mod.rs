@@ -783,7 +783,12 @@ pub fn with_capacity_in(capacity: usize, alloc: A) -> Self {
     /// ```
     #[inline]
     #[unstable(feature = "allocator_api", issue = "32838")]
-    pub unsafe fn from_raw_parts_in(ptr: *mut T, length: usize, capacity: usize, alloc: A) -> Self {
+    pub const unsafe fn from_raw_parts_in(
+        ptr: *mut T,
+        length: usize,
+        capacity: usize,
+        alloc: A,
+    ) -> Self {
         unsafe { Vec { buf: RawVec::from_raw_parts_in(ptr, capacity, alloc), len: length } }
     }
 
@@ -2199,13 +2204,16 @@ pub fn resize_with<F>(&mut self, new_len: usize, f: F)
     /// assert_eq!(static_ref, &[2, 2, 3]);
     /// ```
     #[stable(feature = "vec_leak", since = "1.47.0")]
+    #[rustc_const_unstable(feature = "const_vec_leak", issue = "none")]
     #[inline]
-    pub fn leak<'a>(self) -> &'a mut [T]
+    pub const fn leak<'a>(self) -> &'a mut [T]
     where
         A: 'a,
     {
-        let mut me = ManuallyDrop::new(self);
-        unsafe { slice::from_raw_parts_mut(me.as_mut_ptr(), me.len) }
+        // Not `ManuallyDrop`, whose `DerefMut` can't be called in a `const fn`.
+        let (ptr, len) = (self.buf.ptr(), self.len);
+        mem::forget(self);
+        unsafe { slice::from_raw_parts_mut(ptr, len) }
     }
 
     /// Returns the remaining spare capacity of the vector as a slice of
@@ -21,6 +21,26 @@
 pub const SET_LEN: usize = SET.len();
 pub const SET_IS_EMPTY: bool = SET.is_empty();
 
+use std::alloc::Global;
+
+const fn vec_in_global() -> Vec<u32> {
+    let buf: &mut [u32; 3] = Box::leak(Box::new_in([1, 2, 3], Global));
+    // SAFETY: the buffer was allocated by `Global` for 3 elements, which are initialized.
+    unsafe { Vec::from_raw_parts_in(buf as *mut [u32; 3] as *mut u32, 3, 3, Global) }
+}
+
+const fn string_in_global() -> String {
+    let buf: &mut [u8; 5] = Box::leak(Box::new_in(*b"hello", Global));
+    // SAFETY: the buffer was allocated by `Global` for 5 bytes, which are valid UTF-8.
+    unsafe {
+        let vec = Vec::from_raw_parts_in(buf as *mut [u8; 5] as *mut u8, 5, 5, Global);
+        String::from_utf8_unchecked(vec)
+    }
+}
+
+pub const LEAKED_VEC: &[u32] = vec_in_global().leak();
+pub const LEAKED_STRING: &str = string_in_global().leak();
+
 #[test]
 fn test_const() {
     assert_eq!(MY_VEC, MY_VEC2);
@@ -33,3 +53,9 @@ fn test_const() {
     assert_eq!(SET_LEN, 0);
     assert!(MAP_IS_EMPTY && SET_IS_EMPTY);
 }
+
+#[test]
+fn test_const_leak() {
+    assert_eq!(LEAKED_VEC, [1, 2, 3]);
+    assert_eq!(LEAKED_STRING, "hello");
+}
@@ -6,11 +6,14 @@
 #![feature(const_box)]
 #![feature(const_convert)]
 #![feature(const_cow_is_borrowed)]
+#![feature(const_global_allocator)]
 #![feature(const_heap)]
 #![feature(const_mut_refs)]
 #![feature(const_nonnull_slice_from_raw_parts)]
 #![feature(const_ptr_write)]
+#![feature(const_string_from_utf8_unchecked)]
 #![feature(const_try)]
+#![feature(const_vec_leak)]
 #![feature(core_intrinsics)]
 #![feature(drain_filter)]
 #![feature(exact_size_is_empty)]
@@ -33,6 +36,7 @@
 #![feature(round_char_boundary)]
 #![feature(slice_group_by)]
 #![feature(slice_partition_dedup)]
+#![feature(string_leak)]
 #![feature(string_remove_matches)]
 #![feature(const_btree_len)]
 #![feature(const_default_impls)]
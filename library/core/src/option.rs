@@ -1919,6 +1919,7 @@ pub const fn transpose(self) -> Result<Option<T>, E> {
 #[cfg_attr(feature = "panic_immediate_abort", inline)]
 #[cold]
 #[track_caller]
+#[cfg_attr(not(bootstrap), lang = "expect_failed")] // needed by the `OutlineColdPanics` MIR pass
 #[rustc_const_unstable(feature = "const_option", issue = "67441")]
 const fn expect_failed(msg: &str) -> ! {
     panic_str(msg)
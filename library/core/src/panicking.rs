@@ -123,6 +123,52 @@ pub fn panic_nounwind(expr: &'static str) -> ! {
     panic_nounwind_fmt(fmt::Arguments::new_v1(&[expr], &[]));
 }
 
+/// Like `panic_fmt`, but with the location passed as an argument instead of through
+/// `track_caller`. This is what the `*_outlined` functions below bottom out in.
+#[cfg_attr(not(feature = "panic_immediate_abort"), inline(never), cold)]
+#[cfg_attr(feature = "panic_immediate_abort", inline)]
+fn panic_fmt_outlined(fmt: fmt::Arguments<'_>, location: &'static Location<'static>) -> ! {
+    if cfg!(feature = "panic_immediate_abort") {
+        super::intrinsics::abort()
+    }
+
+    // NOTE This function never crosses the FFI boundary; it's a Rust-to-Rust call
+    // that gets resolved to the `#[panic_handler]` function.
+    extern "Rust" {
+        #[lang = "panic_impl"]
+        fn panic_impl(pi: &PanicInfo<'_>) -> !;
+    }
+
+    let pi = PanicInfo::internal_constructor(Some(&fmt), location, true);
+
+    // SAFETY: `panic_impl` is defined in safe Rust code and thus is safe to call.
+    unsafe { panic_impl(&pi) }
+}
+
+/// Like `panic`, but with the location passed as an argument instead of through `track_caller`.
+///
+/// The `OutlineColdPanics` MIR pass rewrites calls to `panic` with a constant message, and
+/// assertions other than bounds checks, into calls to this function, so that identical panics
+/// of a function can share a single call site.
+#[cfg_attr(not(feature = "panic_immediate_abort"), inline(never), cold)]
+#[cfg_attr(feature = "panic_immediate_abort", inline)]
+#[cfg_attr(not(bootstrap), lang = "panic_outlined")] // needed by the `OutlineColdPanics` MIR pass
+pub fn panic_outlined(expr: &'static str, location: &'static Location<'static>) -> ! {
+    panic_fmt_outlined(fmt::Arguments::new_v1(&[expr], &[]), location);
+}
+
+/// Like `panic_str`, but with the location passed as an argument instead of through
+/// `track_caller`.
+///
+/// The `OutlineColdPanics` MIR pass rewrites calls to `Option::expect`'s failure path with a
+/// constant message into calls to this function.
+#[cfg_attr(not(feature = "panic_immediate_abort"), inline(never), cold)]
+#[cfg_attr(feature = "panic_immediate_abort", inline)]
+#[cfg_attr(not(bootstrap), lang = "panic_str_outlined")] // needed by the `OutlineColdPanics` MIR pass
+pub fn panic_str_outlined(expr: &str, location: &'static Location<'static>) -> ! {
+    panic_fmt_outlined(format_args!("{}", expr), location);
+}
+
 #[inline]
 #[track_caller]
 #[rustc_diagnostic_item = "panic_str"]
@@ -159,6 +205,24 @@ fn panic_bounds_check(index: usize, len: usize) -> ! {
     panic!("index out of bounds: the len is {len} but the index is {index}")
 }
 
+/// Like `panic_bounds_check`, but with the location passed as an argument instead of through
+/// `track_caller`.
+///
+/// The `OutlineColdPanics` MIR pass rewrites bounds checks into calls to this function.
+#[cfg_attr(not(feature = "panic_immediate_abort"), inline(never), cold)]
+#[cfg_attr(feature = "panic_immediate_abort", inline)]
+#[cfg_attr(not(bootstrap), lang = "panic_bounds_check_outlined")] // needed by the `OutlineColdPanics` MIR pass
+pub fn panic_bounds_check_outlined(
+    index: usize,
+    len: usize,
+    location: &'static Location<'static>,
+) -> ! {
+    panic_fmt_outlined(
+        format_args!("index out of bounds: the len is {len} but the index is {index}"),
+        location,
+    );
+}
+
 /// Panic because we cannot unwind out of a function.
 ///
 /// This function is called directly by the codegen backend, and must not have
@@ -32,6 +32,8 @@
 )]
 #[doc(hidden)]
 #[repr(transparent)]
+// Lets const evaluation intern the buffers of `Vec` and `String` according to their type.
+#[rustc_diagnostic_item = "ptr_unique"]
 pub struct Unique<T: ?Sized> {
     pointer: NonNull<T>,
     // NOTE: this marker has no consequences for variance, but is necessary
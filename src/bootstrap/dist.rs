@@ -434,7 +434,14 @@ fn prepare_image(builder: &Builder<'_>, compiler: Compiler, image: &Path) {
             let backends_dst = image.join("lib").join(&backends_rel);
 
             t!(fs::create_dir_all(&backends_dst));
-            builder.cp_r(&backends_src, &backends_dst);
+            for entry in builder.read_dir(&backends_src) {
+                // Cranelift is shipped in its own component by `CodegenBackend`.
+                let file_name = entry.file_name();
+                let file_name = file_name.to_str().unwrap();
+                if !file_name.contains("rustc_codegen_cranelift-") {
+                    builder.install(&entry.path(), &backends_dst, 0o644);
+                }
+            }
 
             // Copy libLLVM.so to the lib dir as well, if needed. While not
             // technically needed by rustc itself it's needed by lots of other
@@ -494,6 +501,91 @@ fn prepare_image(builder: &Builder<'_>, compiler: Compiler, image: &Path) {
     }
 }
 
+#[derive(Debug, PartialOrd, Ord, Copy, Clone, Hash, PartialEq, Eq)]
+pub struct CodegenBackend {
+    pub compiler: Compiler,
+    pub backend: Interned<String>,
+}
+
+impl Step for CodegenBackend {
+    type Output = Option<GeneratedTarball>;
+    const DEFAULT: bool = true;
+    const ONLY_HOSTS: bool = true;
+
+    fn should_run(run: ShouldRun<'_>) -> ShouldRun<'_> {
+        run.path("compiler/rustc_codegen_cranelift")
+    }
+
+    fn make_run(run: RunConfig<'_>) {
+        for &backend in &run.builder.config.rust_codegen_backends {
+            if backend == "llvm" {
+                continue; // Already shipped as part of rustc
+            }
+
+            run.builder.ensure(CodegenBackend {
+                compiler: run.builder.compiler(run.builder.top_stage, run.target),
+                backend,
+            });
+        }
+    }
+
+    /// Creates the installer component of a codegen backend other than LLVM, which is loaded
+    /// from the sysroot with `-Z codegen-backend=<name>`.
+    ///
+    /// The component contains only the backend. No standard library is built with it: crates
+    /// compiled by the backend link against the LLVM-built one of the `rust-std` component, which
+    /// `tests/run-make/codegen-backend-mixed` checks.
+    fn run(self, builder: &Builder<'_>) -> Option<GeneratedTarball> {
+        // This prevents the codegen backends from being built for "dist" or "install" on the
+        // stable/beta channels. They can only be selected with an unstable flag and should not
+        // be included.
+        if !builder.build.unstable_features() {
+            return None;
+        }
+
+        let compiler = self.compiler;
+        let backend = self.backend;
+        let overlay = match &*backend {
+            "cranelift" => OverlayKind::RustcCodegenCranelift,
+            _ => {
+                builder.info(&format!("no installer component for codegen backend {}", backend));
+                return None;
+            }
+        };
+
+        builder.ensure(compile::Assemble { target_compiler: compiler });
+
+        let mut tarball =
+            Tarball::new(builder, &format!("rustc-codegen-{}", backend), &compiler.host.triple);
+        tarball.set_overlay(overlay);
+        tarball.is_preview(true);
+        tarball.add_legal_and_readme_to(format!("share/doc/rustc_codegen_{}", backend));
+
+        let src = builder.sysroot(compiler);
+        let backends_src = builder.sysroot_codegen_backends(compiler);
+        let backends_rel = backends_src
+            .strip_prefix(&src)
+            .unwrap()
+            .strip_prefix(builder.sysroot_libdir_relative(compiler))
+            .unwrap();
+        // Don't use custom libdir here because ^lib/ will be resolved again with installer
+        let backends_dst = PathBuf::from("lib").join(&backends_rel);
+
+        let backend_name = format!("rustc_codegen_{}-", backend);
+        let mut found_backend = false;
+        for entry in builder.read_dir(&backends_src) {
+            let file_name = entry.file_name();
+            if file_name.to_str().map_or(false, |name| name.contains(&backend_name)) {
+                tarball.add_file(entry.path(), &backends_dst, 0o644);
+                found_backend = true;
+            }
+        }
+        assert!(found_backend, "codegen backend {} was not built", backend);
+
+        Some(tarball.generate())
+    }
+}
+
 #[derive(Debug, Copy, Clone, Hash, PartialEq, Eq)]
 pub struct DebuggerScripts {
     pub sysroot: Interned<PathBuf>,
@@ -492,6 +492,8 @@ pub fn parse(args: &[String]) -> Flags {
         ./x.py test library/std --stage 0 --no-doc
         ./x.py test tests/ui --bless
         ./x.py test tests/ui --compare-mode chalk
+        ./x.py test tests/ui/nll --compare-mode polonius-hybrid
+        ./x.py test tests/ui --compare-mode parallel-frontend
 
     Note that `test tests/* --stage N` does NOT depend on `build compiler/rustc --stage N`;
     just like `build library/std --stage N` it tests the compiler produced by the previous
@@ -15,6 +15,7 @@
 use crate::Compiler;
 
 use crate::builder::{Builder, RunConfig, ShouldRun, Step};
+use crate::cache::INTERNER;
 use crate::config::{Config, TargetSelection};
 
 #[cfg(target_os = "illumos")]
@@ -261,6 +262,19 @@ fn run($sel, $builder: &Builder<'_>) {
         });
         install_sh(builder, "rustc", self.compiler.stage, Some(self.target), &tarball);
     };
+    RustcCodegenCranelift, alias = "rustc-codegen-cranelift", _config.rust_codegen_backends.contains(&INTERNER.intern_str("cranelift")), only_hosts: true, {
+        if let Some(tarball) = builder.ensure(dist::CodegenBackend {
+            compiler: builder.compiler(builder.top_stage, self.target),
+            backend: INTERNER.intern_str("cranelift"),
+        }) {
+            install_sh(builder, "rustc-codegen-cranelift", self.compiler.stage, Some(self.target), &tarball);
+        } else {
+            // The Cranelift backend is only available on nightly
+            builder.info(
+                &format!("skipping Install rustc-codegen-cranelift stage{} ({})", self.compiler.stage, self.target),
+            );
+        }
+    };
 );
 
 #[derive(Debug, Copy, Clone, Hash, PartialEq, Eq)]
@@ -1719,6 +1719,10 @@ fn run(self, builder: &Builder<'_>) {
             cmd.env("RUSTC_PROFILER_SUPPORT", "1");
         }
 
+        if builder.config.rustc_parallel {
+            cmd.env("RUSTC_PARALLEL_COMPILER", "1");
+        }
+
         cmd.env("RUST_TEST_TMPDIR", builder.tempdir());
 
         cmd.arg("--adb-path").arg("adb");
@@ -194,6 +194,8 @@ if [ "$RUN_CHECK_WITH_PARALLEL_QUERIES" != "" ]; then
   fi
 
   CARGO_INCREMENTAL=0 ../x check
+  # Output of the frontend must not depend on the number of threads it uses.
+  CARGO_INCREMENTAL=0 ../x test --stage 1 tests/run-make/parallel-frontend-determinism
 fi
 
 sccache --show-stats || true
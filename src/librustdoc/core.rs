@@ -310,6 +310,7 @@ pub(crate) fn create_config(
             };
         }),
         make_codegen_backend: None,
+        diagnostic_snapshot: None,
         registry: rustc_driver::diagnostics_registry(),
     }
 }
@@ -103,6 +103,7 @@ pub(crate) fn run(options: RustdocOptions) -> Result<(), ErrorGuaranteed> {
         register_lints: Some(Box::new(crate::lint::register_lints)),
         override_queries: None,
         make_codegen_backend: None,
+        diagnostic_snapshot: None,
         registry: rustc_driver::diagnostics_registry(),
     };
 
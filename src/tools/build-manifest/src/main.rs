@@ -185,7 +185,8 @@
 
 static MINGW: &[&str] = &["i686-pc-windows-gnu", "x86_64-pc-windows-gnu"];
 
-static NIGHTLY_ONLY_COMPONENTS: &[PkgType] = &[PkgType::Miri, PkgType::JsonDocs];
+static NIGHTLY_ONLY_COMPONENTS: &[PkgType] =
+    &[PkgType::Miri, PkgType::RustcCodegenCranelift, PkgType::JsonDocs];
 
 macro_rules! t {
     ($e:expr) => {
@@ -411,6 +412,7 @@ fn target_host_combination(&mut self, host: &str, manifest: &Manifest) -> Option
                 // but might be marked as unavailable if they weren't built.
                 PkgType::Clippy
                 | PkgType::Miri
+                | PkgType::RustcCodegenCranelift
                 | PkgType::Rls
                 | PkgType::RustAnalyzer
                 | PkgType::Rustfmt
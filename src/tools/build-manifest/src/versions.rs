@@ -55,6 +55,7 @@ pub(crate) fn all() -> &'static [PkgType] {
     Rustfmt = "rustfmt"; preview = true,
     LlvmTools = "llvm-tools"; preview = true,
     Miri = "miri"; preview = true,
+    RustcCodegenCranelift = "rustc-codegen-cranelift"; preview = true,
     JsonDocs = "rust-docs-json"; preview = true,
 }
 
@@ -84,6 +85,7 @@ fn should_use_rust_version(&self) -> bool {
             PkgType::RustStd => true,
             PkgType::RustSrc => true,
             PkgType::Rustc => true,
+            PkgType::RustcCodegenCranelift => true,
             PkgType::JsonDocs => true,
             PkgType::HtmlDocs => true,
             PkgType::RustcDev => true,
@@ -114,6 +116,7 @@ pub(crate) fn targets(&self) -> &[&str] {
             RustAnalyzer => HOSTS,
             Clippy => HOSTS,
             Miri => HOSTS,
+            RustcCodegenCranelift => HOSTS,
             Rustfmt => HOSTS,
             RustAnalysis => TARGETS,
             LlvmTools => TARGETS,
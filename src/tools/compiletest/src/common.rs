@@ -122,27 +122,33 @@ pub enum FailMode {
 #[derive(Clone, Debug, PartialEq)]
 pub enum CompareMode {
     Polonius,
+    PoloniusHybrid,
     Chalk,
     SplitDwarf,
     SplitDwarfSingle,
+    ParallelFrontend,
 }
 
 impl CompareMode {
     pub(crate) fn to_str(&self) -> &'static str {
         match *self {
             CompareMode::Polonius => "polonius",
+            CompareMode::PoloniusHybrid => "polonius-hybrid",
             CompareMode::Chalk => "chalk",
             CompareMode::SplitDwarf => "split-dwarf",
             CompareMode::SplitDwarfSingle => "split-dwarf-single",
+            CompareMode::ParallelFrontend => "parallel-frontend",
         }
     }
 
     pub fn parse(s: String) -> CompareMode {
         match s.as_str() {
             "polonius" => CompareMode::Polonius,
+            "polonius-hybrid" => CompareMode::PoloniusHybrid,
             "chalk" => CompareMode::Chalk,
             "split-dwarf" => CompareMode::SplitDwarf,
             "split-dwarf-single" => CompareMode::SplitDwarfSingle,
+            "parallel-frontend" => CompareMode::ParallelFrontend,
             x => panic!("unknown --compare-mode option: {}", x),
         }
     }
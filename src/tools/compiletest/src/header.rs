@@ -709,9 +709,11 @@ fn parse_cfg_name_directive(&self, line: &str, prefix: &str) -> ParsedNameDirect
             (self.remote_test_client.is_some() && name == "remote") ||
             match self.compare_mode {
                 Some(CompareMode::Polonius) => name == "compare-mode-polonius",
+                Some(CompareMode::PoloniusHybrid) => name == "compare-mode-polonius-hybrid",
                 Some(CompareMode::Chalk) => name == "compare-mode-chalk",
                 Some(CompareMode::SplitDwarf) => name == "compare-mode-split-dwarf",
                 Some(CompareMode::SplitDwarfSingle) => name == "compare-mode-split-dwarf-single",
+                Some(CompareMode::ParallelFrontend) => name == "compare-mode-parallel-frontend",
                 None => false,
             } ||
             (cfg!(debug_assertions) && name == "debug") ||
@@ -930,6 +932,7 @@ pub fn make_test_description<R: Read>(
 
     let rustc_has_profiler_support = env::var_os("RUSTC_PROFILER_SUPPORT").is_some();
     let rustc_has_sanitizer_support = env::var_os("RUSTC_SANITIZER_SUPPORT").is_some();
+    let rustc_is_parallel = env::var_os("RUSTC_PARALLEL_COMPILER").is_some();
     let has_asm_support = config.has_asm_support();
     let has_asan = util::ASAN_SUPPORTED_TARGETS.contains(&&*config.target);
     let has_cfi = util::CFI_SUPPORTED_TARGETS.contains(&&*config.target);
@@ -980,6 +983,7 @@ pub fn make_test_description<R: Read>(
             config.run_clang_based_tests_with.is_none() && config.parse_needs_matching_clang(ln);
         ignore |= !has_asm_support && config.parse_name_directive(ln, "needs-asm-support");
         ignore |= !rustc_has_profiler_support && config.parse_needs_profiler_support(ln);
+        ignore |= !rustc_is_parallel && config.parse_name_directive(ln, "needs-parallel-compiler");
         ignore |= !config.run_enabled() && config.parse_name_directive(ln, "needs-run-enabled");
         ignore |= !rustc_has_sanitizer_support
             && config.parse_name_directive(ln, "needs-sanitizer-support");
@@ -1003,6 +1007,10 @@ pub fn make_test_description<R: Read>(
         should_fail |= config.parse_name_directive(ln, "should-fail");
     });
 
+    // A compiler without `parallel-compiler` ignores `-Z threads`, so the comparison would pass
+    // without testing anything.
+    ignore |= config.compare_mode == Some(CompareMode::ParallelFrontend) && !rustc_is_parallel;
+
     // The `should-fail` annotation doesn't apply to pretty tests,
     // since we run the pretty printer across all tests by default.
     // If desired, we could add a `should-fail-pretty` annotation.
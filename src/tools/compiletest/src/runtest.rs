@@ -1948,6 +1948,8 @@ fn make_compile_args(
                 rustc.arg("-Ztranslate-remapped-path-to-local-path=no");
 
                 rustc.arg("-Zdeduplicate-diagnostics=no");
+                // Keep every error attached to its own `//~ ERROR` annotation.
+                rustc.arg("-Zdiagnostic-grouping=no");
                 // FIXME: use this for other modes too, for perf?
                 rustc.arg("-Cstrip=debuginfo");
             }
@@ -2028,6 +2030,9 @@ fn make_compile_args(
             Some(CompareMode::Polonius) => {
                 rustc.args(&["-Zpolonius"]);
             }
+            Some(CompareMode::PoloniusHybrid) => {
+                rustc.args(&["-Zpolonius=hybrid"]);
+            }
             Some(CompareMode::Chalk) => {
                 rustc.args(&["-Ztrait-solver=chalk"]);
             }
@@ -2040,6 +2045,9 @@ fn make_compile_args(
             Some(CompareMode::SplitDwarfSingle) => {
                 rustc.args(&["-Csplit-debuginfo=packed"]);
             }
+            Some(CompareMode::ParallelFrontend) => {
+                rustc.args(&["-Zthreads=4"]);
+            }
             None => {}
         }
 
@@ -3706,6 +3714,12 @@ fn normalize_platform_differences(output: &str) -> String {
     }
 
     fn expected_output_path(&self, kind: &str) -> PathBuf {
+        // The output with `-Z threads` has to be the same as without it, so there is no
+        // expected output specific to the `parallel-frontend` compare mode.
+        if let Some(CompareMode::ParallelFrontend) = self.config.compare_mode {
+            return expected_output_path(&self.testpaths, self.revision, &None, kind);
+        }
+
         let mut path =
             expected_output_path(&self.testpaths, self.revision, &self.config.compare_mode, kind);
 
@@ -3755,7 +3769,11 @@ fn compare_output(&self, kind: &str, actual: &str, expected: &str) -> usize {
             return 0;
         }
 
-        if !self.config.bless {
+        // Differences in the `parallel-frontend` compare mode are bugs, which can't be blessed.
+        let bless =
+            self.config.bless && self.config.compare_mode != Some(CompareMode::ParallelFrontend);
+
+        if !bless {
             if expected.is_empty() {
                 println!("normalized {}:\n{}\n", kind, actual);
             } else {
@@ -3772,7 +3790,7 @@ fn compare_output(&self, kind: &str, actual: &str, expected: &str) -> usize {
             .with_extra_extension(kind);
 
         let mut files = vec![output_file];
-        if self.config.bless {
+        if bless {
             // Delete non-revision .stderr/.stdout file if revisions are used.
             // Without this, we'd just generate the new files and leave the old files around.
             if self.revision.is_some() {
@@ -3805,7 +3823,7 @@ fn compare_output(&self, kind: &str, actual: &str, expected: &str) -> usize {
         for output_file in files {
             println!("Actual {} saved to {}", kind, output_file.display());
         }
-        if self.config.bless { 0 } else { 1 }
+        if bless { 0 } else { 1 }
     }
 
     fn check_and_prune_duplicate_outputs(
@@ -0,0 +1,43 @@
+// We specify incremental here because we want to test the partitioning for
+// incremental compilation
+// incremental
+// compile-flags:-Zprint-mono-items=lazy
+// compile-flags:-Zcgu-call-graph-profile={{src-base}}/partitioning/auxiliary/call-graph-profile.txt
+
+#![crate_type = "lib"]
+
+// This test makes sure that hot callees are placed in the codegen unit of their
+// hottest caller. Callees that were already placed for a hotter edge stay
+// where they are and have their caller placed with them instead, which empties
+// the codegen unit of `other_caller`. Cold edges are ignored.
+
+pub mod caller {
+    //~ MONO_ITEM fn caller::run @@ call_graph_profile-caller[External]
+    pub fn run() {
+        super::callee::hot();
+        super::callee::cold();
+    }
+
+    //~ MONO_ITEM fn caller::unprofiled @@ call_graph_profile-caller[External]
+    pub fn unprofiled() {}
+}
+
+pub mod other_caller {
+    //~ MONO_ITEM fn other_caller::run @@ call_graph_profile-caller[External]
+    pub fn run() {
+        super::callee::hot();
+    }
+}
+
+pub mod callee {
+    //~ MONO_ITEM fn callee::hot @@ call_graph_profile-caller[External]
+    pub fn hot() {
+        chained();
+    }
+
+    //~ MONO_ITEM fn callee::chained @@ call_graph_profile-caller[External]
+    pub fn chained() {}
+
+    //~ MONO_ITEM fn callee::cold @@ call_graph_profile-callee[External]
+    pub fn cold() {}
+}
@@ -0,0 +1,154 @@
+// compile-flags:-Zpolymorphize=on -Zpolymorphize-by-layout -Zprint-mono-items=lazy -Copt-level=1
+// ignore-32bit
+
+#![crate_type = "rlib"]
+#![feature(core_intrinsics)]
+
+// This test checks that functions which only use a type parameter through its layout are shared
+// between all types with the same size and alignment.
+
+use std::cell::Cell;
+
+mod functions {
+    use std::intrinsics;
+
+    // Function uses the size of the type parameter.
+    pub fn size<T>() -> usize {
+        intrinsics::size_of::<T>()
+    }
+
+//~ MONO_ITEM fn functions::size::<[std::mem::MaybeUninit<u32>; 1]>
+//~ MONO_ITEM fn functions::size::<[std::mem::MaybeUninit<u16>; 2]>
+//~ MONO_ITEM fn functions::size::<[std::mem::MaybeUninit<u64>; 1]>
+//~ MONO_ITEM fn functions::size::<std::cell::Cell<u32>>
+
+    // Function uses type parameter in substitutions to another function, which only uses it
+    // through its layout.
+    pub fn size_twice<T>() -> usize {
+        size::<T>() * 2
+    }
+
+//~ MONO_ITEM fn functions::size_twice::<[std::mem::MaybeUninit<u32>; 1]>
+
+    // Function has an unused type parameter.
+    pub fn unused<T>() {}
+
+//~ MONO_ITEM fn functions::unused::<T>
+
+    // Function passes the type parameter on to a function that doesn't use it, which only
+    // depends on its layout, unlike unused type parameters.
+    pub fn used_substs<T>() {
+        unused::<T>()
+    }
+
+//~ MONO_ITEM fn functions::used_substs::<[std::mem::MaybeUninit<u32>; 1]>
+//~ MONO_ITEM fn functions::used_substs::<[std::mem::MaybeUninit<u64>; 1]>
+
+    // Function calls itself, and otherwise only uses the type parameter through its layout.
+    pub fn size_recursive<T>(n: usize) -> usize {
+        if n == 0 { size::<T>() } else { size_recursive::<T>(n - 1) }
+    }
+
+//~ MONO_ITEM fn functions::size_recursive::<[std::mem::MaybeUninit<u32>; 1]>
+
+    // Functions call each other, which isn't analyzed, so they use the type parameter.
+    pub fn ping<T>(n: usize) -> usize {
+        if n == 0 { size::<T>() } else { pong::<T>(n - 1) }
+    }
+
+    pub fn pong<T>(n: usize) -> usize {
+        if n == 0 { size::<T>() } else { ping::<T>(n - 1) }
+    }
+
+//~ MONO_ITEM fn functions::ping::<u32>
+//~ MONO_ITEM fn functions::ping::<f32>
+//~ MONO_ITEM fn functions::pong::<u32>
+//~ MONO_ITEM fn functions::pong::<f32>
+
+    // Function moves values of the type parameter around in an array.
+    pub fn swap<T: Copy>(a: &mut [T; 2]) {
+        let first = a[0];
+        a[0] = a[1];
+        a[1] = first;
+    }
+
+//~ MONO_ITEM fn functions::swap::<[std::mem::MaybeUninit<u32>; 1]>
+
+    // Function offsets a pointer to the type parameter.
+    pub unsafe fn offset<T>(p: *const T, count: isize) -> *const T {
+        intrinsics::offset(p, count)
+    }
+
+//~ MONO_ITEM fn functions::offset::<[std::mem::MaybeUninit<u32>; 1]>
+
+    // Function uses the name of the type parameter.
+    pub fn name<T>() -> &'static str {
+        intrinsics::type_name::<T>()
+    }
+
+//~ MONO_ITEM fn functions::name::<u32>
+//~ MONO_ITEM fn functions::name::<f32>
+
+    // Function uses the niche of the type parameter.
+    pub fn is_some<T>(o: &Option<T>) -> bool {
+        match o {
+            Some(_) => true,
+            None => false,
+        }
+    }
+
+//~ MONO_ITEM fn functions::is_some::<u32>
+//~ MONO_ITEM fn functions::is_some::<char>
+
+    // Function passes the type parameter by value.
+    pub fn identity<T>(t: T) -> T {
+        t
+    }
+
+//~ MONO_ITEM fn functions::identity::<u32>
+//~ MONO_ITEM fn functions::identity::<f32>
+}
+
+pub fn foo() {
+    // Types with the same size and alignment share a single copy..
+    functions::size::<u32>();
+    functions::size::<f32>();
+    functions::size::<char>();
+    // ..but types with the same size and a different alignment do not..
+    functions::size::<(u16, u16)>();
+    functions::size::<u64>();
+    // ..and neither do types with interior mutability.
+    functions::size::<Cell<u32>>();
+
+    functions::size_twice::<u32>();
+    functions::size_twice::<f32>();
+
+    functions::used_substs::<u32>();
+    functions::used_substs::<f32>();
+    functions::used_substs::<u64>();
+
+    functions::size_recursive::<u32>(1);
+    functions::size_recursive::<f32>(1);
+
+    functions::ping::<u32>(1);
+    functions::ping::<f32>(1);
+
+    functions::swap::<u32>(&mut [1, 2]);
+    functions::swap::<f32>(&mut [1.0, 2.0]);
+
+    unsafe {
+        functions::offset::<u32>(&1, 0);
+        functions::offset::<char>(&'c', 0);
+    }
+
+    functions::name::<u32>();
+    functions::name::<f32>();
+
+    functions::is_some::<u32>(&None);
+    functions::is_some::<char>(&None);
+
+    functions::identity::<u32>(1);
+    functions::identity::<f32>(1.0);
+}
+
+//~ MONO_ITEM fn foo @@ layout_only_type_parameters-cgu.0[External]
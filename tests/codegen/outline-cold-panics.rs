@@ -0,0 +1,66 @@
+// Checks that panics with a constant message, assertions and `Option::expect` are outlined into
+// shared cold functions that receive the location as an argument, and that the identical failure
+// paths of inlined calls share a single call site.
+
+// compile-flags: -Copt-level=s -Coverflow-checks=on -Zinline-mir -Zoutline-cold-panics
+// ignore-debug: the debug assertions get in the way
+
+#![crate_type = "lib"]
+
+#[inline]
+fn nonzero(x: u32) -> u32 {
+    if x == 0 {
+        unreachable!()
+    }
+    x
+}
+
+// CHECK-LABEL: @sum_nonzero
+#[no_mangle]
+pub fn sum_nonzero(a: u32, b: u32) -> u32 {
+    // CHECK-NOT: call {{.*}}core9panicking5panic17h
+    // CHECK: call void @{{.*}}core9panicking14panic_outlined{{.*}}(ptr {{.*}}, i{{32|64}} {{.*}}, ptr {{.*}})
+    // CHECK-NOT: call
+    // CHECK: {{^}$}}
+    nonzero(a).wrapping_add(nonzero(b))
+}
+
+// CHECK-LABEL: @unwrap
+#[no_mangle]
+pub fn unwrap(x: Option<u32>) -> u32 {
+    // CHECK-NOT: call {{.*}}core9panicking5panic17h
+    // CHECK: call void @{{.*}}core9panicking14panic_outlined{{.*}}(ptr {{.*}}, i{{32|64}} {{.*}}, ptr {{.*}})
+    // CHECK-NOT: call
+    // CHECK: {{^}$}}
+    x.unwrap()
+}
+
+// CHECK-LABEL: @checked_add
+#[no_mangle]
+pub fn checked_add(a: u32, b: u32) -> u32 {
+    // CHECK-NOT: call {{.*}}core9panicking5panic17h
+    // CHECK: call void @{{.*}}core9panicking14panic_outlined{{.*}}(ptr {{.*}}, i{{32|64}} {{.*}}, ptr {{.*}})
+    // CHECK-NOT: call
+    // CHECK: {{^}$}}
+    a + b
+}
+
+// CHECK-LABEL: @index
+#[no_mangle]
+pub fn index(a: &[u32], i: usize) -> u32 {
+    // CHECK-NOT: call {{.*}}core9panicking18panic_bounds_check17h
+    // CHECK: call void @{{.*}}core9panicking27panic_bounds_check_outlined{{.*}}(i{{32|64}} %i, i{{32|64}} %a.1, ptr {{.*}})
+    // CHECK-NOT: call
+    // CHECK: {{^}$}}
+    a[i]
+}
+
+// CHECK-LABEL: @expect
+#[no_mangle]
+pub fn expect(x: Option<u32>) -> u32 {
+    // CHECK-NOT: call {{.*}}expect_failed
+    // CHECK: call void @{{.*}}core9panicking18panic_str_outlined{{.*}}(ptr {{.*}}, i{{32|64}} {{.*}}, ptr {{.*}})
+    // CHECK-NOT: call
+    // CHECK: {{^}$}}
+    x.expect("a value")
+}
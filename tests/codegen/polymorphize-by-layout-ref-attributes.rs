@@ -0,0 +1,33 @@
+// compile-flags: -O -C no-prepopulate-passes -Z polymorphize=on -Z polymorphize-by-layout
+
+#![crate_type = "lib"]
+
+// This test checks that references to a type parameter that is only used through its layout
+// get the same attributes in the instance that is shared between all types of that layout as
+// in a monomorphic function for one of those types.
+
+// CHECK-DAG: define{{.*}}void @copy_u32(ptr [[DST:[^%]*]]%dst, ptr [[SRC:[^%]*]]%src)
+// CHECK-DAG: define{{.*}}void @{{.*}}copy_ref{{.*}}(ptr [[DST]]%dst, ptr [[SRC]]%src)
+#[no_mangle]
+pub fn copy_u32(dst: &mut u32, src: &u32) {
+    *dst = *src;
+}
+
+// CHECK-DAG: define{{.*}}void @copy_u16x3(ptr [[DST16:[^%]*]]%dst, ptr [[SRC16:[^%]*]]%src)
+// CHECK-DAG: define{{.*}}void @{{.*}}copy_ref{{.*}}(ptr [[DST16]]%dst, ptr [[SRC16]]%src)
+#[no_mangle]
+pub fn copy_u16x3(dst: &mut [u16; 3], src: &[u16; 3]) {
+    *dst = *src;
+}
+
+#[inline(never)]
+pub fn copy_ref<T: Copy>(dst: &mut T, src: &T) {
+    *dst = *src;
+}
+
+pub fn copy_all(a: &mut u32, b: &mut f32, c: &mut [u16; 3], d: &mut [i16; 3]) {
+    copy_ref(a, &1);
+    copy_ref(b, &1.0);
+    copy_ref(c, &[1, 2, 3]);
+    copy_ref(d, &[1, 2, 3]);
+}
@@ -0,0 +1,26 @@
+// Test that a build that only emits metadata, after a build of the same crate
+// that emits a linked artifact, keeps the work products of the latter, so that
+// a later build that links again reuses them instead of doing codegen again.
+
+// revisions: cfail1 cfail2 cfail3
+// compile-flags: -Z query-dep-graph
+// build-pass
+//[cfail1] compile-flags: --emit=link
+//[cfail2] compile-flags: --emit=metadata
+//[cfail3] compile-flags: --emit=link
+
+#![feature(rustc_attrs)]
+#![crate_type = "rlib"]
+#![rustc_partition_reused(module="link_then_metadata", cfg="cfail3")]
+
+#[rustc_clean(cfg="cfail2")]
+#[rustc_clean(cfg="cfail3")]
+pub fn add(a: u32, b: u32) -> u32 {
+    a.wrapping_add(b)
+}
+
+#[rustc_clean(cfg="cfail2")]
+#[rustc_clean(cfg="cfail3")]
+pub fn call_add() -> u32 {
+    add(1, 2)
+}
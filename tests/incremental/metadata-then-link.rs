@@ -0,0 +1,27 @@
+// Test that a build that only emits metadata and a later build of the same
+// crate that emits a linked artifact share their incremental state, so that
+// the latter reuses the analysis of the former and only has to do codegen.
+
+// revisions: cfail1 cfail2 cfail3
+// compile-flags: -Z query-dep-graph
+// build-pass
+//[cfail1] compile-flags: --emit=metadata
+//[cfail2] compile-flags: --emit=link
+//[cfail3] compile-flags: --emit=metadata
+
+#![feature(rustc_attrs)]
+#![crate_type = "rlib"]
+#![rustc_partition_codegened(module="metadata_then_link", cfg="cfail2")]
+
+// The results of type checking are loaded from disk, not just marked green.
+#[rustc_clean(cfg="cfail2", loaded_from_disk="typeck")]
+#[rustc_clean(cfg="cfail3")]
+pub fn add(a: u32, b: u32) -> u32 {
+    a.wrapping_add(b)
+}
+
+#[rustc_clean(cfg="cfail2", loaded_from_disk="typeck")]
+#[rustc_clean(cfg="cfail3")]
+pub fn call_add() -> u32 {
+    add(1, 2)
+}
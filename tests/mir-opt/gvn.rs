@@ -0,0 +1,50 @@
+// unit-test: GVN
+
+use std::cell::Cell;
+
+// EMIT_MIR gvn.repeated_bitand.GVN.diff
+pub fn repeated_bitand(x: u64, y: u64) -> u64 {
+    (x & y) | (x & y)
+}
+
+// EMIT_MIR gvn.repeated_deref.GVN.diff
+pub fn repeated_deref(x: &(u32, u32)) -> u32 {
+    x.0 & x.0
+}
+
+// Writes through a mutable reference change the value behind it.
+// EMIT_MIR gvn.mutable_reference_write.GVN.diff
+pub fn mutable_reference_write(x: &mut u32) -> u32 {
+    let a = *x;
+    *x = 5;
+    a ^ *x
+}
+
+// Nothing is read through shared references to types with interior mutability, even if the
+// read field is not the one behind the `Cell`.
+// EMIT_MIR gvn.interior_mutability.GVN.diff
+pub fn interior_mutability(x: &(Cell<u32>, u32)) -> u32 {
+    let a = x.1;
+    x.0.set(a);
+    a ^ x.1
+}
+
+// `x` changes in each iteration, so `b` cannot reuse `a`.
+// EMIT_MIR gvn.loop_carried.GVN.diff
+pub fn loop_carried(mut x: u32, y: u32) {
+    while x > y {
+        let a = x & y;
+        x = a - 1;
+        let b = x & y;
+        x = b;
+    }
+}
+
+// `a` is borrowed, so it may no longer hold the value it was assigned.
+// EMIT_MIR gvn.borrowed.GVN.diff
+pub fn borrowed(x: u32) -> u32 {
+    let mut a = x & 1;
+    let r = &mut a;
+    *r = 5;
+    a ^ (x & 1)
+}
@@ -0,0 +1,12 @@
+// unit-test: OutlineColdPanics
+// compile-flags: -Zlocation-detail=none
+// ignore-endian-big
+// EMIT_MIR_FOR_EACH_BIT_WIDTH
+// EMIT_MIR outline_cold_panics.unwrap_or_unreachable.OutlineColdPanics.diff
+
+pub fn unwrap_or_unreachable(x: Option<u32>) -> u32 {
+    match x {
+        Some(x) => x,
+        None => unreachable!(),
+    }
+}
@@ -58,6 +58,7 @@ fn compile(code: String, output: PathBuf, sysroot: PathBuf) {
         register_lints: None,
         override_queries: None,
         make_codegen_backend: None,
+        diagnostic_snapshot: None,
         registry: rustc_driver::diagnostics_registry(),
     };
 
@@ -0,0 +1,31 @@
+#![feature(rustc_private)]
+
+extern crate rustc_driver;
+extern crate rustc_hir;
+#[macro_use]
+extern crate rustc_lint;
+#[macro_use]
+extern crate rustc_session;
+
+use rustc_driver::plugin::declare_lint_plugin;
+use rustc_hir as hir;
+use rustc_lint::{LateContext, LateLintPass, LintContext};
+
+declare_tool_lint!(pub our_org::FOO_FN, Warn, "functions named `foo`");
+
+declare_lint_pass!(FooFn => [FOO_FN]);
+
+impl<'tcx> LateLintPass<'tcx> for FooFn {
+    fn check_item(&mut self, cx: &LateContext<'tcx>, it: &'tcx hir::Item<'tcx>) {
+        if let hir::ItemKind::Fn(..) = it.kind {
+            if it.ident.name.as_str() == "foo" {
+                cx.lint(FOO_FN, "function named `foo`", |lint| lint.set_span(it.span));
+            }
+        }
+    }
+}
+
+declare_lint_plugin!(our_org, |reg| {
+    reg.lint_store.register_lints(&[&FOO_FN]);
+    reg.lint_store.register_late_pass(|_| Box::new(FooFn));
+});
@@ -0,0 +1,8 @@
+pub mod a {
+    pub fn foo() {}
+}
+
+#[allow(our_org::foo_fn)]
+pub mod b {
+    pub fn foo() {}
+}
@@ -0,0 +1,53 @@
+#![feature(rustc_private)]
+
+//! This program implements a tool that prints a summary of the Stable MIR of
+//! every item of a crate: its kind, its generic parameters, the types of its
+//! arguments and return value, and the functions it calls.
+
+extern crate rustc_driver;
+extern crate rustc_smir;
+
+use rustc_smir::stable_mir::mir::{Operand, TerminatorKind};
+use rustc_smir::stable_mir::ty::{Const, TyKind};
+use rustc_smir::stable_mir::{CrateItem, Version, VERSION};
+
+/// The version of the Stable MIR model this tool is written against.
+const REQUIRED_VERSION: Version = Version { major: 0, minor: 1, patch: 0 };
+
+fn main() {
+    assert!(VERSION.is_compatible_with(REQUIRED_VERSION));
+    let exit_code = rustc_driver::catch_with_exit_code(move || {
+        let rustc_args: Vec<_> = std::env::args().collect();
+        rustc_smir::rustc_internal::run_analysis(&rustc_args, print_item)
+    });
+    std::process::exit(exit_code);
+}
+
+fn print_item(item: &CrateItem) {
+    let generics: Vec<_> = item.generics.params.iter().map(|param| &param.name[..]).collect();
+    println!("{} ({:?}, generics: {:?})", item.def_id.path, item.kind, generics);
+    println!(
+        "  args: {:?}",
+        item.body.args().iter().map(|arg| ty_name(arg.ty.kind())).collect::<Vec<_>>()
+    );
+    println!("  return: {}", ty_name(item.body.ret_ty().kind()));
+    for block in &item.body.blocks {
+        if let TerminatorKind::Call { func: Operand::Constant(func), .. } = &block.terminator.kind {
+            let Const { ty, .. } = &func.literal;
+            if let TyKind::FnDef(def_id, _) = ty.kind() {
+                println!("  calls: {}", def_id.path);
+            }
+        }
+    }
+}
+
+fn ty_name(ty: &TyKind) -> String {
+    match ty {
+        TyKind::Uint(uint_ty) => format!("{uint_ty:?}"),
+        TyKind::Adt(def_id, _) => def_id.path.clone(),
+        TyKind::Param(param) => param.name.clone(),
+        TyKind::Closure(def_id, _) => def_id.path.clone(),
+        TyKind::Ref(_, ty, _) => format!("&{}", ty_name(ty.kind())),
+        other => format!("{other:?}"),
+    }
+}
@@ -0,0 +1,13 @@
+#![crate_type = "lib"]
+
+pub struct Wrapper(pub u32);
+
+pub const LIMIT: u32 = 10;
+
+pub fn clamp(x: u32) -> u32 {
+    if x > LIMIT { LIMIT } else { x }
+}
+
+pub fn sum<T: Into<u32>>(items: Vec<T>) -> u32 {
+    items.into_iter().map(|item| clamp(item.into())).sum()
+}
@@ -0,0 +1,9 @@
+#![crate_type = "lib"]
+
+pub fn f() {
+    let a = 1;
+    let b = 2;
+    let c = 3;
+    let d = 4;
+    let e = 5;
+}
@@ -0,0 +1,11 @@
+pub mod caller {
+    pub fn run() -> u32 {
+        super::callee::hot()
+    }
+}
+
+pub mod callee {
+    pub fn hot() -> u32 {
+        1
+    }
+}
@@ -0,0 +1,6 @@
+#![feature(rustc_attrs)]
+
+#[rustc_clean(cfg = "build", loaded_from_disk = "typeck")]
+pub fn add(a: u32, b: u32) -> u32 {
+    a.wrapping_add(b)
+}
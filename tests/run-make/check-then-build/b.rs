@@ -0,0 +1,8 @@
+#![feature(rustc_attrs)]
+
+extern crate a;
+
+#[rustc_clean(cfg = "build", loaded_from_disk = "typeck")]
+pub fn call_add() -> u32 {
+    a::add(1, 2)
+}
@@ -0,0 +1,28 @@
+extern crate llvm_lib;
+
+use llvm_lib::{Rect, Shape};
+use std::collections::HashMap;
+
+pub struct Square(pub f64);
+
+impl Shape for Square {
+    fn area(&self) -> f64 {
+        self.0 * self.0
+    }
+}
+
+pub fn doubled(items: &[u32]) -> Vec<u32> {
+    llvm_lib::map(items, |x| llvm_lib::add(*x, *x))
+}
+
+pub fn shapes() -> Vec<Box<dyn Shape>> {
+    vec![Box::new(Rect { width: 2.0, height: 3.0 }), Box::new(Square(2.5))]
+}
+
+pub fn summary(llvm: u32, clif: &[u32], shapes: f64) -> String {
+    let mut parts = HashMap::new();
+    parts.insert("llvm", llvm.to_string());
+    parts.insert("clif", format!("{:?}", clif));
+    parts.insert("shapes", shapes.to_string());
+    format!("llvm: {}, clif: {}, shapes: {}", parts["llvm"], parts["clif"], parts["shapes"])
+}
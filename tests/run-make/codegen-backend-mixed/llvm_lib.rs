@@ -0,0 +1,22 @@
+pub trait Shape {
+    fn area(&self) -> f64;
+}
+
+pub struct Rect {
+    pub width: f64,
+    pub height: f64,
+}
+
+impl Shape for Rect {
+    fn area(&self) -> f64 {
+        self.width * self.height
+    }
+}
+
+pub fn add(a: u32, b: u32) -> u32 {
+    a + b
+}
+
+pub fn map<T, U>(items: &[T], f: impl Fn(&T) -> U) -> Vec<U> {
+    items.iter().map(f).collect()
+}
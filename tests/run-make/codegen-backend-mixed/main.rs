@@ -0,0 +1,10 @@
+extern crate clif_lib;
+extern crate llvm_lib;
+
+use llvm_lib::Shape;
+
+fn main() {
+    let shapes: f64 = clif_lib::shapes().iter().map(|shape| shape.area()).sum();
+    let clif = clif_lib::doubled(&[1, 2, 3]);
+    println!("{}", clif_lib::summary(llvm_lib::add(1, 2), &clif, shapes));
+}
@@ -0,0 +1,20 @@
+#![cfg_attr(limit, feature(const_eval_limit))]
+#![cfg_attr(limit, const_eval_limit = "1000")]
+
+const fn fib(n: u32) -> u32 {
+    if n < 2 { n } else { fib(n - 1) + fib(n - 2) }
+}
+
+const fn square(n: u32) -> u32 {
+    n * n
+}
+
+pub const TABLE: [u32; 16] = {
+    let mut table = [0; 16];
+    let mut i = 0;
+    while i < 16 {
+        table[i] = fib(i as u32) + square(i as u32);
+        i += 1;
+    }
+    table
+};
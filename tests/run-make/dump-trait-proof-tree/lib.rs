@@ -0,0 +1,26 @@
+pub trait Show {}
+
+impl Show for u8 {}
+impl<T: Show> Show for Vec<T> {}
+impl<T: Show> Show for Option<T> {}
+
+fn show<T: Show>(_: T) {}
+
+pub fn check<T: Show>(value: Option<Vec<T>>) {
+    show(value);
+}
+
+pub fn unrelated(value: Vec<u8>) {
+    show(value);
+}
+
+#[cfg(overflow)]
+pub struct Wrap<T>(T);
+
+#[cfg(overflow)]
+impl<T> Show for Wrap<T> where Wrap<Wrap<T>>: Show {}
+
+#[cfg(overflow)]
+pub fn overflow() {
+    show(Wrap(0u8));
+}
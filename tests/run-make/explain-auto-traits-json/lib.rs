@@ -0,0 +1,15 @@
+use std::rc::Rc;
+
+struct Outer {
+    middle: Middle,
+}
+
+struct Middle {
+    leaf: Rc<()>,
+}
+
+fn is_send<T: Send>() {}
+
+pub fn check() {
+    is_send::<Outer>();
+}
@@ -0,0 +1,3 @@
+fn main() {
+    let async = "héllo"; /* a comment */ let _: () = async;
+}
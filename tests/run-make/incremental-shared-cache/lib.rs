@@ -0,0 +1,15 @@
+pub mod a {
+    pub fn foo() -> u32 {
+        bar() + 1
+    }
+
+    fn bar() -> u32 {
+        41
+    }
+}
+
+pub mod b {
+    pub fn baz() -> u32 {
+        42
+    }
+}
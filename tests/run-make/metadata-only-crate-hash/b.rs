@@ -0,0 +1,7 @@
+#![crate_type = "rlib"]
+
+extern crate a;
+
+pub fn foo() {
+    a::foo::<u32>();
+}
@@ -0,0 +1,5 @@
+extern crate b;
+
+fn main() {
+    b::foo();
+}
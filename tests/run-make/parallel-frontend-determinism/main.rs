@@ -0,0 +1,55 @@
+// Many small modules, each with warnings from several passes that run in parallel over
+// modules and bodies. Checking each module forces queries of a later module, so that a
+// parallel compiler may run those queries for another module than a single-threaded one.
+
+macro_rules! module {
+    ($($name:ident => $later:ident),*) => {
+        $(
+            pub mod $name {
+                pub fn used<T: Clone>(x: T) -> (T, T) {
+                    let unused = 1;
+                    (x.clone(), x)
+                }
+
+                fn dead() -> u8 {
+                    0
+                }
+
+                pub fn generic<T: std::fmt::Debug>(x: T) -> String {
+                    format!("{}: {:?}", stringify!($name), x)
+                }
+
+                // The warnings of this function are emitted by its `typeck` and `mir_borrowck`
+                // queries, which checking whether its return type is `Send` forces.
+                pub fn opaque() -> impl Sized {
+                    let mut not_mut = 1u8;
+                    return not_mut;
+                    let unreachable = 2u8;
+                }
+
+                pub fn forces_later() {
+                    crate::is_send(super::$later::opaque());
+                }
+            }
+        )*
+
+        pub fn run() {
+            $(
+                $name::used(stringify!($name));
+                println!("{}", $name::generic((1u8, "x")));
+                $name::forces_later();
+            )*
+        }
+    };
+}
+
+fn is_send<T: Send>(_: T) {}
+
+module!(
+    a => p, b => o, c => n, d => m, e => l, f => k, g => j, h => i,
+    i => h, j => g, k => f, l => e, m => d, n => c, o => b, p => a
+);
+
+fn main() {
+    run();
+}
@@ -0,0 +1,40 @@
+// A function whose body only depends on the size and alignment of `T`.
+#[inline(never)]
+fn rotate<T: Copy>(items: &mut [T; 8]) {
+    let first = items[0];
+    let mut i = 0;
+    while i < 7 {
+        items[i] = items[i + 1];
+        i += 1;
+    }
+    items[7] = first;
+}
+
+#[derive(Clone, Copy, PartialEq)]
+struct Meters(u32);
+
+#[derive(Clone, Copy, PartialEq)]
+struct Seconds(f32);
+
+fn main() {
+    let mut a = [0u32, 1, 2, 3, 4, 5, 6, 7];
+    let mut b = [0i32, -1, -2, -3, -4, -5, -6, -7];
+    let mut c = [0.0f32, 0.5, 1.0, 1.5, 2.0, 2.5, 3.0, 3.5];
+    let mut d = ['a', 'b', 'c', 'd', 'e', 'f', 'g', 'h'];
+    let mut e = a.map(Meters);
+    let mut f = c.map(Seconds);
+
+    rotate(&mut a);
+    rotate(&mut b);
+    rotate(&mut c);
+    rotate(&mut d);
+    rotate(&mut e);
+    rotate(&mut f);
+
+    assert!(a == [1, 2, 3, 4, 5, 6, 7, 0]);
+    assert!(b == [-1, -2, -3, -4, -5, -6, -7, 0]);
+    assert!(c == [0.5, 1.0, 1.5, 2.0, 2.5, 3.0, 3.5, 0.0]);
+    assert!(d == ['b', 'c', 'd', 'e', 'f', 'g', 'h', 'a']);
+    assert!(e == [1, 2, 3, 4, 5, 6, 7, 0].map(Meters));
+    assert!(f == [0.5, 1.0, 1.5, 2.0, 2.5, 3.0, 3.5, 0.0].map(Seconds));
+}
@@ -0,0 +1,25 @@
+fn many_statements() -> u64 {
+    let mut x = 0u64;
+    x = x.wrapping_mul(3).wrapping_add(1);
+    x = x.wrapping_mul(3).wrapping_add(2);
+    x = x.wrapping_mul(3).wrapping_add(3);
+    x = x.wrapping_mul(3).wrapping_add(4);
+    x = x.wrapping_mul(3).wrapping_add(5);
+    x = x.wrapping_mul(3).wrapping_add(6);
+    x = x.wrapping_mul(3).wrapping_add(7);
+    x = x.wrapping_mul(3).wrapping_add(8);
+    x
+}
+
+fn instantiated_often<T: Default + Clone>() -> Vec<T> {
+    vec![T::default(); 4]
+}
+
+fn main() {
+    many_statements();
+    instantiated_often::<u8>();
+    instantiated_often::<u16>();
+    instantiated_often::<u32>();
+    instantiated_often::<u64>();
+    instantiated_often::<String>();
+}
@@ -0,0 +1,184 @@
+// run-pass
+// Test that the borrow set, the region values and the loans in scope that
+// `rustc_borrowck::consumers` exposes match the borrows of a small program.
+
+// ignore-cross-compile
+// ignore-stage1
+// ignore-remote
+
+#![feature(rustc_private)]
+
+extern crate rustc_borrowck;
+extern crate rustc_driver;
+extern crate rustc_hir;
+extern crate rustc_interface;
+extern crate rustc_middle;
+extern crate rustc_session;
+extern crate rustc_span;
+
+use rustc_borrowck::consumers::{
+    get_body_with_borrowck_facts, BodyWithBorrowckFacts, BorrowIndex, ConsumerOptions,
+    LoansInScope, TwoPhaseActivation,
+};
+use rustc_hir::def_id::LocalDefId;
+use rustc_interface::interface;
+use rustc_middle::mir::{BorrowKind, TerminatorKind, START_BLOCK};
+use rustc_middle::ty::query::query_values::mir_borrowck;
+use rustc_middle::ty::query::{ExternProviders, Providers};
+use rustc_middle::ty::{self, TyCtxt};
+use rustc_session::Session;
+use rustc_span::source_map::FileLoader;
+use std::io;
+use std::path::Path;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+const PROGRAM: &str = r#"
+#![feature(no_core, lang_items)]
+#![no_core]
+
+#[lang = "sized"]
+trait Sized {}
+#[lang = "copy"]
+trait Copy {}
+impl Copy for i32 {}
+
+pub struct Counter {
+    count: i32,
+}
+
+impl Counter {
+    fn get(&self) -> i32 {
+        self.count
+    }
+
+    fn set(&mut self, count: i32) {
+        self.count = count;
+    }
+}
+
+pub fn reset(counter: &mut Counter) {
+    counter.set(counter.get());
+}
+
+pub fn reborrow(x: &mut i32) -> i32 {
+    let y = &*x;
+    *y
+}
+"#;
+
+/// The number of bodies that have been checked.
+static CHECKED: AtomicUsize = AtomicUsize::new(0);
+
+struct InMemoryLoader;
+
+impl FileLoader for InMemoryLoader {
+    fn file_exists(&self, path: &Path) -> bool {
+        path == Path::new("program.rs")
+    }
+
+    fn read_file(&self, path: &Path) -> io::Result<String> {
+        assert_eq!(path, Path::new("program.rs"));
+        Ok(PROGRAM.to_string())
+    }
+}
+
+struct CompilerCalls;
+
+impl rustc_driver::Callbacks for CompilerCalls {
+    fn config(&mut self, config: &mut interface::Config) {
+        config.override_queries = Some(override_queries);
+    }
+}
+
+fn override_queries(_session: &Session, local: &mut Providers, _external: &mut ExternProviders) {
+    local.mir_borrowck = mir_borrowck;
+}
+
+fn mir_borrowck<'tcx>(tcx: TyCtxt<'tcx>, def_id: LocalDefId) -> mir_borrowck<'tcx> {
+    let body_with_facts = get_body_with_borrowck_facts(
+        tcx,
+        ty::WithOptConstParam::unknown(def_id),
+        ConsumerOptions::RegionInferenceContext,
+    );
+    assert!(body_with_facts.input_facts.is_none());
+    assert!(body_with_facts.output_facts.is_none());
+    match tcx.item_name(def_id.to_def_id()).as_str() {
+        "reborrow" => check_reborrow(tcx, &body_with_facts),
+        "reset" => check_reset(tcx, &body_with_facts),
+        _ => {}
+    }
+
+    let mut providers = Providers::default();
+    rustc_borrowck::provide(&mut providers);
+    (providers.mir_borrowck)(tcx, def_id)
+}
+
+/// `let y = &*x; *y`: the loan is in scope until `y` is last used.
+fn check_reborrow<'tcx>(tcx: TyCtxt<'tcx>, body_with_facts: &BodyWithBorrowckFacts<'tcx>) {
+    let BodyWithBorrowckFacts { body, borrow_set, region_inference_context, .. } =
+        body_with_facts;
+    assert_eq!(borrow_set.location_map.len(), 1);
+    let loan = BorrowIndex::from_usize(0);
+    let borrow = &borrow_set[loan];
+    assert_eq!(borrow.kind, BorrowKind::Shared);
+    assert_eq!(borrow.activation_location, TwoPhaseActivation::NotTwoPhase);
+
+    let mut loans_in_scope = LoansInScope::new(tcx, body_with_facts);
+
+    let after_borrow = borrow.reserve_location.successor_within_block();
+    assert!(region_inference_context.region_contains_location(borrow.region, after_borrow));
+    assert_eq!(loans_in_scope.at(after_borrow), [loan]);
+
+    let exit = body.terminator_loc(START_BLOCK);
+    assert!(matches!(body[START_BLOCK].terminator().kind, TerminatorKind::Return));
+    assert!(!region_inference_context.region_contains_location(borrow.region, exit));
+    assert!(loans_in_scope.at(exit).is_empty());
+
+    CHECKED.fetch_add(1, Ordering::SeqCst);
+}
+
+/// `counter.set(counter.get())`: the mutable borrow of `counter` is a two-phase borrow, which
+/// is activated by the call to `set`, after the shared borrow for `get` has ended.
+fn check_reset<'tcx>(tcx: TyCtxt<'tcx>, body_with_facts: &BodyWithBorrowckFacts<'tcx>) {
+    let borrow_set = &body_with_facts.borrow_set;
+    assert_eq!(borrow_set.location_map.len(), 2);
+    let mut two_phase = None;
+    let mut shared = None;
+    for (index, borrow) in borrow_set.location_map.values().enumerate() {
+        let loan = BorrowIndex::from_usize(index);
+        match (borrow.kind, borrow.activation_location) {
+            (BorrowKind::Mut { .. }, TwoPhaseActivation::ActivatedAt(activation)) => {
+                two_phase = Some((loan, activation))
+            }
+            (BorrowKind::Shared, TwoPhaseActivation::NotTwoPhase) => shared = Some(loan),
+            other => panic!("unexpected borrow {:?}", other),
+        }
+    }
+    let (two_phase, activation) = two_phase.unwrap();
+    let shared = shared.unwrap();
+    assert_eq!(borrow_set.activation_map[&activation], [two_phase]);
+
+    let mut loans_in_scope = LoansInScope::new(tcx, body_with_facts);
+    let at_activation = loans_in_scope.at(activation);
+    assert!(at_activation.contains(&two_phase));
+    assert!(!at_activation.contains(&shared));
+
+    CHECKED.fetch_add(1, Ordering::SeqCst);
+}
+
+fn main() {
+    let args = vec![
+        "borrowck-consumers".to_string(),
+        "program.rs".to_string(),
+        "--crate-type=lib".to_string(),
+        "--emit=metadata".to_string(),
+    ];
+    rustc_driver::catch_fatal_errors(|| {
+        rustc_driver::RunCompiler::new(&args, &mut CompilerCalls)
+            .set_file_loader(Some(Box::new(InMemoryLoader)))
+            .run()
+    })
+    .unwrap()
+    .unwrap();
+    assert_eq!(CHECKED.load(Ordering::SeqCst), 2);
+}
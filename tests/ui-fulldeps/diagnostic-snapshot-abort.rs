@@ -0,0 +1,67 @@
+// run-pass
+// Test that drivers get a snapshot of the diagnostics emitted before a fatal error aborted
+// the compilation.
+
+// ignore-cross-compile
+// ignore-stage1
+// ignore-remote
+
+#![feature(rustc_private)]
+
+extern crate rustc_driver;
+extern crate rustc_errors;
+extern crate rustc_interface;
+extern crate rustc_session;
+extern crate rustc_span;
+
+use rustc_errors::snapshot::{ResolvedDiagnostic, SnapshotPhase};
+use rustc_errors::{DiagnosticId, Level};
+use rustc_interface::interface;
+use rustc_session::config::Input;
+use rustc_span::FileName;
+use std::sync::{Arc, Mutex};
+
+// The lexer raises a fatal error for the unterminated comment while parsing.
+const SOURCE: &str = "fn main() {}\n/* unterminated\n";
+
+type Snapshots = Arc<Mutex<Vec<(SnapshotPhase, Vec<ResolvedDiagnostic>)>>>;
+
+struct SnapshotCalls {
+    snapshots: Snapshots,
+}
+
+impl rustc_driver::Callbacks for SnapshotCalls {
+    fn config(&mut self, config: &mut interface::Config) {
+        config.input = Input::Str {
+            name: FileName::Custom("snapshot".to_string()),
+            input: SOURCE.to_string(),
+        };
+        let snapshots = self.snapshots.clone();
+        config.diagnostic_snapshot = Some(Box::new(move |phase, diagnostics| {
+            snapshots.lock().unwrap().push((phase, diagnostics));
+        }));
+    }
+}
+
+fn main() {
+    let snapshots = Snapshots::default();
+    let args = vec!["diagnostic-snapshot-abort".to_string(), "snapshot.rs".to_string()];
+    rustc_driver::catch_fatal_errors(|| {
+        rustc_driver::RunCompiler::new(&args, &mut SnapshotCalls { snapshots: snapshots.clone() })
+            .run()
+            .ok();
+    })
+    .ok();
+
+    let snapshots = snapshots.lock().unwrap();
+    let [(SnapshotPhase::Abort, diagnostics)] = &snapshots[..] else {
+        panic!("expected only the snapshot of the abort")
+    };
+    let [error] = &diagnostics[..] else { panic!("expected one error") };
+    assert_eq!(error.level, Level::Fatal);
+    assert_eq!(error.message, "unterminated block comment");
+    assert_eq!(error.code, Some(DiagnosticId::Error("E0758".to_string())));
+    let [span] = &error.spans[..] else { panic!("expected one span") };
+    assert_eq!(span.file_name, "<snapshot>");
+    assert_eq!((span.line_start, span.column_start), (2, 1));
+}
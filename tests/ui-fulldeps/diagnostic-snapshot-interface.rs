@@ -0,0 +1,67 @@
+// run-pass
+// Test that users of `rustc_interface` that don't run codegen still get the diagnostics of
+// analysis in the last snapshot.
+
+// ignore-cross-compile
+// ignore-stage1
+// ignore-remote
+
+#![feature(rustc_private)]
+
+extern crate rustc_driver;
+extern crate rustc_errors;
+extern crate rustc_interface;
+extern crate rustc_session;
+extern crate rustc_span;
+
+use rustc_errors::snapshot::{ResolvedDiagnostic, SnapshotPhase};
+use rustc_errors::Level;
+use rustc_interface::interface;
+use rustc_session::config::{Input, Options};
+use rustc_span::FileName;
+use std::sync::{Arc, Mutex};
+
+const SOURCE: &str = "fn main() {\n    let unused = 1;\n}\n";
+
+type Snapshots = Arc<Mutex<Vec<(SnapshotPhase, Vec<ResolvedDiagnostic>)>>>;
+
+fn main() {
+    let snapshots = Snapshots::default();
+    let recorded = snapshots.clone();
+    let config = interface::Config {
+        opts: Options::default(),
+        crate_cfg: Default::default(),
+        crate_check_cfg: Default::default(),
+        input: Input::Str {
+            name: FileName::Custom("snapshot".to_string()),
+            input: SOURCE.to_string(),
+        },
+        output_file: None,
+        output_dir: None,
+        file_loader: None,
+        lint_caps: Default::default(),
+        parse_sess_created: None,
+        register_lints: None,
+        override_queries: None,
+        make_codegen_backend: None,
+        diagnostic_snapshot: Some(Box::new(move |phase, diagnostics| {
+            recorded.lock().unwrap().push((phase, diagnostics));
+        })),
+        registry: rustc_driver::diagnostics_registry(),
+    };
+
+    // Only run analysis, without going through `Queries::ongoing_codegen`.
+    interface::run_compiler(config, |compiler| {
+        compiler.enter(|queries| {
+            queries.global_ctxt().unwrap().enter(|tcx| tcx.analysis(()).ok());
+        });
+    });
+
+    let snapshots = snapshots.lock().unwrap();
+    let phases: Vec<_> = snapshots.iter().map(|(phase, _)| *phase).collect();
+    assert_eq!(phases, [SnapshotPhase::Parse, SnapshotPhase::Expansion, SnapshotPhase::Finish]);
+
+    let [warning] = &snapshots[2].1[..] else { panic!("expected one warning") };
+    assert_eq!(warning.level, Level::Warning(None));
+    assert_eq!(warning.message, "unused variable: `unused`");
+}
@@ -0,0 +1,89 @@
+// run-pass
+// Test that drivers can get snapshots of the emitted diagnostics with resolved spans.
+
+// ignore-cross-compile
+// ignore-stage1
+// ignore-remote
+
+#![feature(rustc_private)]
+
+extern crate rustc_driver;
+extern crate rustc_errors;
+extern crate rustc_interface;
+extern crate rustc_session;
+extern crate rustc_span;
+
+use rustc_driver::Compilation;
+use rustc_errors::snapshot::{ResolvedDiagnostic, SnapshotPhase};
+use rustc_errors::Level;
+use rustc_interface::{interface, Queries};
+use rustc_session::config::Input;
+use rustc_span::FileName;
+use std::sync::{Arc, Mutex};
+
+const SOURCE: &str = "fn main() {\n    let unused = 1;\n}\n";
+
+type Snapshots = Arc<Mutex<Vec<(SnapshotPhase, Vec<ResolvedDiagnostic>)>>>;
+
+struct SnapshotCalls {
+    snapshots: Snapshots,
+}
+
+impl rustc_driver::Callbacks for SnapshotCalls {
+    fn config(&mut self, config: &mut interface::Config) {
+        config.input = Input::Str {
+            name: FileName::Custom("snapshot".to_string()),
+            input: SOURCE.to_string(),
+        };
+        let snapshots = self.snapshots.clone();
+        config.diagnostic_snapshot = Some(Box::new(move |phase, diagnostics| {
+            snapshots.lock().unwrap().push((phase, diagnostics));
+        }));
+    }
+
+    fn after_analysis<'tcx>(
+        &mut self,
+        _compiler: &interface::Compiler,
+        _queries: &'tcx Queries<'tcx>,
+    ) -> Compilation {
+        Compilation::Stop
+    }
+}
+
+fn main() {
+    let snapshots = Snapshots::default();
+    let args = vec!["diagnostic-snapshot".to_string(), "snapshot.rs".to_string()];
+    rustc_driver::catch_fatal_errors(|| {
+        rustc_driver::RunCompiler::new(&args, &mut SnapshotCalls { snapshots: snapshots.clone() })
+            .run()
+            .ok();
+    })
+    .ok();
+
+    let snapshots = snapshots.lock().unwrap();
+    let phases: Vec<_> = snapshots.iter().map(|(phase, _)| *phase).collect();
+    assert_eq!(
+        phases,
+        [
+            SnapshotPhase::Parse,
+            SnapshotPhase::Expansion,
+            SnapshotPhase::Analysis,
+            SnapshotPhase::Finish
+        ]
+    );
+    assert!(snapshots[0].1.is_empty());
+    assert!(snapshots[1].1.is_empty());
+    assert!(snapshots[3].1.is_empty());
+
+    let [warning] = &snapshots[2].1[..] else { panic!("expected one warning") };
+    assert_eq!(warning.level, Level::Warning(None));
+    assert_eq!(warning.message, "unused variable: `unused`");
+    let [span] = &warning.spans[..] else { panic!("expected one span") };
+    assert_eq!(span.file_name, "<snapshot>");
+    assert!(span.is_primary);
+    assert_eq!((span.line_start, span.column_start), (2, 9));
+    assert_eq!((span.line_end, span.column_end), (2, 15));
+
+    let [suggestion] = &warning.suggestions[..] else { panic!("expected one suggestion") };
+    assert_eq!(suggestion.substitutions[0][0].1, "_unused");
+}
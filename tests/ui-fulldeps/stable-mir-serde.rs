@@ -0,0 +1,89 @@
+// run-pass
+// Test that the Stable MIR model of a crate survives a round trip through serde.
+
+// ignore-cross-compile
+// ignore-stage1
+// ignore-remote
+
+#![feature(rustc_private)]
+
+extern crate rustc_driver;
+extern crate rustc_interface;
+extern crate rustc_session;
+extern crate rustc_smir;
+extern crate rustc_span;
+extern crate serde_json;
+
+use rustc_driver::Compilation;
+use rustc_interface::{interface, Queries};
+use rustc_session::config::Input;
+use rustc_smir::stable_mir::{Crate, ItemKind, VERSION};
+use rustc_span::FileName;
+
+const SOURCE: &str = "\
+pub struct Wrapper(pub u32);
+
+pub static mut COUNTER: u32 = 0;
+
+pub fn apply<F: Fn(u32) -> u32>(f: F, x: u32) -> u32 {
+    f(x)
+}
+
+pub fn add_one(x: u32) -> Wrapper {
+    Wrapper(apply(|y| y + 1, x))
+}
+
+pub fn first(xs: &[u32]) -> Option<&u32> {
+    xs.first()
+}
+";
+
+struct ModelCalls {
+    model: Option<Crate>,
+}
+
+impl rustc_driver::Callbacks for ModelCalls {
+    fn config(&mut self, config: &mut interface::Config) {
+        config.input = Input::Str {
+            name: FileName::Custom("model".to_string()),
+            input: SOURCE.to_string(),
+        };
+    }
+
+    fn after_analysis<'tcx>(
+        &mut self,
+        _compiler: &interface::Compiler,
+        queries: &'tcx Queries<'tcx>,
+    ) -> Compilation {
+        queries.global_ctxt().unwrap().enter(|tcx| {
+            self.model = Some(rustc_smir::rustc_smir::crate_model(tcx));
+        });
+        Compilation::Stop
+    }
+}
+
+fn main() {
+    let mut callbacks = ModelCalls { model: None };
+    let args = vec![
+        "stable-mir-serde".to_string(),
+        "--crate-type=lib".to_string(),
+        "model.rs".to_string(),
+    ];
+    rustc_driver::catch_fatal_errors(|| {
+        rustc_driver::RunCompiler::new(&args, &mut callbacks).run().ok();
+    })
+    .ok();
+
+    let model = callbacks.model.expect("no Stable MIR model was built");
+    assert_eq!(model.version, VERSION);
+    let kinds: Vec<_> = model.items.iter().map(|item| item.kind).collect();
+    assert!(kinds.contains(&ItemKind::Fn));
+    assert!(kinds.contains(&ItemKind::Closure));
+    assert!(kinds.contains(&ItemKind::Ctor));
+    assert!(kinds.iter().any(|kind| matches!(kind, ItemKind::Static(_))));
+
+    let json = serde_json::to_string(&model).unwrap();
+    let round_tripped: Crate = serde_json::from_str(&json).unwrap();
+    assert_eq!(round_tripped, model);
+    assert_eq!(serde_json::to_string(&round_tripped).unwrap(), json);
+}
@@ -0,0 +1,7 @@
+// ignore-tidy-linelength
+// compile-flags: -Z cgu-call-graph-profile={{src-base}}/codegen/auxiliary/malformed-call-graph-profile.txt
+
+// This test checks that a malformed call-graph profile is reported when the session is built,
+// even if the crate is never partitioned.
+
+fn main() {}
@@ -0,0 +1,8 @@
+// ignore-tidy-linelength
+// normalize-stderr-test: "missing-call-graph-profile.txt`:[^(]*" -> "missing-call-graph-profile.txt`: $$FILE_NOT_FOUND_MSG "
+// normalize-stderr-test: "os error \d+" -> "os error $$FILE_NOT_FOUND_CODE"
+// compile-flags: -Z cgu-call-graph-profile={{src-base}}/codegen/auxiliary/missing-call-graph-profile.txt
+
+// This test checks that a call-graph profile that can't be read is reported as an error.
+
+fn main() {}
@@ -0,0 +1,34 @@
+// check-pass
+#![feature(generic_const_exprs)]
+#![allow(incomplete_features)]
+
+// Tests that concrete generic const expressions which call a `const fn` are evaluated. The `N`
+// of `take` is inferred to `size_of::<u32>()` and `header_len::<u16>()` respectively, which
+// used to ICE when checking that it is evaluatable.
+
+use std::mem::size_of;
+
+const fn header_len<T>() -> usize {
+    2 * size_of::<T>()
+}
+
+fn take<const N: usize>(_: [u8; N + 1]) {}
+
+fn sized<T>() -> [u8; size_of::<T>() + 1]
+where
+    [u8; size_of::<T>() + 1]:,
+{
+    [0; size_of::<T>() + 1]
+}
+
+fn header<T>() -> [u8; header_len::<T>() + 1]
+where
+    [u8; header_len::<T>() + 1]:,
+{
+    [0; header_len::<T>() + 1]
+}
+
+fn main() {
+    take(sized::<u32>());
+    take(header::<u16>());
+}
@@ -0,0 +1,17 @@
+#![feature(generic_const_exprs)]
+#![allow(incomplete_features)]
+
+// Tests that a concrete generic const expression which overflows is reported. The `N` of
+// `take` is inferred to `0 - 1`.
+
+fn take<const N: usize>(_: [u8; N + 1]) {}
+
+fn make<const K: usize>() -> [u8; K - 1 + 1] {
+    //~^ ERROR evaluation of `make::<0>::{constant#0}` failed
+    todo!()
+}
+
+fn main() {
+    take(make::<0>());
+    //~^ ERROR failed to evaluate generic const expression
+}
@@ -0,0 +1,48 @@
+// run-pass
+#![feature(generic_const_exprs)]
+#![allow(incomplete_features)]
+
+// Tests that generic constants unify with where bounds that are written differently, but are
+// equal once the operands of commutative operators are sorted and constant operands are folded.
+
+use std::mem::size_of;
+
+struct Encoded<const N: usize>
+where
+    [(); N * 2 + 4]:,
+{
+    bytes: [u8; N * 2 + 4],
+}
+
+impl<const N: usize> Encoded<N>
+where
+    [(); N * 2 + 4]:,
+{
+    fn new() -> Self {
+        Encoded { bytes: [0; 4 + 2 * N] }
+    }
+
+    fn into_bytes(self) -> [u8; 2 * N + 1 + 3] {
+        self.bytes
+    }
+}
+
+fn sum<const N: usize, const M: usize>() -> [u8; M + N]
+where
+    [(); N + M]:,
+{
+    [0; N + M]
+}
+
+fn with_tag<T>() -> [u8; 1 + size_of::<T>()]
+where
+    [(); size_of::<T>() + 1]:,
+{
+    [0; size_of::<T>() + 1]
+}
+
+fn main() {
+    assert_eq!(Encoded::<3>::new().into_bytes().len(), 10);
+    assert_eq!(sum::<2, 3>().len(), 5);
+    assert_eq!(with_tag::<u32>().len(), 5);
+}
@@ -1,3 +1,5 @@
+// check-pass
+
 #![feature(generic_const_exprs, adt_const_params, const_trait_impl)]
 #![allow(incomplete_features)]
 
@@ -26,8 +28,6 @@ fn bar<const N: Foo>() {}
 
 fn foo2<const N: usize>(a: Evaluatable2<{ N + N }>) {
     bar2::<{ std::ops::Add::add(N, N) }>();
-    //~^ error: unconstrained generic constant
-    // FIXME(generic_const_exprs) make this not an error
 }
 
 fn bar2<const N: usize>() {}
@@ -0,0 +1,22 @@
+// compile-flags: -Z dump-const-eval-locals
+#![crate_type = "lib"]
+
+// Tests that `-Zdump-const-eval-locals` shows the locals of recursive frames on the notes of
+// the stacktrace, including enums and uninitialized locals, and folds repeated frames.
+
+enum Step {
+    Down(u32),
+    Done,
+}
+
+const fn countdown(n: u32) -> u32 {
+    let step = if n == 0 { Step::Done } else { Step::Down(n - 1) };
+    let _later: u32;
+    match step {
+        Step::Down(next) => countdown(next),
+        Step::Done => panic!("reached zero"),
+        //~^ ERROR evaluation of constant value failed
+    }
+}
+
+const X: u32 = countdown(4);
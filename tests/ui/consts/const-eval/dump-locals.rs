@@ -0,0 +1,25 @@
+// compile-flags: -Z dump-const-eval-locals
+#![crate_type = "lib"]
+
+// Tests that `-Zdump-const-eval-locals` shows the values of the locals of the panicking frame
+// and of its callers.
+
+struct Point {
+    x: u32,
+    y: u32,
+}
+
+const fn check(point: Point, limit: u32) -> u32 {
+    let sum = point.x + point.y;
+    if sum > limit {
+        panic!("out of bounds");
+        //~^ ERROR evaluation of constant value failed
+    }
+    sum
+}
+
+const SUM: u32 = {
+    let limit = 10;
+    let flags = (true, [1u8, 2, 3]);
+    check(Point { x: 7, y: 5 }, limit)
+};
@@ -0,0 +1,43 @@
+// Memory that a constant points to is immutable, so heap memory that is only reachable through a
+// raw pointer is rejected: without a type, it can't be checked for `UnsafeCell`s.
+#![feature(allocator_api)]
+#![feature(const_box)]
+#![feature(const_global_allocator)]
+#![feature(const_mut_refs)]
+
+use std::alloc::Global;
+use std::cell::Cell;
+use std::ptr::NonNull;
+
+struct Counter {
+    count: *const Cell<u32>,
+}
+
+const fn counter() -> &'static Counter {
+    let count = Box::leak(Box::new_in(Cell::new(0), Global));
+    Box::leak(Box::new_in(Counter { count }, Global))
+}
+
+const COUNTER: &Counter = counter(); //~ ERROR untyped pointers are not allowed in constant
+
+struct Erased {
+    ptr: NonNull<u8>,
+}
+
+const fn erased() -> &'static Erased {
+    let cell: &'static mut Cell<u32> = Box::leak(Box::new_in(Cell::new(0), Global));
+    Box::leak(Box::new_in(Erased { ptr: NonNull::from(cell).cast() }, Global))
+}
+
+const ERASED: &Erased = erased(); //~ ERROR untyped pointers are not allowed in constant
+
+// The elements of a vector are interned according to their type, but not validated.
+const fn cells() -> &'static Vec<Cell<u32>> {
+    let buf: &mut [Cell<u32>; 1] = Box::leak(Box::new_in([Cell::new(0)], Global));
+    let cells = unsafe { Vec::from_raw_parts_in(buf as *mut _ as *mut Cell<u32>, 1, 1, Global) };
+    Box::leak(Box::new_in(cells, Global))
+}
+
+const CELLS: &Vec<Cell<u32>> = cells(); //~ ERROR untyped pointers are not allowed in constant
+
+fn main() {}
@@ -0,0 +1,37 @@
+// run-pass
+// Heap allocations made with `Global` during const evaluation become part of the constant when
+// they are leaked.
+#![feature(allocator_api)]
+#![feature(const_box)]
+#![feature(const_global_allocator)]
+#![feature(const_mut_refs)]
+#![feature(const_trait_impl)]
+
+use std::alloc::Global;
+
+struct Entry {
+    key: u32,
+    name: &'static str,
+}
+
+const fn build_table() -> Box<[Entry; 3], Global> {
+    let mut table = Box::new_in(
+        [Entry { key: 1, name: "one" }, Entry { key: 2, name: "two" }, Entry { key: 0, name: "" }],
+        Global,
+    );
+    table[2] = Entry { key: 3, name: "three" };
+    table
+}
+
+const TABLE: &[Entry] = Box::leak(build_table());
+
+// The inner box is owned by the leaked outer one, so it is never freed either.
+const NESTED: &Box<[u32], Global> =
+    Box::leak(Box::new_in(Box::new_in([1, 2, 3], Global) as Box<[u32], Global>, Global));
+
+fn main() {
+    assert_eq!(TABLE.len(), 3);
+    assert_eq!(TABLE.iter().map(|entry| entry.key).sum::<u32>(), 6);
+    assert_eq!(TABLE[2].name, "three");
+    assert_eq!(**NESTED, [1, 2, 3]);
+}
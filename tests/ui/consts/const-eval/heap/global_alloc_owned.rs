@@ -0,0 +1,24 @@
+// A constant can't own a heap allocation: every use of it would free the allocation again.
+#![feature(allocator_api)]
+#![feature(const_box)]
+#![feature(const_global_allocator)]
+#![feature(const_mut_refs)]
+
+use std::alloc::Global;
+
+const BOX: Box<i32, Global> = Box::new_in(42, Global);
+//~^ ERROR the final value of a constant must not own heap memory that could be freed
+
+// Boxes owned by other boxes are freed along with them.
+const NESTED: Option<Box<Box<i32, Global>, Global>> =
+    Some(Box::new_in(Box::new_in(42, Global), Global));
+//~^^ ERROR the final value of a constant must not own heap memory that could be freed
+
+// So are the buffers of vectors.
+const VEC: Vec<i32> = {
+//~^ ERROR the final value of a constant must not own heap memory that could be freed
+    let buf: &mut [i32; 1] = Box::leak(Box::new_in([42], Global));
+    unsafe { Vec::from_raw_parts_in(buf as *mut [i32; 1] as *mut i32, 1, 1, Global) }
+};
+
+fn main() {}
@@ -0,0 +1,15 @@
+// A static can't own a heap allocation that safe code can replace, as that would free memory of
+// the static.
+#![feature(allocator_api)]
+#![feature(const_box)]
+#![feature(const_global_allocator)]
+
+use std::alloc::Global;
+use std::sync::Mutex;
+
+static M: Mutex<Option<Box<i32, Global>>> = Mutex::new(Some(Box::new_in(1, Global)));
+//~^ ERROR the final value of a static must not own heap memory that could be freed
+
+fn main() {
+    *M.lock().unwrap() = None;
+}
@@ -0,0 +1,42 @@
+// run-pass
+// The buffers of a `Vec` and a `String` made with `Global` during const evaluation become part of
+// the constant when they are leaked, or when the constant refers to the `Vec` or `String`.
+#![feature(allocator_api)]
+#![feature(const_box)]
+#![feature(const_global_allocator)]
+#![feature(const_mut_refs)]
+#![feature(const_string_from_utf8_unchecked)]
+#![feature(const_vec_leak)]
+#![feature(string_leak)]
+
+use std::alloc::Global;
+
+const fn vec() -> Vec<u32> {
+    let buf: &mut [u32; 3] = Box::leak(Box::new_in([1, 2, 3], Global));
+    // SAFETY: the buffer was allocated by `Global` for 3 elements, which are initialized.
+    unsafe { Vec::from_raw_parts_in(buf as *mut [u32; 3] as *mut u32, 3, 3, Global) }
+}
+
+const fn string() -> String {
+    let buf: &mut [u8; 5] = Box::leak(Box::new_in(*b"hello", Global));
+    // SAFETY: the buffer was allocated by `Global` for 5 bytes, which are valid UTF-8.
+    unsafe {
+        let vec = Vec::from_raw_parts_in(buf as *mut [u8; 5] as *mut u8, 5, 5, Global);
+        String::from_utf8_unchecked(vec)
+    }
+}
+
+const VEC: &[u32] = vec().leak();
+const STRING: &str = string().leak();
+
+// The buffers are owned by the leaked `Vec` and `String`, so they are never freed either.
+const VEC_REF: &Vec<u32> = Box::leak(Box::new_in(vec(), Global));
+const STRING_REF: &String = Box::leak(Box::new_in(string(), Global));
+
+fn main() {
+    assert_eq!(VEC, [1, 2, 3]);
+    assert_eq!(STRING, "hello");
+    assert_eq!(*VEC_REF, [1, 2, 3]);
+    assert_eq!(VEC_REF.capacity(), 3);
+    assert_eq!(STRING_REF.as_str(), "hello");
+}
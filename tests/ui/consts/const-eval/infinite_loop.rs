@@ -1,7 +1,8 @@
 fn main() {
     // Tests the Collatz conjecture with an incorrect base case (0 instead of 1).
     // The value of `n` will loop indefinitely (4 - 2 - 1 - 4).
-    let _ = [(); {
+    let _ = [(); { //~ WARN constant evaluation is taking a long time
+        //~| WARN constant evaluation is taking a long time
         let mut n = 113383; // #20 in https://oeis.org/A006884
         while n != 0 {
             //~^ ERROR evaluation of constant value failed
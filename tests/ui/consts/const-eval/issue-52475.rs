@@ -1,5 +1,6 @@
 fn main() {
-    let _ = [(); {
+    let _ = [(); { //~ WARN constant evaluation is taking a long time
+        //~| WARN constant evaluation is taking a long time
         let mut x = &0;
         let mut n = 0;
         while n < 5 {
@@ -1,3 +1,5 @@
 static _X: () = loop {}; //~ ERROR could not evaluate static initializer
+//~^ WARN constant evaluation is taking a long time
+//~| WARN constant evaluation is taking a long time
 
 fn main() {}
@@ -0,0 +1,17 @@
+// check-pass
+
+// The evaluation is first reported after a quarter of the default limit of 2000000 steps, and
+// would be reported again after 1000000 steps.
+const X: usize = {
+//~^ WARN constant evaluation is taking a long time
+    let mut x = 0;
+    while x != 250_000 {
+        x += 1;
+    }
+
+    x
+};
+
+fn main() {
+    assert_eq!(X, 250_000);
+}
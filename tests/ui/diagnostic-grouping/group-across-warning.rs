@@ -0,0 +1,13 @@
+// compile-flags: --error-format=human -Z diagnostic-grouping=yes
+
+// This test checks that a warning emitted between two errors of the same group doesn't split
+// the group, and is emitted after it.
+
+fn foo(a: u32) -> u32 { a }
+
+fn main() {
+    foo(10i32); //~ ERROR mismatched types
+    return;
+    foo(20i32); //~ ERROR mismatched types
+    //~^ WARN unreachable statement
+}
@@ -0,0 +1,17 @@
+// compile-flags: --error-format=human -Z diagnostic-grouping=yes
+
+// This test checks that errors of the same pass with the same code and primary cause are
+// reported once in the human error output, in the place of the first of them, followed by the
+// locations of the others and the suggestions of all of them, even if other errors are
+// emitted between them.
+
+fn foo(a: u32) -> u32 { a }
+
+fn main() {
+    foo(10i32); //~ ERROR mismatched types
+    foo(20i32); //~ ERROR mismatched types
+    foo(30i32); //~ ERROR mismatched types
+    let _: () = 0u8; //~ ERROR mismatched types
+    foo(40i32); //~ ERROR mismatched types
+    foo(50i32); //~ ERROR mismatched types
+}
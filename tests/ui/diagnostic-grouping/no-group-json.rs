@@ -0,0 +1,11 @@
+// compile-flags: -Z diagnostic-grouping=yes
+
+// This test checks that `-Z diagnostic-grouping` has no effect on the JSON error output, where
+// every error is reported separately.
+
+fn foo(a: u32) -> u32 { a }
+
+fn main() {
+    foo(10i32); //~ ERROR mismatched types
+    foo(20i32); //~ ERROR mismatched types
+}
@@ -0,0 +1,12 @@
+// ignore-tidy-tab
+// compile-flags: -Z highlight-snippets
+
+// Highlighting the quoted source must not change the text of the snippet or move the
+// underlines, even around multi-byte characters, tabs, literals and comments.
+
+fn main() {
+    let _: () = "héllo"; /* a comment */ let _: () = 'x';
+    //~^ ERROR mismatched types
+    //~| ERROR mismatched types
+	let _: () = "	tab"; //~ ERROR mismatched types
+}
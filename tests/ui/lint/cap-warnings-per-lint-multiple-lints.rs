@@ -0,0 +1,13 @@
+// check-pass
+// compile-flags: -Z unstable-options --cap-warnings-per-lint=2
+
+// Check that `--cap-warnings-per-lint` counts the warnings of each lint separately, and only
+// reports a summary for the lints that went over the cap.
+
+fn main() {
+    let Alpha = 1; //~ WARN variable `Alpha` should have a snake case name
+    let Beta = 2; //~ WARN variable `Beta` should have a snake case name
+    let unused_one = Alpha + Beta; //~ WARN unused variable: `unused_one`
+    let unused_two = 3; //~ WARN unused variable: `unused_two`
+    let unused_three = 4;
+}
@@ -0,0 +1,13 @@
+// check-pass
+// compile-flags: -Z unstable-options --cap-warnings-per-lint=2
+
+// Check that `--cap-warnings-per-lint` stops showing the warnings of a lint once the cap is
+// reached, and reports the exact number of warnings the lint emitted.
+
+fn main() {
+    let a = 1; //~ WARN unused variable: `a`
+    let b = 2; //~ WARN unused variable: `b`
+    let c = 3;
+    let d = 4;
+    let e = 5;
+}
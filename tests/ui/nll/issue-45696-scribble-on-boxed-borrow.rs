@@ -3,6 +3,7 @@
 // the return occurs.
 
 // ignore-compare-mode-polonius
+// ignore-compare-mode-polonius-hybrid
 
 struct Scribble<'a>(&'a mut u32);
 
@@ -1,8 +1,9 @@
-// This tests passes in Polonius mode, so is skipped in the automated compare-mode.
+// This tests passes in Polonius mode, so is skipped in the automated compare-modes.
 // We will manually check it passes in Polonius tests, as we can't have a test here
 // which conditionally passes depending on a test revision/compile-flags.
 
 // ignore-compare-mode-polonius
+// ignore-compare-mode-polonius-hybrid
 
 struct Foo;
 
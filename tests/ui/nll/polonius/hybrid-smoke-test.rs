@@ -0,0 +1,35 @@
+// Check that the hybrid Polonius mode accepts the conditional return of a borrow, which NLL
+// rejects, and still reports the borrow conflicts that Polonius agrees with.
+// compile-flags: -Z polonius=hybrid
+
+use std::collections::HashMap;
+
+// NLL problem case #3.
+pub fn get_default(map: &mut HashMap<u32, String>, key: u32) -> &mut String {
+    match map.get_mut(&key) {
+        Some(value) => value,
+        None => {
+            map.insert(key, String::new());
+            map.get_mut(&key).unwrap()
+        }
+    }
+}
+
+pub fn position_dependent_outlives(x: &mut i32, cond: bool) -> &mut i32 {
+    let y = &mut *x;
+    if cond {
+        return y;
+    } else {
+        *x = 0;
+        return x;
+    }
+}
+
+pub fn use_while_mut() {
+    let mut x = 0;
+    let y = &mut x;
+    let z = x; //~ ERROR cannot use `x` because it was mutably borrowed
+    let w = y;
+}
+
+fn main() {}
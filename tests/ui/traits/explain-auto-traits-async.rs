@@ -0,0 +1,21 @@
+// edition:2021
+// compile-flags: -Z explain-auto-traits
+
+// Check that `-Z explain-auto-traits` labels the generator witness of a future that holds a
+// value across an await point, and the value itself, in the chain.
+
+use std::rc::Rc;
+
+fn is_send<T: Send>(_: T) {}
+
+async fn bar() {}
+
+async fn foo() {
+    let r = Rc::new(());
+    bar().await;
+}
+
+fn main() {
+    is_send(foo());
+    //~^ ERROR future cannot be sent between threads safely
+}
@@ -0,0 +1,13 @@
+// compile-flags: -Z explain-auto-traits
+
+// Check that `-Z explain-auto-traits` labels the captured upvars of a closure in the chain.
+
+use std::rc::Rc;
+
+fn is_send<T: Send>(_: T) {}
+
+fn main() {
+    let x = Rc::new(3);
+    is_send(move || drop(x));
+    //~^ ERROR `Rc<i32>` cannot be sent between threads safely
+}
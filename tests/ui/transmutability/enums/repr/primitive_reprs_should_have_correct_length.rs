@@ -1,4 +1,5 @@
 //! An enum with a primitive repr should have exactly the size of that primitive.
+// normalize-stderr-test "(V0[iu]size`, and requires .* at offset) [48]" -> "$1 $$SIZE"
 
 #![crate_type = "lib"]
 #![feature(transmutability)]
@@ -0,0 +1,39 @@
+//! The fields of the variants of an enum with a primitive representation are laid out after its
+//! tag, with padding to satisfy their alignment.
+
+#![crate_type = "lib"]
+#![feature(transmutability)]
+#![allow(dead_code)]
+
+mod assert {
+    use std::mem::{Assume, BikeshedIntrinsicFrom};
+    pub struct Context;
+
+    pub fn is_transmutable<Src, Dst>()
+    where
+        Dst: BikeshedIntrinsicFrom<Src, Context, { Assume::SAFETY }>
+    {}
+}
+
+#[repr(u8)] enum V1 { V = 1 }
+
+#[repr(u8)]
+enum Enum {
+    A(u8),
+    B(u16),
+}
+
+#[repr(C)] struct B(V1, u16);
+
+fn should_accept_variant() {
+    assert::is_transmutable::<B, Enum>();
+    assert::is_transmutable::<Enum, u8>();
+}
+
+fn should_reject_padding() {
+    assert::is_transmutable::<Enum, [u8; 2]>(); //~ ERROR cannot be safely transmuted
+}
+
+fn should_reject_other_variants() {
+    assert::is_transmutable::<Enum, B>(); //~ ERROR cannot be safely transmuted
+}
@@ -0,0 +1,56 @@
+// check-pass
+//! References to slices are transmutable if the elements of their referents are of the same size
+//! and transmutable, as the length of a slice counts its elements.
+
+#![crate_type = "lib"]
+#![feature(transmutability)]
+#![allow(dead_code, incomplete_features)]
+
+mod assert {
+    use std::mem::{Assume, BikeshedIntrinsicFrom};
+    pub struct Context;
+
+    pub fn is_transmutable<Src, Dst>()
+    where
+        Dst: BikeshedIntrinsicFrom<Src, Context, { Assume::SAFETY }>
+    {}
+
+    pub fn is_maybe_transmutable<Src, Dst>()
+    where
+        Dst: BikeshedIntrinsicFrom<Src, Context, {
+            Assume {
+                alignment: true,
+                lifetimes: true,
+                safety: true,
+                validity: true,
+            }
+        }>
+    {}
+}
+
+fn should_accept_identity() {
+    assert::is_transmutable::<&'static [u8], &'static [u8]>();
+    assert::is_transmutable::<&'static mut [u8], &'static mut [u8]>();
+    assert::is_transmutable::<&'static [u32], &'static [u32]>();
+}
+
+fn should_accept_compatible_elements() {
+    assert::is_transmutable::<&'static [bool], &'static [u8]>();
+    assert::is_transmutable::<&'static [u16], &'static [[u8; 2]]>();
+    assert::is_transmutable::<&'static mut [u16], &'static mut [[u8; 2]]>();
+}
+
+fn should_accept_weakening_uniqueness() {
+    assert::is_transmutable::<&'static mut [u8], &'static [u8]>();
+}
+
+fn should_accept_slice_references_in_repr_c_types() {
+    #[repr(C)] struct Src<'a>(&'a [u32], u32);
+    #[repr(C)] struct Dst<'a>(&'a [[u8; 4]], [u8; 4]);
+    assert::is_transmutable::<Src<'static>, Dst<'static>>();
+}
+
+fn should_accept_with_assumptions() {
+    assert::is_maybe_transmutable::<&'static [[u8; 2]], &'static [u16]>();
+    assert::is_maybe_transmutable::<&'static [u8], &'static [bool]>();
+}
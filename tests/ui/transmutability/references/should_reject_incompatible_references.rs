@@ -0,0 +1,28 @@
+//! A reference is only transmutable into another if its referent is sufficiently aligned and
+//! transmutable, and if it is mutable when the other is.
+
+#![crate_type = "lib"]
+#![feature(transmutability)]
+#![allow(dead_code, incomplete_features)]
+
+mod assert {
+    use std::mem::{Assume, BikeshedIntrinsicFrom};
+    pub struct Context;
+
+    pub fn is_transmutable<Src, Dst>()
+    where
+        Dst: BikeshedIntrinsicFrom<Src, Context, { Assume::SAFETY }>
+    {}
+}
+
+fn should_reject_stricter_alignment() {
+    assert::is_transmutable::<&'static [u8; 2], &'static u16>(); //~ ERROR cannot be safely transmuted
+}
+
+fn should_reject_mut_from_shared() {
+    assert::is_transmutable::<&'static u8, &'static mut u8>(); //~ ERROR cannot be safely transmuted
+}
+
+fn should_reject_incompatible_referents() {
+    assert::is_transmutable::<&'static u8, &'static bool>(); //~ ERROR cannot be safely transmuted
+}
@@ -0,0 +1,32 @@
+//! A reference to a slice is only transmutable into a reference to a slice whose elements are of
+//! the same size, and transmutable. References to other unsized types are not supported yet.
+
+#![crate_type = "lib"]
+#![feature(transmutability)]
+#![allow(dead_code, incomplete_features)]
+
+mod assert {
+    use std::mem::{Assume, BikeshedIntrinsicFrom};
+    pub struct Context;
+
+    pub fn is_transmutable<Src, Dst>()
+    where
+        Dst: BikeshedIntrinsicFrom<Src, Context, { Assume::SAFETY }>
+    {}
+}
+
+fn should_reject_different_element_sizes() {
+    assert::is_transmutable::<&'static [u16], &'static [u8]>(); //~ ERROR cannot be safely transmuted
+}
+
+fn should_reject_incompatible_elements() {
+    assert::is_transmutable::<&'static [u8], &'static [bool]>(); //~ ERROR cannot be safely transmuted
+}
+
+fn should_reject_thin_from_wide() {
+    assert::is_transmutable::<&'static [u8], &'static u8>(); //~ ERROR cannot be safely transmuted
+}
+
+fn should_reject_str_referent() {
+    assert::is_transmutable::<&'static [u8], &'static str>(); //~ ERROR cannot be safely transmuted
+}